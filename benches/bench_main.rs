@@ -3,5 +3,6 @@ use codspeed_criterion_compat::criterion_main;
 mod benchmarks;
 criterion_main! {
     benchmarks::check_texts::checks,
+    benchmarks::encode_requests::encodings,
 
 }
@@ -3,7 +3,7 @@ use futures::future::join_all;
 use languagetool_rust::{
     check::{CheckRequest, CheckResponse, CheckResponseWithContext},
     error::Error,
-    server::ServerClient,
+    server::{ServerCli, ServerClient, ServerClientBuilder},
 };
 
 static FILES: [(&str, &str); 3] = [
@@ -16,7 +16,7 @@ async fn request_until_success(req: &CheckRequest, client: &ServerClient) -> Che
     loop {
         match client.check(req).await {
             Ok(resp) => return resp,
-            Err(Error::InvalidRequest(body))
+            Err(Error::Server { body, .. })
                 if body == *"Error: Server overloaded, please try again later" =>
             {
                 continue;
@@ -60,6 +60,30 @@ async fn check_text_split(text: &str) -> CheckResponse {
         .into()
 }
 
+#[tokio::main]
+async fn check_snippets(snippets: &[&str], pool_max_idle_per_host: usize) -> usize {
+    let cli = ServerCli::from_env().expect(
+        "Please use a local server for benchmarking, and configure the environ variables to use \
+         it.",
+    );
+    let client = ServerClientBuilder::from(cli)
+        .pool_max_idle_per_host(pool_max_idle_per_host)
+        .concurrency(32)
+        .build()
+        .unwrap();
+
+    let resps = join_all(snippets.iter().map(|snippet| {
+        let client = &client;
+        async move {
+            let req = CheckRequest::default().with_text(snippet.to_string());
+            request_until_success(&req, client).await
+        }
+    }))
+    .await;
+
+    resps.iter().map(|resp| resp.matches.len()).sum()
+}
+
 fn bench_basic(c: &mut Criterion) {
     let mut group = c.benchmark_group("basic");
 
@@ -78,4 +102,22 @@ fn bench_split(c: &mut Criterion) {
     }
 }
 
-criterion_group!(checks, bench_basic, bench_split,);
+fn bench_pool(c: &mut Criterion) {
+    let mut group = c.benchmark_group("pool");
+    let snippets: Vec<&str> = FILES
+        .iter()
+        .flat_map(|(_, source)| source.lines())
+        .filter(|line| !line.trim().is_empty())
+        .cycle()
+        .take(1000)
+        .collect();
+
+    for max in [0, 8, 32, 128] {
+        group.throughput(Throughput::Elements(snippets.len() as u64));
+        group.bench_with_input(max.to_string(), &max, |b, &max| {
+            b.iter(|| check_snippets(&snippets, max))
+        });
+    }
+}
+
+criterion_group!(checks, bench_basic, bench_split, bench_pool,);
@@ -0,0 +1,124 @@
+//! Compare form and multipart encodings of large check requests.
+//!
+//! Requests are sent to a local server that reads them entirely and returns a
+//! response without any match, so that only encoding and sending are
+//! measured.
+
+use codspeed_criterion_compat::{criterion_group, BenchmarkId, Criterion, Throughput};
+use languagetool_rust::{
+    check::{CheckRequest, Data, DataAnnotation},
+    server::ServerClient,
+};
+use std::{
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+};
+
+static TEXT: &str = include_str!("../large.txt");
+
+static RESPONSE: &str = r#"{
+    "language": {
+        "code": "en-US",
+        "detectedLanguage": {"code": "en-US", "name": "English (US)"},
+        "name": "English (US)"
+    },
+    "matches": [],
+    "software": {
+        "apiVersion": 1,
+        "buildDate": "",
+        "name": "LanguageTool",
+        "premium": false,
+        "status": "",
+        "version": ""
+    }
+}"#;
+
+/// Read HTTP requests from `stream` and answer each with [`RESPONSE`].
+///
+/// Bodies are read up to their `Content-Length`, and chunked bodies are not
+/// handled, as check requests are always sent with a known length.
+fn serve(stream: TcpStream) -> io::Result<()> {
+    stream.set_nodelay(true)?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut writer = stream;
+
+    loop {
+        let mut content_length = 0;
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line)? == 0 {
+                return Ok(());
+            }
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse().unwrap();
+                }
+            }
+        }
+        io::copy(&mut (&mut reader).take(content_length), &mut io::sink())?;
+        write!(
+            writer,
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+            RESPONSE.len(),
+            RESPONSE
+        )?;
+    }
+}
+
+/// Start a local server in the background and return its port.
+fn start_server() -> u16 {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            std::thread::spawn(move || serve(stream.unwrap()));
+        }
+    });
+
+    port
+}
+
+/// Return an annotation stream of at least `size` bytes, alternating text and
+/// markup.
+fn data(size: usize) -> Data {
+    TEXT.lines()
+        .cycle()
+        .scan(0, |len, line| {
+            (*len < size).then(|| {
+                *len += line.len() + 7;
+                [
+                    DataAnnotation::new_text(line.to_string()),
+                    DataAnnotation::new_markup("<br/>\n".to_string()),
+                ]
+            })
+        })
+        .flatten()
+        .collect()
+}
+
+fn bench_encoding(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let port = start_server().to_string();
+    let mut group = c.benchmark_group("encoding");
+    group.sample_size(10);
+
+    for size in [1 << 12, 1 << 14, 1 << 20, 4 << 20] {
+        let request = CheckRequest::default().with_data(data(size));
+        group.throughput(Throughput::Bytes(size as u64));
+
+        for (name, threshold) in [("form", usize::MAX), ("multipart", 0)] {
+            let client =
+                ServerClient::new("http://127.0.0.1", &port).with_multipart_threshold(threshold);
+            group.bench_with_input(BenchmarkId::new(name, size), &request, |b, request| {
+                b.iter(|| runtime.block_on(client.check(request)).unwrap())
+            });
+        }
+    }
+}
+
+criterion_group!(encodings, bench_encoding);
@@ -1 +1,2 @@
 pub mod check_texts;
+pub mod encode_requests;
@@ -0,0 +1,34 @@
+//! Export build information, see `src/lib/build_info.rs`.
+
+use std::{path::Path, process::Command};
+
+fn main() {
+    println!(
+        "cargo:rustc-env=LTRS_BUILD_TARGET={}",
+        std::env::var("TARGET").unwrap()
+    );
+
+    // Only this crate's own repository, and not one it is vendored in, tells
+    // which commit it was built from
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let git_dir = Path::new(&manifest_dir).join(".git");
+    let sha = git_dir
+        .exists()
+        .then(|| {
+            Command::new("git")
+                .args(["rev-parse", "--short", "HEAD"])
+                .current_dir(&manifest_dir)
+                .output()
+                .ok()
+        })
+        .flatten()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok());
+
+    if let Some(sha) = sha {
+        println!("cargo:rustc-env=LTRS_BUILD_GIT_SHA={}", sha.trim());
+        println!("cargo:rerun-if-changed={}", git_dir.join("HEAD").display());
+        println!("cargo:rerun-if-changed={}", git_dir.join("refs").display());
+    }
+    println!("cargo:rerun-if-changed=build.rs");
+}
@@ -1,14 +1,18 @@
-use clap::Parser;
 use languagetool_rust::{cli::Cli, error::Result};
 
 #[tokio::main]
 async fn main() {
     if let Err(e) = try_main().await {
         eprintln!("{e}");
+        if e.is_auth_error() {
+            eprintln!("hint: check your credentials, see `--username` and `--api-key`");
+        } else if e.is_rate_limited() {
+            eprintln!("hint: send fewer requests, see `--rate-limit` and `--retries`");
+        }
         std::process::exit(2);
     }
 }
 
 async fn try_main() -> Result<()> {
-    Cli::parse().execute().await
+    Cli::parse_with_config()?.execute().await
 }
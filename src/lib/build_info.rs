@@ -0,0 +1,114 @@
+//! Information about how this crate was built, useful when reporting issues.
+
+use serde::Serialize;
+use std::fmt;
+
+/// Build information.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[non_exhaustive]
+pub struct BuildInfo {
+    /// Crate version.
+    pub version: &'static str,
+    /// Target triple.
+    pub target: &'static str,
+    /// Short SHA of the git commit, if built from a git repository.
+    pub git_sha: Option<&'static str>,
+    /// Enabled crate features, sorted alphabetically.
+    pub features: &'static [&'static str],
+}
+
+/// Build information of this crate.
+pub const BUILD_INFO: BuildInfo = BuildInfo {
+    version: env!("CARGO_PKG_VERSION"),
+    target: env!("LTRS_BUILD_TARGET"),
+    git_sha: option_env!("LTRS_BUILD_GIT_SHA"),
+    features: &[
+        #[cfg(feature = "annotate")]
+        "annotate",
+        #[cfg(feature = "asciidoc")]
+        "asciidoc",
+        #[cfg(feature = "bibtex")]
+        "bibtex",
+        #[cfg(feature = "cli")]
+        "cli",
+        #[cfg(feature = "cli-complete")]
+        "cli-complete",
+        #[cfg(feature = "color")]
+        "color",
+        #[cfg(feature = "docker")]
+        "docker",
+        #[cfg(feature = "html")]
+        "html",
+        #[cfg(feature = "latex")]
+        "latex",
+        #[cfg(feature = "lsp")]
+        "lsp",
+        #[cfg(feature = "markdown")]
+        "markdown",
+        #[cfg(feature = "minimal")]
+        "minimal",
+        #[cfg(feature = "multithreaded")]
+        "multithreaded",
+        #[cfg(feature = "native-tls")]
+        "native-tls",
+        #[cfg(feature = "native-tls-vendored")]
+        "native-tls-vendored",
+        #[cfg(feature = "org")]
+        "org",
+        #[cfg(feature = "po")]
+        "po",
+        #[cfg(feature = "rules")]
+        "rules",
+        #[cfg(feature = "source-code")]
+        "source-code",
+        #[cfg(feature = "toml")]
+        "toml",
+        #[cfg(feature = "typst")]
+        "typst",
+        #[cfg(feature = "yaml")]
+        "yaml",
+    ],
+};
+
+impl BuildInfo {
+    /// Return the `User-Agent` header value sent to LanguageTool servers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use languagetool_rust::build_info::BUILD_INFO;
+    /// assert!(BUILD_INFO.user_agent().starts_with("languagetool-rust/"));
+    /// ```
+    #[must_use]
+    pub fn user_agent(&self) -> String {
+        match self.git_sha {
+            Some(sha) => format!("{}/{} ({sha})", env!("CARGO_PKG_NAME"), self.version),
+            None => format!("{}/{}", env!("CARGO_PKG_NAME"), self.version),
+        }
+    }
+}
+
+impl fmt::Display for BuildInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "version: {}", self.version)?;
+        writeln!(f, "target: {}", self.target)?;
+        writeln!(f, "git: {}", self.git_sha.unwrap_or("unknown"))?;
+        write!(f, "features: {}", self.features.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_info() {
+        assert_eq!(BUILD_INFO.version, env!("CARGO_PKG_VERSION"));
+        assert!(!BUILD_INFO.target.is_empty());
+        assert_eq!(BUILD_INFO.features.contains(&"cli"), cfg!(feature = "cli"));
+        assert!(BUILD_INFO.features.windows(2).all(|w| w[0] < w[1]));
+        assert!(BUILD_INFO
+            .to_string()
+            .starts_with(&format!("version: {}\n", BUILD_INFO.version)));
+    }
+}
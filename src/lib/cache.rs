@@ -0,0 +1,477 @@
+//! Cache of check responses, so that unchanged text is not checked again.
+//!
+//! Responses are keyed by a hash of the server's address, of the maximum
+//! number of suggestions, and of the whole request (text or data, language,
+//! rules, etc.), and they can be saved to, and loaded from, a JSON file. The
+//! cache is bounded in number of entries and in size, evicting the least
+//! recently used entries first.
+//!
+//! Together with [`CheckRequest::try_split_stable`], the cache allows
+//! incremental checking: after an edit, only the fragments of a document that
+//! changed are sent to the server again.
+
+use crate::{
+    check::{fnv1a, CheckRequest, CheckResponse},
+    error::Result,
+};
+#[cfg(feature = "cli")]
+use clap::{Parser, Subcommand};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "cli")]
+use std::io::Write;
+use std::{
+    collections::HashMap,
+    fmt,
+    path::{Path, PathBuf},
+};
+
+/// Default maximum number of cached responses.
+pub const DEFAULT_MAX_ENTRIES: usize = 10_000;
+
+/// Default maximum size of the cached responses, in bytes of JSON.
+pub const DEFAULT_MAX_SIZE: usize = 64 * 1024 * 1024;
+
+/// Cached response.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct Entry {
+    /// File the response was checked from, if any.
+    file: Option<PathBuf>,
+    /// Value of [`Cache::clock`] when the entry was last used.
+    last_used: u64,
+    /// Size of the response, in bytes of JSON.
+    size: usize,
+    response: CheckResponse,
+}
+
+/// Statistics of a [`Cache`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct CacheStats {
+    /// Number of cached responses.
+    #[serde(skip)]
+    pub entries: usize,
+    /// Size of the cached responses, in bytes of JSON.
+    #[serde(skip)]
+    pub size: usize,
+    /// Number of requests whose response was cached.
+    pub hits: u64,
+    /// Number of requests whose response was not cached.
+    pub misses: u64,
+    /// Number of responses evicted to keep the cache within its limits.
+    pub evictions: u64,
+}
+
+impl CacheStats {
+    /// Return the ratio of hits over all lookups, or [`None`] if the cache
+    /// was never used.
+    #[must_use]
+    pub fn hit_rate(&self) -> Option<f64> {
+        let lookups = self.hits + self.misses;
+        (lookups > 0).then(|| self.hits as f64 / lookups as f64)
+    }
+}
+
+impl fmt::Display for CacheStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "entries: {}", self.entries)?;
+        writeln!(f, "size: {} bytes", self.size)?;
+        writeln!(f, "hits: {}", self.hits)?;
+        writeln!(f, "misses: {}", self.misses)?;
+        match self.hit_rate() {
+            Some(rate) => writeln!(f, "hit rate: {:.1}%", rate * 100.0)?,
+            None => writeln!(f, "hit rate: -")?,
+        }
+        write!(f, "evictions: {}", self.evictions)
+    }
+}
+
+/// Cache of check responses.
+///
+/// # Examples
+///
+/// ```
+/// # use languagetool_rust::{cache::Cache, CheckRequest, ServerClient};
+/// let client = ServerClient::default();
+/// let mut cache = Cache::default().with_max_entries(100);
+/// let request = CheckRequest::default().with_text("Some text.".to_string());
+///
+/// assert!(cache.get(&client, &request).is_none());
+/// assert_eq!(cache.stats().misses, 1);
+/// ```
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Cache {
+    /// Maximum number of entries.
+    #[serde(skip, default = "default_max_entries")]
+    max_entries: usize,
+    /// Maximum size of the entries.
+    #[serde(skip, default = "default_max_size")]
+    max_size: usize,
+    /// Logical clock, incremented on each use, to find the least recently
+    /// used entries.
+    clock: u64,
+    entries: HashMap<String, Entry>,
+    stats: CacheStats,
+}
+
+fn default_max_entries() -> usize {
+    DEFAULT_MAX_ENTRIES
+}
+
+fn default_max_size() -> usize {
+    DEFAULT_MAX_SIZE
+}
+
+impl Default for Cache {
+    fn default() -> Self {
+        Self {
+            max_entries: DEFAULT_MAX_ENTRIES,
+            max_size: DEFAULT_MAX_SIZE,
+            clock: 0,
+            entries: HashMap::new(),
+            stats: CacheStats::default(),
+        }
+    }
+}
+
+impl Cache {
+    /// Return the default path of the cache file, inside the user's cache
+    /// directory (e.g., `~/.cache/ltrs/responses.json` on Linux).
+    #[cfg(feature = "cli")]
+    #[must_use]
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::cache_dir().map(|dir| dir.join("ltrs").join("responses.json"))
+    }
+
+    /// Read a cache file, or return an empty cache if it does not exist.
+    ///
+    /// # Errors
+    ///
+    /// If the file exists but cannot be read or is not a valid cache.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(content) => Ok(serde_json::from_str(&content)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Write the cache to `path`, creating its parent directories if needed.
+    ///
+    /// # Errors
+    ///
+    /// If the file cannot be written.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// Set the maximum number of cached responses, evicting the least
+    /// recently used ones if needed.
+    #[must_use]
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries;
+        self.evict();
+        self
+    }
+
+    /// Set the maximum size of the cached responses, in bytes of JSON,
+    /// evicting the least recently used ones if needed.
+    #[must_use]
+    pub fn with_max_size(mut self, max_size: usize) -> Self {
+        self.max_size = max_size;
+        self.evict();
+        self
+    }
+
+    /// Return the key of `request` sent with `client`.
+    fn key(client: &crate::ServerClient, request: &CheckRequest) -> String {
+        let request = serde_json::to_string(request).unwrap_or_default();
+        let bytes = client
+            .api
+            .bytes()
+            .chain([0])
+            .chain(client.max_suggestions.to_le_bytes())
+            .chain(request.bytes());
+        format!("{:016x}", fnv1a(bytes))
+    }
+
+    /// Return the cached response to `request` sent with `client`, if any.
+    pub fn get(
+        &mut self,
+        client: &crate::ServerClient,
+        request: &CheckRequest,
+    ) -> Option<CheckResponse> {
+        self.clock += 1;
+        match self.entries.get_mut(&Self::key(client, request)) {
+            Some(entry) => {
+                self.stats.hits += 1;
+                entry.last_used = self.clock;
+                Some(entry.response.clone())
+            },
+            None => {
+                self.stats.misses += 1;
+                None
+            },
+        }
+    }
+
+    /// Cache the `response` to `request` sent with `client`, checked from
+    /// `file`, if any, see [`Cache::invalidate_file`].
+    ///
+    /// Responses with incomplete results are not cached.
+    pub fn insert(
+        &mut self,
+        client: &crate::ServerClient,
+        request: &CheckRequest,
+        file: Option<&Path>,
+        response: &CheckResponse,
+    ) {
+        if response
+            .warnings
+            .as_ref()
+            .is_some_and(|warnings| warnings.incomplete_results)
+        {
+            return;
+        }
+
+        self.clock += 1;
+        let entry = Entry {
+            file: file.map(Path::to_path_buf),
+            last_used: self.clock,
+            size: serde_json::to_string(response).map_or(0, |json| json.len()),
+            response: response.clone(),
+        };
+        self.entries.insert(Self::key(client, request), entry);
+        self.evict();
+    }
+
+    /// Remove the responses checked from `file`, and return how many were
+    /// removed.
+    ///
+    /// This should be called when `file` is modified, e.g., by applying
+    /// replacements, as its responses are unlikely to be used again.
+    pub fn invalidate_file<P: AsRef<Path>>(&mut self, file: P) -> usize {
+        let file = file.as_ref();
+        let len = self.entries.len();
+        self.entries
+            .retain(|_, entry| entry.file.as_deref() != Some(file));
+        len - self.entries.len()
+    }
+
+    /// Remove the responses checked from `file` that do not answer any of
+    /// `requests`, sent with `client`, and return how many were removed.
+    ///
+    /// This should be called after checking the fragments of `file`, see
+    /// [`CheckRequest::try_split_stable`], so that only the responses to its
+    /// latest fragments are kept.
+    pub fn retain_file_requests<P: AsRef<Path>>(
+        &mut self,
+        client: &crate::ServerClient,
+        file: P,
+        requests: &[CheckRequest],
+    ) -> usize {
+        let file = file.as_ref();
+        let keys: Vec<String> = requests
+            .iter()
+            .map(|request| Self::key(client, request))
+            .collect();
+        let len = self.entries.len();
+        self.entries
+            .retain(|key, entry| entry.file.as_deref() != Some(file) || keys.contains(key));
+        len - self.entries.len()
+    }
+
+    /// Remove all the responses, keeping the statistics.
+    pub fn invalidate_all(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Return the statistics of this cache.
+    #[must_use]
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            entries: self.entries.len(),
+            size: self.size(),
+            ..self.stats
+        }
+    }
+
+    /// Return the total size of the cached responses.
+    fn size(&self) -> usize {
+        self.entries.values().map(|entry| entry.size).sum()
+    }
+
+    /// Evict the least recently used entries until the cache is within its
+    /// limits.
+    fn evict(&mut self) {
+        let mut size = self.size();
+
+        while self.entries.len() > self.max_entries || size > self.max_size {
+            let Some(key) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            if let Some(entry) = self.entries.remove(&key) {
+                size -= entry.size;
+                self.stats.evictions += 1;
+            }
+        }
+    }
+}
+
+/// Cache's subcommand.
+#[cfg(feature = "cli")]
+#[derive(Clone, Debug, Subcommand)]
+pub enum CacheSubcommand {
+    /// Print the path and the statistics of the cache.
+    Stats,
+    /// Remove all the cached responses.
+    Clear,
+}
+
+/// Manage the cache of check responses, see `ltrs check --cache`.
+#[cfg(feature = "cli")]
+#[derive(Debug, Parser)]
+pub struct CacheCommand {
+    /// Subcommand.
+    #[command(subcommand)]
+    pub subcommand: CacheSubcommand,
+}
+
+#[cfg(feature = "cli")]
+impl CacheCommand {
+    /// Execute the command, using the cache file at `path`.
+    pub fn execute<W: Write>(&self, path: &Path, stdout: &mut W) -> Result<()> {
+        let mut cache = Cache::from_path(path)?;
+
+        match self.subcommand {
+            CacheSubcommand::Stats => {
+                writeln!(stdout, "# {}", path.display())?;
+                writeln!(stdout, "{}", cache.stats())?;
+            },
+            CacheSubcommand::Clear => {
+                let entries = cache.stats().entries;
+                cache.invalidate_all();
+                cache.save(path)?;
+                writeln!(stdout, "Removed {entries} cached response(s)")?;
+            },
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{check::test_utils::response_with_matches, ServerClient};
+
+    fn request(text: &str) -> CheckRequest {
+        CheckRequest::default().with_text(text.to_string())
+    }
+
+    #[test]
+    fn test_get_and_insert() {
+        let client = ServerClient::default();
+        let mut cache = Cache::default();
+        let response = response_with_matches("Some smal text.", &[(5, 4, &["small"])]);
+
+        assert!(cache.get(&client, &request("Some smal text.")).is_none());
+        cache.insert(&client, &request("Some smal text."), None, &response);
+        assert_eq!(
+            cache.get(&client, &request("Some smal text.")),
+            Some(response)
+        );
+        assert!(cache.get(&client, &request("Other text.")).is_none());
+
+        let other_client = ServerClient::default().with_max_suggestions(1);
+        assert!(cache
+            .get(&other_client, &request("Some smal text."))
+            .is_none());
+
+        let stats = cache.stats();
+        assert_eq!((stats.entries, stats.hits, stats.misses), (1, 1, 3));
+        assert_eq!(stats.hit_rate(), Some(0.25));
+    }
+
+    #[test]
+    fn test_eviction() {
+        let client = ServerClient::default();
+        let mut cache = Cache::default().with_max_entries(2);
+        let response = response_with_matches("Text.", &[]);
+
+        cache.insert(&client, &request("a"), None, &response);
+        cache.insert(&client, &request("b"), None, &response);
+        // Use "a", so that "b" is the least recently used
+        assert!(cache.get(&client, &request("a")).is_some());
+        cache.insert(&client, &request("c"), None, &response);
+
+        assert!(cache.get(&client, &request("a")).is_some());
+        assert!(cache.get(&client, &request("b")).is_none());
+        assert_eq!(cache.stats().evictions, 1);
+
+        let cache = cache.with_max_size(0);
+        assert_eq!(cache.stats().entries, 0);
+    }
+
+    #[test]
+    fn test_invalidate() {
+        let client = ServerClient::default();
+        let mut cache = Cache::default();
+        let response = response_with_matches("Text.", &[]);
+
+        cache.insert(&client, &request("a"), Some(Path::new("a.md")), &response);
+        cache.insert(&client, &request("b"), Some(Path::new("b.md")), &response);
+        cache.insert(&client, &request("c"), None, &response);
+
+        assert_eq!(cache.invalidate_file("a.md"), 1);
+        assert_eq!(cache.invalidate_file("a.md"), 0);
+        assert_eq!(cache.stats().entries, 2);
+        cache.invalidate_all();
+        assert_eq!(cache.stats().entries, 0);
+    }
+
+    #[test]
+    fn test_retain_file_requests() {
+        let client = ServerClient::default();
+        let mut cache = Cache::default();
+        let response = response_with_matches("Text.", &[]);
+        let file = Path::new("a.md");
+
+        cache.insert(&client, &request("a"), Some(file), &response);
+        cache.insert(&client, &request("b"), Some(file), &response);
+        cache.insert(&client, &request("c"), None, &response);
+
+        assert_eq!(
+            cache.retain_file_requests(&client, file, &[request("b"), request("d")]),
+            1
+        );
+        assert!(cache.get(&client, &request("a")).is_none());
+        assert!(cache.get(&client, &request("b")).is_some());
+        assert!(cache.get(&client, &request("c")).is_some());
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let client = ServerClient::default();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cache").join("responses.json");
+        let response = response_with_matches("Text.", &[]);
+
+        assert_eq!(Cache::from_path(&path).unwrap().stats().entries, 0);
+
+        let mut cache = Cache::default();
+        cache.insert(&client, &request("a"), None, &response);
+        cache.save(&path).unwrap();
+
+        let mut cache = Cache::from_path(&path).unwrap();
+        assert_eq!(cache.get(&client, &request("a")), Some(response));
+    }
+}
@@ -1,6 +1,10 @@
 //! Structures for `check` requests and responses.
 
-use super::error::{Error, Result};
+use super::{
+    error::{Error, Result},
+    experimental::{self, ExperimentalFields, ExperimentalResponse},
+    languages::LanguageCode,
+};
 #[cfg(feature = "annotate")]
 use annotate_snippets::{
     display_list::{DisplayList, FormatOptions},
@@ -8,9 +12,9 @@ use annotate_snippets::{
 };
 #[cfg(feature = "cli")]
 use clap::{Args, Parser, ValueEnum};
-use serde::{Deserialize, Serialize, Serializer};
-#[cfg(feature = "cli")]
-use std::path::PathBuf;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
+use std::path::{Path, PathBuf};
 
 /// Requests
 
@@ -21,7 +25,8 @@ use std::path::PathBuf;
 /// - a five character string matching pattern `[a-z]{2}-[A-Z]{2}
 /// - or some more complex ascii string (see below)
 ///
-/// Language code is case insensitive.
+/// Language code is case insensitive, and it is normalized, see
+/// [`LanguageCode`].
 ///
 /// Therefore, a valid language code must match the following:
 ///
@@ -54,40 +59,9 @@ use std::path::PathBuf;
 /// assert!(parse_language_code("some random text").is_err());
 /// ```
 #[cfg(feature = "cli")]
-pub fn parse_language_code(v: &str) -> Result<String> {
-    #[inline]
-    fn is_match(v: &str) -> bool {
-        let mut splits = v.split('-');
-
-        match splits.next() {
-            Some(s)
-                if (s.len() == 2 || s.len() == 3) && s.chars().all(|c| c.is_ascii_alphabetic()) => {
-            },
-            _ => return false,
-        }
-
-        match splits.next() {
-            Some(s) if s.len() != 2 || s.chars().any(|c| !c.is_ascii_alphabetic()) => return false,
-            Some(_) => (),
-            None => return true,
-        }
-        for s in splits {
-            if !s.chars().all(|c| c.is_ascii_alphabetic()) {
-                return false;
-            }
-        }
-        true
-    }
-
-    if v == "auto" || is_match(v) {
-        Ok(v.to_string())
-    } else {
-        Err(Error::InvalidValue(
-            "The value should be `\"auto\"` or match regex pattern: \
-             ^[a-zA-Z]{2,3}(-[a-zA-Z]{2}(-[a-zA-Z]+)*)?$"
-                .to_string(),
-        ))
-    }
+#[allow(clippy::empty_line_after_doc_comments)]
+pub fn parse_language_code(v: &str) -> Result<LanguageCode> {
+    v.parse()
 }
 
 /// Utility function to serialize a optional vector a strings
@@ -95,24 +69,25 @@ pub fn parse_language_code(v: &str) -> Result<String> {
 ///
 /// This is required by reqwest's RequestBuilder, otherwise it
 /// will not work.
-pub(crate) fn serialize_option_vec_string<S>(
-    v: &Option<Vec<String>>,
+pub(crate) fn serialize_option_vec_string<S, T>(
+    v: &Option<Vec<T>>,
     serializer: S,
 ) -> std::result::Result<S::Ok, S::Error>
 where
     S: Serializer,
+    T: AsRef<str>,
 {
     match v {
-        Some(v) if v.len() == 1 => serializer.serialize_str(&v[0]),
+        Some(v) if v.len() == 1 => serializer.serialize_str(v[0].as_ref()),
         Some(v) if v.len() > 1 => {
-            let size = v.iter().map(|s| s.len()).sum::<usize>() + v.len() - 1;
+            let size = v.iter().map(|s| s.as_ref().len()).sum::<usize>() + v.len() - 1;
             let mut string = String::with_capacity(size);
 
-            string.push_str(&v[0]);
+            string.push_str(v[0].as_ref());
 
             for s in &v[1..] {
                 string.push(',');
-                string.push_str(s);
+                string.push_str(s.as_ref());
             }
 
             serializer.serialize_str(string.as_ref())
@@ -121,6 +96,38 @@ where
     }
 }
 
+/// Utility function to deserialize an optional vector of strings, from a comma
+/// separated list of strings, as serialized by [`serialize_option_vec_string`],
+/// or from a sequence.
+///
+/// Each string is parsed into `T`.
+pub(crate) fn deserialize_option_vec_string<'de, D, T>(
+    deserializer: D,
+) -> std::result::Result<Option<Vec<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StringOrVec {
+        String(String),
+        Vec(Vec<String>),
+    }
+
+    let strings = match Option::<StringOrVec>::deserialize(deserializer)? {
+        Some(StringOrVec::String(s)) => s.split(',').map(ToString::to_string).collect(),
+        Some(StringOrVec::Vec(v)) => v,
+        None => return Ok(None),
+    };
+    strings
+        .iter()
+        .map(|s| s.parse().map_err(serde::de::Error::custom))
+        .collect::<std::result::Result<_, _>>()
+        .map(Some)
+}
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize, Hash)]
 #[non_exhaustive]
 #[serde(rename_all = "camelCase")]
@@ -180,6 +187,40 @@ impl DataAnnotation {
             text: None,
         }
     }
+
+    /// Instantiate a new `DataAnnotation` with markup interpreted as a
+    /// paragraph break, i.e., `"\n\n"`, e.g., for a `<p>` tag.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use languagetool_rust::check::DataAnnotation;
+    /// let da = DataAnnotation::markup_as_paragraph("<p>".to_string());
+    ///
+    /// assert_eq!(da.interpret_as.as_deref(), Some("\n\n"));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn markup_as_paragraph(markup: String) -> Self {
+        Self::new_interpreted_markup(markup, PARAGRAPH_BREAK.to_string())
+    }
+
+    /// Instantiate a new `DataAnnotation` with markup interpreted as a space,
+    /// e.g., for a non-breaking space such as `&nbsp;` or `~`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use languagetool_rust::check::DataAnnotation;
+    /// let da = DataAnnotation::markup_as_space("&nbsp;".to_string());
+    ///
+    /// assert_eq!(da.interpret_as.as_deref(), Some(" "));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn markup_as_space(markup: String) -> Self {
+        Self::new_interpreted_markup(markup, " ".to_string())
+    }
 }
 
 #[cfg(test)]
@@ -205,6 +246,148 @@ mod data_annotation_tests {
         assert!(da.interpret_as.is_none());
     }
 
+    #[test]
+    fn test_data_from_inline_math() {
+        use crate::check::Data;
+
+        let data = Data::from_inline_math("$$a$$ costs \\$5, $b$ and $c", "X");
+
+        assert_eq!(
+            data.annotation,
+            vec![
+                DataAnnotation::new_interpreted_markup("$$a$$".to_string(), "X".to_string()),
+                DataAnnotation::new_text(" costs \\$5, ".to_string()),
+                DataAnnotation::new_interpreted_markup("$b$".to_string(), "X".to_string()),
+                DataAnnotation::new_text(" and $c".to_string()),
+            ]
+        );
+
+        // An escaped backslash does not escape the dollar that follows
+        let data = Data::from_inline_math(r"Costs \\$x$ or $5\\$.", "X");
+
+        assert_eq!(
+            data.annotation,
+            vec![
+                DataAnnotation::new_text(r"Costs \\".to_string()),
+                DataAnnotation::new_interpreted_markup("$x$".to_string(), "X".to_string()),
+                DataAnnotation::new_text(" or ".to_string()),
+                DataAnnotation::new_interpreted_markup(r"$5\\$".to_string(), "X".to_string()),
+                DataAnnotation::new_text(".".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_data_from_inline_math_fixtures() {
+        use crate::{check::Data, parsers::test_utils::source_of};
+
+        /// Render text as is, and math as `[markup => interpretation]`.
+        fn snapshot(data: &Data) -> String {
+            data.annotation
+                .iter()
+                .map(|da| {
+                    match (&da.text, &da.markup, &da.interpret_as) {
+                        (Some(text), None, None) => text.clone(),
+                        (None, Some(markup), Some(interpret_as)) => {
+                            format!("[{markup} => {interpret_as}]")
+                        },
+                        _ => panic!("unexpected annotation {da:?}"),
+                    }
+                })
+                .collect()
+        }
+
+        let fixtures = [
+            // Display math
+            ("$$a$$", "[$$a$$ => X]"),
+            ("$$a + b$$ is big.", "[$$a + b$$ => X] is big."),
+            ("Both $$x$$ and $y$.", "Both [$$x$$ => X] and [$y$ => X]."),
+            ("$$a$ b$$ c", "[$$a$ b$$ => X] c"),
+            ("$$$$", "[$$$$ => X]"),
+            // Escaped dollars
+            (r"Costs \$5 and \$6.", r"Costs \$5 and \$6."),
+            (r"$a \$ b$ c", r"[$a \$ b$ => X] c"),
+            (r"\$$x$", r"\$[$x$ => X]"),
+            (r"\$\$x\$\$", r"\$\$x\$\$"),
+            (r"$$a \$$ b$$", r"[$$a \$$ b$$ => X]"),
+            // Unbalanced delimiters
+            ("", ""),
+            ("$", "$"),
+            ("$$", "$$"),
+            ("Costs $5.", "Costs $5."),
+            ("$a$ and $b", "[$a$ => X] and $b"),
+            ("$a$$", "[$a$ => X]$"),
+            ("$$a$", "$$a$"),
+            ("$$a$$ and $$b", "[$$a$$ => X] and $$b"),
+        ];
+
+        for (text, expected) in fixtures {
+            let data = Data::from_inline_math(text, "X");
+            assert_eq!(snapshot(&data), expected, "for {text:?}");
+            assert_eq!(source_of(&data), text, "for {text:?}");
+        }
+    }
+
+    #[test]
+    fn test_find_unescaped() {
+        use super::find_unescaped;
+
+        assert_eq!(find_unescaped("a$b", "$"), Some(1));
+        assert_eq!(find_unescaped(r"a\$b$", "$"), Some(4));
+        assert_eq!(find_unescaped(r"a\\$b", "$"), Some(3));
+        assert_eq!(find_unescaped(r"a\\\$b$", "$"), Some(6));
+        assert_eq!(find_unescaped(r"\\\)", r"\)"), Some(2));
+        assert_eq!(find_unescaped(r"\$", "$"), None);
+    }
+
+    #[test]
+    fn test_data_builder() {
+        use crate::check::DataBuilder;
+
+        let mut builder = DataBuilder::default();
+        builder.push_text("a");
+        builder.push_text("b");
+        builder.push_markup("");
+        builder.push_markup("<");
+        builder.push_markup(">");
+        builder.push_interpreted_markup("&", "and");
+        builder.push_markup("</");
+        builder.push_interpreted_markup("", "");
+        builder.push_paragraph_markup("");
+        builder
+            .push(DataAnnotation::markup_as_space("~".to_string()))
+            .unwrap();
+        builder.push(DataAnnotation::new_text("c".to_string())).unwrap();
+        builder.push(DataAnnotation::new_text("d".to_string())).unwrap();
+
+        assert_eq!(
+            builder.build().annotation,
+            vec![
+                DataAnnotation::new_text("ab".to_string()),
+                DataAnnotation::new_markup("<>".to_string()),
+                DataAnnotation::new_interpreted_markup("&".to_string(), "and".to_string()),
+                DataAnnotation::new_markup("</".to_string()),
+                DataAnnotation::markup_as_paragraph(String::new()),
+                DataAnnotation::markup_as_space("~".to_string()),
+                DataAnnotation::new_text("cd".to_string()),
+            ]
+        );
+
+        let mut builder = DataBuilder::default();
+        let interpret_as_only = DataAnnotation {
+            interpret_as: Some("\n\n".to_string()),
+            markup: None,
+            text: None,
+        };
+        assert!(builder.push(interpret_as_only).is_err());
+        let text_and_markup = DataAnnotation {
+            interpret_as: None,
+            markup: Some("<p>".to_string()),
+            text: Some("a".to_string()),
+        };
+        assert!(builder.push(text_and_markup).is_err());
+    }
+
     #[test]
     fn test_interpreted_markup() {
         let da =
@@ -216,8 +399,15 @@ mod data_annotation_tests {
     }
 }
 
+/// Text that markup is interpreted as to separate paragraphs, see
+/// [`DataAnnotation::markup_as_paragraph`].
+pub const PARAGRAPH_BREAK: &str = "\n\n";
+
 /// Alternative text to be checked.
-#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Hash)]
+///
+/// It is serialized as a map, and deserialized from either a map or a JSON
+/// string, as sent to the API by [`CheckRequest::to_form_parts`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Hash)]
 #[non_exhaustive]
 pub struct Data {
     /// Vector of markup text, see [`DataAnnotation`].
@@ -231,15 +421,279 @@ impl<T: Into<DataAnnotation>> FromIterator<T> for Data {
     }
 }
 
-impl Serialize for Data {
-    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+/// Return the byte index of the next `delim` in `s` that is not escaped, i.e.,
+/// not preceded by an odd number of backslashes.
+pub(crate) fn find_unescaped(s: &str, delim: &str) -> Option<usize> {
+    let mut start = 0;
+    while let Some(i) = s[start..].find(delim) {
+        let i = start + i;
+        let backslashes = s[..i].bytes().rev().take_while(|&b| b == b'\\').count();
+        if backslashes % 2 == 0 {
+            return Some(i);
+        }
+        start = i + 1;
+    }
+    None
+}
+
+impl Data {
+    /// Build data from a text where inline math, delimited by `$` (or `$$`) as
+    /// in Markdown or Typst, is marked up and interpreted as `placeholder`.
+    ///
+    /// Interpreting math as a noun, e.g., `"X"`, keeps agreement checks working
+    /// around formulas. Escaped dollars (`\$`) and unterminated math are kept
+    /// as text.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use languagetool_rust::check::{Data, DataAnnotation};
+    /// let data = Data::from_inline_math("Let $x+1$ be positive.", "X");
+    ///
+    /// assert_eq!(
+    ///     data.annotation,
+    ///     vec![
+    ///         DataAnnotation::new_text("Let ".to_string()),
+    ///         DataAnnotation::new_interpreted_markup("$x+1$".to_string(), "X".to_string()),
+    ///         DataAnnotation::new_text(" be positive.".to_string()),
+    ///     ]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn from_inline_math(text: &str, placeholder: &str) -> Self {
+        let mut annotation = Vec::new();
+        let mut rest = text;
+
+        while let Some(start) = find_unescaped(rest, "$") {
+            let delim = if rest[start..].starts_with("$$") {
+                "$$"
+            } else {
+                "$"
+            };
+            let inner = start + delim.len();
+            let Some(end) = find_unescaped(&rest[inner..], delim).map(|i| inner + i) else {
+                break;
+            };
+            let end = end + delim.len();
+
+            if start > 0 {
+                annotation.push(DataAnnotation::new_text(rest[..start].to_string()));
+            }
+            annotation.push(DataAnnotation::new_interpreted_markup(
+                rest[start..end].to_string(),
+                placeholder.to_string(),
+            ));
+            rest = &rest[end..];
+        }
+
+        if !rest.is_empty() {
+            annotation.push(DataAnnotation::new_text(rest.to_string()));
+        }
+
+        Self { annotation }
+    }
+}
+
+/// Incrementally build [`Data`], merging consecutive text and consecutive
+/// (non-interpreted) markup annotations, as done by the
+/// [`parsers`](crate::parsers).
+///
+/// Annotations are only ever made of either text or markup, and markup may
+/// be interpreted as some text, as required by LanguageTool.
+///
+/// # Examples
+///
+/// ```
+/// # use languagetool_rust::check::{DataAnnotation, DataBuilder};
+/// let mut builder = DataBuilder::default();
+/// builder.push_markup("<p>");
+/// builder.push_text("A ");
+/// builder.push_text("first");
+/// builder.push_space_markup("&nbsp;");
+/// builder.push_text("paragraph.");
+/// builder.push_paragraph_markup("</p>\n<p>");
+///
+/// assert_eq!(
+///     builder.build().annotation,
+///     vec![
+///         DataAnnotation::new_markup("<p>".to_string()),
+///         DataAnnotation::new_text("A first".to_string()),
+///         DataAnnotation::markup_as_space("&nbsp;".to_string()),
+///         DataAnnotation::new_text("paragraph.".to_string()),
+///         DataAnnotation::markup_as_paragraph("</p>\n<p>".to_string()),
+///     ]
+/// );
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct DataBuilder {
+    annotation: Vec<DataAnnotation>,
+}
+
+impl DataBuilder {
+    /// Append text, ignoring empty strings.
+    pub fn push_text(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        match self.annotation.last_mut() {
+            Some(DataAnnotation {
+                text: Some(last), ..
+            }) => last.push_str(text),
+            _ => {
+                self.annotation
+                    .push(DataAnnotation::new_text(text.to_string()))
+            },
+        }
+    }
+
+    /// Append markup, ignoring empty strings.
+    pub fn push_markup(&mut self, markup: &str) {
+        if markup.is_empty() {
+            return;
+        }
+        match self.annotation.last_mut() {
+            Some(DataAnnotation {
+                markup: Some(last),
+                interpret_as: None,
+                ..
+            }) => last.push_str(markup),
+            _ => {
+                self.annotation
+                    .push(DataAnnotation::new_markup(markup.to_string()))
+            },
+        }
+    }
+
+    /// Append markup that is interpreted as `interpret_as`.
+    ///
+    /// Empty markup is ignored if `interpret_as` is empty too, and is kept
+    /// otherwise, as it still stands for some text, e.g., a paragraph break.
+    pub fn push_interpreted_markup(&mut self, markup: &str, interpret_as: &str) {
+        if interpret_as.is_empty() {
+            self.push_markup(markup);
+            return;
+        }
+        self.annotation.push(DataAnnotation::new_interpreted_markup(
+            markup.to_string(),
+            interpret_as.to_string(),
+        ));
+    }
+
+    /// Append markup that is interpreted as a paragraph break, see
+    /// [`DataAnnotation::markup_as_paragraph`].
+    pub fn push_paragraph_markup(&mut self, markup: &str) {
+        self.push_interpreted_markup(markup, PARAGRAPH_BREAK);
+    }
+
+    /// Append markup that is interpreted as a space, see
+    /// [`DataAnnotation::markup_as_space`].
+    pub fn push_space_markup(&mut self, markup: &str) {
+        self.push_interpreted_markup(markup, " ");
+    }
+
+    /// Append text from a double-quoted string, where escape sequences, such
+    /// as `\n` or `\u00e9`, are interpreted as the character they stand for.
+    pub fn push_escaped_text(&mut self, text: &str) {
+        let mut rest = text;
+
+        while let Some(i) = rest.find('\\') {
+            self.push_text(&rest[..i]);
+            let escape = &rest[i..];
+            let (len, interpret_as) = match escape[1..].chars().next() {
+                Some('n') => (2, Some("\n".to_string())),
+                Some('t') => (2, Some("\t".to_string())),
+                Some(c @ ('"' | '\\' | '/' | '\'')) => (2, Some(c.to_string())),
+                Some(c @ ('u' | 'U')) => {
+                    let digits = if c == 'u' { 4 } else { 8 };
+                    let decoded = escape
+                        .get(2..2 + digits)
+                        .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                        .and_then(char::from_u32);
+                    match decoded {
+                        Some(decoded) => (2 + digits, Some(decoded.to_string())),
+                        None => (2, None),
+                    }
+                },
+                // Line continuation, or unknown escape sequence
+                Some(c) => (1 + c.len_utf8(), None),
+                None => (1, None),
+            };
+
+            match interpret_as {
+                Some(interpret_as) => self.push_interpreted_markup(&escape[..len], &interpret_as),
+                None => self.push_markup(&escape[..len]),
+            }
+            rest = &escape[len..];
+        }
+
+        self.push_text(rest);
+    }
+
+    /// Append an annotation, merging it with the last one if both are text,
+    /// or both are non-interpreted markup.
+    ///
+    /// # Errors
+    ///
+    /// If the annotation has both text and markup, neither of them, or is
+    /// interpreted as some text without markup.
+    pub fn push(&mut self, annotation: DataAnnotation) -> Result<()> {
+        match annotation {
+            DataAnnotation {
+                text: Some(text),
+                markup: None,
+                interpret_as: None,
+            } => self.push_text(&text),
+            DataAnnotation {
+                text: None,
+                markup: Some(markup),
+                interpret_as,
+            } => self.push_interpreted_markup(&markup, interpret_as.as_deref().unwrap_or("")),
+            _ => {
+                return Err(Error::InvalidDataAnnotation(format!(
+                    "expected either text or (interpreted) markup in {annotation:?}"
+                )))
+            },
+        }
+        Ok(())
+    }
+
+    /// Return the built data.
+    #[must_use]
+    pub fn build(self) -> Data {
+        Data {
+            annotation: self.annotation,
+        }
+    }
+}
+
+impl From<DataBuilder> for Data {
+    fn from(builder: DataBuilder) -> Self {
+        builder.build()
+    }
+}
+
+impl<'de> Deserialize<'de> for Data {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
     where
-        S: serde::Serializer,
+        D: Deserializer<'de>,
     {
-        let mut map = std::collections::HashMap::new();
-        map.insert("annotation", &self.annotation);
+        #[derive(Deserialize)]
+        struct Map {
+            annotation: Vec<DataAnnotation>,
+        }
 
-        serializer.serialize_str(&serde_json::to_string(&map).unwrap())
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum StringOrMap {
+            String(String),
+            Map(Map),
+        }
+
+        let Map { annotation } = match StringOrMap::deserialize(deserializer)? {
+            StringOrMap::String(s) => serde_json::from_str(&s).map_err(serde::de::Error::custom)?,
+            StringOrMap::Map(map) => map,
+        };
+        Ok(Self { annotation })
     }
 }
 
@@ -253,6 +707,37 @@ impl std::str::FromStr for Data {
     }
 }
 
+/// Unit symbols recognized by [`is_unit_expression`].
+const UNITS: &[&str] = &[
+    "%", "°", "°C", "°F", "A", "B", "GB", "GHz", "Hz", "J", "K", "MB", "MHz", "MeV", "Pa", "TB",
+    "V", "W", "bar", "cm", "eV", "g", "h", "kB", "kHz", "kPa", "keV", "kW", "kg", "km", "L", "m",
+    "mA", "mL", "mV", "mg", "min", "mm", "mol", "ms", "nm", "ns", "s", "µm", "µs", "μm", "μs",
+];
+
+/// Return `true` if `s` is a number followed by a unit symbol, e.g., `"5 µm"`
+/// or `"3.2GHz"`.
+///
+/// # Examples
+///
+/// ```
+/// # use languagetool_rust::check::is_unit_expression;
+/// assert!(is_unit_expression("5 µm"));
+/// assert!(is_unit_expression("3.2GHz"));
+/// assert!(!is_unit_expression("5 apples"));
+/// assert!(!is_unit_expression("µm"));
+/// ```
+#[must_use]
+pub fn is_unit_expression(s: &str) -> bool {
+    let s = s.trim();
+    let unit_start = s
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == ','))
+        .unwrap_or(s.len());
+    let (number, unit) = s.split_at(unit_start);
+    let unit = unit.trim_start_matches([' ', '\u{a0}', '\u{202f}']);
+
+    number.starts_with(|c: char| c.is_ascii_digit()) && UNITS.contains(&unit)
+}
+
 /// Possible levels for additional rules.
 ///
 /// Currently, `Level::Picky` adds additional rules
@@ -376,126 +861,590 @@ pub fn split_len<'source>(s: &'source str, n: usize, pat: &str) -> Vec<&'source
     vec
 }
 
-/// LanguageTool POST check request.
+/// Return the ranges (`[start, end]`, in characters, `end` excluded) of the
+/// sentences of `text`, like the [`CheckResponse::sentence_ranges`] returned
+/// by some servers, but guessed locally.
 ///
-/// The main feature - check a text with LanguageTool for possible style and
-/// grammar issues.
+/// A sentence ends with `.`, `!`, `?` or `…`, possibly followed by closing
+/// quotes or brackets, when the next one, after some whitespace, does not
+/// start with a lowercase letter, e.g., not after "e.g.". Paragraphs, i.e.,
+/// blank lines, always end sentences.
 ///
-/// The structure below tries to follow as closely as possible the JSON API
-/// described [here](https://languagetool.org/http-api/swagger-ui/#!/default/post_check).
-#[cfg_attr(feature = "cli", derive(Args))]
-#[derive(Clone, Deserialize, Debug, PartialEq, Eq, Serialize, Hash)]
-#[serde(rename_all = "camelCase")]
-#[non_exhaustive]
-pub struct CheckRequest {
-    /// The text to be checked. This or 'data' is required.
-    #[cfg_attr(
-        feature = "cli",
-        clap(short = 't', long, conflicts_with = "data", allow_hyphen_values(true))
-    )]
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub text: Option<String>,
-    /// The text to be checked, given as a JSON document that specifies what's
-    /// text and what's markup. This or 'text' is required.
-    ///
-    /// Markup will be ignored when looking for errors. Example text:
-    /// ```html
-    /// A <b>test</b>
-    /// ```
-    /// JSON for the example text:
-    /// ```json
-    /// {"annotation":[
-    ///  {"text": "A "},
-    ///  {"markup": "<b>"},
-    ///  {"text": "test"},
-    ///  {"markup": "</b>"}
-    /// ]}
-    /// ```
-    /// If you have markup that should be interpreted as whitespace, like `<p>`
-    /// in HTML, you can have it interpreted like this:
-    ///
-    /// ```json
-    /// {"markup": "<p>", "interpretAs": "\n\n"}
-    /// ```
-    /// The 'data' feature is not limited to HTML or XML, it can be used for any
-    /// kind of markup. Entities will need to be expanded in this input.
-    #[cfg_attr(feature = "cli", clap(short = 'd', long, conflicts_with = "text"))]
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub data: Option<Data>,
-    /// A language code like `en-US`, `de-DE`, `fr`, or `auto` to guess the
-    /// language automatically (see `preferredVariants` below).
-    ///
-    /// For languages with variants (English, German, Portuguese) spell checking
-    /// will only be activated when you specify the variant, e.g. `en-GB`
-    /// instead of just `en`.
-    #[cfg_attr(
-        all(feature = "cli", feature = "cli", feature = "cli"),
-        clap(
-            short = 'l',
-            long,
-            default_value = "auto",
-            value_parser = parse_language_code
-        )
-    )]
-    pub language: String,
-    /// Set to get Premium API access: Your username/email as used to log in at
-    /// languagetool.org.
-    #[cfg_attr(
-        feature = "cli",
-        clap(short = 'u', long, requires = "api_key", env = "LANGUAGETOOL_USERNAME")
-    )]
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub username: Option<String>,
-    /// Set to get Premium API access: [your API
-    /// key](https://languagetool.org/editor/settings/api).
-    #[cfg_attr(
-        feature = "cli",
-        clap(short = 'k', long, requires = "username", env = "LANGUAGETOOL_API_KEY")
-    )]
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub api_key: Option<String>,
-    /// Comma-separated list of dictionaries to include words from; uses special
-    /// default dictionary if this is unset.
-    #[cfg_attr(feature = "cli", clap(long))]
-    #[serde(serialize_with = "serialize_option_vec_string")]
-    pub dicts: Option<Vec<String>>,
-    /// A language code of the user's native language, enabling false friends
-    /// checks for some language pairs.
-    #[cfg_attr(feature = "cli", clap(long))]
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub mother_tongue: Option<String>,
-    /// Comma-separated list of preferred language variants.
-    ///
-    /// The language detector used with `language=auto` can detect e.g. English,
-    /// but it cannot decide whether British English or American English is
-    /// used. Thus this parameter can be used to specify the preferred variants
-    /// like `en-GB` and `de-AT`. Only available with `language=auto`. You
-    /// should set variants for at least German and English, as otherwise the
+/// # Examples
+///
+/// ```
+/// # use languagetool_rust::check::sentence_ranges;
+/// let text = "A first one, e.g. this. \"Another?\" Yes!\n\nList item";
+///
+/// assert_eq!(
+///     sentence_ranges(text),
+///     vec![[0, 23], [24, 34], [35, 39], [41, 50]]
+/// );
+/// ```
+#[must_use]
+pub fn sentence_ranges(text: &str) -> Vec<[usize; 2]> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let is_terminator = matches!(chars[i], '.' | '!' | '?' | '…');
+        let mut end = i + 1;
+        if is_terminator {
+            while end < chars.len() && matches!(chars[end], '"' | '\'' | ')' | ']' | '»' | '”' | '’')
+            {
+                end += 1;
+            }
+        }
+        let mut next = end;
+        let mut newlines = 0;
+        while next < chars.len() && chars[next].is_whitespace() {
+            if chars[next] == '\n' {
+                newlines += 1;
+            }
+            next += 1;
+        }
+
+        let is_boundary = next < chars.len()
+            && next > end
+            && (newlines >= 2 || (is_terminator && !chars[next].is_lowercase()));
+        if is_boundary {
+            ranges.push([start, end]);
+            start = next;
+            i = next;
+        } else {
+            i += 1;
+        }
+    }
+
+    let end = chars.len() - chars.iter().rev().take_while(|c| c.is_whitespace()).count();
+    if start < end {
+        ranges.push([start, end]);
+    }
+    ranges
+}
+
+/// Split a string into as few fragments as possible, where each fragment
+/// contains (if possible) a maximum of `n` characters, like [`split_len`],
+/// but only where one of the sentence `ranges` starts, see
+/// [`sentence_ranges`].
+///
+/// Whitespace between sentences ends the previous fragment.
+///
+/// # Examples
+///
+/// ```
+/// # use languagetool_rust::check::{sentence_ranges, split_sentences};
+/// let s = "I have so many friends. They are very funny. I think I am very lucky.";
+///
+/// let split = split_sentences(s, 50, &sentence_ranges(s));
+///
+/// assert_eq!(
+///     split,
+///     vec![
+///         "I have so many friends. They are very funny. ",
+///         "I think I am very lucky."
+///     ]
+/// );
+/// ```
+#[must_use]
+pub fn split_sentences<'source>(
+    s: &'source str,
+    n: usize,
+    ranges: &[[usize; 2]],
+) -> Vec<&'source str> {
+    let mut starts: Vec<usize> = ranges.iter().map(|[start, _]| *start).collect();
+    starts.sort_unstable();
+    let mut starts = starts.into_iter().filter(|start| *start > 0).peekable();
+
+    // Byte index of each sentence start, and of the end of `s`
+    let mut boundaries = Vec::new();
+    for (i, (byte, _)) in s.char_indices().enumerate() {
+        while starts.next_if(|start| *start <= i).is_some() {
+            if boundaries.last() != Some(&byte) {
+                boundaries.push(byte);
+            }
+        }
+    }
+    boundaries.push(s.len());
+
+    let mut vec: Vec<&'source str> = Vec::new();
+    let mut start = 0;
+    let mut end = 0;
+    for boundary in boundaries {
+        if end > start && boundary - start >= n {
+            vec.push(&s[start..end]);
+            start = end;
+        }
+        end = boundary;
+    }
+    if end > start {
+        vec.push(&s[start..end]);
+    }
+    vec
+}
+
+/// Maximum number of sentences, from the end of the previous fragment, that
+/// [`CheckRequest::try_split`] prepends to each data fragment, as context.
+pub const SPLIT_CONTEXT_SENTENCES: usize = 2;
+
+/// Return the length, in characters, of the text checked for an annotation,
+/// i.e., of its text or of what its markup is interpreted as.
+fn checked_len(da: &DataAnnotation) -> usize {
+    da.text
+        .as_deref()
+        .or(da.interpret_as.as_deref())
+        .map_or(0, |s| s.chars().count())
+}
+
+/// Split data into pieces, ending after each annotation whose text or markup
+/// contains `pat`, with their checked length, see [`checked_len`].
+fn data_pieces(data: &Data, pat: &str) -> Vec<(Vec<DataAnnotation>, usize)> {
+    let mut pieces: Vec<(Vec<DataAnnotation>, usize)> = Vec::new();
+    let mut piece = Vec::new();
+    let mut piece_len = 0;
+
+    for da in data.annotation.iter() {
+        let s = da
+            .text
+            .as_deref()
+            .or(da.markup.as_deref())
+            .unwrap_or_default();
+        piece_len += checked_len(da);
+        piece.push(da.clone());
+        if !pat.is_empty() && s.contains(pat) {
+            pieces.push((std::mem::take(&mut piece), std::mem::take(&mut piece_len)));
+        }
+    }
+    if !piece.is_empty() {
+        pieces.push((piece, piece_len));
+    }
+    pieces
+}
+
+/// Return the last (at most [`SPLIT_CONTEXT_SENTENCES`]) sentences of `text`,
+/// with the whitespace that follows them, that hold in `n` characters.
+fn context_sentences(text: &str, n: usize) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let start = sentence_ranges(text)
+        .iter()
+        .rev()
+        .take(SPLIT_CONTEXT_SENTENCES)
+        .map(|[start, _]| *start)
+        .take_while(|start| chars.len() - start <= n)
+        .last()
+        .unwrap_or(chars.len());
+    chars[start..].iter().collect()
+}
+
+/// Split data into fragments, in the same way [`split_len`] splits text, but
+/// without ever splitting an annotation.
+///
+/// Fragments end after an annotation whose text or markup contains `pat`, and
+/// their checked text, see [`checked_len`], holds in `n` characters, unless
+/// a single annotation is longer. Each fragment starts with the last sentences
+/// of the previous one, see [`context_sentences`], as text, whose length is
+/// returned with the fragment.
+fn split_data(data: &Data, n: usize, pat: &str) -> Vec<(Data, usize)> {
+    let mut fragments: Vec<(Vec<DataAnnotation>, usize)> = Vec::new();
+    let mut push = |piece: Vec<DataAnnotation>, len: usize| {
+        match fragments.last_mut() {
+            Some((fragment, fragment_len)) if *fragment_len + len <= n => {
+                fragment.extend(piece);
+                *fragment_len += len;
+            },
+            _ => fragments.push((piece, len)),
+        }
+    };
+    for (piece, len) in data_pieces(data, pat) {
+        if len <= n {
+            push(piece, len);
+        } else {
+            for da in piece {
+                let len = checked_len(&da);
+                push(vec![da], len);
+            }
+        }
+    }
+
+    let mut previous = String::new();
+    fragments
+        .into_iter()
+        .map(|(mut annotation, len)| {
+            let context = context_sentences(&previous, n.saturating_sub(len));
+            previous = annotation
+                .iter()
+                .filter_map(|da| da.text.as_deref().or(da.interpret_as.as_deref()))
+                .collect();
+            let context_length = context.chars().count();
+            if context_length > 0 {
+                annotation.insert(0, DataAnnotation::new_text(context));
+            }
+            (Data { annotation }, context_length)
+        })
+        .collect()
+}
+
+/// Average number of pieces per fragment, for fragments shorter than the
+/// maximum length, see [`CheckRequest::try_split_stable`].
+const STABLE_SPLIT_RATIO: u64 = 4;
+
+/// Group pieces, given by their length and the hash of their content, into
+/// fragments, and return the number of pieces of each fragment.
+///
+/// Like with [`split_len`], a piece is appended to the current fragment if it
+/// stays shorter than `n`, but fragments also end after each piece whose hash
+/// is a multiple of [`STABLE_SPLIT_RATIO`]. As these boundaries only depend on
+/// the content of the pieces, editing a piece only changes the fragments up to
+/// the next boundary.
+fn stable_fragment_sizes(pieces: impl IntoIterator<Item = (usize, u64)>, n: usize) -> Vec<usize> {
+    let mut sizes: Vec<usize> = Vec::new();
+    let mut len = 0;
+    let mut boundary = true;
+
+    for (piece_len, hash) in pieces {
+        match sizes.last_mut() {
+            Some(size) if !boundary && len + piece_len < n => {
+                *size += 1;
+                len += piece_len;
+            },
+            _ => {
+                sizes.push(1);
+                len = piece_len;
+            },
+        }
+        boundary = hash % STABLE_SPLIT_RATIO == 0;
+    }
+    sizes
+}
+
+/// Possible ways of handling control characters, other than tabulations and
+/// line breaks, found in a text to be checked.
+///
+/// Such characters, e.g., form feeds or vertical tabs, may be rejected by the
+/// server or break the alignment of annotations.
+#[derive(Clone, Copy, Default, Deserialize, Debug, PartialEq, Eq, Serialize, Hash)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum ControlChars {
+    /// Keep control characters as is.
+    #[default]
+    Keep,
+    /// Replace each control character with a space.
+    Replace,
+    /// Remove control characters.
+    Strip,
+}
+
+#[inline]
+fn is_unsupported_control(c: char) -> bool {
+    c.is_control() && !matches!(c, '\t' | '\n' | '\r')
+}
+
+/// Return the line number (starting at 1), line offset (starting at 0) and
+/// value of each control character, other than tabulations and line breaks,
+/// found in `text`.
+///
+/// # Examples
+///
+/// ```
+/// # use languagetool_rust::check::find_control_chars;
+/// let text = "Some text\nwith a\x0cform feed.";
+///
+/// assert_eq!(find_control_chars(text), vec![(2, 6, '\x0c')]);
+/// ```
+#[must_use]
+pub fn find_control_chars(text: &str) -> Vec<(usize, usize, char)> {
+    let mut found = Vec::new();
+    let mut line_number = 1;
+    let mut line_offset = 0;
+
+    for c in text.chars() {
+        if is_unsupported_control(c) {
+            found.push((line_number, line_offset, c));
+        }
+        if c == '\n' {
+            line_number += 1;
+            line_offset = 0;
+        } else {
+            line_offset += 1;
+        }
+    }
+
+    found
+}
+
+/// Text whose control characters were handled according to [`ControlChars`],
+/// and that can map offsets back to the original text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SanitizedText {
+    /// Text to be checked.
+    pub text: String,
+    /// Char indices, in the original text, of removed characters.
+    removed: Vec<usize>,
+}
+
+impl SanitizedText {
+    /// Handle control characters in `text`, see [`ControlChars`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use languagetool_rust::check::{ControlChars, SanitizedText};
+    /// let text = "A\x0bvertical tab.";
+    ///
+    /// assert_eq!(SanitizedText::new(text, ControlChars::Keep).text, text);
+    /// assert_eq!(
+    ///     SanitizedText::new(text, ControlChars::Replace).text,
+    ///     "A vertical tab."
+    /// );
+    ///
+    /// let sanitized = SanitizedText::new(text, ControlChars::Strip);
+    ///
+    /// assert_eq!(sanitized.text, "Avertical tab.");
+    /// assert_eq!(sanitized.original_offset(1), 2);
+    /// ```
+    #[must_use]
+    pub fn new(text: &str, control_chars: ControlChars) -> Self {
+        match control_chars {
+            ControlChars::Keep => {
+                Self {
+                    text: text.to_string(),
+                    removed: Vec::new(),
+                }
+            },
+            ControlChars::Replace => {
+                Self {
+                    text: text
+                        .chars()
+                        .map(|c| if is_unsupported_control(c) { ' ' } else { c })
+                        .collect(),
+                    removed: Vec::new(),
+                }
+            },
+            ControlChars::Strip => {
+                let mut removed = Vec::new();
+                let text = text
+                    .chars()
+                    .enumerate()
+                    .filter_map(|(i, c)| {
+                        if is_unsupported_control(c) {
+                            removed.push(i);
+                            None
+                        } else {
+                            Some(c)
+                        }
+                    })
+                    .collect();
+                Self { text, removed }
+            },
+        }
+    }
+
+    /// Map a char offset in the sanitized text to the corresponding offset in
+    /// the original text.
+    #[must_use]
+    pub fn original_offset(&self, offset: usize) -> usize {
+        let mut original = offset;
+        for &i in &self.removed {
+            if i <= original {
+                original += 1;
+            } else {
+                break;
+            }
+        }
+        original
+    }
+
+    /// Map the offsets and lengths of a response's matches, obtained by
+    /// checking the sanitized text, back to the original text.
+    pub fn restore_offsets(&self, response: &mut CheckResponse) {
+        if self.removed.is_empty() {
+            return;
+        }
+        for m in response.iter_matches_mut() {
+            let start = self.original_offset(m.offset);
+            let end = match m.length {
+                0 => start,
+                length => self.original_offset(m.offset + length - 1) + 1,
+            };
+            m.offset = start;
+            m.length = end - start;
+        }
+    }
+}
+
+/// LanguageTool POST check request.
+///
+/// The main feature - check a text with LanguageTool for possible style and
+/// grammar issues.
+///
+/// The structure below tries to follow as closely as possible the JSON API
+/// described [here](https://languagetool.org/http-api/swagger-ui/#!/default/post_check).
+#[cfg_attr(feature = "cli", derive(Args))]
+#[derive(Clone, Deserialize, Debug, PartialEq, Eq, Serialize, Hash)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct CheckRequest {
+    /// The text to be checked. This or 'data' is required.
+    #[cfg_attr(
+        feature = "cli",
+        clap(short = 't', long, conflicts_with = "data", allow_hyphen_values(true))
+    )]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    /// The text to be checked, given as a JSON document that specifies what's
+    /// text and what's markup. This or 'text' is required.
+    ///
+    /// Markup will be ignored when looking for errors. Example text:
+    /// ```html
+    /// A <b>test</b>
+    /// ```
+    /// JSON for the example text:
+    /// ```json
+    /// {"annotation":[
+    ///  {"text": "A "},
+    ///  {"markup": "<b>"},
+    ///  {"text": "test"},
+    ///  {"markup": "</b>"}
+    /// ]}
+    /// ```
+    /// If you have markup that should be interpreted as whitespace, like `<p>`
+    /// in HTML, you can have it interpreted like this:
+    ///
+    /// ```json
+    /// {"markup": "<p>", "interpretAs": "\n\n"}
+    /// ```
+    /// The 'data' feature is not limited to HTML or XML, it can be used for any
+    /// kind of markup. Entities will need to be expanded in this input.
+    #[cfg_attr(feature = "cli", clap(short = 'd', long, conflicts_with = "text"))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Data>,
+    /// A language code like `en-US`, `de-DE`, `fr`, or `auto` to guess the
+    /// language automatically (see `preferredVariants` below).
+    ///
+    /// For languages with variants (English, German, Portuguese) spell checking
+    /// will only be activated when you specify the variant, e.g. `en-GB`
+    /// instead of just `en`.
+    #[cfg_attr(
+        all(feature = "cli", feature = "cli", feature = "cli"),
+        clap(
+            short = 'l',
+            long,
+            default_value = "auto",
+            value_parser = parse_language_code
+        )
+    )]
+    #[serde(default)]
+    pub language: LanguageCode,
+    /// Set to get Premium API access: Your username/email as used to log in at
+    /// languagetool.org.
+    #[cfg_attr(
+        feature = "cli",
+        clap(short = 'u', long, requires = "api_key", env = "LANGUAGETOOL_USERNAME")
+    )]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    /// Set to get Premium API access: [your API
+    /// key](https://languagetool.org/editor/settings/api).
+    #[cfg_attr(
+        feature = "cli",
+        clap(short = 'k', long, requires = "username", env = "LANGUAGETOOL_API_KEY")
+    )]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    /// Comma-separated (or repeated) list of dictionaries to include words
+    /// from; uses special default dictionary if this is unset.
+    #[cfg_attr(
+        feature = "cli",
+        clap(long, value_delimiter = ',', action = clap::ArgAction::Append)
+    )]
+    #[serde(
+        default,
+        serialize_with = "serialize_option_vec_string",
+        deserialize_with = "deserialize_option_vec_string"
+    )]
+    pub dicts: Option<Vec<String>>,
+    /// A language code of the user's native language, enabling false friends
+    /// checks for some language pairs.
+    #[cfg_attr(feature = "cli", clap(long, value_parser = parse_language_code))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mother_tongue: Option<LanguageCode>,
+    /// Comma-separated (or repeated) list of preferred language variants.
+    ///
+    /// The language detector used with `language=auto` can detect e.g. English,
+    /// but it cannot decide whether British English or American English is
+    /// used. Thus this parameter can be used to specify the preferred variants
+    /// like `en-GB` and `de-AT`. Only available with `language=auto`. You
+    /// should set variants for at least German and English, as otherwise the
     /// spell checking will not work for those, as no spelling dictionary can be
     /// selected for just `en` or `de`.
-    #[cfg_attr(feature = "cli", clap(long, conflicts_with = "language"))]
-    #[serde(serialize_with = "serialize_option_vec_string")]
-    pub preferred_variants: Option<Vec<String>>,
-    /// IDs of rules to be enabled, comma-separated.
-    #[cfg_attr(feature = "cli", clap(long))]
-    #[serde(serialize_with = "serialize_option_vec_string")]
+    #[cfg_attr(
+        feature = "cli",
+        clap(
+            long,
+            value_delimiter = ',',
+            action = clap::ArgAction::Append,
+            conflicts_with = "language",
+            value_parser = parse_language_code
+        )
+    )]
+    #[serde(
+        default,
+        serialize_with = "serialize_option_vec_string",
+        deserialize_with = "deserialize_option_vec_string"
+    )]
+    pub preferred_variants: Option<Vec<LanguageCode>>,
+    /// IDs of rules to be enabled, comma-separated or repeated.
+    #[cfg_attr(
+        feature = "cli",
+        clap(long, value_delimiter = ',', action = clap::ArgAction::Append)
+    )]
+    #[serde(
+        default,
+        serialize_with = "serialize_option_vec_string",
+        deserialize_with = "deserialize_option_vec_string"
+    )]
     pub enabled_rules: Option<Vec<String>>,
-    /// IDs of rules to be disabled, comma-separated.
-    #[cfg_attr(feature = "cli", clap(long))]
-    #[serde(serialize_with = "serialize_option_vec_string")]
+    /// IDs of rules to be disabled, comma-separated or repeated.
+    #[cfg_attr(
+        feature = "cli",
+        clap(long, value_delimiter = ',', action = clap::ArgAction::Append)
+    )]
+    #[serde(
+        default,
+        serialize_with = "serialize_option_vec_string",
+        deserialize_with = "deserialize_option_vec_string"
+    )]
     pub disabled_rules: Option<Vec<String>>,
-    /// IDs of categories to be enabled, comma-separated.
-    #[cfg_attr(feature = "cli", clap(long))]
-    #[serde(serialize_with = "serialize_option_vec_string")]
+    /// IDs of categories to be enabled, comma-separated or repeated.
+    #[cfg_attr(
+        feature = "cli",
+        clap(long, value_delimiter = ',', action = clap::ArgAction::Append)
+    )]
+    #[serde(
+        default,
+        serialize_with = "serialize_option_vec_string",
+        deserialize_with = "deserialize_option_vec_string"
+    )]
     pub enabled_categories: Option<Vec<String>>,
-    /// IDs of categories to be disabled, comma-separated.
-    #[cfg_attr(feature = "cli", clap(long))]
-    #[serde(serialize_with = "serialize_option_vec_string")]
+    /// IDs of categories to be disabled, comma-separated or repeated.
+    #[cfg_attr(
+        feature = "cli",
+        clap(long, value_delimiter = ',', action = clap::ArgAction::Append)
+    )]
+    #[serde(
+        default,
+        serialize_with = "serialize_option_vec_string",
+        deserialize_with = "deserialize_option_vec_string"
+    )]
     pub disabled_categories: Option<Vec<String>>,
     /// If true, only the rules and categories whose IDs are specified with
     /// `enabledRules` or `enabledCategories` are enabled.
     #[cfg_attr(feature = "cli", clap(long))]
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(default, skip_serializing_if = "is_false")]
     pub enabled_only: bool,
     /// If set to `picky`, additional rules will be activated, i.e. rules that
     /// you might only find useful when checking formal text.
@@ -503,8 +1452,18 @@ pub struct CheckRequest {
         feature = "cli",
         clap(long, default_value = "default", ignore_case = true, value_enum)
     )]
-    #[serde(skip_serializing_if = "Level::is_default")]
+    #[serde(default, skip_serializing_if = "Level::is_default")]
     pub level: Level,
+    /// Extra fields passed through as is, e.g., to enable features of newer
+    /// servers that are not supported yet, see [`crate::experimental`].
+    #[cfg_attr(feature = "cli", clap(skip))]
+    #[serde(flatten)]
+    pub experimental_fields: ExperimentalFields,
+    /// Number of characters, at the start of the data, prepended as context
+    /// by [`CheckRequest::try_split`].
+    #[cfg_attr(feature = "cli", clap(skip))]
+    #[serde(skip)]
+    context_length: usize,
 }
 
 impl Default for CheckRequest {
@@ -513,7 +1472,7 @@ impl Default for CheckRequest {
         CheckRequest {
             text: Default::default(),
             data: Default::default(),
-            language: "auto".to_string(),
+            language: LanguageCode::AUTO,
             username: Default::default(),
             api_key: Default::default(),
             dicts: Default::default(),
@@ -525,10 +1484,16 @@ impl Default for CheckRequest {
             disabled_categories: Default::default(),
             enabled_only: Default::default(),
             level: Default::default(),
+            experimental_fields: Default::default(),
+            context_length: 0,
         }
     }
 }
 
+/// ID of the category of spelling rules, e.g., `MORFOLOGIK_RULE_EN_US`, see
+/// [`CheckRequest::spelling_only`].
+pub const SPELLING_CATEGORY: &str = "TYPOS";
+
 #[inline]
 fn is_false(b: &bool) -> bool {
     !(*b)
@@ -540,6 +1505,7 @@ impl CheckRequest {
     pub fn with_text(mut self, text: String) -> Self {
         self.text = Some(text);
         self.data = None;
+        self.context_length = 0;
         self
     }
 
@@ -548,6 +1514,7 @@ impl CheckRequest {
     pub fn with_data(mut self, data: Data) -> Self {
         self.data = Some(data);
         self.text = None;
+        self.context_length = 0;
         self
     }
 
@@ -559,11 +1526,357 @@ impl CheckRequest {
 
     /// Set the language of the text / data.
     #[must_use]
-    pub fn with_language(mut self, language: String) -> Self {
+    pub fn with_language(mut self, language: LanguageCode) -> Self {
         self.language = language;
         self
     }
 
+    /// Set the language code of the user's native language, enabling false
+    /// friends checks for some language pairs.
+    #[must_use]
+    pub fn with_mother_tongue(mut self, mother_tongue: LanguageCode) -> Self {
+        self.mother_tongue = Some(mother_tongue);
+        self
+    }
+
+    /// Set the preferred variants used to pick a variant of the language
+    /// detected with `language=auto`, e.g., `en-GB` and `de-AT`.
+    ///
+    /// They are only sent with `language=auto`, see
+    /// [`CheckRequest::to_form_parts`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use languagetool_rust::{check::CheckRequest, languages::LanguageCode};
+    /// let request = CheckRequest::default()
+    ///     .with_text("Colour".to_string())
+    ///     .with_preferred_variants([LanguageCode::EN_GB, LanguageCode::PT_BR]);
+    ///
+    /// assert!(request
+    ///     .to_form_parts()
+    ///     .contains(&("preferredVariants", "en-GB,pt-BR".to_string())));
+    /// ```
+    #[must_use]
+    pub fn with_preferred_variants<I>(mut self, variants: I) -> Self
+    where
+        I: IntoIterator<Item = LanguageCode>,
+    {
+        self.preferred_variants = Some(variants.into_iter().collect());
+        self
+    }
+
+    /// Set the username and API key used for Premium API access.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use languagetool_rust::check::CheckRequest;
+    /// let request = CheckRequest::default().with_login("me@example.com", "secret");
+    ///
+    /// assert_eq!(request.username.as_deref(), Some("me@example.com"));
+    /// assert_eq!(request.api_key.as_deref(), Some("secret"));
+    /// ```
+    #[must_use]
+    pub fn with_login<U: Into<String>, K: Into<String>>(mut self, username: U, api_key: K) -> Self {
+        self.username = Some(username.into());
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    /// Set the personal dictionaries to take additional words from, which
+    /// requires Premium API access.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use languagetool_rust::check::CheckRequest;
+    /// let request = CheckRequest::default().with_dicts(["work", "names"]);
+    ///
+    /// assert_eq!(
+    ///     request.dicts,
+    ///     Some(vec!["work".to_string(), "names".to_string()])
+    /// );
+    /// ```
+    #[must_use]
+    pub fn with_dicts<I>(mut self, dicts: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.dicts = Some(dicts.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Set the IDs of the rules to be enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use languagetool_rust::check::CheckRequest;
+    /// let request = CheckRequest::default()
+    ///     .with_enabled_rules(["EN_QUOTES"])
+    ///     .with_enabled_only(true);
+    ///
+    /// assert_eq!(request.enabled_rules, Some(vec!["EN_QUOTES".to_string()]));
+    /// assert!(request.enabled_only);
+    /// ```
+    #[must_use]
+    pub fn with_enabled_rules<I>(mut self, rules: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.enabled_rules = Some(rules.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Set the IDs of the rules to be disabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use languagetool_rust::check::CheckRequest;
+    /// let request = CheckRequest::default().with_disabled_rules(["WHITESPACE_RULE"]);
+    ///
+    /// assert_eq!(
+    ///     request.disabled_rules,
+    ///     Some(vec!["WHITESPACE_RULE".to_string()])
+    /// );
+    /// ```
+    #[must_use]
+    pub fn with_disabled_rules<I>(mut self, rules: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.disabled_rules = Some(rules.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Set the IDs of the categories to be enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use languagetool_rust::check::CheckRequest;
+    /// let request = CheckRequest::default().with_enabled_categories(["STYLE"]);
+    ///
+    /// assert_eq!(request.enabled_categories, Some(vec!["STYLE".to_string()]));
+    /// ```
+    #[must_use]
+    pub fn with_enabled_categories<I>(mut self, categories: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.enabled_categories = Some(categories.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Set the IDs of the categories to be disabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use languagetool_rust::check::CheckRequest;
+    /// let request = CheckRequest::default().with_disabled_categories(["TYPOGRAPHY", "STYLE"]);
+    ///
+    /// assert_eq!(
+    ///     request.disabled_categories,
+    ///     Some(vec!["TYPOGRAPHY".to_string(), "STYLE".to_string()])
+    /// );
+    /// ```
+    #[must_use]
+    pub fn with_disabled_categories<I>(mut self, categories: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.disabled_categories = Some(categories.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Set whether only the rules and categories set with
+    /// [`CheckRequest::with_enabled_rules`] and
+    /// [`CheckRequest::with_enabled_categories`] are enabled.
+    #[must_use]
+    pub fn with_enabled_only(mut self, enabled_only: bool) -> Self {
+        self.enabled_only = enabled_only;
+        self
+    }
+
+    /// Set the level of rules, e.g., [`Level::Picky`] to activate additional
+    /// rules, useful when checking formal text.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use languagetool_rust::check::{CheckRequest, Level};
+    /// let request = CheckRequest::default()
+    ///     .with_text("Some text.".to_string())
+    ///     .with_level(Level::Picky);
+    ///
+    /// assert!(request
+    ///     .to_form_parts()
+    ///     .contains(&("level", "picky".to_string())));
+    /// ```
+    #[must_use]
+    pub fn with_level(mut self, level: Level) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Add an extra field passed through as is, see [`crate::experimental`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use languagetool_rust::check::CheckRequest;
+    /// let request = CheckRequest::default()
+    ///     .with_text("Some text.".to_string())
+    ///     .with_experimental_field("mode", "textLevelOnly");
+    ///
+    /// assert!(request
+    ///     .to_form_parts()
+    ///     .contains(&("mode", "textLevelOnly".to_string())));
+    /// ```
+    #[must_use]
+    pub fn with_experimental_field<K: Into<String>, V: Into<String>>(
+        mut self,
+        name: K,
+        value: V,
+    ) -> Self {
+        self.experimental_fields.insert(name.into(), value.into());
+        self
+    }
+
+    /// Only enable the spelling rules, i.e., those of [`SPELLING_CATEGORY`],
+    /// which is much faster on long texts.
+    ///
+    /// Other enabled and disabled rules and categories are cleared, as the
+    /// server rejects disabled ones together with `enabledOnly`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use languagetool_rust::check::CheckRequest;
+    /// let request = CheckRequest::default().spelling_only();
+    ///
+    /// assert!(request.enabled_only);
+    /// assert_eq!(request.enabled_categories, Some(vec!["TYPOS".to_string()]));
+    /// ```
+    #[must_use]
+    pub fn spelling_only(mut self) -> Self {
+        self.enabled_rules = None;
+        self.disabled_rules = None;
+        self.enabled_categories = Some(vec![SPELLING_CATEGORY.to_string()]);
+        self.disabled_categories = None;
+        self.enabled_only = true;
+        self
+    }
+
+    /// Return the fields of this request as `(name, value)` pairs, in the
+    /// encoding expected by the API, to be sent as a form body.
+    ///
+    /// Unset fields are omitted, lists are comma-separated, and `data` is
+    /// encoded as a JSON string. Preferred variants are only sent with
+    /// `language=auto`, as the server rejects them otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use languagetool_rust::check::CheckRequest;
+    /// let mut request = CheckRequest::default().with_text("Some text.".to_string());
+    /// request.disabled_rules = Some(vec!["A".to_string(), "B".to_string()]);
+    ///
+    /// assert_eq!(
+    ///     request.to_form_parts(),
+    ///     vec![
+    ///         ("text", "Some text.".to_string()),
+    ///         ("language", "auto".to_string()),
+    ///         ("disabledRules", "A,B".to_string()),
+    ///     ]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn to_form_parts(&self) -> Vec<(&str, String)> {
+        fn list<T: AsRef<str>>(v: &Option<Vec<T>>) -> Option<String> {
+            v.as_ref().filter(|v| !v.is_empty()).map(|v| {
+                v.iter()
+                    .map(AsRef::as_ref)
+                    .collect::<Vec<&str>>()
+                    .join(",")
+            })
+        }
+
+        let data = self.data.as_ref().map(|data| {
+            serde_json::to_string(data).expect("data annotations are always serializable")
+        });
+        let level = (!self.level.is_default()).then(|| {
+            match self.level {
+                Level::Default => "default",
+                Level::Picky => "picky",
+            }
+            .to_string()
+        });
+
+        [
+            ("text", self.text.clone()),
+            ("data", data),
+            ("language", Some(self.language.to_string())),
+            ("username", self.username.clone()),
+            ("apiKey", self.api_key.clone()),
+            ("dicts", list(&self.dicts)),
+            ("motherTongue", self.mother_tongue.as_ref().map(ToString::to_string)),
+            (
+                "preferredVariants",
+                list(&self.preferred_variants).filter(|_| self.language.is_auto()),
+            ),
+            ("enabledRules", list(&self.enabled_rules)),
+            ("disabledRules", list(&self.disabled_rules)),
+            ("enabledCategories", list(&self.enabled_categories)),
+            ("disabledCategories", list(&self.disabled_categories)),
+            ("enabledOnly", self.enabled_only.then(|| "true".to_string())),
+            ("level", level),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| value.map(|value| (name, value)))
+        .chain(
+            self.experimental_fields
+                .iter()
+                .map(|(name, value)| (name.as_str(), value.clone())),
+        )
+        .collect()
+    }
+
+    /// Return a copy of this request that is safe to be written in reports,
+    /// i.e., without credentials (username and API key), and without the text
+    /// or data, that can be read again from the checked file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use languagetool_rust::check::CheckRequest;
+    /// let mut request = CheckRequest::default().with_text("Some text.".to_string());
+    /// request.api_key = Some("secret".to_string());
+    ///
+    /// let sanitized = request.sanitized();
+    /// assert_eq!(sanitized.api_key, None);
+    /// assert_eq!(sanitized.text, None);
+    /// assert_eq!(sanitized.language, request.language);
+    /// ```
+    #[must_use]
+    pub fn sanitized(&self) -> Self {
+        Self {
+            text: None,
+            data: None,
+            username: None,
+            api_key: None,
+            ..self.clone()
+        }
+    }
+
     /// Return a copy of the text within the request.
     ///
     /// # Errors
@@ -609,10 +1922,30 @@ impl CheckRequest {
     /// Split this request into multiple, using [`split_len`] function to split
     /// text.
     ///
+    /// Data annotations are never split: fragments end after an annotation
+    /// containing `pat`, and their checked text, i.e., their text and what
+    /// their markup is interpreted as, holds in `n` characters, unless a single
+    /// annotation is longer. Each data fragment also starts with (at most
+    /// [`SPLIT_CONTEXT_SENTENCES`]) sentences of the previous one, as text, so
+    /// that rules spanning sentences do not report false positives at its
+    /// start. [`CheckResponseWithContext::from_fragment`] removes this context
+    /// from the response to a fragment, with the matches that start in it.
+    ///
     /// # Errors
     ///
-    /// If `self.text` is none.
+    /// If both `self.text` and `self.data` are none.
     pub fn try_split(&self, n: usize, pat: &str) -> Result<Vec<Self>> {
+        if let (None, Some(data)) = (&self.text, &self.data) {
+            return Ok(split_data(data, n, pat)
+                .into_iter()
+                .map(|(data_fragment, context_length)| {
+                    let mut request = self.clone().with_data(data_fragment);
+                    request.context_length = context_length;
+                    request
+                })
+                .collect());
+        }
+
         let text = self
             .text
             .as_ref()
@@ -624,30 +1957,260 @@ impl CheckRequest {
             .collect())
     }
 
+    /// Split this request into multiple, like [`CheckRequest::try_split`], but
+    /// so that fragment boundaries only depend on the content of nearby
+    /// pieces of text (e.g., paragraphs), and not on the length of everything
+    /// before them.
+    ///
+    /// Editing a piece then only changes its fragment, and maybe a few
+    /// following ones, so that the responses to the other fragments can be
+    /// reused from a [`Cache`](crate::cache::Cache). Fragments are shorter, on
+    /// average, than with [`CheckRequest::try_split`].
+    ///
+    /// # Errors
+    ///
+    /// If both `self.text` and `self.data` are none.
+    pub fn try_split_stable(&self, n: usize, pat: &str) -> Result<Vec<Self>> {
+        if let (None, Some(data)) = (&self.text, &self.data) {
+            let pieces = data_pieces(data, pat);
+            let sizes = stable_fragment_sizes(
+                pieces.iter().map(|(annotation, len)| {
+                    let bytes = annotation
+                        .iter()
+                        .flat_map(|da| da.text.iter().chain(&da.markup))
+                        .flat_map(|s| s.bytes());
+                    (*len, fnv1a(bytes))
+                }),
+                n,
+            );
+            let mut pieces = pieces.into_iter();
+
+            return Ok(sizes
+                .into_iter()
+                .map(|size| {
+                    let annotation = pieces
+                        .by_ref()
+                        .take(size)
+                        .flat_map(|(annotation, _)| annotation)
+                        .collect();
+                    self.clone().with_data(Data { annotation })
+                })
+                .collect());
+        }
+
+        let text = self
+            .text
+            .as_ref()
+            .ok_or(Error::InvalidRequest("missing text field".to_string()))?;
+        let pieces: Vec<&str> = text.split_inclusive(pat).collect();
+        let sizes = stable_fragment_sizes(
+            pieces
+                .iter()
+                .map(|piece| (piece.len(), fnv1a(piece.bytes()))),
+            n,
+        );
+        let mut pieces = pieces.into_iter();
+        let mut start = 0;
+
+        Ok(sizes
+            .into_iter()
+            .map(|size| {
+                let len: usize = pieces.by_ref().take(size).map(str::len).sum();
+                let fragment = &text[start..start + len];
+                start += len;
+                self.clone().with_text(fragment.to_string())
+            })
+            .collect())
+    }
+
+    /// Split this request into multiple, like [`CheckRequest::try_split`], but
+    /// on sentence boundaries guessed with [`sentence_ranges`], so that
+    /// fragment edges never cut a sentence, e.g., a long paragraph, which
+    /// could produce false positives.
+    ///
+    /// Data annotations are split like [`CheckRequest::try_split`] does, on
+    /// paragraphs.
+    ///
+    /// # Errors
+    ///
+    /// If both `self.text` and `self.data` are none.
+    pub fn try_split_sentences(&self, n: usize) -> Result<Vec<Self>> {
+        match &self.text {
+            Some(text) => self.try_split_ranges(n, &sentence_ranges(text)),
+            None => self.try_split(n, "\n\n"),
+        }
+    }
+
+    /// Split this request's text into multiple, like
+    /// [`CheckRequest::try_split_sentences`], but on the given sentence
+    /// `ranges`, e.g., the [`CheckResponse::sentence_ranges`] of a response to
+    /// a previous version of this text.
+    ///
+    /// # Errors
+    ///
+    /// If `self.text` is none.
+    pub fn try_split_ranges(&self, n: usize, ranges: &[[usize; 2]]) -> Result<Vec<Self>> {
+        let text = self
+            .text
+            .as_ref()
+            .ok_or(Error::InvalidRequest("missing text field".to_string()))?;
+
+        Ok(split_sentences(text.as_str(), n, ranges)
+            .iter()
+            .map(|text_fragment| self.clone().with_text(text_fragment.to_string()))
+            .collect())
+    }
+
     /// Split this request into multiple, using [`split_len`] function to split
     /// text.
     /// Call [`CheckRequest::try_split`] but panic on error.
     ///
-    /// # Panics
+    /// # Panics
+    ///
+    /// If `self.text` is none.
+    #[must_use]
+    pub fn split(&self, n: usize, pat: &str) -> Vec<Self> {
+        self.try_split(n, pat).unwrap()
+    }
+}
+
+/// Parse a string slice into a [`PathBuf`], and error if the file or
+/// directory does not exist.
+#[cfg(feature = "cli")]
+fn parse_filename(s: &str) -> Result<PathBuf> {
+    let path_buf: PathBuf = s.parse().unwrap();
+
+    if path_buf.is_file() || path_buf.is_dir() {
+        Ok(path_buf)
+    } else {
+        Err(Error::InvalidFilename(s.to_string()))
+    }
+}
+
+/// Reference point used to select files that changed since then.
+#[cfg(feature = "cli")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChangedSince {
+    /// Files modified after a given time.
+    Timestamp(std::time::SystemTime),
+    /// Files that differ from a given git revision, or that are not tracked.
+    GitRef(String),
+}
+
+#[cfg(feature = "cli")]
+impl std::str::FromStr for ChangedSince {
+    type Err = Error;
+
+    /// Parse a number of seconds since the UNIX epoch, or a git revision
+    /// otherwise.
+    fn from_str(s: &str) -> Result<Self> {
+        if let Ok(secs) = s.parse::<u64>() {
+            Ok(Self::Timestamp(
+                std::time::UNIX_EPOCH + std::time::Duration::from_secs(secs),
+            ))
+        } else if s.is_empty() || s.starts_with('-') {
+            Err(Error::InvalidValue(
+                "The value should be a UNIX timestamp or a git revision".to_string(),
+            ))
+        } else {
+            Ok(Self::GitRef(s.to_string()))
+        }
+    }
+}
+
+#[cfg(feature = "cli")]
+impl ChangedSince {
+    /// Return the files of `filenames` that changed since `self`, in the
+    /// same order.
+    ///
+    /// A git revision is resolved, and compared with the working tree, only
+    /// once for all files.
     ///
-    /// If `self.text` is none.
-    #[must_use]
-    pub fn split(&self, n: usize, pat: &str) -> Vec<Self> {
-        self.try_split(n, pat).unwrap()
+    /// # Errors
+    ///
+    /// If a file's metadata cannot be read, if git is not available, or if
+    /// the revision cannot be resolved, e.g., outside of a work tree.
+    pub fn filter_changed(&self, filenames: Vec<PathBuf>) -> Result<Vec<PathBuf>> {
+        match self {
+            Self::Timestamp(time) => {
+                let mut changed = Vec::with_capacity(filenames.len());
+                for filename in filenames {
+                    if std::fs::metadata(&filename)?.modified()? > *time {
+                        changed.push(filename);
+                    }
+                }
+                Ok(changed)
+            },
+            Self::GitRef(rev) => {
+                let changed = crate::git::ChangedFiles::from_git(rev)?;
+                Ok(filenames
+                    .into_iter()
+                    .filter(|filename| changed.contains(filename))
+                    .collect())
+            },
+        }
     }
 }
 
-/// Parse a string slice into a [`PathBuf`], and error if the file does not
-/// exist.
 #[cfg(feature = "cli")]
-fn parse_filename(s: &str) -> Result<PathBuf> {
-    let path_buf: PathBuf = s.parse().unwrap();
+fn parse_percentage(s: &str) -> Result<f64> {
+    s.strip_suffix('%')
+        .unwrap_or(s)
+        .parse::<f64>()
+        .ok()
+        .filter(|percentage| *percentage > 0.0 && *percentage <= 100.0)
+        .ok_or_else(|| {
+            Error::InvalidValue(
+                "The value should be a percentage greater than 0 and at most 100, e.g., `5%`"
+                    .to_string(),
+            )
+        })
+}
 
-    if path_buf.is_file() {
-        Ok(path_buf)
-    } else {
-        Err(Error::InvalidFilename(s.to_string()))
-    }
+/// Parse an experimental request field, given as `NAME=VALUE`.
+#[cfg(feature = "cli")]
+fn parse_experimental_field(s: &str) -> Result<(String, String)> {
+    s.split_once('=')
+        .filter(|(name, _)| !name.is_empty())
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .ok_or_else(|| {
+            Error::InvalidValue("The value should be a field given as `NAME=VALUE`".to_string())
+        })
+}
+
+/// Read and import the rules of a LanguageTool `grammar.xml` file.
+#[cfg(all(feature = "cli", feature = "rules"))]
+fn parse_rules(s: &str) -> Result<crate::rules::RuleSet> {
+    crate::rules::RuleSet::from_grammar_xml(&std::fs::read_to_string(s)?)
+}
+
+/// Read a list of known words.
+#[cfg(feature = "cli")]
+fn parse_known_words(s: &str) -> Result<crate::ignore::KnownWords> {
+    crate::ignore::KnownWords::from_path(s)
+}
+
+/// Select `count` files out of `filenames`, in their original order.
+///
+/// The selection only depends on `seed` and on the file names, so the same
+/// seed always gives the same sample.
+#[cfg(feature = "cli")]
+#[must_use]
+pub fn sample_files(filenames: &[PathBuf], count: usize, seed: u64) -> Vec<PathBuf> {
+    let mut ranks: Vec<(u64, usize)> = filenames
+        .iter()
+        .enumerate()
+        .map(|(i, filename)| {
+            let name = filename.to_string_lossy();
+            (fnv1a(seed.to_le_bytes().into_iter().chain(name.bytes())), i)
+        })
+        .collect();
+    ranks.sort_unstable();
+    ranks.truncate(count);
+
+    let mut indices: Vec<usize> = ranks.into_iter().map(|(_, i)| i).collect();
+    indices.sort_unstable();
+    indices.into_iter().map(|i| filenames[i].clone()).collect()
 }
 
 /// Check text using LanguageTool server.
@@ -655,11 +2218,115 @@ fn parse_filename(s: &str) -> Result<PathBuf> {
 #[derive(Debug, Parser)]
 pub struct CheckCommand {
     /// If present, raw JSON output will be printed instead of annotated text.
-    /// This has no effect if `--data` is used, because it is never
-    /// annotated.
+    /// This is a shorthand for `--format json`.
     #[cfg(feature = "cli")]
-    #[clap(short = 'r', long)]
+    #[clap(short = 'r', long, conflicts_with = "format")]
     pub raw: bool,
+    /// Output format: text, json, fingerprints, diff, or the name of a report
+    /// format, e.g., sarif or checkstyle. Text is never annotated, nor
+    /// rendered as a diff, if `--data` is used, and raw JSON is printed
+    /// instead.
+    #[clap(short = 'f', long, value_name = "NAME", default_value = "text")]
+    pub format: String,
+    /// Keys whose string values are checked in YAML and TOML files, separated
+    /// by commas. A key matches a value if it is equal to its full key path,
+    /// e.g., `package.description`, or to its last key, e.g., `description`.
+    #[clap(
+        long,
+        value_delimiter = ',',
+        default_value = "description,summary,title"
+    )]
+    pub keys: Vec<String>,
+    /// If present, only print the fingerprint and rule id of each match, one
+    /// per line. This is a shorthand for `--format fingerprints`.
+    #[clap(long, conflicts_with_all(["raw", "format"]))]
+    pub list_matches_only: bool,
+    /// If present, only print the number of issues found in each checked
+    /// text, by kind, instead of annotated text.
+    #[clap(long, conflicts_with_all(["raw", "format", "list_matches_only"]))]
+    pub summary_only: bool,
+    /// If present, only check spelling, which is much faster on long texts,
+    /// see [`CheckRequest::spelling_only`].
+    #[clap(
+        long,
+        conflicts_with_all([
+            "enabled_rules",
+            "disabled_rules",
+            "enabled_categories",
+            "disabled_categories",
+            "enabled_only",
+        ])
+    )]
+    pub spell_only: bool,
+    /// Language of the files whose path matches a glob, formatted as
+    /// `GLOB=LANGUAGE`, e.g., `docs/fr/**=fr-FR`, taking precedence over
+    /// `--language`. The first matching glob is used. Can be repeated.
+    #[clap(
+        long,
+        value_name = "GLOB=LANGUAGE",
+        value_parser = crate::cli::language::parse_language_override
+    )]
+    pub language_override: Vec<crate::cli::language::LanguageOverride>,
+    /// Mother tongue of the files whose path matches a glob, formatted as
+    /// `GLOB=LANGUAGE`, taking precedence over `--mother-tongue`. The first
+    /// matching glob is used. Can be repeated.
+    #[clap(
+        long,
+        value_name = "GLOB=LANGUAGE",
+        value_parser = crate::cli::language::parse_language_override
+    )]
+    pub mother_tongue_override: Vec<crate::cli::language::LanguageOverride>,
+    /// Preferred variants of the files whose path matches a glob, formatted
+    /// as `GLOB=VARIANT,...`, e.g., `docs/uk/**=en-GB,de-AT`, taking
+    /// precedence over `--preferred-variants`. The first matching glob is
+    /// used. Can be repeated.
+    #[clap(
+        long,
+        value_name = "GLOB=VARIANTS",
+        value_parser = crate::cli::language::parse_variants_override
+    )]
+    pub preferred_variants_override: Vec<crate::cli::language::VariantsOverride>,
+    /// If present, also print the word count, sentence count, average
+    /// sentence length and readability scores (Flesch reading ease and LIX) of
+    /// each checked text, after the number of issues found.
+    #[clap(long, conflicts_with_all(["raw", "format", "list_matches_only"]))]
+    pub stats: bool,
+    /// Markdown elements whose content is not checked, separated by commas.
+    /// Skipped code spans and links are replaced by a placeholder word.
+    #[clap(
+        long,
+        value_delimiter = ',',
+        default_value = "code",
+        ignore_case = true,
+        value_enum
+    )]
+    pub markdown_skip: Vec<crate::parsers::MarkdownElement>,
+    /// Type of the checked files. By default, it is guessed from each file's
+    /// extension, and standard input is checked as raw text, see
+    /// `--stdin-filepath`.
+    #[clap(
+        long = "type",
+        value_name = "TYPE",
+        default_value = "auto",
+        ignore_case = true,
+        value_enum
+    )]
+    pub file_type: crate::parsers::FileType,
+    /// Path of the file whose content is read from standard input, or given
+    /// with `--text`. Its extension selects the type of the text, see
+    /// `--type`, and it is printed as the origin of matches.
+    #[clap(long, value_name = "PATH", conflicts_with_all(["filenames", "diff"]))]
+    pub stdin_filepath: Option<PathBuf>,
+    /// Type of the text read from standard input, or given with `--text`,
+    /// which overrides the extension of `--stdin-filepath`.
+    #[clap(
+        long,
+        value_name = "TYPE",
+        ignore_case = true,
+        value_enum,
+        conflicts_with_all(["filenames", "diff"])
+    )]
+    pub stdin_type: Option<crate::parsers::FileType>,
     /// If present, more context (i.e., line number and line offset) will be
     /// added to response.
     #[clap(short = 'm', long, hide = true)]
@@ -674,22 +2341,320 @@ pub struct CheckCommand {
     /// If text is too long, will split on this pattern.
     #[clap(long, default_value = "\n\n")]
     pub split_pattern: String,
+    /// If present, split long texts on sentence boundaries instead of
+    /// `--split-pattern`, so that a sentence is never cut in two fragments.
+    #[clap(long, conflicts_with = "incremental")]
+    pub split_sentences: bool,
     /// Max. number of suggestions kept. If negative, all suggestions are kept.
     #[clap(long, default_value_t = 5, allow_negative_numbers = true)]
     pub max_suggestions: isize,
+    /// Maximum number of check requests sent concurrently, e.g., for the
+    /// fragments of long texts. By default, there is no limit. This also
+    /// bounds the number of files checked concurrently, which is 4 by default,
+    /// and 1 with `--interactive`.
+    #[clap(short = 'j', long, value_name = "N")]
+    pub jobs: Option<usize>,
+    /// Maximum number of check requests sent per minute, spacing them evenly,
+    /// e.g., `20` for the free public API. By default, there is no limit.
+    #[clap(long, value_name = "REQUESTS_PER_MINUTE")]
+    pub rate_limit: Option<u32>,
+    /// Number of times a check request is retried after a transient error,
+    /// i.e., a timeout, a connection error, `429 Too Many Requests` or a
+    /// server error, waiting longer after each failure.
+    #[clap(long, default_value_t = 0, value_name = "N")]
+    pub retries: u32,
+    /// If present, report an error when the server answers that a fragment is
+    /// too long, instead of splitting it in halves and checking these again.
+    #[clap(long)]
+    pub no_resplit: bool,
+    /// Number of columns between tab stops, used to expand tabs in annotated
+    /// output so that underlines point at the right characters.
+    #[clap(long, default_value_t = DEFAULT_TAB_WIDTH, value_name = "COLUMNS")]
+    pub tab_width: usize,
+    /// If present, apply the first suggested replacement of each match back
+    /// to the checked files. All the occurrences of the same mistake get the
+    /// same replacement, and the number of replaced occurrences is reported.
+    #[clap(short = 'w', long, requires = "filenames")]
+    pub write: bool,
+    /// How to handle control characters, other than tabulations and line
+    /// breaks, before checking. A warning is always printed if some are found.
+    #[clap(long, default_value = "keep", ignore_case = true, value_enum)]
+    pub control_chars: ControlChars,
+    /// Only check files that changed since a given time, as a UNIX timestamp
+    /// in seconds, or since a given git revision (untracked files are always
+    /// checked).
+    #[clap(long, value_name = "TIMESTAMP|REF", requires = "filenames")]
+    pub only_changed_since: Option<ChangedSince>,
+    /// If present, only report matches on the lines added or modified
+    /// according to `git diff`, and check the files of the diff if no
+    /// filenames are given, e.g., in a pre-commit hook.
+    #[clap(long, conflicts_with_all(["text", "data"]))]
+    pub diff: bool,
+    /// If present, compare the index, i.e., the staged changes, instead of the
+    /// working tree, see `--diff`.
+    #[clap(long, requires = "diff")]
+    pub staged: bool,
+    /// Compare with this git revision instead of the index, see `--diff`.
+    #[clap(long, value_name = "REF", requires = "diff")]
+    pub since: Option<String>,
+    /// Only check a random sample of the files, given as a percentage, e.g.,
+    /// `5%`, to estimate the quality of large documentation sets.
+    #[clap(
+        long,
+        value_name = "PERCENT",
+        value_parser = parse_percentage,
+        requires = "filenames",
+        conflicts_with = "sample_files"
+    )]
+    pub sample: Option<f64>,
+    /// Only check a random sample of N files.
+    #[clap(long, value_name = "N", requires = "filenames")]
+    pub sample_files: Option<usize>,
+    /// Seed used to sample files with `--sample` or `--sample-files`. By
+    /// default, it is derived from the current time, and it is always printed
+    /// on standard error so that the same sample can be checked again.
+    #[clap(long)]
+    pub seed: Option<u64>,
+    /// Maximum time spent checking each file, in seconds. Files that take
+    /// longer are skipped with a warning, so that a single file cannot stall
+    /// the whole run, and the command fails after checking the other files.
+    #[clap(long, value_name = "SECONDS", requires = "filenames")]
+    pub per_file_timeout: Option<u64>,
+    /// Maximum number of matches reported for each file, or for the text
+    /// read from standard input. Further matches are dropped with a warning.
+    #[clap(long, value_name = "N")]
+    pub max_errors_per_file: Option<usize>,
+    /// Maximum number of matches reported in total. Once it is exceeded, the
+    /// files that are not checked yet are skipped, their pending requests are
+    /// cancelled to save API quota, and the command fails, e.g., in CI.
+    #[clap(long, value_name = "N")]
+    pub max_errors: Option<usize>,
+    /// If present, review matches one by one and choose which replacement to
+    /// apply back to the checked files.
+    #[clap(
+        short = 'i',
+        long,
+        requires = "filenames",
+        conflicts_with_all(["raw", "format", "list_matches_only", "write"])
+    )]
+    pub interactive: bool,
+    /// Minimum confidence, from 0 to 1, of the server's language detection
+    /// to warn that the text does not look written in the requested
+    /// `--language`.
+    #[clap(long, default_value_t = 0.9, value_name = "CONFIDENCE")]
+    pub language_mismatch_threshold: f64,
+    /// If present, check that `--language`, `--mother-tongue` and
+    /// `--preferred-variants` are supported by the server before checking
+    /// anything, and suggest close codes if they are not.
+    #[clap(long)]
+    pub validate_language: bool,
+    /// If present, check text against a LanguageTool server started locally
+    /// as a Java child process, instead of `--hostname`. LanguageTool is
+    /// downloaded into the cache directory on first use, unless `--lt-home`
+    /// is set.
+    #[cfg(feature = "local-server")]
+    #[clap(long)]
+    pub local: bool,
+    /// LanguageTool installation used by `--local`, i.e., a directory
+    /// containing `languagetool-server.jar`.
+    #[cfg(feature = "local-server")]
+    #[clap(long, value_name = "DIR", requires = "local")]
+    pub lt_home: Option<PathBuf>,
+    /// If present, do not suppress matches listed in the closest
+    /// `.ltrsignore` file, found by walking up from each checked file (or from
+    /// the current directory when reading standard input).
+    #[clap(long)]
+    pub no_ignore_file: bool,
+    /// If present, do not suppress matches using `ltrs-disable-file`,
+    /// `ltrs-disable-line`, or `ltrs-disable-next-line` comments found in the
+    /// checked text.
+    #[clap(long)]
+    pub no_inline_ignores: bool,
+    /// Suppress matches starting on lines that match this regular expression,
+    /// e.g., `^\|` for table rows or `^:::` for admonitions. Can be repeated.
+    #[clap(long, value_name = "REGEX", value_parser = regex::Regex::new)]
+    pub ignore_line_regex: Vec<regex::Regex>,
+    /// Comma-separated (or repeated) list of rule ids whose matches are
+    /// suppressed after the request, e.g., in cached responses.
+    #[clap(long, value_name = "RULES", value_delimiter = ',', action = clap::ArgAction::Append)]
+    pub ignore_rules: Vec<String>,
+    /// Comma-separated (or repeated) list of category ids, e.g., `GRAMMAR`,
+    /// whose matches are the only ones kept after the request.
+    #[clap(
+        long,
+        value_name = "CATEGORIES",
+        value_delimiter = ',',
+        action = clap::ArgAction::Append
+    )]
+    pub only_categories: Vec<String>,
+    /// If present, suppress spelling matches after the request.
+    #[clap(long)]
+    pub ignore_spelling: bool,
+    /// If present, only keep matches from picky rules, i.e., the issues that
+    /// are only reported with `--level picky`, which is usually set too.
+    #[clap(long)]
+    pub picky_only: bool,
+    /// Suppress matches whose matched text matches this regular expression,
+    /// e.g., `^\{\{.*\}\}$` for templating placeholders. Can be repeated.
+    #[clap(long, value_name = "REGEX", value_parser = regex::Regex::new)]
+    pub ignore_pattern: Vec<regex::Regex>,
+    /// If present, fetch the words of your personal dictionaries (see
+    /// `--dicts`) once per run, and suppress spelling matches on these words.
+    /// This is useful with servers that ignore personal dictionaries, such as
+    /// some self-hosted servers. Requires `--username` and `--api-key`.
+    #[clap(long, requires = "username")]
+    pub use_remote_dictionary_filter: bool,
+    /// File listing known words, one per line, on which spelling matches are
+    /// suppressed. Unlike `--dicts`, this does not require a Premium account.
+    #[clap(long, value_name = "FILE", value_parser = parse_known_words)]
+    pub known_words: Option<crate::ignore::KnownWords>,
+    /// If present, reuse the responses cached by previous runs for unchanged
+    /// text, and cache new ones, see `ltrs cache`.
+    #[clap(long)]
+    pub cache: bool,
+    /// If present, split texts into fragments whose boundaries only depend on
+    /// nearby paragraphs, so that only the fragments that changed since the
+    /// previous run are checked again, the others being read from the cache.
+    /// This implies `--cache`.
+    #[clap(long)]
+    pub incremental: bool,
+    /// If present, include the parameters of the request that was sent, e.g.,
+    /// the language and the enabled rules, in JSON output, so that results can
+    /// be reproduced later. Credentials are never included.
+    #[clap(long)]
+    pub include_request: bool,
+    /// If present, keep a copy of each file modified by `--write` or
+    /// `--interactive`, with a `.bak` extension appended to its name.
+    #[clap(long)]
+    pub backup: bool,
+    /// LanguageTool `grammar.xml` file whose simple pattern rules are also
+    /// checked locally, see [`crate::rules`]. Unsupported rules are skipped
+    /// with a warning.
+    #[cfg(feature = "rules")]
+    #[clap(long, value_name = "FILE", value_parser = parse_rules)]
+    pub rules: Option<crate::rules::RuleSet>,
+    /// If present, keep response fields that are not part of the documented
+    /// API, e.g., AI-assisted rewriting suggestions returned by newer servers,
+    /// and print the suggestions found in them. This is experimental.
+    #[clap(long)]
+    pub experimental: bool,
+    /// Extra field sent as is with each request, e.g., to enable features of
+    /// newer servers. Can be repeated.
+    #[clap(
+        long,
+        value_name = "NAME=VALUE",
+        value_parser = parse_experimental_field,
+        requires = "experimental"
+    )]
+    pub experimental_field: Vec<(String, String)>,
     /// Inner [`CheckRequest`].
     #[command(flatten)]
     pub request: CheckRequest,
-    /// Optional filenames from which input is read.
+    /// Only check the files, found in directories passed as `FILENAMES`, that
+    /// match this glob, e.g., `**/*.md`, relative to these directories. Files
+    /// matching a glob starting with `!` are skipped instead. Can be repeated.
+    #[clap(long, value_name = "GLOB", requires = "filenames")]
+    pub glob: Vec<String>,
+    /// If present, skip the files, found in directories passed as
+    /// `FILENAMES`, that are ignored by `.gitignore` or `.ignore` files.
+    #[clap(long, requires = "filenames")]
+    pub respect_gitignore: bool,
+    /// Optional filenames from which input is read. Directories are walked
+    /// recursively, skipping hidden and binary files, see `--glob`.
     #[arg(conflicts_with_all(["text", "data"]), value_parser = parse_filename)]
     pub filenames: Vec<PathBuf>,
 }
 
+#[cfg(feature = "cli")]
+impl CheckCommand {
+    /// Return the client-side filter set by `--ignore-rules`,
+    /// `--only-categories`, `--ignore-spelling`, `--picky-only` and
+    /// `--ignore-pattern`.
+    #[must_use]
+    pub fn match_filter(&self) -> crate::ignore::MatchFilter {
+        crate::ignore::MatchFilter::default()
+            .with_ignore_rules(self.ignore_rules.iter().cloned())
+            .with_only_categories(self.only_categories.iter().cloned())
+            .with_ignore_spelling(self.ignore_spelling)
+            .with_picky_only(self.picky_only)
+            .with_ignore_patterns(self.ignore_pattern.iter().cloned())
+    }
+
+    /// Return the files sampled with `--sample` or `--sample-files`, and the
+    /// seed used, or `None` if no sampling was requested.
+    #[must_use]
+    pub fn sampled_filenames(&self) -> Option<(Vec<PathBuf>, u64)> {
+        let total = self.filenames.len();
+        let count = match (self.sample, self.sample_files) {
+            (Some(percentage), _) => (total as f64 * percentage / 100.0).ceil() as usize,
+            (None, Some(count)) => count,
+            (None, None) => return None,
+        };
+        let seed = self.seed.unwrap_or_else(|| {
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |duration| duration.as_nanos() as u64)
+        });
+
+        Some((sample_files(&self.filenames, count, seed), seed))
+    }
+}
+
 #[cfg(test)]
 mod request_tests {
 
+    use super::{
+        checked_len, test_utils::response_with_matches, CheckResponseWithContext, Data,
+        DataAnnotation,
+    };
     use crate::CheckRequest;
 
+    #[cfg(feature = "cli")]
+    #[test]
+    fn test_changed_since_timestamp() {
+        use super::ChangedSince;
+
+        let file = tempfile::NamedTempFile::new().unwrap();
+
+        let filenames = vec![file.path().to_path_buf()];
+
+        let since: ChangedSince = "0".parse().unwrap();
+        assert_eq!(since.filter_changed(filenames.clone()).unwrap(), filenames);
+
+        let since: ChangedSince = "99999999999".parse().unwrap();
+        assert!(since.filter_changed(filenames).unwrap().is_empty());
+
+        assert_eq!(
+            "main".parse::<ChangedSince>().unwrap(),
+            ChangedSince::GitRef("main".to_string())
+        );
+        assert!("--all".parse::<ChangedSince>().is_err());
+    }
+
+    #[cfg(feature = "cli")]
+    #[test]
+    fn test_sample_files() {
+        use super::{parse_percentage, sample_files};
+        use std::path::PathBuf;
+
+        let filenames: Vec<PathBuf> = (0..100).map(|i| format!("doc{i}.md").into()).collect();
+        let sample = sample_files(&filenames, 10, 42);
+
+        assert_eq!(sample.len(), 10);
+        assert_eq!(sample, sample_files(&filenames, 10, 42));
+        assert_ne!(sample, sample_files(&filenames, 10, 43));
+        assert!(sample.windows(2).all(|w| {
+            let index = |f: &PathBuf| filenames.iter().position(|g| g == f);
+            index(&w[0]) < index(&w[1])
+        }));
+        assert_eq!(sample_files(&filenames, 200, 42), filenames);
+
+        assert_eq!(parse_percentage("5%").unwrap(), 5.0);
+        assert_eq!(parse_percentage("12.5").unwrap(), 12.5);
+        assert!(parse_percentage("0%").is_err());
+        assert!(parse_percentage("150%").is_err());
+    }
+
     #[test]
     fn test_with_text() {
         let req = CheckRequest::default().with_text("hello".to_string());
@@ -705,10 +2670,260 @@ mod request_tests {
         assert_eq!(req.text.unwrap(), "hello".to_string());
         assert!(req.data.is_none());
     }
+
+    #[test]
+    fn test_split_data() {
+        use crate::check::{Data, DataAnnotation};
+
+        let data = Data {
+            annotation: vec![
+                DataAnnotation::new_text("First paragraph.\n\n".to_string()),
+                DataAnnotation::new_markup("<b>".to_string()),
+                DataAnnotation::new_text("Second\n\nparagraph.".to_string()),
+                DataAnnotation::new_markup("</b>".to_string()),
+                DataAnnotation::new_text("\n\nThird.".to_string()),
+            ],
+        };
+        let requests = CheckRequest::default().with_data(data).split(20, "\n\n");
+        let fragments: Vec<String> = requests.iter().map(CheckRequest::get_text).collect();
+
+        assert_eq!(
+            fragments,
+            vec![
+                "First paragraph.\n\n",
+                "<b>Second\n\nparagraph.",
+                "paragraph.</b>\n\nThird.",
+            ]
+        );
+        // The last fragment is checked after the end of the second one
+        assert_eq!(
+            requests[2].data.as_ref().unwrap().annotation[0],
+            DataAnnotation::new_text("paragraph.".to_string())
+        );
+        assert_eq!(requests[2].context_length, 10);
+
+        // Interpreted markup counts, and long pieces are split on annotations
+        let data = Data::from_iter([
+            DataAnnotation::new_text("One two. ".to_string()),
+            DataAnnotation::new_interpreted_markup("<br>".to_string(), "\n\n".to_string()),
+            DataAnnotation::new_text("Three. ".to_string()),
+            DataAnnotation::new_interpreted_markup("~".to_string(), "Long placeholder".to_string()),
+        ]);
+        let requests = CheckRequest::default().with_data(data).split(12, "\n\n");
+        let fragments: Vec<String> = requests.iter().map(CheckRequest::get_text).collect();
+
+        assert_eq!(fragments, vec!["One two. <br>", "Three. ", "~"]);
+    }
+
+    /// Return the offset and length of each "teh" in the text checked for
+    /// `data`, mapped to its source like the server does: what markup is
+    /// interpreted as maps to the whole markup.
+    fn find_typos(data: &Data) -> Vec<(usize, usize)> {
+        let mut checked = Vec::new();
+        let mut source_ranges = Vec::new();
+        let mut source_len = 0;
+        for da in &data.annotation {
+            let markup_len = da.markup.as_deref().map_or(0, |s| s.chars().count());
+            match (&da.text, &da.interpret_as) {
+                (Some(text), _) => {
+                    for c in text.chars() {
+                        checked.push(c);
+                        source_ranges.push((source_len, source_len + 1));
+                        source_len += 1;
+                    }
+                },
+                (None, Some(interpret_as)) => {
+                    for c in interpret_as.chars() {
+                        checked.push(c);
+                        source_ranges.push((source_len, source_len + markup_len));
+                    }
+                    source_len += markup_len;
+                },
+                (None, None) => source_len += markup_len,
+            }
+        }
+
+        let checked: String = checked.into_iter().collect();
+        checked
+            .match_indices("teh")
+            .map(|(i, _)| {
+                let start = checked[..i].chars().count();
+                let (offset, _) = source_ranges[start];
+                (offset, source_ranges[start + 2].1 - offset)
+            })
+            .collect()
+    }
+
+    fn annotation_strategy() -> impl proptest::strategy::Strategy<Value = DataAnnotation> {
+        use proptest::prelude::*;
+
+        prop_oneof![
+            4 => "([A-Z][a-z]{0,6}|teh)( [a-z]{1,6}| teh){0,5}[.!?]( |\n|\n\n)"
+                .prop_map(DataAnnotation::new_text),
+            1 => "<[a-z]{1,3}>".prop_map(DataAnnotation::new_markup),
+            1 => ("&[a-z]{2};", prop_oneof![Just("X"), Just(" "), Just("\n\n")])
+                .prop_map(|(markup, interpret_as)| {
+                    DataAnnotation::new_interpreted_markup(markup, interpret_as.to_string())
+                }),
+        ]
+    }
+
+    /// Check that splitting `data` in fragments of `n` characters never splits
+    /// nor loses annotations, and that joining the responses to all fragments
+    /// gives the matches of the whole data.
+    fn check_split_data_reassembles(
+        data: Data,
+        n: usize,
+    ) -> Result<(), proptest::test_runner::TestCaseError> {
+        let request = CheckRequest::default().with_data(data.clone());
+        let fragments = request.try_split(n, "\n\n").unwrap();
+
+        let mut annotation = Vec::new();
+        let mut joined: Option<CheckResponseWithContext> = None;
+        for (i, fragment) in fragments.iter().enumerate() {
+            let fragment_data = fragment.data.as_ref().unwrap();
+            let mut fragment_annotation = fragment_data.annotation.clone();
+            if fragment.context_length > 0 {
+                proptest::prop_assert!(i > 0);
+                fragment_annotation.remove(0);
+            }
+            let len: usize = fragment_data.annotation.iter().map(checked_len).sum();
+            proptest::prop_assert!(len <= n || fragment_annotation.len() == 1);
+            annotation.extend(fragment_annotation);
+
+            let text = fragment.get_text();
+            let matches: Vec<_> = find_typos(fragment_data)
+                .into_iter()
+                .map(|(offset, length)| (offset, length, &[][..]))
+                .collect();
+            let response = CheckResponseWithContext::from_fragment(
+                fragment,
+                response_with_matches(&text, &matches),
+            )
+            .unwrap();
+            joined = Some(match joined {
+                Some(joined) => joined.append(response),
+                None => response,
+            });
+        }
+
+        // Annotations are never split nor lost
+        proptest::prop_assert_eq!(&annotation, &data.annotation);
+        let joined_matches: Vec<(usize, usize)> = joined
+            .map(|joined| {
+                joined
+                    .iter_matches()
+                    .map(|m| (m.offset, m.length))
+                    .collect()
+            })
+            .unwrap_or_default();
+        proptest::prop_assert_eq!(joined_matches, find_typos(&data));
+        Ok(())
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn prop_split_data_reassembles(
+            annotation in proptest::collection::vec(annotation_strategy(), 0..40),
+            n in 1usize..200,
+        ) {
+            check_split_data_reassembles(Data { annotation }, n)?;
+        }
+    }
+
+    #[test]
+    fn test_split_data_typo_in_context() {
+        // A typo in the context of the second fragment is only reported once
+        let data = Data::from_iter([
+            DataAnnotation::new_text("teh aa aaaaaa aaaa? ".to_string()),
+            DataAnnotation::new_text("Aa aaaaaa teh? ".to_string()),
+            DataAnnotation::new_text("Aaa aaa aaa aaa aaaaaa! ".to_string()),
+            DataAnnotation::new_text("A. ".to_string()),
+        ]);
+        check_split_data_reassembles(data, 42).unwrap();
+    }
+
+    #[test]
+    fn test_from_fragment() {
+        let data = Data::from_iter([
+            DataAnnotation::new_text("One. Two.\n\n".to_string()),
+            DataAnnotation::new_text("Three.".to_string()),
+        ]);
+        let requests = CheckRequest::default().with_data(data).split(15, "\n\n");
+        assert_eq!(requests[1].get_text(), "Two.\n\nThree.");
+
+        // Matches in the context, or that start in it, are removed
+        let response = response_with_matches(
+            &requests[1].get_text(),
+            &[(0, 3, &[]), (2, 6, &[]), (6, 5, &[])],
+        );
+        let response = CheckResponseWithContext::from_fragment(&requests[1], response).unwrap();
+        assert_eq!(response.text, "Three.");
+        assert_eq!(
+            response
+                .iter_matches()
+                .map(|m| (m.offset, m.length))
+                .collect::<Vec<_>>(),
+            [(0, 5)]
+        );
+    }
+
+    #[test]
+    fn test_try_split_stable() {
+        use crate::check::{Data, DataAnnotation};
+
+        let paragraphs: Vec<String> = (0..50).map(|i| format!("Paragraph {i}.\n\n")).collect();
+        let split = |paragraphs: &[String]| -> Vec<String> {
+            CheckRequest::default()
+                .with_text(paragraphs.concat())
+                .try_split_stable(100, "\n\n")
+                .unwrap()
+                .iter()
+                .map(CheckRequest::get_text)
+                .collect()
+        };
+        let fragments = split(&paragraphs);
+
+        assert_eq!(fragments.concat(), paragraphs.concat());
+        assert!(fragments.len() > 1);
+        assert!(fragments.iter().all(|fragment| fragment.len() < 100));
+
+        // Inserting a paragraph only changes the fragments around it
+        let mut edited = paragraphs.clone();
+        edited.insert(10, "A new paragraph.\n\n".to_string());
+        let edited_fragments = split(&edited);
+        let changed = edited_fragments
+            .iter()
+            .filter(|fragment| !fragments.contains(fragment))
+            .count();
+
+        assert!(changed <= 3, "{changed} fragments changed");
+
+        let data = Data {
+            annotation: paragraphs
+                .iter()
+                .map(|paragraph| DataAnnotation::new_text(paragraph.clone()))
+                .collect(),
+        };
+        let data_fragments: Vec<String> = CheckRequest::default()
+            .with_data(data)
+            .try_split_stable(100, "\n\n")
+            .unwrap()
+            .iter()
+            .map(CheckRequest::get_text)
+            .collect();
+
+        assert_eq!(data_fragments, fragments);
+    }
 }
 
 /// Responses
 
+/// Default number of columns between tab stops in annotated output, see
+/// [`CheckResponse::annotate_with_tab_width`].
+#[allow(clippy::empty_line_after_doc_comments)]
+pub const DEFAULT_TAB_WIDTH: usize = 4;
+
 /// Detected language from check request.
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
@@ -717,13 +2932,14 @@ pub struct DetectedLanguage {
     /// Language code, e.g., `"sk-SK"` for Slovak.
     pub code: String,
     /// Confidence level, from 0 to 1.
-    #[cfg(feature = "unstable")]
     pub confidence: Option<f64>,
     /// Language name, e.g., `"Slovak"`.
     pub name: String,
     /// Source (file) for the language detection.
-    #[cfg(feature = "unstable")]
     pub source: Option<String>,
+    /// Unknown fields, as raw JSON.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
 /// Language information in check response.
@@ -737,6 +2953,9 @@ pub struct LanguageResponse {
     pub detected_language: DetectedLanguage,
     /// Language name, e.g., `"Slovak"`.
     pub name: String,
+    /// Unknown fields, as raw JSON.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
 /// Match context in check response.
@@ -759,6 +2978,23 @@ pub struct MoreContext {
     pub line_number: usize,
     /// Char index at which the match starts on the current line.
     pub line_offset: usize,
+    /// Column at which the match starts, i.e., `line_offset + 1`.
+    #[serde(default)]
+    pub column: usize,
+    /// UTF-16 code unit index at which the match starts, as used by editors
+    /// and the Language Server Protocol.
+    #[serde(default)]
+    pub utf16_offset: usize,
+    /// Length of the match, in UTF-16 code units.
+    #[serde(default)]
+    pub utf16_length: usize,
+    /// UTF-16 code unit index at which the match starts on the current line.
+    #[serde(default)]
+    pub utf16_line_offset: usize,
+    /// Key path of the value where match occurred, in structured documents
+    /// such as YAML or TOML files.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_path: Option<String>,
 }
 
 /// Possible replacement for a given match in check response.
@@ -767,11 +3003,17 @@ pub struct MoreContext {
 pub struct Replacement {
     /// Possible replacement value.
     pub value: String,
+    /// Unknown fields, as raw JSON.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
 impl From<String> for Replacement {
     fn from(value: String) -> Self {
-        Self { value }
+        Self {
+            value,
+            extra: Map::new(),
+        }
     }
 }
 
@@ -811,21 +3053,26 @@ pub struct Rule {
     /// Rule id.
     pub id: String,
     /// Indicate if the rule is from the premium API.
-    #[cfg(feature = "unstable")]
     pub is_premium: Option<bool>,
     /// Issue type.
     pub issue_type: String,
     /// Rule source file.
-    #[cfg(feature = "unstable")]
     pub source_file: Option<String>,
     /// Rule sub id.
     pub sub_id: Option<String>,
+    /// Rule tags, e.g., `"picky"` for rules only enabled with
+    /// [`Level::Picky`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
     /// Rule list of urls.
     pub urls: Option<Vec<Url>>,
+    /// Unknown fields, as raw JSON.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
 /// Type of a given match.
-#[derive(PartialEq, Eq, Clone, Debug, Deserialize, Serialize)]
+#[derive(PartialEq, Eq, Clone, Debug, Default, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 #[non_exhaustive]
 pub struct Type {
@@ -842,11 +3089,11 @@ pub struct Match {
     pub context: Context,
     /// Unknown: please fill a [PR](https://github.com/jeertmans/languagetool-rust/pulls) of your
     /// know that this attribute is used for.
-    #[cfg(feature = "unstable")]
+    #[serde(default)]
     pub context_for_sure_match: isize,
     /// Unknown: please fill a [PR](https://github.com/jeertmans/languagetool-rust/pulls) of your
     /// know that this attribute is used for.
-    #[cfg(feature = "unstable")]
+    #[serde(default)]
     pub ignore_for_incomplete_sentence: bool,
     /// Match length.
     pub length: usize,
@@ -866,9 +3113,80 @@ pub struct Match {
     /// Short message about the error.
     pub short_message: String,
     /// Match type.
-    #[cfg(feature = "unstable")]
-    #[serde(rename = "type")]
+    #[serde(default, rename = "type")]
     pub type_: Type,
+    /// Unknown fields, as raw JSON.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Match {
+    /// Return the text covered by this match, read from its context.
+    #[must_use]
+    pub fn matched_text(&self) -> String {
+        self.context
+            .text
+            .chars()
+            .skip(self.context.offset)
+            .take(self.context.length)
+            .collect()
+    }
+
+    /// Return a fingerprint identifying this match by its rule id and matched
+    /// text, as 16 hexadecimal digits.
+    ///
+    /// The fingerprint does not depend on the match position, so it stays the
+    /// same when the surrounding text is edited, and it is stable across
+    /// versions and platforms.
+    #[must_use]
+    pub fn fingerprint(&self) -> String {
+        let text = self.matched_text();
+        let hash = fnv1a(self.rule.id.bytes().chain([0]).chain(text.bytes()));
+        format!("{hash:016x}")
+    }
+
+    /// Return `text` where this match is replaced by its replacement at index
+    /// `replacement`, and the resulting change of length, in characters.
+    ///
+    /// Offsets are counted in characters, as returned by LanguageTool, and
+    /// converted into byte indices, so that `text` is never split inside a
+    /// multi-byte character. Offsets of following matches must be shifted by
+    /// the returned delta to be applied to the edited text.
+    ///
+    /// Return [`None`] if there is no such replacement, or if the match does
+    /// not fit in `text`.
+    #[must_use]
+    pub fn apply_to(&self, text: &str, replacement: usize) -> Option<(String, isize)> {
+        let value = &self.replacements.get(replacement)?.value;
+        let mut indices = text
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain(std::iter::once(text.len()));
+        let start = indices.nth(self.offset)?;
+        let end = if self.length == 0 {
+            start
+        } else {
+            indices.nth(self.length - 1)?
+        };
+
+        let mut edited = String::with_capacity(text.len() - (end - start) + value.len());
+        edited.push_str(&text[..start]);
+        edited.push_str(value);
+        edited.push_str(&text[end..]);
+
+        let delta = value.chars().count() as isize - self.length as isize;
+        Some((edited, delta))
+    }
+}
+
+/// Hash `bytes` with 64-bit FNV-1a, see <http://www.isthe.com/chongo/tech/comp/fnv/>.
+pub(crate) fn fnv1a(bytes: impl IntoIterator<Item = u8>) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in bytes {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash
 }
 
 /// LanguageTool software details.
@@ -885,13 +3203,15 @@ pub struct Software {
     /// Tell whether the server uses premium API or not.
     pub premium: bool,
     /// Sentence that indicates if using premium API would find more errors.
-    #[cfg(feature = "unstable")]
     pub premium_hint: Option<String>,
     /// Unknown: please fill a [PR](https://github.com/jeertmans/languagetool-rust/pulls) of your
     /// know that this attribute is used for.
     pub status: String,
     /// LanguageTool version.
     pub version: String,
+    /// Unknown fields, as raw JSON.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
 }
 
 /// Warnings about check response.
@@ -913,16 +3233,298 @@ pub struct CheckResponse {
     /// List of error matches.
     pub matches: Vec<Match>,
     /// Ranges ([start, end]) of sentences.
-    #[cfg(feature = "unstable")]
     pub sentence_ranges: Option<Vec<[usize; 2]>>,
     /// LanguageTool software information.
     pub software: Software,
     /// Possible warnings.
-    #[cfg(feature = "unstable")]
     pub warnings: Option<Warnings>,
+    /// Unknown fields, as raw JSON, see [`crate::experimental`].
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Disagreement between the requested language and the language detected by
+/// the server.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct LanguageMismatch {
+    /// Requested language code.
+    pub requested: String,
+    /// Detected language code.
+    pub detected: String,
+    /// Detected language name.
+    pub detected_name: String,
+    /// Confidence level of the detection, from 0 to 1.
+    pub confidence: f64,
+}
+
+impl std::fmt::Display for LanguageMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "text was checked as {}, but looks like {} ({:.0}% confidence), consider using \
+             `--language {}`",
+            self.requested,
+            self.detected_name,
+            self.confidence * 100.0,
+            self.detected
+        )
+    }
+}
+
+/// Number of matches of a response by kind of issue, see
+/// [`CheckResponse::summary`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct MatchSummary {
+    /// Number of spelling issues, i.e., with the `misspelling` issue type.
+    pub spelling: usize,
+    /// Number of grammar issues.
+    pub grammar: usize,
+    /// Number of style issues, including `locale-violation` and `register`.
+    pub style: usize,
+    /// Number of other issues, e.g., typographical ones.
+    pub other: usize,
+}
+
+impl MatchSummary {
+    /// Return the total number of matches.
+    #[must_use]
+    pub fn total(&self) -> usize {
+        self.spelling + self.grammar + self.style + self.other
+    }
+}
+
+impl std::fmt::Display for MatchSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.total() {
+            0 => return write!(f, "no issues"),
+            1 => write!(f, "1 issue")?,
+            total => write!(f, "{total} issues")?,
+        }
+        write!(
+            f,
+            ": {} spelling, {} grammar, {} style",
+            self.spelling, self.grammar, self.style
+        )?;
+        if self.other > 0 {
+            write!(f, ", {} other", self.other)?;
+        }
+        Ok(())
+    }
+}
+
+/// Number of matches removed on the client by each suppression mechanism,
+/// see [`PreparedText::finish_counting`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct SuppressedMatches {
+    /// Matches listed in an ignore file, see
+    /// [`IgnoreFile`](crate::ignore::IgnoreFile).
+    pub ignore_file: usize,
+    /// Matches on lines with inline ignore comments, see
+    /// [`InlineIgnores`](crate::ignore::InlineIgnores).
+    pub inline_ignores: usize,
+    /// Matches on ignored lines, e.g., with `--ignore-line-regex`.
+    pub ignored_lines: usize,
+    /// Spelling matches on known words, see
+    /// [`KnownWords`](crate::ignore::KnownWords).
+    pub known_words: usize,
+    /// Spelling matches on the words of personal dictionaries, e.g., with
+    /// `--use-remote-dictionary-filter`.
+    pub dictionary: usize,
+    /// Matches whose text matches an ignored pattern, e.g., with
+    /// `--ignore-pattern`.
+    pub ignore_patterns: usize,
+    /// Other matches removed by a [`MatchFilter`](crate::ignore::MatchFilter),
+    /// e.g., of ignored rules.
+    pub filter: usize,
+}
+
+impl SuppressedMatches {
+    /// Return the total number of suppressed matches.
+    #[must_use]
+    pub fn total(&self) -> usize {
+        self.counts().iter().map(|(count, _)| count).sum()
+    }
+
+    /// Return whether no match was suppressed.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.total() == 0
+    }
+
+    /// Return the number of suppressed matches, and the name of the
+    /// suppression mechanism, for each mechanism.
+    fn counts(&self) -> [(usize, &'static str); 7] {
+        [
+            (self.ignore_file, "ignore file"),
+            (self.inline_ignores, "inline ignores"),
+            (self.ignored_lines, "ignored lines"),
+            (self.known_words, "known words"),
+            (self.dictionary, "dictionary"),
+            (self.ignore_patterns, "ignore patterns"),
+            (self.filter, "filter"),
+        ]
+    }
+}
+
+impl std::fmt::Display for SuppressedMatches {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} suppressed", self.total())?;
+        let mut separator = ": ";
+        for (count, name) in self.counts() {
+            if count > 0 {
+                write!(f, "{separator}{count} by {name}")?;
+                separator = ", ";
+            }
+        }
+        Ok(())
+    }
 }
 
 impl CheckResponse {
+    /// Count matches by kind of issue.
+    #[must_use]
+    pub fn summary(&self) -> MatchSummary {
+        let mut summary = MatchSummary::default();
+        for m in self.iter_matches() {
+            match m.rule.issue_type.as_str() {
+                "misspelling" => summary.spelling += 1,
+                "grammar" => summary.grammar += 1,
+                "style" | "locale-violation" | "register" => summary.style += 1,
+                _ => summary.other += 1,
+            }
+        }
+        summary
+    }
+
+    /// Sort matches by position in the text, and remove duplicated matches,
+    /// i.e., with the same position, length and rule, keeping the first one.
+    ///
+    /// Joined responses may contain both, e.g., when fragments overlap or a
+    /// request is retried. Matches starting at the same position are sorted
+    /// by length and rule id, so that the order is deterministic. This is done
+    /// when converting a [`CheckResponseWithContext`] into a response.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use languagetool_rust::check::CheckResponse;
+    /// # fn join(first: CheckResponse, second: CheckResponse) -> CheckResponse {
+    /// let mut response = first.append(second);
+    /// response.normalize();
+    /// assert!(response.matches.windows(2).all(|w| w[0].offset <= w[1].offset));
+    /// response
+    /// # }
+    /// ```
+    pub fn normalize(&mut self) {
+        self.matches.sort_by(|a, b| {
+            (a.offset, a.length, &a.rule.id, &a.rule.sub_id).cmp(&(
+                b.offset,
+                b.length,
+                &b.rule.id,
+                &b.rule.sub_id,
+            ))
+        });
+        self.matches.dedup_by(|b, a| {
+            (a.offset, a.length, &a.rule.id, &a.rule.sub_id)
+                == (b.offset, b.length, &b.rule.id, &b.rule.sub_id)
+        });
+    }
+
+    /// Return the matches sorted by position in the text, so that matches of
+    /// joined responses are rendered line by line.
+    fn sorted_matches(&self) -> Vec<&Match> {
+        let mut matches: Vec<&Match> = self.matches.iter().collect();
+        matches.sort_by_key(|m| m.offset);
+        matches
+    }
+
+    /// Return the mismatch between the requested and the detected languages,
+    /// if the detection's confidence is at least `threshold`.
+    ///
+    /// Only primary language subtags are compared, e.g., `en-US` matches
+    /// `en-GB`, and responses without confidence never mismatch.
+    #[must_use]
+    pub fn language_mismatch(&self, threshold: f64) -> Option<LanguageMismatch> {
+        let primary = |code: &str| code.split('-').next().unwrap_or_default().to_lowercase();
+        let detected = &self.language.detected_language;
+        let confidence = detected.confidence?;
+
+        (confidence >= threshold && primary(&self.language.code) != primary(&detected.code)).then(
+            || {
+                LanguageMismatch {
+                    requested: self.language.code.clone(),
+                    detected: detected.code.clone(),
+                    detected_name: detected.name.clone(),
+                    confidence,
+                }
+            },
+        )
+    }
+
+    /// Append the matches of another check response to the current, without
+    /// adjusting their offsets.
+    ///
+    /// Offsets in `other` must already refer to the same text as offsets in
+    /// `self`, e.g., when both responses come from checking the same text with
+    /// different rules. To join responses obtained by checking consecutive
+    /// fragments of a text, use [`CheckResponse::append_with_offset`] or
+    /// [`CheckResponseWithContext::append`] instead, otherwise the matches of
+    /// `other` will point to the wrong positions.
+    ///
+    /// Other fields, such as the language, are kept from `self`.
+    #[must_use]
+    pub fn append(self, other: Self) -> Self {
+        self.append_with_offset(other, 0)
+    }
+
+    /// Append the matches of another check response to the current, shifting
+    /// their offsets, as well as sentence ranges, by `offset` characters.
+    ///
+    /// This is what to use when `other` was obtained by checking a fragment
+    /// starting `offset` characters (not bytes) after the start of the text
+    /// checked for `self`. If the text of `self` is available,
+    /// [`CheckResponseWithContext::append`] computes `offset` for you.
+    ///
+    /// Other fields, such as the language, are kept from `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use languagetool_rust::check::CheckResponse;
+    /// # fn join(first: CheckResponse, second: CheckResponse) -> CheckResponse {
+    /// let first_fragment = "Some text.\n\n";
+    /// // Matches of `second` are shifted past the first fragment
+    /// first.append_with_offset(second, first_fragment.chars().count())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn append_with_offset(mut self, mut other: Self, offset: usize) -> Self {
+        for m in other.iter_matches_mut() {
+            m.offset += offset;
+        }
+
+        if let Some(mut sr_other) = other.sentence_ranges.take() {
+            for range in sr_other.iter_mut() {
+                range[0] += offset;
+                range[1] += offset;
+            }
+            match self.sentence_ranges {
+                Some(ref mut sr_self) => sr_self.append(&mut sr_other),
+                None => self.sentence_ranges = Some(sr_other),
+            }
+        }
+
+        self.matches.append(&mut other.matches);
+        experimental::append_with_offset(&mut self.extra, other.extra, offset);
+        self
+    }
+
     /// Return an iterator over matches.
     pub fn iter_matches(&self) -> std::slice::Iter<'_, Match> {
         self.matches.iter()
@@ -933,15 +3535,80 @@ impl CheckResponse {
         self.matches.iter_mut()
     }
 
+    /// Render matches as plain text, one `origin:line:column: message [RULE]`
+    /// line per match, followed by its suggested replacements, if any.
+    ///
+    /// Unlike [`CheckResponse::annotate`], this does not require any optional
+    /// dependency.
+    #[must_use]
+    pub fn render_plain(&self, text: &str, origin: Option<&str>) -> String {
+        if self.matches.is_empty() {
+            return "No error were found in provided text".to_string();
+        }
+        let origin = origin.unwrap_or("-");
+        let mut rendered = String::new();
+
+        for m in self.sorted_matches() {
+            let (line, column) = match &m.more_context {
+                Some(more_context) => (more_context.line_number, more_context.line_offset + 1),
+                None => {
+                    let before: Vec<char> = text.chars().take(m.offset).collect();
+                    let line_start = before.iter().rposition(|c| *c == '\n').map_or(0, |i| i + 1);
+                    (
+                        1 + before.iter().filter(|c| **c == '\n').count(),
+                        1 + before.len() - line_start,
+                    )
+                },
+            };
+
+            if !rendered.is_empty() {
+                rendered.push('\n');
+            }
+            rendered.push_str(&format!(
+                "{origin}:{line}:{column}: {} [{}]",
+                m.message, m.rule.id
+            ));
+            if let Some(key_path) = m.more_context.as_ref().and_then(|c| c.key_path.as_ref()) {
+                rendered.push_str(&format!(" (at key {key_path})"));
+            }
+            if !m.replacements.is_empty() {
+                let replacements: Vec<&str> =
+                    m.replacements.iter().map(|r| r.value.as_str()).collect();
+                rendered.push_str(&format!("\n  suggestions: {}", replacements.join(", ")));
+            }
+        }
+        rendered
+    }
+
     /// Creates an annotated string from current response.
+    ///
+    /// Tabs are expanded to [`DEFAULT_TAB_WIDTH`] columns, see
+    /// [`CheckResponse::annotate_with_tab_width`].
     #[cfg(feature = "annotate")]
     #[must_use]
     pub fn annotate(&self, text: &str, origin: Option<&str>, color: bool) -> String {
+        self.annotate_with_tab_width(text, origin, color, DEFAULT_TAB_WIDTH)
+    }
+
+    /// Creates an annotated string from current response, where tabs are
+    /// expanded to spaces, up to the next multiple of `tab_width` columns.
+    ///
+    /// Otherwise, underlines would not point at the right characters in lines
+    /// containing tabs, as terminals do not render them as a single column.
+    #[cfg(feature = "annotate")]
+    #[must_use]
+    pub fn annotate_with_tab_width(
+        &self,
+        text: &str,
+        origin: Option<&str>,
+        color: bool,
+        tab_width: usize,
+    ) -> String {
         if self.matches.is_empty() {
             return "No error were found in provided text".to_string();
         }
-        let replacements: Vec<_> = self
-            .matches
+        let matches = self.sorted_matches();
+        let replacements: Vec<_> = matches
             .iter()
             .map(|m| {
                 m.replacements.iter().fold(String::new(), |mut acc, r| {
@@ -953,39 +3620,72 @@ impl CheckResponse {
                 })
             })
             .collect();
+        let key_paths: Vec<_> = matches
+            .iter()
+            .map(|m| {
+                m.more_context
+                    .as_ref()
+                    .and_then(|more_context| more_context.key_path.as_ref())
+                    .map(|key_path| format!("at key {key_path}"))
+            })
+            .collect();
+        let contexts: Vec<_> = matches
+            .iter()
+            .map(|m| {
+                expand_tabs(
+                    &m.context.text,
+                    (m.context.offset, m.context.offset + m.context.length),
+                    tab_width,
+                )
+            })
+            .collect();
 
-        let snippets = self.matches.iter().zip(replacements.iter()).map(|(m, r)| {
-            Snippet {
-                title: Some(Annotation {
-                    label: Some(&m.message),
-                    id: Some(&m.rule.id),
-                    annotation_type: AnnotationType::Error,
-                }),
-                footer: vec![],
-                slices: vec![Slice {
-                    source: &m.context.text,
-                    line_start: 1 + text.chars().take(m.offset).filter(|c| *c == '\n').count(),
-                    origin,
-                    fold: true,
-                    annotations: vec![
-                        SourceAnnotation {
-                            label: &m.rule.description,
-                            annotation_type: AnnotationType::Error,
-                            range: (m.context.offset, m.context.offset + m.context.length),
-                        },
-                        SourceAnnotation {
-                            label: r,
-                            annotation_type: AnnotationType::Help,
-                            range: (m.context.offset, m.context.offset + m.context.length),
-                        },
-                    ],
-                }],
-                opt: FormatOptions {
-                    color,
-                    ..Default::default()
-                },
-            }
-        });
+        let snippets = matches
+            .iter()
+            .zip(replacements.iter())
+            .zip(key_paths.iter())
+            .zip(contexts.iter())
+            .map(|(((m, r), key_path), (context, range))| {
+                Snippet {
+                    title: Some(Annotation {
+                        label: Some(&m.message),
+                        id: Some(&m.rule.id),
+                        annotation_type: AnnotationType::Error,
+                    }),
+                    footer: key_path
+                        .iter()
+                        .map(|key_path| {
+                            Annotation {
+                                label: Some(key_path),
+                                id: None,
+                                annotation_type: AnnotationType::Note,
+                            }
+                        })
+                        .collect(),
+                    slices: vec![Slice {
+                        source: context,
+                        line_start: 1 + text.chars().take(m.offset).filter(|c| *c == '\n').count(),
+                        origin,
+                        fold: true,
+                        annotations: vec![
+                            SourceAnnotation {
+                                label: &m.rule.description,
+                                annotation_type: AnnotationType::Error,
+                                range: *range,
+                            },
+                            SourceAnnotation {
+                                label: r,
+                                annotation_type: AnnotationType::Help,
+                                range: *range,
+                            },
+                        ],
+                    }],
+                    opt: FormatOptions {
+                        color,
+                        ..Default::default()
+                    },
+                }
+            });
 
         let mut annotation = String::new();
 
@@ -999,10 +3699,122 @@ impl CheckResponse {
     }
 }
 
+/// Expand tabs in `text` to spaces, up to the next multiple of `tab_width`
+/// columns, and return the expanded text with `range` (in characters) mapped
+/// to it.
+#[cfg(feature = "annotate")]
+fn expand_tabs(text: &str, range: (usize, usize), tab_width: usize) -> (String, (usize, usize)) {
+    let tab_width = tab_width.max(1);
+    let mut expanded = String::with_capacity(text.len());
+    let mut mapped = range;
+    let mut column = 0;
+    let mut len = 0;
+
+    for (i, c) in text.chars().enumerate() {
+        if i == range.0 {
+            mapped.0 = len;
+        }
+        if i == range.1 {
+            mapped.1 = len;
+        }
+        match c {
+            '\t' => {
+                let spaces = tab_width - column % tab_width;
+                expanded.extend(std::iter::repeat(' ').take(spaces));
+                column += spaces;
+                len += spaces;
+            },
+            '\n' => {
+                expanded.push(c);
+                column = 0;
+                len += 1;
+            },
+            _ => {
+                expanded.push(c);
+                column += 1;
+                len += 1;
+            },
+        }
+    }
+    // Ranges may end at the end of the text
+    let count = text.chars().count();
+    if range.0 >= count {
+        mapped.0 = len + range.0 - count;
+    }
+    if range.1 >= count {
+        mapped.1 = len + range.1 - count;
+    }
+
+    (expanded, mapped)
+}
+
+/// Return the char offset at which each line of `text` starts.
+fn line_starts(text: &str) -> Vec<usize> {
+    std::iter::once(0)
+        .chain(
+            text.chars()
+                .enumerate()
+                .filter(|(_, c)| *c == '\n')
+                .map(|(i, _)| i + 1),
+        )
+        .collect()
+}
+
+/// Return the line index and line offset, both starting at 0, of the char at
+/// `offset`, given the char offset at which each line starts.
+fn line_position(line_starts: &[usize], offset: usize) -> (usize, usize) {
+    let line = line_starts.partition_point(|start| *start <= offset) - 1;
+    (line, offset - line_starts[line])
+}
+
+/// Check response, with what the client found out about it.
+///
+/// Unlike the fields of [`CheckResponse`], these are never sent by the
+/// server, and are only serialized if set.
+#[derive(Clone, PartialEq, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct CheckReport {
+    /// Check response.
+    #[serde(flatten)]
+    pub response: CheckResponse,
+    /// Language mismatch, see [`CheckResponse::language_mismatch`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language_mismatch: Option<LanguageMismatch>,
+    /// Parameters of the request the response is to, without credentials,
+    /// see [`CheckRequest::sanitized`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request: Option<CheckRequest>,
+    /// Number of matches removed on the client, by suppression mechanism.
+    #[serde(skip_serializing_if = "SuppressedMatches::is_empty")]
+    pub suppressed: SuppressedMatches,
+    /// Unknown fields of the response, see [`crate::experimental`].
+    #[serde(flatten)]
+    pub experimental: ExperimentalResponse,
+}
+
+impl From<CheckResponse> for CheckReport {
+    /// Build a report from a response, moving its unknown fields to
+    /// [`CheckReport::experimental`].
+    fn from(mut response: CheckResponse) -> Self {
+        let fields = std::mem::take(&mut response.extra);
+        Self {
+            response,
+            language_mismatch: None,
+            request: None,
+            suppressed: SuppressedMatches::default(),
+            experimental: ExperimentalResponse { fields },
+        }
+    }
+}
+
 /// Check response with additional context.
 ///
 /// This structure exists to keep a link between a check response
 /// and the original text that was checked.
+///
+/// The position of any match in the text can then be found, whatever the
+/// order of matches, see [`CheckResponseWithContext::position_of`].
 #[derive(Debug, Clone, PartialEq)]
 pub struct CheckResponseWithContext {
     /// Original text that was checked by LT.
@@ -1014,6 +3826,38 @@ pub struct CheckResponseWithContext {
 }
 
 impl CheckResponseWithContext {
+    /// Bind the response to `request`, a fragment returned by
+    /// [`CheckRequest::try_split`], with the text of this fragment.
+    ///
+    /// The sentences prepended to data fragments as context are removed from
+    /// the text, as well as the matches that start in them, since they were
+    /// already reported with the previous fragment.
+    ///
+    /// # Errors
+    ///
+    /// If the request has neither text nor valid data annotations.
+    pub fn from_fragment(request: &CheckRequest, mut response: CheckResponse) -> Result<Self> {
+        let text = request.try_get_text()?;
+        let context_length = request.context_length;
+        if context_length == 0 {
+            return Ok(Self::new(text, response));
+        }
+
+        response.matches.retain(|m| m.offset >= context_length);
+        for m in response.iter_matches_mut() {
+            m.offset -= context_length;
+        }
+        if let Some(ranges) = &mut response.sentence_ranges {
+            ranges.retain(|[_, end]| *end > context_length);
+            for range in ranges.iter_mut() {
+                range[0] = range[0].saturating_sub(context_length);
+                range[1] -= context_length;
+            }
+        }
+        experimental::remove_prefix(&mut response.extra, context_length);
+        Ok(Self::new(text.chars().skip(context_length).collect(), response))
+    }
+
     /// Bind a check response with its original text.
     #[must_use]
     pub fn new(text: String, response: CheckResponse) -> Self {
@@ -1025,168 +3869,702 @@ impl CheckResponseWithContext {
         }
     }
 
-    /// Return an iterator over matches.
-    pub fn iter_matches(&self) -> std::slice::Iter<'_, Match> {
-        self.response.iter_matches()
+    /// Return the line number and the column, both starting at 1, at which
+    /// `m` starts in the text.
+    ///
+    /// Matches past the end of the text are positioned on its last line. As
+    /// lines are indexed on each call, prefer
+    /// [`CheckResponseWithContext::iter_match_positions`] to position all
+    /// matches.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use languagetool_rust::check::{CheckResponse, CheckResponseWithContext};
+    /// # fn position(first: CheckResponse, second: CheckResponse) {
+    /// let first = CheckResponseWithContext::new("First line.\n".to_string(), first);
+    /// let second = CheckResponseWithContext::new("Second line.".to_string(), second);
+    /// let response = first.append(second);
+    ///
+    /// for m in response.iter_matches() {
+    ///     let (line, column) = response.position_of(m);
+    ///     println!("{line}:{column}: {}", m.message);
+    /// }
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn position_of(&self, m: &Match) -> (usize, usize) {
+        let (line, line_offset) = line_position(&line_starts(&self.text), m.offset);
+        (line + 1, line_offset + 1)
+    }
+
+    /// Return an iterator over matches.
+    pub fn iter_matches(&self) -> std::slice::Iter<'_, Match> {
+        self.response.iter_matches()
+    }
+
+    /// Return an iterator over mutable matches.
+    pub fn iter_matches_mut(&mut self) -> std::slice::IterMut<'_, Match> {
+        self.response.iter_matches_mut()
+    }
+
+    /// Return an iterator over matches and corresponding line number and line
+    /// offset.
+    #[must_use]
+    pub fn iter_match_positions(&self) -> MatchPositions<'_, std::slice::Iter<'_, Match>> {
+        self.into()
+    }
+
+    /// Return the original text where each match is replaced by its first
+    /// suggested replacement.
+    ///
+    /// Matches without any replacement, or overlapping a match that was
+    /// already replaced, are left untouched.
+    #[must_use]
+    pub fn apply_replacements(&self) -> String {
+        let mut matches: Vec<&Match> = self
+            .iter_matches()
+            .filter(|m| !m.replacements.is_empty())
+            .collect();
+        matches.sort_by_key(|m| m.offset);
+
+        let mut end = 0;
+        matches.retain(|m| {
+            let kept = m.offset >= end;
+            if kept {
+                end = m.offset + m.length;
+            }
+            kept
+        });
+
+        // Replacing from the end keeps the offsets of remaining matches valid
+        matches.iter().rev().fold(self.text.clone(), |text, m| {
+            m.apply_to(&text, 0).map_or(text, |(edited, _)| edited)
+        })
+    }
+
+    /// Return the matches grouped by rule id and matched text, e.g., all the
+    /// occurrences of the same misspelling, in order of first occurrence.
+    #[must_use]
+    pub fn identical_matches(&self) -> Vec<Vec<&Match>> {
+        let mut matches: Vec<&Match> = self.iter_matches().collect();
+        matches.sort_by_key(|m| m.offset);
+
+        let mut groups: Vec<Vec<&Match>> = Vec::new();
+        let mut indices = std::collections::HashMap::new();
+        for m in matches {
+            let i = *indices
+                .entry((m.rule.id.as_str(), m.matched_text()))
+                .or_insert_with(|| {
+                    groups.push(Vec::new());
+                    groups.len() - 1
+                });
+            groups[i].push(m);
+        }
+        groups
+    }
+
+    /// Return the original text where all the occurrences of identical
+    /// matches (see [`CheckResponseWithContext::identical_matches`]) are
+    /// replaced by the first suggested replacement of the first occurrence,
+    /// and the replacements that were applied.
+    ///
+    /// Unlike [`CheckResponseWithContext::apply_replacements`], this never
+    /// fixes the same mistake in different ways.
+    #[must_use]
+    pub fn apply_grouped_replacements(&self) -> (String, Vec<GroupedReplacement>) {
+        let mut grouped = self.clone();
+        grouped.response.matches.clear();
+        let mut applied = Vec::new();
+
+        for group in self.identical_matches() {
+            let Some(replacement) = group[0].replacements.first() else {
+                continue;
+            };
+            grouped.response.matches.extend(group.iter().map(|m| {
+                let mut m = (*m).clone();
+                m.replacements = vec![replacement.clone()];
+                m
+            }));
+            applied.push(GroupedReplacement {
+                rule_id: group[0].rule.id.clone(),
+                text: group[0].matched_text(),
+                replacement: replacement.value.clone(),
+                occurrences: group.len(),
+            });
+        }
+
+        (grouped.apply_replacements(), applied)
+    }
+
+    /// Remove matches on unit expressions (see [`is_unit_expression`]), such
+    /// as `"5 µm"`, where the match may only cover the unit.
+    pub fn remove_unit_matches(&mut self) {
+        let chars: Vec<char> = self.text.chars().collect();
+
+        self.response.matches.retain(|m| {
+            let end = (m.offset + m.length).min(chars.len());
+            let mut start = m.offset.min(end);
+
+            // Include the number preceding the unit, if any.
+            if start > 0 && matches!(chars[start - 1], ' ' | '\u{a0}' | '\u{202f}') {
+                start -= 1;
+            }
+            while start > 0 && matches!(chars[start - 1], '0'..='9' | '.' | ',') {
+                start -= 1;
+            }
+
+            let expression: String = chars[start..end].iter().collect();
+            !is_unit_expression(&expression)
+        });
+    }
+
+    /// Append a check response to the current while
+    /// adjusting the matches' offsets.
+    ///
+    /// This is especially useful when a text was split in multiple requests,
+    /// as offsets are shifted by the length of the current text, see
+    /// [`CheckResponse::append_with_offset`].
+    #[must_use]
+    pub fn append(mut self, other: Self) -> Self {
+        self.response = self
+            .response
+            .append_with_offset(other.response, self.text_length);
+        self.text.push_str(other.text.as_str());
+        self.text_length += other.text_length;
+        self
+    }
+}
+
+/// Warning raised while checking, that does not prevent a (possibly partial)
+/// response from being returned.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CheckWarning {
+    /// The deadline elapsed before every fragment was checked.
+    ///
+    /// Missing fragments keep their text in the joined response, so that
+    /// offsets remain valid, but they do not contain any match.
+    DeadlineExceeded {
+        /// Indices of the fragments that were not checked in time.
+        missing: Vec<usize>,
+        /// Total number of fragments.
+        total: usize,
+    },
+}
+
+impl std::fmt::Display for CheckWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::DeadlineExceeded { missing, total } => {
+                write!(
+                    f,
+                    "deadline exceeded: {} out of {total} fragment(s) were not checked",
+                    missing.len()
+                )
+            },
+        }
+    }
+}
+
+/// Replacement applied to all the occurrences of identical matches, see
+/// [`CheckResponseWithContext::apply_grouped_replacements`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct GroupedReplacement {
+    /// Rule id of the matches.
+    pub rule_id: String,
+    /// Matched text.
+    pub text: String,
+    /// Value replacing the matched text.
+    pub replacement: String,
+    /// Number of replaced occurrences.
+    pub occurrences: usize,
+}
+
+impl std::fmt::Display for GroupedReplacement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "replaced {} occurrence(s) of \"{}\" with \"{}\" ({})",
+            self.occurrences, self.text, self.replacement, self.rule_id
+        )
+    }
+}
+
+/// Return the UTF-16 code unit index of each char of `text`, followed by the
+/// length of `text` in UTF-16 code units.
+fn utf16_offsets(text: &str) -> Vec<usize> {
+    let mut offsets = Vec::with_capacity(text.len() + 1);
+    let mut offset = 0;
+    for c in text.chars() {
+        offsets.push(offset);
+        offset += c.len_utf16();
+    }
+    offsets.push(offset);
+    offsets
+}
+
+impl From<CheckResponseWithContext> for CheckResponse {
+    fn from(mut resp: CheckResponseWithContext) -> Self {
+        resp.response.normalize();
+        // Char and UTF-16 indices only differ outside of the ASCII range
+        let offsets = (!resp.text.is_ascii()).then(|| utf16_offsets(&resp.text));
+        let to_utf16 = |offset: usize| {
+            match &offsets {
+                Some(offsets) => offsets[offset.min(offsets.len() - 1)],
+                None => offset,
+            }
+        };
+        let positions: Vec<(usize, usize)> = resp
+            .iter_match_positions()
+            .map(|(line_number, line_offset, _)| (line_number, line_offset + 1))
+            .collect();
+
+        let matches = resp.response.iter_matches_mut();
+
+        for ((line_number, column), m) in positions.into_iter().zip(matches) {
+            let line_offset = column - 1;
+            let utf16_offset = to_utf16(m.offset);
+            m.more_context = Some(MoreContext {
+                line_number,
+                line_offset,
+                column,
+                utf16_offset,
+                utf16_length: to_utf16(m.offset + m.length) - utf16_offset,
+                utf16_line_offset: utf16_offset - to_utf16(m.offset - line_offset),
+                key_path: None,
+            });
+        }
+        resp.response
+    }
+}
+
+/// Iterator over matches and their corresponding line number and line offset.
+///
+/// Matches do not need to be sorted, see
+/// [`CheckResponseWithContext::position_of`].
+#[derive(Clone, Debug)]
+pub struct MatchPositions<'source, T> {
+    line_starts: Vec<usize>,
+    matches: T,
+    line_number: usize,
+    source: std::marker::PhantomData<&'source str>,
+}
+
+impl<'source> From<&'source CheckResponseWithContext>
+    for MatchPositions<'source, std::slice::Iter<'source, Match>>
+{
+    fn from(response: &'source CheckResponseWithContext) -> Self {
+        MatchPositions {
+            line_starts: line_starts(&response.text),
+            matches: response.iter_matches(),
+            line_number: 1,
+            source: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<'source> From<&'source mut CheckResponseWithContext>
+    for MatchPositions<'source, std::slice::IterMut<'source, Match>>
+{
+    fn from(response: &'source mut CheckResponseWithContext) -> Self {
+        MatchPositions {
+            line_starts: line_starts(&response.text),
+            matches: response.response.iter_matches_mut(),
+            line_number: 1,
+            source: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<'source, T> MatchPositions<'source, T> {
+    /// Set the line number to a give value.
+    ///
+    /// By default, the first line number is 1.
+    pub fn set_line_number(mut self, line_number: usize) -> Self {
+        self.line_number = line_number;
+        self
+    }
+
+    fn line_number_and_offset(&self, m: &Match) -> (usize, usize) {
+        let (line, line_offset) = line_position(&self.line_starts, m.offset);
+        (self.line_number + line, line_offset)
+    }
+}
+
+impl<'source> Iterator for MatchPositions<'source, std::slice::Iter<'source, Match>> {
+    type Item = (usize, usize, &'source Match);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let m = self.matches.next()?;
+        let (line_number, line_offset) = self.line_number_and_offset(m);
+        Some((line_number, line_offset, m))
+    }
+}
+
+impl<'source> Iterator for MatchPositions<'source, std::slice::IterMut<'source, Match>> {
+    type Item = (usize, usize, &'source mut Match);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let m = self.matches.next()?;
+        let (line_number, line_offset) = self.line_number_and_offset(m);
+        Some((line_number, line_offset, m))
+    }
+}
+
+/// Options of [`ServerClient::check_text`](crate::server::ServerClient::check_text)
+/// and [`ServerClient::check_file`](crate::server::ServerClient::check_file),
+/// like the ones of `ltrs check`.
+///
+/// # Examples
+///
+/// ```
+/// # use languagetool_rust::{check::CheckOptions, ignore::MatchFilter, parsers::FileType};
+/// let options = CheckOptions::default()
+///     .with_file_type(FileType::Markdown)
+///     .with_filter(MatchFilter::default().with_ignore_spelling(true));
+/// ```
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct CheckOptions {
+    /// Request whose text or data is replaced by each checked text, e.g., to
+    /// set the language or the enabled rules.
+    pub request: CheckRequest,
+    /// Type of the checked text, guessed from the file's extension by
+    /// default.
+    pub file_type: crate::parsers::FileType,
+    /// Options used to parse documents.
+    pub parse: crate::parsers::ParseOptions,
+    /// Maximum number of characters before splitting.
+    pub max_length: usize,
+    /// Pattern on which long texts are split.
+    pub split_pattern: String,
+    /// How to handle control characters in raw text.
+    pub control_chars: ControlChars,
+    /// If `true`, suppress the matches listed in the closest ignore file of
+    /// checked files, see [`IgnoreFile`](crate::ignore::IgnoreFile).
+    pub ignore_file: bool,
+    /// If `true`, suppress the matches on lines with inline ignore comments,
+    /// see [`InlineIgnores`](crate::ignore::InlineIgnores).
+    pub inline_ignores: bool,
+    /// Words whose spelling matches are suppressed.
+    pub known_words: Option<crate::ignore::KnownWords>,
+    /// Filter applied to the matches.
+    pub filter: crate::ignore::MatchFilter,
+}
+
+impl Default for CheckOptions {
+    fn default() -> Self {
+        Self {
+            request: CheckRequest::default(),
+            file_type: crate::parsers::FileType::default(),
+            parse: crate::parsers::ParseOptions::default(),
+            max_length: 1500,
+            split_pattern: "\n\n".to_string(),
+            control_chars: ControlChars::default(),
+            ignore_file: true,
+            inline_ignores: true,
+            known_words: None,
+            filter: crate::ignore::MatchFilter::default(),
+        }
+    }
+}
+
+impl CheckOptions {
+    /// Set the request used as a template.
+    #[must_use]
+    pub fn with_request(mut self, request: CheckRequest) -> Self {
+        self.request = request;
+        self
+    }
+
+    /// Set the language, see [`CheckRequest::with_language`].
+    #[must_use]
+    pub fn with_language(mut self, language: LanguageCode) -> Self {
+        self.request = self.request.with_language(language);
+        self
+    }
+
+    /// Set the type of the checked text.
+    #[must_use]
+    pub fn with_file_type(mut self, file_type: crate::parsers::FileType) -> Self {
+        self.file_type = file_type;
+        self
     }
 
-    /// Return an iterator over mutable matches.
-    pub fn iter_matches_mut(&mut self) -> std::slice::IterMut<'_, Match> {
-        self.response.iter_matches_mut()
+    /// Set the options used to parse documents.
+    #[must_use]
+    pub fn with_parse_options(mut self, parse: crate::parsers::ParseOptions) -> Self {
+        self.parse = parse;
+        self
     }
 
-    /// Return an iterator over matches and corresponding line number and line
-    /// offset.
+    /// Set the maximum number of characters before splitting, and the
+    /// pattern on which long texts are split.
     #[must_use]
-    pub fn iter_match_positions(&self) -> MatchPositions<'_, std::slice::Iter<'_, Match>> {
-        self.into()
+    pub fn with_split(mut self, max_length: usize, split_pattern: impl Into<String>) -> Self {
+        self.max_length = max_length;
+        self.split_pattern = split_pattern.into();
+        self
     }
 
-    /// Append a check response to the current while
-    /// adjusting the matches' offsets.
-    ///
-    /// This is especially useful when a text was split in multiple requests.
+    /// Set how control characters are handled.
     #[must_use]
-    pub fn append(mut self, mut other: Self) -> Self {
-        let offset = self.text_length;
-        for m in other.iter_matches_mut() {
-            m.offset += offset;
-        }
+    pub fn with_control_chars(mut self, control_chars: ControlChars) -> Self {
+        self.control_chars = control_chars;
+        self
+    }
 
-        #[cfg(feature = "unstable")]
-        if let Some(ref mut sr_other) = other.response.sentence_ranges {
-            match self.response.sentence_ranges {
-                Some(ref mut sr_self) => {
-                    sr_self.append(sr_other);
-                },
-                None => {
-                    std::mem::swap(
-                        &mut self.response.sentence_ranges,
-                        &mut other.response.sentence_ranges,
-                    );
-                },
-            }
-        }
+    /// Set whether ignore files and inline ignore comments are honored.
+    #[must_use]
+    pub fn with_ignores(mut self, ignore_file: bool, inline_ignores: bool) -> Self {
+        self.ignore_file = ignore_file;
+        self.inline_ignores = inline_ignores;
+        self
+    }
 
-        self.response.matches.append(&mut other.response.matches);
-        self.text.push_str(other.text.as_str());
-        self.text_length += other.text_length;
+    /// Set the words whose spelling matches are suppressed.
+    #[must_use]
+    pub fn with_known_words(mut self, known_words: crate::ignore::KnownWords) -> Self {
+        self.known_words = Some(known_words);
         self
     }
-}
 
-impl From<CheckResponseWithContext> for CheckResponse {
-    #[allow(clippy::needless_borrow)]
-    fn from(mut resp: CheckResponseWithContext) -> Self {
-        let iter: MatchPositions<'_, std::slice::IterMut<'_, Match>> = (&mut resp).into();
+    /// Set the filter applied to the matches.
+    #[must_use]
+    pub fn with_filter(mut self, filter: crate::ignore::MatchFilter) -> Self {
+        self.filter = filter;
+        self
+    }
 
-        for (line_number, line_offset, m) in iter {
-            m.more_context = Some(MoreContext {
-                line_number,
-                line_offset,
-            });
+    /// Parse `text`, found in `file`, if any, into the request to check, see
+    /// [`PreparedText`].
+    ///
+    /// The request is checked in the language the document declares, if any
+    /// and if the language of [`CheckOptions::request`] is automatic.
+    #[must_use]
+    pub fn prepare<'source>(
+        &self,
+        text: &'source str,
+        file: Option<&'source Path>,
+    ) -> PreparedText<'source> {
+        let document = self.file_type.resolve(file).parse(text, &self.parse);
+        let mut request = self.request.clone();
+        let mut prepared = PreparedText {
+            text,
+            file,
+            document,
+            sanitized: None,
+            request: CheckRequest::default(),
+        };
+
+        if let Some(language) = prepared.declared_language() {
+            if request.language.is_auto() {
+                request = request.with_language(language);
+            }
         }
-        resp.response
+        prepared.request = match &prepared.document {
+            Some(document) => request.with_data(document.data.clone()),
+            None => {
+                let sanitized = SanitizedText::new(text, self.control_chars);
+                let request = request.with_text(sanitized.text.clone());
+                prepared.sanitized = Some(sanitized);
+                request
+            },
+        };
+        prepared
     }
 }
 
-/// Iterator over matches and their corresponding line number and line offset.
+/// Text parsed by [`CheckOptions::prepare`], whose request is to be split,
+/// sent, and whose response is to be passed to [`PreparedText::finish`].
 #[derive(Clone, Debug)]
-pub struct MatchPositions<'source, T> {
-    text_chars: std::str::Chars<'source>,
-    matches: T,
-    line_number: usize,
-    line_offset: usize,
-    offset: usize,
+pub struct PreparedText<'source> {
+    /// Checked text.
+    text: &'source str,
+    /// File where the text was found, if any.
+    file: Option<&'source Path>,
+    /// Parsed document, unless the text is raw.
+    document: Option<crate::parsers::Document>,
+    /// Raw text, with control characters handled.
+    sanitized: Option<SanitizedText>,
+    /// Request with the text or data to check.
+    pub request: CheckRequest,
 }
 
-impl<'source> From<&'source CheckResponseWithContext>
-    for MatchPositions<'source, std::slice::Iter<'source, Match>>
-{
-    fn from(response: &'source CheckResponseWithContext) -> Self {
-        MatchPositions {
-            text_chars: response.text.chars(),
-            matches: response.iter_matches(),
-            line_number: 1,
-            line_offset: 0,
-            offset: 0,
-        }
+impl PreparedText<'_> {
+    /// Return the language the document declares to be written in, if any
+    /// and if it is valid.
+    #[must_use]
+    pub fn declared_language(&self) -> Option<LanguageCode> {
+        self.document
+            .as_ref()
+            .and_then(|document| document.language.as_deref())
+            .and_then(|language| language.parse().ok())
     }
-}
 
-impl<'source> From<&'source mut CheckResponseWithContext>
-    for MatchPositions<'source, std::slice::IterMut<'source, Match>>
-{
-    fn from(response: &'source mut CheckResponseWithContext) -> Self {
-        MatchPositions {
-            text_chars: response.text.chars(),
-            matches: response.response.iter_matches_mut(),
-            line_number: 1,
-            line_offset: 0,
-            offset: 0,
-        }
+    /// Return the data annotations of the parsed document, or `None` if the
+    /// text is raw.
+    #[must_use]
+    pub fn data(&self) -> Option<&Data> {
+        self.document.as_ref().map(|document| &document.data)
     }
-}
 
-impl<'source, T> MatchPositions<'source, T> {
-    /// Set the line number to a give value.
+    /// Turn the joined `response` to the request into a response that refers
+    /// to the checked text, with the context of each match, and remove the
+    /// matches that are ignored, see [`CheckOptions`].
     ///
-    /// By default, the first line number is 1.
-    pub fn set_line_number(mut self, line_number: usize) -> Self {
-        self.line_number = line_number;
-        self
+    /// # Errors
+    ///
+    /// If the ignore file of the checked file is not valid.
+    pub fn finish(self, response: CheckResponse, options: &CheckOptions) -> Result<CheckResponse> {
+        self.finish_counting(response, options)
+            .map(|(response, _)| response)
     }
 
-    fn update_line_number_and_offset(&mut self, m: &Match) {
-        let n = m.offset - self.offset;
-        for _ in 0..n {
-            match self.text_chars.next() {
-                Some('\n') => {
-                    self.line_number += 1;
-                    self.line_offset = 0;
-                },
-                None => {
-                    panic!(
-                        "text is shorter than expected, are you sure this text was the one used \
-                         for the check request?"
-                    )
-                },
-                _ => self.line_offset += 1,
+    /// Like [`PreparedText::finish`], but also return the number of matches
+    /// removed by each suppression mechanism.
+    ///
+    /// # Errors
+    ///
+    /// If the ignore file of the checked file is not valid.
+    pub fn finish_counting(
+        self,
+        mut response: CheckResponse,
+        options: &CheckOptions,
+    ) -> Result<(CheckResponse, SuppressedMatches)> {
+        if let Some(sanitized) = &self.sanitized {
+            sanitized.restore_offsets(&mut response);
+        }
+        let mut response: CheckResponse =
+            CheckResponseWithContext::new(self.text.to_string(), response).into();
+        if let Some(document) = self.document {
+            document.set_key_paths(&mut response);
+            document.set_source_context(&mut response);
+        }
+
+        let mut suppressed = SuppressedMatches::default();
+        if let Some(file) = self.file.filter(|_| options.ignore_file) {
+            if let Some(ignore) = crate::ignore::IgnoreFile::discover(file)? {
+                suppressed.ignore_file = ignore.filter(Some(file), &mut response);
             }
         }
-        self.offset = m.offset;
+        if options.inline_ignores {
+            suppressed.inline_ignores =
+                crate::ignore::InlineIgnores::parse(self.text).filter(self.text, &mut response);
+        }
+        if let Some(known_words) = &options.known_words {
+            suppressed.known_words = known_words.filter(&mut response);
+        }
+        suppressed.ignore_patterns = options.filter.filter_patterns(&mut response);
+        suppressed.filter = options.filter.filter(&mut response);
+        Ok((response, suppressed))
     }
 }
 
-impl<'source> Iterator for MatchPositions<'source, std::slice::Iter<'source, Match>> {
-    type Item = (usize, usize, &'source Match);
+/// Match with the file, if any, and the position in this file where it
+/// occurred.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[non_exhaustive]
+pub struct PositionedMatch {
+    /// File where the match occurred.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<PathBuf>,
+    /// Line number, starting at 1.
+    pub line: usize,
+    /// Column, in chars, starting at 1.
+    pub column: usize,
+    /// Match, whose offset refers to the checked text.
+    pub r#match: Match,
+}
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if let Some(m) = self.matches.next() {
-            self.update_line_number_and_offset(m);
-            Some((self.line_number, self.line_offset, m))
-        } else {
-            None
-        }
+impl PositionedMatch {
+    /// Position the matches of `response`, whose
+    /// [`Match::more_context`] was computed, e.g., with
+    /// [`CheckResponseWithContext`], found in `file`.
+    #[must_use]
+    pub fn from_response(file: Option<&Path>, response: CheckResponse) -> Vec<Self> {
+        response
+            .matches
+            .into_iter()
+            .map(|m| {
+                let (line, column) = match &m.more_context {
+                    Some(more_context) => (more_context.line_number, more_context.column),
+                    None => (1, m.offset + 1),
+                };
+                Self {
+                    file: file.map(Path::to_path_buf),
+                    line,
+                    column,
+                    r#match: m,
+                }
+            })
+            .collect()
     }
 }
 
-impl<'source> Iterator for MatchPositions<'source, std::slice::IterMut<'source, Match>> {
-    type Item = (usize, usize, &'source mut Match);
+/// Utilities shared by tests across modules.
+#[cfg(test)]
+pub(crate) mod test_utils {
+    use super::CheckResponse;
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if let Some(m) = self.matches.next() {
-            self.update_line_number_and_offset(m);
-            Some((self.line_number, self.line_offset, m))
-        } else {
-            None
-        }
+    /// Build a check response for `text` with the given matches, each
+    /// described by its offset, length and replacements.
+    pub(crate) fn response_with_matches(
+        text: &str,
+        matches: &[(usize, usize, &[&str])],
+    ) -> CheckResponse {
+        let matches: Vec<_> = matches
+            .iter()
+            .map(|(offset, length, replacements)| {
+                serde_json::json!({
+                    "context": {"length": length, "offset": offset, "text": text},
+                    "contextForSureMatch": 0,
+                    "ignoreForIncompleteSentence": false,
+                    "length": length,
+                    "message": "",
+                    "offset": offset,
+                    "replacements": replacements
+                        .iter()
+                        .map(|value| serde_json::json!({"value": value}))
+                        .collect::<Vec<_>>(),
+                    "rule": {
+                        "category": {"id": "TYPOS", "name": "Possible Typo"},
+                        "description": "",
+                        "id": "MORFOLOGIK_RULE_EN_US",
+                        "issueType": "misspelling",
+                    },
+                    "sentence": "",
+                    "shortMessage": "",
+                    "type": {"typeName": "Other"},
+                })
+            })
+            .collect();
+
+        serde_json::from_value(serde_json::json!({
+            "language": {
+                "code": "en-US",
+                "detectedLanguage": {"code": "en-US", "name": "English (US)"},
+                "name": "English (US)",
+            },
+            "matches": matches,
+            "software": {
+                "apiVersion": 1,
+                "buildDate": "",
+                "name": "LanguageTool",
+                "premium": false,
+                "status": "",
+                "version": "",
+            },
+        }))
+        .unwrap()
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::*;
+    use super::{test_utils::response_with_matches, *};
 
     #[derive(Debug)]
     enum Token<'source> {
@@ -1194,6 +4572,7 @@ mod tests {
         Skip(&'source str),
     }
 
+    #[allow(dead_code)]
     #[derive(Debug, Clone)]
     struct ParseTokenError;
 
@@ -1233,6 +4612,373 @@ mod tests {
         assert_eq!(data, expected_data);
     }
 
+    #[test]
+    fn test_language_mismatch() {
+        let mut response = response_with_matches("Bonjour à tous.", &[]);
+
+        assert_eq!(response.language_mismatch(0.9), None);
+
+        response.language.detected_language.code = "fr".to_string();
+        response.language.detected_language.name = "French".to_string();
+        response.language.detected_language.confidence = Some(0.95);
+
+        let mismatch = response.language_mismatch(0.9).unwrap();
+        assert_eq!(mismatch.requested, "en-US");
+        assert_eq!(mismatch.detected, "fr");
+        assert!(mismatch.to_string().contains("`--language fr`"));
+        assert_eq!(response.language_mismatch(0.99), None);
+
+        response.language.detected_language.code = "en-GB".to_string();
+        assert_eq!(response.language_mismatch(0.9), None);
+    }
+
+    #[test]
+    fn test_render_plain() {
+        let text = "Some text\nwith a smal mistake.";
+        let response = response_with_matches(text, &[(17, 4, &["small", "seal"]), (0, 4, &[])]);
+
+        // Matches are sorted by position
+        assert_eq!(
+            response.render_plain(text, Some("file.txt")),
+            "file.txt:1:1:  [MORFOLOGIK_RULE_EN_US]\nfile.txt:2:8:  \
+             [MORFOLOGIK_RULE_EN_US]\n  suggestions: small, seal"
+        );
+    }
+
+    #[test]
+    fn test_summary() {
+        let text = "Some text with a few mistakes.";
+        let mut response = response_with_matches(
+            text,
+            &[(0, 4, &[]), (5, 4, &[]), (10, 4, &[]), (15, 1, &[])],
+        );
+        for (m, issue_type) in response
+            .iter_matches_mut()
+            .zip(["misspelling", "grammar", "style", "typographical"])
+        {
+            m.rule.issue_type = issue_type.to_string();
+        }
+
+        let summary = response.summary();
+        assert_eq!(summary.total(), 4);
+        assert_eq!(
+            summary.to_string(),
+            "4 issues: 1 spelling, 1 grammar, 1 style, 1 other"
+        );
+
+        response.matches.truncate(1);
+        assert_eq!(
+            response.summary().to_string(),
+            "1 issue: 1 spelling, 0 grammar, 0 style"
+        );
+        response.matches.clear();
+        assert_eq!(response.summary().to_string(), "no issues");
+    }
+
+    #[test]
+    fn test_suppressed_matches() {
+        let mut suppressed = SuppressedMatches::default();
+        assert_eq!(suppressed.total(), 0);
+        assert_eq!(suppressed.to_string(), "0 suppressed");
+
+        suppressed.ignore_file = 2;
+        suppressed.ignore_patterns = 1;
+        assert_eq!(suppressed.total(), 3);
+        assert_eq!(
+            suppressed.to_string(),
+            "3 suppressed: 2 by ignore file, 1 by ignore patterns"
+        );
+
+        // Reports only list suppressed matches if there are any
+        let mut report = CheckReport::from(response_with_matches("Some text.", &[]));
+        let json = serde_json::to_value(&report).unwrap();
+        assert!(json.get("suppressed").is_none());
+
+        report.suppressed = suppressed;
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["suppressed"]["ignoreFile"], 2);
+        assert_eq!(json["suppressed"]["ignorePatterns"], 1);
+        assert_eq!(json["suppressed"]["knownWords"], 0);
+    }
+
+    #[test]
+    fn test_append_with_offset() {
+        let first = response_with_matches("Thé smal", &[(4, 4, &["small"])]);
+        let second = response_with_matches("A smal", &[(2, 4, &["small"])]);
+        let offsets = |response: &CheckResponse| -> Vec<usize> {
+            response.iter_matches().map(|m| m.offset).collect()
+        };
+
+        let appended = first.clone().append(second.clone());
+        assert_eq!(offsets(&appended), vec![4, 2]);
+
+        let shifted = first.clone().append_with_offset(second.clone(), 9);
+        assert_eq!(offsets(&shifted), vec![4, 11]);
+
+        let joined = CheckResponseWithContext::new("Thé smal\n".to_string(), first)
+            .append(CheckResponseWithContext::new("A smal".to_string(), second));
+        assert_eq!(offsets(&joined.response), offsets(&shifted));
+    }
+
+    #[test]
+    fn test_normalize() {
+        let text = "A smal and smal text";
+        let mut response = response_with_matches(
+            text,
+            &[(11, 4, &["small"]), (2, 4, &["small"]), (11, 4, &["small"]), (2, 8, &[])],
+        );
+        response.normalize();
+        let positions: Vec<_> = response
+            .iter_matches()
+            .map(|m| (m.offset, m.length))
+            .collect();
+
+        assert_eq!(positions, vec![(2, 4), (2, 8), (11, 4)]);
+    }
+
+    #[test]
+    fn test_more_context_positions() {
+        let text = "A smal text\n🎉 A smal party";
+        let response = response_with_matches(text, &[(2, 4, &["small"]), (16, 4, &["small"])]);
+        let response: CheckResponse =
+            CheckResponseWithContext::new(text.to_string(), response).into();
+        let positions: Vec<_> = response
+            .iter_matches()
+            .map(|m| {
+                let c = m.more_context.as_ref().unwrap();
+                (
+                    c.line_number,
+                    c.column,
+                    c.utf16_offset,
+                    c.utf16_length,
+                    c.utf16_line_offset,
+                )
+            })
+            .collect();
+
+        assert_eq!(positions, vec![(1, 3, 2, 4, 2), (2, 5, 17, 4, 5)]);
+    }
+
+    #[test]
+    fn test_position_of_joined_responses() {
+        let first = response_with_matches("Thé smal\nline", &[(4, 4, &["small"])]);
+        let second = response_with_matches(" and a smal\nA smal", &[(14, 4, &[]), (7, 4, &[])]);
+        let joined = CheckResponseWithContext::new("Thé smal\nline".to_string(), first)
+            .append(CheckResponseWithContext::new(" and a smal\nA smal".to_string(), second));
+        let positions: Vec<_> = joined
+            .iter_matches()
+            .map(|m| joined.position_of(m))
+            .collect();
+
+        // Matches are not sorted, and the second fragment starts mid-line
+        assert_eq!(positions, vec![(1, 5), (3, 3), (2, 12)]);
+        let lines: Vec<_> = joined
+            .iter_match_positions()
+            .map(|(line, line_offset, _)| (line, line_offset + 1))
+            .collect();
+        assert_eq!(lines, positions);
+
+        let response: CheckResponse = joined.into();
+        let more_context = response.matches[2].more_context.as_ref().unwrap();
+        assert_eq!((more_context.line_number, more_context.column), (3, 3));
+    }
+
+    #[test]
+    fn test_split_sentences() {
+        let text = "Été chaud. Très long, vraiment très long. Fin.";
+        let ranges = sentence_ranges(text);
+        assert_eq!(ranges, vec![[0, 10], [11, 41], [42, 46]]);
+
+        // A sentence longer than `n` is never cut
+        let split = split_sentences(text, 12, &ranges);
+        assert_eq!(split, vec!["Été chaud. ", "Très long, vraiment très long. ", "Fin."]);
+        assert_eq!(split_sentences(text, 100, &ranges), vec![text]);
+        assert_eq!(split_sentences("", 10, &[]), Vec::<&str>::new());
+
+        let request = CheckRequest::default().with_text(text.to_string());
+        let fragments = request.try_split_sentences(12).unwrap();
+        assert_eq!(fragments.len(), 3);
+        assert_eq!(fragments[1].text.as_deref(), Some(split[1]));
+    }
+
+    #[test]
+    fn test_remove_unit_matches() {
+        let text = "It measures 5 µm and 3 apples.";
+        let response = response_with_matches(text, &[(14, 2, &["um"]), (23, 6, &["apple"])]);
+        let mut response = CheckResponseWithContext::new(text.to_string(), response);
+
+        response.remove_unit_matches();
+
+        assert_eq!(response.response.matches.len(), 1);
+        assert_eq!(response.response.matches[0].offset, 23);
+    }
+
+    #[cfg(feature = "annotate")]
+    #[test]
+    fn test_expand_tabs() {
+        assert_eq!(
+            expand_tabs("a\tbc\td", (5, 6), 4),
+            ("a   bc  d".to_string(), (8, 9))
+        );
+        assert_eq!(
+            expand_tabs("\tx\n\ty", (3, 5), 2),
+            ("  x\n  y".to_string(), (4, 7))
+        );
+        assert_eq!(expand_tabs("ab", (2, 2), 8), ("ab".to_string(), (2, 2)));
+    }
+
+    #[test]
+    fn test_match_apply_to() {
+        let text = "Thé smal mistaek.";
+        let response = response_with_matches(text, &[(4, 4, &["small", "seal"]), (17, 0, &["!"])]);
+        let (small, end) = (&response.matches[0], &response.matches[1]);
+
+        assert_eq!(
+            small.apply_to(text, 0),
+            Some(("Thé small mistaek.".to_string(), 1))
+        );
+        assert_eq!(
+            small.apply_to(text, 1),
+            Some(("Thé seal mistaek.".to_string(), 0))
+        );
+        assert_eq!(small.apply_to(text, 2), None);
+        assert_eq!(
+            end.apply_to(text, 0),
+            Some(("Thé smal mistaek.!".to_string(), 1))
+        );
+        assert_eq!(end.apply_to("Too short.", 0), None);
+    }
+
+    #[test]
+    fn test_apply_replacements() {
+        let text = "Some phrase with a smal mistaek.".to_string();
+        let response =
+            response_with_matches(&text, &[(19, 4, &["small", "seal"]), (24, 7, &["mistake"])]);
+        let response = CheckResponseWithContext::new(text, response);
+
+        assert_eq!(
+            response.apply_replacements(),
+            "Some phrase with a small mistake."
+        );
+    }
+
+    #[test]
+    fn test_apply_replacements_skips_empty_and_overlapping() {
+        let text = "Thé smal mistaek.".to_string();
+        let response =
+            response_with_matches(&text, &[(4, 4, &[]), (9, 7, &["mistake"]), (12, 4, &["x"])]);
+        let response = CheckResponseWithContext::new(text, response);
+
+        assert_eq!(response.apply_replacements(), "Thé smal mistake.");
+    }
+
+    #[test]
+    fn test_response_with_sanitized_request() {
+        let mut request = CheckRequest::default()
+            .with_text("Some smal text.".to_string())
+            .with_language(LanguageCode::EN_US);
+        request.username = Some("me@example.com".to_string());
+        request.api_key = Some("secret".to_string());
+        request.disabled_rules = Some(vec!["A".to_string(), "B".to_string()]);
+        let response = response_with_matches("Some smal text.", &[(5, 4, &["small"])]);
+        let mut report = CheckReport::from(response.clone());
+        report.request = Some(request.sanitized());
+
+        let json = serde_json::to_string(&report).unwrap();
+        assert!(json.contains(r#""language":"en-US""#));
+        assert!(!json.contains("me@example.com"));
+        assert!(!json.contains("secret"));
+
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            serde_json::from_value::<CheckRequest>(value["request"].clone()).unwrap(),
+            request.sanitized()
+        );
+        // The request is not part of the response
+        let parsed: CheckResponse = serde_json::from_value(value).unwrap();
+        assert_eq!(parsed.matches, response.matches);
+        assert!(!serde_json::to_string(&response).unwrap().contains("request"));
+    }
+
+    #[test]
+    fn test_response_unknown_fields() {
+        let response = response_with_matches("Some smal text.", &[(5, 4, &["small"])]);
+        let mut value = serde_json::to_value(&response).unwrap();
+        value["software"]["premiumHint"] = "Premium finds more".into();
+        value["sentenceRanges"] = serde_json::json!([[0, 15]]);
+        value["hiddenMatches"] = serde_json::json!([]);
+        value["matches"][0]["newField"] = 1.into();
+        value["matches"][0]["rule"]["confidence"] = 0.9.into();
+        value["matches"][0]["replacements"][0]["shortDescription"] = "adj.".into();
+        value["matches"][0].as_object_mut().unwrap().remove("type");
+
+        let response: CheckResponse = serde_json::from_value(value.clone()).unwrap();
+        let m = &response.matches[0];
+        assert_eq!(m.extra["newField"], 1);
+        assert_eq!(m.rule.extra["confidence"], 0.9);
+        assert_eq!(m.replacements[0].extra["shortDescription"], "adj.");
+        assert_eq!(m.type_, Type::default());
+        assert_eq!(response.software.premium_hint.as_deref(), Some("Premium finds more"));
+        assert_eq!(response.sentence_ranges, Some(vec![[0, 15]]));
+        assert!(response.extra.contains_key("hiddenMatches"));
+        assert!(!response.extra.contains_key("sentenceRanges"));
+
+        // Unknown fields are serialized back as they were
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["matches"][0]["rule"]["confidence"], 0.9);
+        assert_eq!(json["matches"][0]["newField"], 1);
+        assert_eq!(json["hiddenMatches"], serde_json::json!([]));
+
+        // and reports keep them as experimental fields
+        let report = CheckReport::from(response);
+        assert!(report.response.extra.is_empty());
+        assert!(report.experimental.fields.contains_key("hiddenMatches"));
+        assert_eq!(serde_json::to_value(&report).unwrap(), json);
+    }
+
+    #[test]
+    fn test_apply_grouped_replacements() {
+        let text = "A smal cat, a smal dog and a smal mistaek.".to_string();
+        let mut response = response_with_matches(
+            &text,
+            &[
+                (2, 4, &["small", "seal"]),
+                (14, 4, &["seal", "small"]),
+                (29, 4, &["small"]),
+                (34, 7, &["mistake"]),
+            ],
+        );
+        response.matches[2].rule.id = "OTHER_RULE".to_string();
+        let response = CheckResponseWithContext::new(text, response);
+
+        let groups: Vec<usize> = response.identical_matches().iter().map(Vec::len).collect();
+        assert_eq!(groups, vec![2, 1, 1]);
+
+        let (fixed, applied) = response.apply_grouped_replacements();
+        assert_eq!(fixed, "A small cat, a small dog and a small mistake.");
+        assert_eq!(
+            applied[0].to_string(),
+            "replaced 2 occurrence(s) of \"smal\" with \"small\" (MORFOLOGIK_RULE_EN_US)"
+        );
+        assert_eq!(applied.len(), 3);
+    }
+
+    #[test]
+    fn test_sanitized_text_restore_offsets() {
+        let text = "\x0cA smal\x0b\x0bmistaek.";
+        let sanitized = SanitizedText::new(text, ControlChars::Strip);
+        assert_eq!(sanitized.text, "A smalmistaek.");
+
+        let mut response = response_with_matches(&sanitized.text, &[(2, 4, &[]), (4, 6, &[])]);
+        sanitized.restore_offsets(&mut response);
+
+        let ranges: Vec<_> = response
+            .iter_matches()
+            .map(|m| (m.offset, m.length))
+            .collect();
+        assert_eq!(ranges, vec![(3, 4), (5, 8)]);
+    }
+
     #[test]
     fn test_serialize_option_vec_string() {
         use serde::Serialize;
@@ -1270,4 +5016,112 @@ mod tests {
         let got = serde_json::to_string(&Foo::none()).unwrap();
         assert_eq!(got, r#"{"values":null}"#);
     }
+
+    #[test]
+    fn test_deserialize_option_vec_string() {
+        #[derive(Deserialize)]
+        struct Foo {
+            #[serde(default, deserialize_with = "deserialize_option_vec_string")]
+            values: Option<Vec<String>>,
+        }
+
+        let got: Foo = serde_json::from_str(r#"{"values":"en-US,de-DE"}"#).unwrap();
+        assert_eq!(
+            got.values,
+            Some(vec!["en-US".to_string(), "de-DE".to_string()])
+        );
+
+        let got: Foo = serde_json::from_str(r#"{"values":["en-US"]}"#).unwrap();
+        assert_eq!(got.values, Some(vec!["en-US".to_string()]));
+
+        let got: Foo = serde_json::from_str(r#"{"values":null}"#).unwrap();
+        assert_eq!(got.values, None);
+
+        let got: Foo = serde_json::from_str("{}").unwrap();
+        assert_eq!(got.values, None);
+    }
+
+    #[test]
+    fn test_data_round_trip() {
+        let data = Data::from_inline_math("Let $x$ be \"positive\".", "X");
+        let json = serde_json::to_string(&data).unwrap();
+        assert!(json.starts_with(r#"{"annotation":["#));
+
+        assert_eq!(serde_json::from_str::<Data>(&json).unwrap(), data);
+        assert_eq!(
+            serde_json::from_str::<Data>(r#""{\"annotation\":[{\"text\":\"A\"}]}""#).unwrap(),
+            Data::from_iter([DataAnnotation::new_text("A".to_string())])
+        );
+    }
+
+    #[test]
+    fn test_to_form_parts() {
+        let mut request = CheckRequest::default()
+            .with_data(Data::from_iter([DataAnnotation::new_text("A".to_string())]));
+        request.level = Level::Picky;
+        request.enabled_only = true;
+        request.dicts = Some(Vec::new());
+        request
+            .experimental_fields
+            .insert("mode".to_string(), "rewrite".to_string());
+
+        assert_eq!(
+            request.to_form_parts(),
+            vec![
+                ("data", r#"{"annotation":[{"text":"A"}]}"#.to_string()),
+                ("language", "auto".to_string()),
+                ("enabledOnly", "true".to_string()),
+                ("level", "picky".to_string()),
+                ("mode", "rewrite".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_preferred_variants() {
+        let request = CheckRequest::default()
+            .with_text("Colour".to_string())
+            .with_mother_tongue(LanguageCode::FR)
+            .with_preferred_variants([LanguageCode::EN_GB, LanguageCode::DE_DE]);
+        let parts = request.to_form_parts();
+        assert!(parts.contains(&("motherTongue", "fr".to_string())));
+        assert!(parts.contains(&("preferredVariants", "en-GB,de-DE".to_string())));
+
+        let json = serde_json::to_string(&request).unwrap();
+        assert!(json.contains(r#""preferredVariants":"en-GB,de-DE""#));
+        assert_eq!(
+            serde_json::from_str::<CheckRequest>(&json).unwrap(),
+            request
+        );
+
+        let request = request.with_language(LanguageCode::EN_US);
+        assert!(request
+            .to_form_parts()
+            .iter()
+            .all(|(name, _)| *name != "preferredVariants"));
+    }
+
+    #[test]
+    fn test_check_request_round_trip() {
+        let mut request = CheckRequest::default()
+            .with_data(Data::from_inline_math("Let $x$ be positive.", "X"))
+            .with_language(LanguageCode::EN_US);
+        request.dicts = Some(vec!["a".to_string(), "b".to_string()]);
+        request.level = Level::Picky;
+        request.enabled_only = true;
+
+        let json = serde_json::to_string(&request).unwrap();
+        assert!(json.contains(r#""dicts":"a,b""#));
+        assert_eq!(
+            serde_json::from_str::<CheckRequest>(&json).unwrap(),
+            request
+        );
+
+        let request = CheckRequest::default().with_text("Some text.".to_string());
+        let json = serde_json::to_string(&request).unwrap();
+        assert_eq!(
+            serde_json::from_str::<CheckRequest>(&json).unwrap(),
+            request
+        );
+    }
 }
@@ -0,0 +1,177 @@
+//! Storage of Premium API credentials in the system's keychain, with
+//! [`keyring`].
+//!
+//! Credentials are resolved in the following order: command line flags,
+//! environ variables, and credentials stored with `ltrs auth login`.
+
+use crate::{
+    error::{Error, Result},
+    server::Credentials,
+};
+use clap::{Parser, Subcommand};
+use is_terminal::IsTerminal;
+use std::io::{self, BufRead, Write};
+
+/// Service name of the keychain entry.
+const SERVICE: &str = "languagetool-rust";
+
+/// User name of the keychain entry, whose password holds the credentials
+/// serialized as JSON.
+const ENTRY: &str = "credentials";
+
+/// Return the keychain entry holding the credentials.
+fn entry() -> Result<keyring::Entry> {
+    Ok(keyring::Entry::new(SERVICE, ENTRY)?)
+}
+
+/// Return the credentials stored in the keychain, if any.
+///
+/// # Errors
+///
+/// If the keychain cannot be accessed, or if the stored credentials are not
+/// valid.
+pub fn stored_credentials() -> Result<Option<Credentials>> {
+    match entry()?.get_password() {
+        Ok(json) => Ok(Some(serde_json::from_str(&json)?)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Store credentials in the keychain, replacing the previous ones.
+///
+/// # Errors
+///
+/// If the keychain cannot be accessed.
+pub fn store_credentials(credentials: &Credentials) -> Result<()> {
+    Ok(entry()?.set_password(&serde_json::to_string(credentials)?)?)
+}
+
+/// Remove the credentials from the keychain, and return whether there were
+/// any.
+///
+/// # Errors
+///
+/// If the keychain cannot be accessed.
+pub fn delete_credentials() -> Result<bool> {
+    match entry()?.delete_password() {
+        Ok(()) => Ok(true),
+        Err(keyring::Error::NoEntry) => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Auth's subcommand.
+#[derive(Clone, Debug, Subcommand)]
+pub enum AuthSubcommand {
+    /// Store credentials in the system's keychain.
+    ///
+    /// The API key is read from standard input, so that it is not kept in the
+    /// shell history, and is not echoed if standard input is a terminal.
+    Login {
+        /// Your username as used to log in at languagetool.org, asked for if
+        /// not set.
+        #[clap(short = 'u', long)]
+        username: Option<String>,
+    },
+    /// Remove the credentials from the system's keychain.
+    Logout,
+    /// Print which credentials are used, if any.
+    Status,
+}
+
+/// Manage the Premium API credentials stored in the system's keychain.
+#[derive(Debug, Parser)]
+pub struct AuthCommand {
+    /// Subcommand.
+    #[command(subcommand)]
+    pub subcommand: AuthSubcommand,
+}
+
+/// Read a trimmed line from `stdin`, after printing `prompt` if standard input
+/// is a terminal.
+fn prompt<R: BufRead, W: Write>(prompt: &str, stdin: &mut R, stdout: &mut W) -> Result<String> {
+    if io::stdin().is_terminal() {
+        write!(stdout, "{prompt}: ")?;
+        stdout.flush()?;
+    }
+    let mut line = String::new();
+    stdin.read_line(&mut line)?;
+    let line = line.trim();
+    if line.is_empty() {
+        return Err(Error::InvalidValue(format!("{prompt} cannot be empty")));
+    }
+    Ok(line.to_string())
+}
+
+/// Read the API key like [`prompt`], but without echoing it if standard input
+/// is a terminal.
+fn prompt_api_key<R: BufRead, W: Write>(stdin: &mut R, stdout: &mut W) -> Result<String> {
+    if !io::stdin().is_terminal() {
+        return prompt("API key", stdin, stdout);
+    }
+    let api_key = rpassword::prompt_password("API key: ")?;
+    let api_key = api_key.trim();
+    if api_key.is_empty() {
+        return Err(Error::InvalidValue("API key cannot be empty".to_string()));
+    }
+    Ok(api_key.to_string())
+}
+
+impl AuthCommand {
+    /// Execute the command, reading input from `stdin`.
+    pub fn execute<R: BufRead, W: Write>(&self, stdin: &mut R, stdout: &mut W) -> Result<()> {
+        match &self.subcommand {
+            AuthSubcommand::Login { username } => {
+                let username = match username {
+                    Some(username) => username.clone(),
+                    None => prompt("Username", stdin, stdout)?,
+                };
+                let api_key = prompt_api_key(stdin, stdout)?;
+                store_credentials(&Credentials::new(username.as_str(), api_key))?;
+                writeln!(stdout, "Stored credentials of {username} in the keychain")?;
+            },
+            AuthSubcommand::Logout => {
+                if delete_credentials()? {
+                    writeln!(stdout, "Removed credentials from the keychain")?;
+                } else {
+                    writeln!(stdout, "No credentials stored in the keychain")?;
+                }
+            },
+            AuthSubcommand::Status => {
+                if let Ok(credentials) = Credentials::from_env() {
+                    writeln!(
+                        stdout,
+                        "Using credentials of {} from environ variables",
+                        credentials.username
+                    )?;
+                } else if let Some(credentials) = stored_credentials()? {
+                    writeln!(
+                        stdout,
+                        "Using credentials of {} from the keychain",
+                        credentials.username
+                    )?;
+                } else {
+                    writeln!(stdout, "Not logged in")?;
+                }
+            },
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prompt() {
+        let mut stdout = Vec::new();
+
+        assert_eq!(
+            prompt("API key", &mut " key \n".as_bytes(), &mut stdout).unwrap(),
+            "key"
+        );
+        assert!(prompt("API key", &mut "\n".as_bytes(), &mut stdout).is_err());
+    }
+}
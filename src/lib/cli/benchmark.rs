@@ -0,0 +1,237 @@
+//! Benchmark of the configured server, with `ltrs benchmark`, e.g., to compare
+//! a local server, with or without n-gram data, to the public API.
+
+use crate::{
+    check::{parse_language_code, CheckRequest},
+    error::Result,
+    languages::LanguageCode,
+    server::ServerClient,
+};
+use clap::Parser;
+use futures::stream::{self, StreamExt};
+use serde::Serialize;
+use std::{
+    fmt,
+    io::Write,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+/// Text checked by default, with a few mistakes so that the server has
+/// matches to report.
+const DEFAULT_TEXT: &str = "LanguageTool is a grammar checker that find mistakes in texts. It \
+                            can checks many languages, and it's rules can be enabled on demand.";
+
+/// Send repeated check requests to the server, and report latency percentiles
+/// and throughput.
+#[derive(Debug, Parser)]
+pub struct BenchmarkCommand {
+    /// Number of check requests sent.
+    #[arg(short = 'n', long, value_name = "N", default_value_t = 100)]
+    pub requests: usize,
+    /// Number of requests sent concurrently.
+    #[arg(short = 'c', long, value_name = "C", default_value_t = 4)]
+    pub concurrency: usize,
+    /// File whose content is checked by each request, instead of a short
+    /// English text.
+    #[arg(long, value_name = "F")]
+    pub text_file: Option<PathBuf>,
+    /// Language of the checked text.
+    #[arg(short = 'l', long, default_value = "en-US", value_parser = parse_language_code)]
+    pub language: LanguageCode,
+    /// Print the report as JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+impl BenchmarkCommand {
+    /// Send the requests with `client`, and write the report to `stdout`.
+    ///
+    /// # Errors
+    ///
+    /// If the text file cannot be read, or if all requests failed, in which
+    /// case the error of the first one is returned.
+    pub async fn execute<W: Write>(&self, client: ServerClient, stdout: &mut W) -> Result<()> {
+        let text = match &self.text_file {
+            Some(path) => std::fs::read_to_string(path)?,
+            None => DEFAULT_TEXT.to_string(),
+        };
+        let request = CheckRequest::default()
+            .with_language(self.language.clone())
+            .with_text(text);
+        let concurrency = self.concurrency.max(1);
+        let client = client.with_concurrency(Some(concurrency));
+        let (client, request) = (&client, &request);
+
+        let start = Instant::now();
+        let results: Vec<_> = stream::iter(0..self.requests)
+            .map(|_| {
+                async move {
+                    let start = Instant::now();
+                    client.check(request).await.map(|_| start.elapsed())
+                }
+            })
+            .buffer_unordered(concurrency)
+            .collect()
+            .await;
+        let elapsed = start.elapsed();
+
+        let mut latencies = Vec::with_capacity(results.len());
+        let mut first_error = None;
+        for result in results {
+            match result {
+                Ok(latency) => latencies.push(latency),
+                Err(e) => {
+                    first_error.get_or_insert(e);
+                },
+            }
+        }
+        if let (true, Some(e)) = (latencies.is_empty(), first_error) {
+            return Err(e);
+        }
+
+        let report = BenchmarkReport::new(latencies, self.requests, concurrency, elapsed);
+        if self.json {
+            writeln!(stdout, "{}", serde_json::to_string_pretty(&report)?)?;
+        } else {
+            writeln!(stdout, "{report}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Latency statistics, in milliseconds.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct Latencies {
+    /// Fastest request.
+    pub min: f64,
+    /// Average over all requests.
+    pub mean: f64,
+    /// Median.
+    pub p50: f64,
+    /// 90th percentile.
+    pub p90: f64,
+    /// 99th percentile.
+    pub p99: f64,
+    /// Slowest request.
+    pub max: f64,
+}
+
+impl Latencies {
+    /// Compute the statistics of `latencies`, sorting them.
+    #[must_use]
+    pub fn new(latencies: &mut [Duration]) -> Self {
+        if latencies.is_empty() {
+            return Self::default();
+        }
+        latencies.sort_unstable();
+        let ms = |duration: Duration| duration.as_secs_f64() * 1000.0;
+        // Nearest-rank percentile
+        let percentile = |p: usize| {
+            let rank = (p * latencies.len()).div_ceil(100).max(1);
+            ms(latencies[rank - 1])
+        };
+        let total: Duration = latencies.iter().sum();
+
+        Self {
+            min: ms(latencies[0]),
+            mean: ms(total) / latencies.len() as f64,
+            p50: percentile(50),
+            p90: percentile(90),
+            p99: percentile(99),
+            max: ms(latencies[latencies.len() - 1]),
+        }
+    }
+}
+
+/// Report of `ltrs benchmark`.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct BenchmarkReport {
+    /// Number of requests sent.
+    pub requests: usize,
+    /// Number of requests that failed.
+    pub failed: usize,
+    /// Number of requests sent concurrently.
+    pub concurrency: usize,
+    /// Time elapsed to send all requests, in seconds.
+    pub elapsed_secs: f64,
+    /// Successful requests per second.
+    pub throughput: f64,
+    /// Latencies of the successful requests.
+    pub latency_ms: Latencies,
+}
+
+impl BenchmarkReport {
+    /// Create a report from the `latencies` of the successful requests, out of
+    /// `requests` sent in `elapsed` time.
+    #[must_use]
+    pub fn new(
+        mut latencies: Vec<Duration>,
+        requests: usize,
+        concurrency: usize,
+        elapsed: Duration,
+    ) -> Self {
+        let elapsed_secs = elapsed.as_secs_f64();
+        let throughput = if elapsed_secs > 0.0 {
+            latencies.len() as f64 / elapsed_secs
+        } else {
+            0.0
+        };
+
+        Self {
+            requests,
+            failed: requests - latencies.len(),
+            concurrency,
+            elapsed_secs,
+            throughput,
+            latency_ms: Latencies::new(&mut latencies),
+        }
+    }
+}
+
+impl fmt::Display for BenchmarkReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let latency = &self.latency_ms;
+        writeln!(
+            f,
+            "Requests: {} ({} failed), concurrency: {}",
+            self.requests, self.failed, self.concurrency
+        )?;
+        writeln!(
+            f,
+            "Elapsed: {:.2} s, throughput: {:.1} requests/s",
+            self.elapsed_secs, self.throughput
+        )?;
+        write!(
+            f,
+            "Latency (ms): min {:.1}, mean {:.1}, p50 {:.1}, p90 {:.1}, p99 {:.1}, max {:.1}",
+            latency.min, latency.mean, latency.p50, latency.p90, latency.p99, latency.max
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report() {
+        let latencies = (1..=100).map(Duration::from_millis).collect();
+        let report = BenchmarkReport::new(latencies, 101, 4, Duration::from_secs(2));
+
+        assert_eq!(report.failed, 1);
+        assert_eq!(report.throughput, 50.0);
+        assert_eq!(report.latency_ms.min, 1.0);
+        assert_eq!(report.latency_ms.mean, 50.5);
+        assert_eq!(report.latency_ms.p50, 50.0);
+        assert_eq!(report.latency_ms.p90, 90.0);
+        assert_eq!(report.latency_ms.p99, 99.0);
+        assert_eq!(report.latency_ms.max, 100.0);
+        assert!(report.to_string().contains("p90 90.0"));
+
+        let report = BenchmarkReport::new(Vec::new(), 0, 1, Duration::ZERO);
+        assert_eq!(report.latency_ms, Latencies::default());
+    }
+}
@@ -0,0 +1,179 @@
+//! Resolution of the language of each checked file, so that multilingual
+//! repositories can be checked in one pass, e.g., with
+//! `--language-override "docs/fr/**=fr-FR"`.
+//!
+//! The language of a file is, by order of precedence:
+//! 1. the one of the first language override whose glob matches its path;
+//! 2. `--language`, unless it is `auto`;
+//! 3. the one declared by the document, e.g., with a `lang` attribute;
+//! 4. `auto`, letting the server detect the language of each request, i.e.,
+//!    of each fragment if the text is split.
+//!
+//! The mother tongue and the preferred variants, used for false friends and
+//! with `auto` respectively, can be overridden per path in the same way.
+
+use crate::{
+    check::parse_language_code,
+    error::{Error, Result},
+    languages::LanguageCode,
+};
+use ::ignore::overrides::{Override, OverrideBuilder};
+use std::{path::Path, str::FromStr};
+
+/// Value of an option for the files whose path matches a glob, formatted as
+/// `GLOB=VALUE`, e.g., a [`LanguageOverride`].
+///
+/// Globs follow the `.gitignore` syntax and are relative to the current
+/// directory: `docs/fr/**` matches all the files of `docs/fr`, and `*.de.md`
+/// matches these files in any directory.
+///
+/// # Examples
+///
+/// ```
+/// # use languagetool_rust::cli::language::LanguageOverride;
+/// # use std::path::Path;
+/// let language_override: LanguageOverride = "docs/fr/**=fr-FR".parse().unwrap();
+///
+/// assert!(language_override.matches(Path::new("docs/fr/index.md")));
+/// assert!(!language_override.matches(Path::new("docs/index.md")));
+/// assert!("docs/fr/**".parse::<LanguageOverride>().is_err());
+/// ```
+#[derive(Clone, Debug)]
+pub struct PathOverride<T> {
+    /// Glob matched against the paths of the checked files.
+    pub glob: String,
+    /// Value of the option for the matching files.
+    pub value: T,
+    matcher: Override,
+}
+
+/// Language, or mother tongue, of the files whose path matches a glob,
+/// formatted as `GLOB=LANGUAGE`.
+pub type LanguageOverride = PathOverride<LanguageCode>;
+
+/// Preferred variants of the files whose path matches a glob, formatted as
+/// `GLOB=VARIANT,...`, e.g., `docs/uk/**=en-GB,de-AT`.
+pub type VariantsOverride = PathOverride<Vec<LanguageCode>>;
+
+impl<T> PathOverride<T> {
+    /// Parse `s`, formatted as `GLOB=VALUE`, parsing its value with `parse`.
+    ///
+    /// # Errors
+    ///
+    /// If `s` has no `=`, if the glob is invalid, or if `parse` fails.
+    pub fn parse_with<F>(s: &str, parse: F) -> Result<Self>
+    where
+        F: FnOnce(&str) -> Result<T>,
+    {
+        let (glob, value) = s.rsplit_once('=').ok_or_else(|| {
+            Error::InvalidValue(format!("invalid override {s:?}, expected GLOB=VALUE"))
+        })?;
+        let mut matcher = OverrideBuilder::new(std::env::current_dir()?);
+        matcher.add(glob)?;
+
+        Ok(Self {
+            glob: glob.to_string(),
+            value: parse(value)?,
+            matcher: matcher.build()?,
+        })
+    }
+
+    /// Return `true` if the glob of this override matches `path`.
+    #[must_use]
+    pub fn matches(&self, path: &Path) -> bool {
+        self.matcher.matched(path, false).is_whitelist()
+    }
+}
+
+impl FromStr for LanguageOverride {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse_with(s, parse_language_code)
+    }
+}
+
+/// Parse `v` if it is a valid language override, formatted as
+/// `GLOB=LANGUAGE`, see [`LanguageOverride`].
+pub fn parse_language_override(v: &str) -> Result<LanguageOverride> {
+    v.parse()
+}
+
+/// Parse `v` if it is a valid override of the preferred variants, formatted
+/// as `GLOB=VARIANT,...`, see [`VariantsOverride`].
+pub fn parse_variants_override(v: &str) -> Result<VariantsOverride> {
+    PathOverride::parse_with(v, |variants| {
+        variants.split(',').map(parse_language_code).collect()
+    })
+}
+
+/// Return the value of the first of `overrides` whose glob matches `path`,
+/// if any.
+#[must_use]
+pub fn find_override<'a, T>(
+    overrides: &'a [PathOverride<T>],
+    path: Option<&Path>,
+) -> Option<&'a T> {
+    let path = path?;
+    overrides
+        .iter()
+        .find(|path_override| path_override.matches(path))
+        .map(|path_override| &path_override.value)
+}
+
+/// Return the language of the file at `path`, i.e., the one of the first of
+/// `overrides` that matches it, if any, or else `language` if it is not
+/// `auto`, or else the `declared` language of the document, if any.
+#[must_use]
+pub fn resolve_language(
+    overrides: &[LanguageOverride],
+    path: Option<&Path>,
+    language: &LanguageCode,
+    declared: Option<LanguageCode>,
+) -> LanguageCode {
+    find_override(overrides, path)
+        .cloned()
+        .or_else(|| (!language.is_auto()).then(|| language.clone()))
+        .or(declared)
+        .unwrap_or(LanguageCode::AUTO)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_language() {
+        let overrides: Vec<LanguageOverride> = ["docs/fr/**=fr-FR", "*.de.md=de-DE"]
+            .iter()
+            .map(|v| parse_language_override(v).unwrap())
+            .collect();
+        let resolve = |path: &str, language: &LanguageCode, declared: Option<LanguageCode>| {
+            resolve_language(&overrides, Some(Path::new(path)), language, declared)
+        };
+
+        assert_eq!(resolve("docs/fr/a.md", &LanguageCode::EN_US, None), "fr-FR");
+        assert_eq!(resolve("src/notes.de.md", &LanguageCode::AUTO, None), "de-DE");
+        assert_eq!(resolve("README.md", &LanguageCode::EN_US, None), "en-US");
+        assert_eq!(
+            resolve("README.md", &LanguageCode::AUTO, Some(LanguageCode::IT)),
+            "it"
+        );
+        assert_eq!(resolve("README.md", &LanguageCode::AUTO, None), "auto");
+        assert_eq!(
+            resolve_language(&overrides, None, &LanguageCode::AUTO, None),
+            "auto"
+        );
+
+        assert!(parse_language_override("docs/**=french").is_err());
+        assert!(parse_language_override("docs/**").is_err());
+
+        let overrides = [parse_variants_override("docs/uk/**=en-GB,de-AT").unwrap()];
+        assert_eq!(
+            find_override(&overrides, Some(Path::new("docs/uk/a.md"))),
+            Some(&vec![LanguageCode::EN_GB, "de-AT".parse().unwrap()])
+        );
+        assert_eq!(find_override(&overrides, Some(Path::new("docs/a.md"))), None);
+        assert!(parse_variants_override("docs/**=en-GB,british").is_err());
+    }
+}
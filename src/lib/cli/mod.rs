@@ -0,0 +1,1505 @@
+//! Command line tools.
+//!
+//! This module is specifically designed to be used by LTRS's binary target.
+//! It contains all the content needed to create LTRS's command line interface.
+
+use crate::{
+    build_info::BUILD_INFO,
+    cache::Cache,
+    check::{
+        find_control_chars, CheckCommand, CheckOptions, CheckReport, CheckRequest, CheckResponse,
+        CheckResponseWithContext, Match,
+    },
+    config::{config_path_from_args, LtrsConfig},
+    error::{Error, Result},
+    experimental::ExperimentalResponse,
+    git::ChangedLines,
+    ignore::{filter_lines, IgnoreFile},
+    output::{to_diff, to_fingerprints, FormatterRegistry, OutputFormat, RunResults},
+    parsers::{MarkdownOptions, ParseOptions},
+    rule_info::{RulesResponse, RulesSubcommand},
+    server::{ServerCli, ServerClient, DEFAULT_MAX_RESPLITS, DEFAULT_RETRY_BASE_DELAY},
+    text_stats::TextStats,
+    walk::walk_paths,
+    words::{LoginArgs, WordsExportFormat, WordsRequest, WordsResponse, WordsSubcommand},
+};
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
+use futures::stream::{self, StreamExt};
+use is_terminal::IsTerminal;
+use std::{
+    collections::{HashMap, HashSet},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::{Mutex, MutexGuard, PoisonError},
+    time::Duration,
+};
+use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
+
+#[cfg(feature = "local-server")]
+use crate::local_server::{
+    LocalServer, RunningLocalServer, DEFAULT_STARTUP_TIMEOUT, LANGUAGETOOL_VERSION,
+};
+
+#[cfg(feature = "keyring")]
+pub mod auth;
+pub mod benchmark;
+pub mod language;
+
+/// Start the LanguageTool server used by `ltrs check --local`, after
+/// downloading it into the cache directory if `lt_home` is `None`.
+#[cfg(feature = "local-server")]
+async fn start_local_server(lt_home: Option<&Path>) -> Result<RunningLocalServer> {
+    let local_server = match lt_home {
+        Some(home) => LocalServer::new(home),
+        None => {
+            let home = LocalServer::default_home().ok_or_else(|| {
+                Error::InvalidValue("cannot find a cache directory, use --lt-home".to_string())
+            })?;
+            if !LocalServer::new(&home).is_installed() {
+                eprintln!(
+                    "Downloading LanguageTool {LANGUAGETOOL_VERSION} into {}",
+                    home.display()
+                );
+            }
+            LocalServer::download(home).await?
+        },
+    };
+    local_server.start(DEFAULT_STARTUP_TIMEOUT).await
+}
+
+/// Read lines from standard input and write to buffer string.
+///
+/// Standard output is used when waiting for user to input text.
+fn read_from_stdin<W>(stdout: &mut W, buffer: &mut String) -> Result<()>
+where
+    W: io::Write,
+{
+    if io::stdin().is_terminal() {
+        #[cfg(windows)]
+        writeln!(
+            stdout,
+            "Reading from STDIN, press [CTRL+Z] when you're done."
+        )?;
+
+        #[cfg(unix)]
+        writeln!(
+            stdout,
+            "Reading from STDIN, press [CTRL+D] when you're done."
+        )?;
+    }
+    let stdin = std::io::stdin();
+
+    while stdin.read_line(buffer)? > 0 {}
+    Ok(())
+}
+
+/// Print a warning on standard error listing the control characters found in
+/// `text`, if any.
+fn warn_control_chars(text: &str, origin: Option<&str>) {
+    let found = find_control_chars(text);
+
+    if !found.is_empty() {
+        let positions: Vec<String> = found
+            .iter()
+            .map(|(line_number, line_offset, c)| {
+                format!("U+{:04X} at {line_number}:{line_offset}", *c as u32)
+            })
+            .collect();
+        eprintln!(
+            "warning: {} contains control character(s) that may break checking: {}",
+            origin.unwrap_or("input"),
+            positions.join(", ")
+        );
+    }
+}
+
+/// Return the path of the cache file.
+fn cache_path() -> Result<PathBuf> {
+    Cache::default_path()
+        .ok_or_else(|| Error::InvalidConfig("could not find the cache directory".to_string()))
+}
+
+/// Split `request` into fragments, whose boundaries are stable across edits
+/// with `--incremental`, or on sentence boundaries with `--split-sentences`.
+fn split_request(cmd: &CheckCommand, request: CheckRequest) -> Result<Vec<CheckRequest>> {
+    if cmd.incremental {
+        request.try_split_stable(cmd.max_length, cmd.split_pattern.as_str())
+    } else if cmd.split_sentences {
+        request.try_split_sentences(cmd.max_length)
+    } else {
+        request.try_split(cmd.max_length, cmd.split_pattern.as_str())
+    }
+}
+
+/// Send `requests` and join their responses, reusing the responses in `cache`,
+/// if any.
+///
+/// If `incremental`, the cached responses to previous fragments of the same
+/// file are removed, see [`Cache::retain_file_requests`].
+async fn check_requests(
+    server_client: &ServerClient,
+    requests: Vec<CheckRequest>,
+    cache: Option<&Mutex<Cache>>,
+    origin: Option<&str>,
+    incremental: bool,
+) -> Result<CheckResponse> {
+    match cache {
+        Some(cache) => {
+            let file = origin.map(Path::new);
+            let fragments = incremental.then(|| requests.clone());
+            let response = server_client
+                .check_multiple_and_join_shared_cache(requests, cache, file)
+                .await?;
+            if let (Some(file), Some(fragments)) = (file, fragments) {
+                lock(cache).retain_file_requests(server_client, file, &fragments);
+            }
+            Ok(response)
+        },
+        None => server_client.check_multiple_and_join(requests).await,
+    }
+}
+
+/// Return the options used to parse the checked files.
+fn parse_options(cmd: &CheckCommand) -> ParseOptions {
+    ParseOptions {
+        keys: cmd.keys.clone(),
+        markdown: MarkdownOptions {
+            skip: cmd.markdown_skip.clone(),
+        },
+    }
+}
+
+/// Return the statistics of the text checked in `text`, i.e., without the
+/// markup of its file type, see `--stats`.
+fn text_stats(cmd: &CheckCommand, text: &str, origin: Option<&str>) -> TextStats {
+    let file_type = cmd.file_type.resolve(origin.map(Path::new));
+    match file_type.parse(text, &parse_options(cmd)) {
+        Some(document) => TextStats::from_data(&document.data),
+        None => TextStats::new(text),
+    }
+}
+
+/// Return the options of [`ServerClient::check_text`] set by `cmd`.
+fn check_options(cmd: &CheckCommand) -> CheckOptions {
+    let mut request = cmd.request.clone();
+    request
+        .experimental_fields
+        .extend(cmd.experimental_field.iter().cloned());
+
+    let options = CheckOptions::default()
+        .with_request(request)
+        .with_file_type(cmd.file_type)
+        .with_parse_options(parse_options(cmd))
+        .with_split(cmd.max_length, cmd.split_pattern.clone())
+        .with_control_chars(cmd.control_chars)
+        .with_ignores(!cmd.no_ignore_file, !cmd.no_inline_ignores)
+        .with_filter(cmd.match_filter());
+    match &cmd.known_words {
+        Some(known_words) => options.with_known_words(known_words.clone()),
+        None => options,
+    }
+}
+
+/// Check `text` like [`ServerClient::check_text`], using the options from
+/// `cmd`.
+///
+/// On top of it, the language, see `--language`, may be overridden for the
+/// file found at `origin`, requests are split as requested and reuse the
+/// `cache`, if any, local rules, see `--rules`, are checked on the same text,
+/// and matches on lines matching `--ignore-line-regex`, or spelling matches on
+/// words of the `dictionary`, see `--use-remote-dictionary-filter`, are
+/// removed. Text read from standard input honors the ignore file of the
+/// current directory.
+///
+/// Control characters are reported. The response of the returned report
+/// always refers to the original `text`, and the report counts the suppressed
+/// matches.
+async fn check_text(
+    server_client: &ServerClient,
+    cmd: &CheckCommand,
+    text: &str,
+    origin: Option<&str>,
+    cache: Option<&Mutex<Cache>>,
+    dictionary: Option<&WordsResponse>,
+) -> Result<CheckReport> {
+    warn_control_chars(text, origin);
+
+    let path = origin.map(Path::new);
+    let options = check_options(cmd);
+    let mut prepared = options.prepare(text, path);
+    let declared = prepared.declared_language();
+    let request = &mut prepared.request;
+    request.language =
+        language::resolve_language(&cmd.language_override, path, &cmd.request.language, declared);
+    if let Some(mother_tongue) = language::find_override(&cmd.mother_tongue_override, path) {
+        request.mother_tongue = Some(mother_tongue.clone());
+    }
+    if let Some(variants) = language::find_override(&cmd.preferred_variants_override, path) {
+        request.preferred_variants = Some(variants.clone());
+    }
+    let request = request.sanitized();
+
+    let requests = split_request(cmd, prepared.request.clone())?;
+    let response = check_requests(server_client, requests, cache, origin, cmd.incremental).await?;
+    #[cfg(feature = "rules")]
+    let response = match &cmd.rules {
+        Some(rules) => {
+            let mut response = response;
+            response.matches.extend(match prepared.data() {
+                Some(data) => rules.check_data(data),
+                None => rules.check(text),
+            });
+            response.matches.sort_by_key(|m| m.offset);
+            response
+        },
+        None => response,
+    };
+    let (mut response, mut suppressed) = prepared.finish_counting(response, &options)?;
+
+    if origin.is_none() && !cmd.no_ignore_file {
+        if let Some(ignore) = IgnoreFile::discover(Path::new("."))? {
+            suppressed.ignore_file += ignore.filter(None, &mut response);
+        }
+    }
+    if !cmd.ignore_line_regex.is_empty() {
+        suppressed.ignored_lines = filter_lines(text, &mut response, |line| {
+            cmd.ignore_line_regex
+                .iter()
+                .any(|regex| regex.is_match(line))
+        });
+    }
+    if let Some(dictionary) = dictionary {
+        suppressed.dictionary = dictionary.filter(&mut response);
+    }
+    let mut report = CheckReport::from(response);
+    report.suppressed = suppressed;
+    if !cmd.experimental {
+        report.experimental = Default::default();
+    }
+    if !request.language.is_auto() {
+        report.language_mismatch = report
+            .response
+            .language_mismatch(cmd.language_mismatch_threshold);
+        if let Some(ref mismatch) = report.language_mismatch {
+            eprintln!("warning: {}: {mismatch}", origin.unwrap_or("input"));
+        }
+    }
+    if cmd.include_request {
+        report.request = Some(request);
+    }
+    Ok(report)
+}
+
+/// Number of files checked concurrently, unless `--jobs` is set.
+const DEFAULT_FILE_JOBS: usize = 4;
+
+/// Lock the cache, even if a thread panicked while holding it.
+fn lock(cache: &Mutex<Cache>) -> MutexGuard<'_, Cache> {
+    cache.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Read and check `filename`, like [`check_text`], giving up with a warning
+/// if this takes longer than `--per-file-timeout`, and only keep the matches
+/// on `changed_lines`, if any, see `--diff`.
+///
+/// Returns the text of the file and the report, or `None` if the file is
+/// skipped.
+async fn check_file(
+    server_client: &ServerClient,
+    cmd: &CheckCommand,
+    filename: &Path,
+    cache: Option<&Mutex<Cache>>,
+    dictionary: Option<&WordsResponse>,
+    changed_lines: Option<&ChangedLines>,
+) -> Result<Option<(String, CheckReport)>> {
+    let text = std::fs::read_to_string(filename)?;
+    let check = check_text(
+        server_client,
+        cmd,
+        &text,
+        filename.to_str(),
+        cache,
+        dictionary,
+    );
+    let mut report = match cmd.per_file_timeout {
+        Some(secs) => {
+            match tokio::time::timeout(Duration::from_secs(secs), check).await {
+                Ok(report) => report?,
+                Err(_) => {
+                    eprintln!(
+                        "warning: {}: skipped, checking took more than {secs} second(s) \
+                         (--per-file-timeout)",
+                        filename.display()
+                    );
+                    return Ok(None);
+                },
+            }
+        },
+        None => check.await?,
+    };
+    if let Some(changed_lines) = changed_lines {
+        changed_lines.filter(filename, &text, &mut report.response);
+    }
+    Ok(Some((text, report)))
+}
+
+/// Keep the first `max` matches of the response to `origin`, if any, warning
+/// about the dropped ones, see `--max-errors-per-file` and `--max-errors`.
+fn truncate_matches(response: &mut CheckResponse, max: Option<usize>, origin: Option<&str>) {
+    if let Some(max) = max.filter(|max| response.matches.len() > *max) {
+        eprintln!(
+            "warning: {}: only reporting the first {max} out of {} match(es)",
+            origin.unwrap_or("input"),
+            response.matches.len()
+        );
+        response.matches.truncate(max);
+    }
+}
+
+/// Progress of the checked files, drawn on standard error with the
+/// **progress** feature, if it is a terminal.
+struct Progress {
+    #[cfg(feature = "progress")]
+    bar: indicatif::ProgressBar,
+}
+
+impl Progress {
+    /// Create the progress of checking `len` files, which is hidden if
+    /// `visible` is `false` or if there is only one file.
+    #[allow(unused_variables)]
+    fn new(len: usize, visible: bool) -> Self {
+        #[cfg(feature = "progress")]
+        {
+            let bar = if visible && len > 1 && io::stderr().is_terminal() {
+                let style = indicatif::ProgressStyle::with_template(
+                    "{bar:40} {pos}/{len} files, {elapsed} {wide_msg}",
+                )
+                .unwrap_or_else(|_| indicatif::ProgressStyle::default_bar());
+                indicatif::ProgressBar::new(len as u64).with_style(style)
+            } else {
+                indicatif::ProgressBar::hidden()
+            };
+            Self { bar }
+        }
+        #[cfg(not(feature = "progress"))]
+        Self {}
+    }
+
+    /// Count `filename` as checked.
+    #[allow(unused_variables)]
+    fn inc(&self, filename: &Path) {
+        #[cfg(feature = "progress")]
+        {
+            self.bar.set_message(filename.display().to_string());
+            self.bar.inc(1);
+        }
+    }
+
+    /// Hide the progress bar while running `f`, e.g., to write the output.
+    fn suspend<F: FnOnce() -> R, R>(&self, f: F) -> R {
+        #[cfg(feature = "progress")]
+        return self.bar.suspend(f);
+        #[cfg(not(feature = "progress"))]
+        f()
+    }
+
+    /// Remove the progress bar.
+    fn finish(&self) {
+        #[cfg(feature = "progress")]
+        self.bar.finish_and_clear();
+    }
+}
+
+/// Write `fixed` text to `filename` if it differs from the `original` text,
+/// optionally keeping a `.bak` copy of the original file.
+fn write_fixed(filename: &Path, original: &str, fixed: String, backup: bool) -> Result<()> {
+    if fixed != original {
+        if backup {
+            let mut backup = filename.to_path_buf().into_os_string();
+            backup.push(".bak");
+            std::fs::copy(filename, backup)?;
+        }
+        std::fs::write(filename, fixed)?;
+    }
+    Ok(())
+}
+
+/// Review matches one by one, reading the user's choices from `input`.
+///
+/// For each match, the user can accept one of its replacements (at most
+/// `max_suggestions`, if positive), skip it, ignore its rule for the rest of
+/// the session, or quit the review. When the same mistake occurs again later,
+/// i.e., another match has the same rule and matched text, a replacement can
+/// also be accepted for all the remaining occurrences at once.
+///
+/// Return the text with accepted replacements applied.
+fn review_matches<R, W>(
+    response: &CheckResponseWithContext,
+    origin: Option<&str>,
+    max_suggestions: isize,
+    color: bool,
+    tab_width: usize,
+    input: &mut R,
+    stdout: &mut W,
+) -> Result<String>
+where
+    R: io::BufRead,
+    W: io::Write,
+{
+    let mut matches: Vec<&Match> = response.iter_matches().collect();
+    matches.sort_by_key(|m| m.offset);
+    let groups: HashMap<(String, String), Vec<&Match>> = response
+        .identical_matches()
+        .into_iter()
+        .map(|group| ((group[0].rule.id.clone(), group[0].matched_text()), group))
+        .collect();
+
+    let mut single = response.response.clone();
+    let mut accepted = Vec::new();
+    let mut ignored_rules = HashSet::new();
+    let mut fixed_for_all = HashSet::new();
+    let mut offset = 0;
+
+    'review: for m in matches {
+        let key = (m.rule.id.clone(), m.matched_text());
+        if m.offset < offset || ignored_rules.contains(&m.rule.id) || fixed_for_all.contains(&key) {
+            continue;
+        }
+        let occurrences: Vec<&Match> = groups[&key]
+            .iter()
+            .copied()
+            .filter(|other| other.offset >= m.offset)
+            .collect();
+
+        single.matches = vec![m.clone()];
+        writeln!(
+            stdout,
+            "{}",
+            render(&single, &response.text, origin, color, tab_width)
+        )?;
+
+        // If suggestions were truncated, the last replacement only tells how
+        // many were not shown.
+        let n = usize::try_from(max_suggestions)
+            .ok()
+            .filter(|max| *max > 0)
+            .map_or(m.replacements.len(), |max| max.min(m.replacements.len()));
+
+        for (i, replacement) in m.replacements[..n].iter().enumerate() {
+            writeln!(stdout, "  [{}] {}", i + 1, replacement.value)?;
+        }
+
+        loop {
+            if n > 0 && occurrences.len() > 1 {
+                write!(
+                    stdout,
+                    "Accept [1-{n}], accept for all {} occurrences [a1-a{n}], [s]kip, [i]gnore \
+                     rule or [q]uit? ",
+                    occurrences.len()
+                )?;
+            } else if n > 0 {
+                write!(stdout, "Accept [1-{n}], [s]kip, [i]gnore rule or [q]uit? ")?;
+            } else {
+                write!(stdout, "[s]kip, [i]gnore rule or [q]uit? ")?;
+            }
+            stdout.flush()?;
+
+            let mut answer = String::new();
+            if input.read_line(&mut answer)? == 0 {
+                break 'review;
+            }
+
+            match answer.trim() {
+                "" | "s" => break,
+                "i" => {
+                    ignored_rules.insert(m.rule.id.clone());
+                    break;
+                },
+                "q" => break 'review,
+                choice => {
+                    let (all, choice) = match choice.strip_prefix('a') {
+                        Some(choice) if occurrences.len() > 1 => (true, choice),
+                        _ => (false, choice),
+                    };
+                    let Some(i) = choice.parse::<usize>().ok().filter(|i| (1..=n).contains(i))
+                    else {
+                        continue;
+                    };
+                    let replacement = m.replacements[i - 1].clone();
+                    if all {
+                        writeln!(
+                            stdout,
+                            "Replaced {} occurrences of \"{}\" with \"{}\"",
+                            occurrences.len(),
+                            key.1,
+                            replacement.value
+                        )?;
+                        fixed_for_all.insert(key);
+                    }
+                    let accepted_occurrences = if all { &occurrences[..] } else { &[m] };
+                    accepted.extend(accepted_occurrences.iter().map(|m| {
+                        let mut m = (*m).clone();
+                        m.replacements = vec![replacement.clone()];
+                        m
+                    }));
+                    offset = m.offset + m.length;
+                    break;
+                },
+            }
+        }
+    }
+
+    let mut reviewed = response.clone();
+    reviewed.response.matches = accepted;
+    Ok(reviewed.apply_replacements())
+}
+
+/// Render a check response as text, annotated if the `annotate` feature is
+/// enabled.
+#[allow(unused_variables)]
+fn render(
+    response: &CheckResponse,
+    text: &str,
+    origin: Option<&str>,
+    color: bool,
+    tab_width: usize,
+) -> String {
+    #[cfg(feature = "annotate")]
+    return response.annotate_with_tab_width(text, origin, color, tab_width);
+    #[cfg(not(feature = "annotate"))]
+    return response.render_plain(text, origin);
+}
+
+/// Write the number of issues found by kind, followed by the number of
+/// suppressed matches by mechanism, if any.
+fn write_summary<W: io::Write>(
+    stdout: &mut W,
+    report: &CheckReport,
+    origin: Option<&str>,
+) -> Result<()> {
+    let summary = report.response.summary();
+    write!(stdout, "{}: {summary}", origin.unwrap_or("-"))?;
+    if !report.suppressed.is_empty() {
+        write!(stdout, " ({})", report.suppressed)?;
+    }
+    writeln!(stdout)?;
+    Ok(())
+}
+
+/// Write a check report as text, followed by its summary, see
+/// [`write_summary`], or only write this summary if `--summary-only` is set,
+/// and the statistics of the text if `--stats` is set.
+fn write_text<W: io::Write>(
+    stdout: &mut W,
+    cmd: &CheckCommand,
+    report: &CheckReport,
+    text: &str,
+    origin: Option<&str>,
+    color: bool,
+) -> Result<()> {
+    let response = &report.response;
+    if !cmd.summary_only {
+        writeln!(
+            stdout,
+            "{}",
+            render(response, text, origin, color, cmd.tab_width)
+        )?;
+        write_experimental(stdout, &report.experimental, origin)?;
+        if response.matches.is_empty() && report.suppressed.is_empty() && !cmd.stats {
+            return Ok(());
+        }
+    }
+    write_summary(stdout, report, origin)?;
+    if cmd.stats {
+        let stats = text_stats(cmd, text, origin);
+        writeln!(stdout, "{}: {stats}", origin.unwrap_or("-"))?;
+    }
+    Ok(())
+}
+
+/// Write a unified diff, coloring removed lines in red and added lines in
+/// green if `stdout` supports color.
+fn write_diff<W: WriteColor>(stdout: &mut W, diff: &str) -> Result<()> {
+    for line in diff.lines() {
+        let mut spec = ColorSpec::new();
+        if line.starts_with("---") || line.starts_with("+++") {
+            spec.set_bold(true);
+        } else if line.starts_with("@@") {
+            spec.set_fg(Some(Color::Cyan));
+        } else if line.starts_with('-') {
+            spec.set_fg(Some(Color::Red));
+        } else if line.starts_with('+') {
+            spec.set_fg(Some(Color::Green));
+        }
+        stdout.set_color(&spec)?;
+        write!(stdout, "{line}")?;
+        stdout.reset()?;
+        writeln!(stdout)?;
+    }
+    Ok(())
+}
+
+/// Write the suggestions found in experimental response fields, if any, see
+/// `--experimental`.
+fn write_experimental<W: io::Write>(
+    stdout: &mut W,
+    experimental: &ExperimentalResponse,
+    origin: Option<&str>,
+) -> Result<()> {
+    for suggestion in experimental.suggestions() {
+        writeln!(
+            stdout,
+            "experimental: {}: {suggestion}",
+            origin.unwrap_or("input")
+        )?;
+    }
+    Ok(())
+}
+
+/// Main command line structure. Contains every subcommand.
+#[derive(Parser, Debug)]
+#[command(
+    author,
+    version,
+    about = "LanguageTool API bindings in Rust.",
+    propagate_version(true),
+    subcommand_required(true),
+    verbatim_doc_comment
+)]
+pub struct Cli {
+    /// Specify WHEN to colorize output.
+    #[arg(short, long, value_name = "WHEN", default_value = "auto", default_missing_value = "always", num_args(0..=1), require_equals(true))]
+    pub color: clap::ColorChoice,
+    /// Path to the configuration file, whose values are used as defaults for
+    /// other options [default: ltrs/config.toml in the user's configuration
+    /// directory].
+    #[arg(long, global = true, value_name = "FILE", env = "LTRS_CONFIG")]
+    pub config: Option<PathBuf>,
+    /// Print build information and exit.
+    #[arg(long, exclusive = true)]
+    pub build_info: bool,
+    /// [`ServerCli`] arguments.
+    #[command(flatten)]
+    pub server_cli: ServerCli,
+    /// Subcommand.
+    #[command(subcommand)]
+    #[allow(missing_docs)]
+    pub command: Command,
+}
+
+/// Enumerate all possible commands.
+#[derive(Subcommand, Debug)]
+#[allow(missing_docs, clippy::large_enum_variant)]
+pub enum Command {
+    /// Manage the Premium API credentials stored in the system's keychain.
+    #[cfg(feature = "keyring")]
+    Auth(auth::AuthCommand),
+    /// Send repeated check requests to the server, and report latency
+    /// percentiles and throughput.
+    Benchmark(benchmark::BenchmarkCommand),
+    /// Check text using LanguageTool server.
+    Check(crate::check::CheckCommand),
+    /// Manage the cache of check responses.
+    Cache(crate::cache::CacheCommand),
+    /// Manage the configuration file.
+    Config(crate::config::ConfigCommand),
+    /// Commands to easily run a LanguageTool server with Docker.
+    #[cfg(feature = "docker")]
+    Docker(crate::docker::DockerCommand),
+    /// Return list of supported languages.
+    #[clap(visible_alias = "lang")]
+    Languages(crate::languages::LanguagesCommand),
+    /// Start a Language Server Protocol server over standard input and output.
+    #[cfg(feature = "lsp")]
+    Lsp(crate::lsp::LspCommand),
+    /// Ping the LanguageTool server and return time elapsed in ms if success.
+    Ping(crate::server::PingCommand),
+    /// Discover the IDs of rules and categories.
+    Rules(crate::rule_info::RulesCommand),
+    /// Generate and validate LanguageTool server configuration files.
+    ServerConfig(crate::server::ServerConfigCommand),
+    /// Retrieve some user's words list, or add / delete word from it.
+    Words(crate::words::WordsCommand),
+    /// Generate tab-completion scripts for supported shells
+    #[cfg(feature = "cli-complete")]
+    Completions(complete::CompleteCommand),
+}
+
+impl Cli {
+    /// Return a standard output stream that optionally supports color.
+    #[must_use]
+    fn stdout(&self) -> StandardStream {
+        let mut choice: ColorChoice = match self.color {
+            clap::ColorChoice::Auto => ColorChoice::Auto,
+            clap::ColorChoice::Always => ColorChoice::Always,
+            clap::ColorChoice::Never => ColorChoice::Never,
+        };
+
+        if choice == ColorChoice::Auto && !io::stdout().is_terminal() {
+            choice = ColorChoice::Never;
+        }
+
+        StandardStream::stdout(choice)
+    }
+
+    /// Parse command line arguments, using the values of the configuration
+    /// file as defaults.
+    ///
+    /// Like [`Parser::parse`], this exits on invalid arguments.
+    ///
+    /// # Errors
+    ///
+    /// If the configuration file cannot be loaded, unless the config command
+    /// is used, e.g., to create it.
+    pub fn parse_with_config() -> Result<Self> {
+        let path = config_path_from_args(std::env::args_os());
+        let (config, error) = match LtrsConfig::load(path.as_deref()) {
+            Ok(config) => (config, None),
+            Err(e) => (LtrsConfig::default(), Some(e)),
+        };
+
+        // `--build-info` is exclusive, so it is the only argument that can
+        // be used without a subcommand
+        let build_info = std::env::args_os().any(|arg| arg == "--build-info");
+        let matches = config
+            .apply(Self::command())
+            .subcommand_required(!build_info)
+            .get_matches();
+        if matches.get_flag("build_info") {
+            println!("{BUILD_INFO}");
+            std::process::exit(0);
+        }
+        let cli = Self::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+
+        match error {
+            Some(e) if !matches!(cli.command, Command::Config(_)) => Err(e),
+            _ => Ok(cli),
+        }
+    }
+
+    /// Execute command, possibly returning an error.
+    pub async fn execute(self) -> Result<()> {
+        self.execute_with_formatters(&FormatterRegistry::default())
+            .await
+    }
+
+    /// Execute command, possibly returning an error, with the report formats
+    /// that `--format` can select.
+    ///
+    /// This lets crates embedding the command line interface add their own
+    /// formats, see [`FormatterRegistry::register`].
+    pub async fn execute_with_formatters(self, formatters: &FormatterRegistry) -> Result<()> {
+        let mut stdout = self.stdout();
+        let command = self.command;
+
+        let server_client = ServerClient::try_from_cli(self.server_cli)?;
+        // Credentials from flags and environ variables are set on requests, and
+        // take precedence over those of the client.
+        #[cfg(feature = "keyring")]
+        let server_client = match command {
+            Command::Auth(_) => server_client,
+            _ => {
+                match auth::stored_credentials() {
+                    Ok(Some(credentials)) => server_client.with_credentials(credentials),
+                    _ => server_client,
+                }
+            },
+        };
+
+        match command {
+            Command::Check(mut cmd) => {
+                let color = stdout.supports_color();
+                cmd.filenames = walk_paths(&cmd.filenames, &cmd.glob, cmd.respect_gitignore)?;
+                if let Some(stdin_type) = cmd.stdin_type {
+                    cmd.file_type = stdin_type;
+                }
+                if cmd.spell_only {
+                    cmd.request = cmd.request.spelling_only();
+                }
+                let changed_lines = if cmd.diff {
+                    let changed_lines = ChangedLines::from_git(cmd.staged, cmd.since.as_deref())?;
+                    if cmd.filenames.is_empty() {
+                        cmd.filenames = changed_lines.existing_files();
+                    }
+                    Some(changed_lines)
+                } else {
+                    None
+                };
+
+                // Kept until the end of the command, to stop the server then.
+                #[cfg(feature = "local-server")]
+                let (server_client, _local_server) = if cmd.local {
+                    let local_server = start_local_server(cmd.lt_home.as_deref()).await?;
+                    (local_server.client().clone(), Some(local_server))
+                } else {
+                    (server_client, None)
+                };
+                let server_client = server_client
+                    .with_max_suggestions(cmd.max_suggestions)
+                    .with_concurrency(cmd.jobs)
+                    .with_rate_limit(cmd.rate_limit)
+                    .with_retries(cmd.retries, DEFAULT_RETRY_BASE_DELAY)
+                    .with_max_resplits(if cmd.no_resplit { 0 } else { DEFAULT_MAX_RESPLITS })
+                    .with_rate_limited_hook(|notice| eprintln!("warning: {notice}"));
+                // Report formats are `None`, and printed by `formatter` at the end.
+                let format = if cmd.raw {
+                    Some(OutputFormat::Json)
+                } else if cmd.list_matches_only {
+                    Some(OutputFormat::Fingerprints)
+                } else {
+                    OutputFormat::from_str(&cmd.format, true)
+                        .ok()
+                        .filter(|format| {
+                            matches!(
+                                format,
+                                OutputFormat::Text
+                                    | OutputFormat::Json
+                                    | OutputFormat::Fingerprints
+                                    | OutputFormat::Diff
+                            )
+                        })
+                };
+                let formatter = match format {
+                    Some(_) => None,
+                    None => {
+                        Some(formatters.get(&cmd.format).ok_or_else(|| {
+                            Error::InvalidValue(format!(
+                                "unknown output format {:?}, expected one of: text, json, \
+                                 fingerprints, diff, {}",
+                                cmd.format,
+                                formatters.names().collect::<Vec<_>>().join(", ")
+                            ))
+                        })?)
+                    },
+                };
+                if cmd.validate_language {
+                    let request = &cmd.request;
+                    for language in std::iter::once(&request.language)
+                        .chain(&request.mother_tongue)
+                        .chain(request.preferred_variants.iter().flatten())
+                    {
+                        server_client.validate_language(language.as_str()).await?;
+                    }
+                }
+                // Reports that gather all the responses are written at the end.
+                let mut reports: Vec<(String, CheckResponse)> = Vec::new();
+                #[cfg(feature = "rules")]
+                for skipped in cmd.rules.iter().flat_map(|rules| &rules.skipped) {
+                    eprintln!("warning: {skipped}");
+                }
+                // Number of files skipped because of `--per-file-timeout`.
+                let mut skipped = 0;
+                // Number of matches reported, and whether `--max-errors` was reached.
+                let mut reported = 0;
+                let mut max_errors_reached = false;
+                // Shared by the files checked concurrently, see `--jobs`.
+                let cache = if cmd.cache || cmd.incremental {
+                    Some(Mutex::new(Cache::from_path(cache_path()?)?))
+                } else {
+                    None
+                };
+                // Fetched once, and reused for every checked file.
+                let login = match (&cmd.request.username, &cmd.request.api_key) {
+                    (Some(username), Some(api_key)) => {
+                        Some(LoginArgs {
+                            username: username.clone(),
+                            api_key: api_key.clone(),
+                        })
+                    },
+                    _ => server_client.credentials().cloned().map(LoginArgs::from),
+                };
+                let dictionary = match login {
+                    Some(login) if cmd.use_remote_dictionary_filter => {
+                        let request = WordsRequest {
+                            offset: 0,
+                            limit: 500,
+                            login,
+                            dicts: cmd.request.dicts.clone(),
+                        };
+                        Some(server_client.words_all(&request).await?)
+                    },
+                    _ => None,
+                };
+
+                if cmd.filenames.is_empty() && !cmd.diff {
+                    let origin = cmd
+                        .stdin_filepath
+                        .as_ref()
+                        .map(|path| path.display().to_string());
+                    let text = match (&cmd.request.text, &cmd.request.data) {
+                        (Some(text), _) => Some(text.clone()),
+                        (None, None) => {
+                            let mut text = String::new();
+                            read_from_stdin(&mut stdout, &mut text)?;
+                            Some(text)
+                        },
+                        (None, Some(_)) => None,
+                    };
+
+                    let mut report = match &text {
+                        Some(text) => {
+                            check_text(
+                                &server_client,
+                                &cmd,
+                                text,
+                                origin.as_deref(),
+                                cache.as_ref(),
+                                dictionary.as_ref(),
+                            )
+                            .await?
+                        },
+                        None => {
+                            let mut request = cmd.request.clone();
+                            request
+                                .experimental_fields
+                                .extend(cmd.experimental_field.iter().cloned());
+                            let response = server_client.check(&request).await?;
+                            let response: CheckResponse =
+                                CheckResponseWithContext::new(request.try_get_text()?, response)
+                                    .into();
+                            let mut report = CheckReport::from(response);
+                            if !cmd.experimental {
+                                report.experimental = Default::default();
+                            }
+                            let response = &mut report.response;
+                            let suppressed = &mut report.suppressed;
+                            if let Some(known_words) = &cmd.known_words {
+                                suppressed.known_words = known_words.filter(response);
+                            }
+                            let filter = cmd.match_filter();
+                            suppressed.ignore_patterns = filter.filter_patterns(response);
+                            suppressed.filter = filter.filter(response);
+                            report
+                        },
+                    };
+
+                    let origin = origin.as_deref();
+                    truncate_matches(&mut report.response, cmd.max_errors_per_file, origin);
+                    if let Some(budget) = cmd.max_errors {
+                        max_errors_reached = report.response.matches.len() > budget;
+                        truncate_matches(&mut report.response, Some(budget), origin);
+                    }
+                    match (format, text) {
+                        (Some(OutputFormat::Text), Some(text)) => {
+                            write_text(
+                                &mut stdout,
+                                &cmd,
+                                &report,
+                                &text,
+                                origin,
+                                color,
+                            )?;
+                        },
+                        (Some(OutputFormat::Text), None) if cmd.summary_only => {
+                            write_summary(&mut stdout, &report, origin)?;
+                        },
+                        (Some(OutputFormat::Diff), Some(text)) => {
+                            let diff = to_diff(origin.unwrap_or("-"), &text, &report.response);
+                            write_diff(&mut stdout, &diff)?;
+                        },
+                        (Some(OutputFormat::Text | OutputFormat::Json | OutputFormat::Diff), _) => {
+                            writeln!(&mut stdout, "{}", serde_json::to_string_pretty(&report)?)?;
+                        },
+                        (Some(OutputFormat::Fingerprints), _) => {
+                            write!(&mut stdout, "{}", to_fingerprints(&report.response))?;
+                        },
+                        _ => reports.push((origin.unwrap_or("-").to_string(), report.response)),
+                    }
+                }
+
+                let filenames = match cmd.sampled_filenames() {
+                    Some((filenames, seed)) => {
+                        eprintln!(
+                            "note: checking a sample of {} out of {} files (--seed {seed})",
+                            filenames.len(),
+                            cmd.filenames.len()
+                        );
+                        filenames
+                    },
+                    None => cmd.filenames.clone(),
+                };
+
+                let changed = match &cmd.only_changed_since {
+                    Some(since) => since.filter_changed(filenames)?,
+                    None => filenames,
+                };
+
+                // Files are checked concurrently, but their responses are
+                // handled in order, so that the output is deterministic.
+                let jobs = if cmd.interactive {
+                    1
+                } else {
+                    cmd.jobs.unwrap_or(DEFAULT_FILE_JOBS).max(1)
+                };
+                let progress = Progress::new(changed.len(), !cmd.interactive);
+                let (client, command) = (&server_client, &cmd);
+                let (shared_cache, dictionary) = (cache.as_ref(), dictionary.as_ref());
+                let changed_lines = changed_lines.as_ref();
+                let mut checks = stream::iter(&changed)
+                    .map(|filename| {
+                        async move {
+                            let result = check_file(
+                                client,
+                                command,
+                                filename,
+                                shared_cache,
+                                dictionary,
+                                changed_lines,
+                            )
+                            .await;
+                            (filename, result)
+                        }
+                    })
+                    .buffered(jobs);
+
+                let mut handled = 0;
+                while !max_errors_reached {
+                    let Some((filename, result)) = checks.next().await else {
+                        break;
+                    };
+                    handled += 1;
+                    progress.inc(filename);
+                    let Some((text, mut report)) = result? else {
+                        skipped += 1;
+                        continue;
+                    };
+                    progress.suspend(|| {
+                        let origin = filename.to_str();
+                        truncate_matches(&mut report.response, cmd.max_errors_per_file, origin);
+                        if let Some(budget) = cmd.max_errors {
+                            let found = report.response.matches.len();
+                            truncate_matches(&mut report.response, Some(budget - reported), origin);
+                            reported += report.response.matches.len();
+                            // Reaching the budget exactly is fine, exceeding it is not
+                            max_errors_reached = found > report.response.matches.len();
+                        }
+                    });
+
+                    if cmd.interactive {
+                        let response = CheckResponseWithContext::new(text.clone(), report.response);
+                        let fixed = review_matches(
+                            &response,
+                            filename.to_str(),
+                            cmd.max_suggestions,
+                            color,
+                            cmd.tab_width,
+                            &mut io::stdin().lock(),
+                            &mut stdout,
+                        )?;
+                        write_fixed(filename, &text, fixed, cmd.backup)?;
+                        if let Some(cache) = &cache {
+                            lock(cache).invalidate_file(filename);
+                        }
+                        continue;
+                    }
+
+                    progress.suspend(|| -> Result<()> {
+                        if cmd.write {
+                            let (fixed, applied) =
+                                CheckResponseWithContext::new(text.clone(), report.response.clone())
+                                    .apply_grouped_replacements();
+                            for replacement in applied {
+                                eprintln!("{}: {replacement}", filename.display());
+                            }
+                            write_fixed(filename, &text, fixed, cmd.backup)?;
+                            if let Some(cache) = &cache {
+                                lock(cache).invalidate_file(filename);
+                            }
+                        }
+
+                        match format {
+                            Some(OutputFormat::Text) => {
+                                write_text(
+                                    &mut stdout,
+                                    &cmd,
+                                    &report,
+                                    &text,
+                                    filename.to_str(),
+                                    color,
+                                )?;
+                            },
+                            Some(OutputFormat::Json) => {
+                                writeln!(
+                                    &mut stdout,
+                                    "{}",
+                                    serde_json::to_string_pretty(&report)?
+                                )?;
+                            },
+                            Some(OutputFormat::Fingerprints) => {
+                                write!(&mut stdout, "{}", to_fingerprints(&report.response))?;
+                            },
+                            Some(OutputFormat::Diff) => {
+                                let origin = filename.display().to_string();
+                                let diff = to_diff(&origin, &text, &report.response);
+                                write_diff(&mut stdout, &diff)?;
+                            },
+                            _ => reports.push((filename.display().to_string(), report.response)),
+                        }
+                        Ok(())
+                    })?;
+                }
+                if max_errors_reached {
+                    // Pending requests of the files being checked are not sent
+                    server_client.cancel();
+                }
+                drop(checks);
+                progress.finish();
+                if max_errors_reached && handled < changed.len() {
+                    eprintln!(
+                        "warning: {} file(s) were not checked (--max-errors)",
+                        changed.len() - handled
+                    );
+                }
+
+                if let Some(formatter) = formatter {
+                    stdout.write_all(&formatter.format(&RunResults::from(reports)))?;
+                }
+
+                if let Some(cache) = cache {
+                    cache
+                        .into_inner()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .save(cache_path()?)?;
+                }
+                if skipped > 0 {
+                    return Err(Error::FilesSkipped(skipped));
+                }
+                if let Some(budget) = cmd.max_errors.filter(|_| max_errors_reached) {
+                    return Err(Error::MaxErrorsReached(budget));
+                }
+            },
+            #[cfg(feature = "keyring")]
+            Command::Auth(cmd) => {
+                cmd.execute(&mut io::stdin().lock(), &mut stdout)?;
+            },
+            Command::Benchmark(cmd) => {
+                cmd.execute(server_client, &mut stdout).await?;
+            },
+            Command::Cache(cmd) => {
+                cmd.execute(&cache_path()?, &mut stdout)?;
+            },
+            Command::Config(cmd) => {
+                cmd.execute(self.config.as_deref(), &mut stdout)?;
+            },
+            #[cfg(feature = "docker")]
+            Command::Docker(cmd) => {
+                cmd.execute(&mut stdout).await?;
+            },
+            Command::Languages(cmd) => {
+                let languages_response = cmd.apply(server_client.languages().await?);
+                let languages = cmd.format(&languages_response)?;
+
+                writeln!(&mut stdout, "{languages}")?;
+            },
+            #[cfg(feature = "lsp")]
+            Command::Lsp(cmd) => {
+                cmd.execute(server_client).await?;
+            },
+            Command::Rules(cmd) => {
+                let RulesSubcommand::List(args) = &cmd.subcommand;
+                let rules = if args.offline {
+                    RulesResponse::builtin(&args.language)
+                } else {
+                    match server_client.rules(&args.language).await {
+                        Ok(rules) => rules,
+                        Err(e) => {
+                            eprintln!("warning: {e}, listing bundled rules instead");
+                            RulesResponse::builtin(&args.language)
+                        },
+                    }
+                };
+                let rules = match &args.category {
+                    Some(category) => rules.with_category(category),
+                    None => rules,
+                };
+
+                writeln!(&mut stdout, "{}", args.format(&rules)?)?;
+            },
+            Command::ServerConfig(cmd) => {
+                cmd.execute(&mut stdout)?;
+            },
+            Command::Ping(cmd) => {
+                cmd.execute(&server_client, &mut stdout).await?;
+            },
+            Command::Words(cmd) => {
+                let words = match &cmd.subcommand {
+                    Some(WordsSubcommand::Add(request)) => {
+                        let words_response = server_client.words_add(request).await?;
+                        serde_json::to_string_pretty(&words_response)?
+                    },
+                    Some(WordsSubcommand::Delete(request)) => {
+                        let words_response = server_client.words_delete(request).await?;
+                        serde_json::to_string_pretty(&words_response)?
+                    },
+                    Some(WordsSubcommand::Import(args)) => {
+                        let words = std::fs::read_to_string(&args.file)?;
+                        let words_response = server_client
+                            .words_add_many(
+                                words.lines(),
+                                &args.login,
+                                args.dict.as_deref(),
+                                Duration::from_millis(args.interval),
+                            )
+                            .await;
+                        eprintln!(
+                            "{} added, {} skipped, {} failed",
+                            words_response.added.len(),
+                            words_response.skipped.len(),
+                            words_response.failed.len()
+                        );
+                        serde_json::to_string_pretty(&words_response)?
+                    },
+                    Some(WordsSubcommand::Export(args)) => {
+                        let words_response = server_client.words_all(&args.into()).await?;
+                        let words = match args.format {
+                            WordsExportFormat::Plain => words_response.words.join("\n"),
+                            WordsExportFormat::Json => {
+                                serde_json::to_string_pretty(&words_response)?
+                            },
+                        };
+
+                        match &args.output {
+                            Some(path) => {
+                                std::fs::write(path, words + "\n")?;
+                                return Ok(());
+                            },
+                            None => words,
+                        }
+                    },
+                    Some(WordsSubcommand::Dicts(args)) => {
+                        let words_response = server_client.words_by_dict(&args.into()).await?;
+                        serde_json::to_string_pretty(&words_response)?
+                    },
+                    None => {
+                        let words_response = server_client.words(&cmd.request.into()).await?;
+                        serde_json::to_string_pretty(&words_response)?
+                    },
+                };
+
+                writeln!(&mut stdout, "{words}")?;
+            },
+            #[cfg(feature = "cli-complete")]
+            Command::Completions(cmd) => {
+                cmd.execute(&mut stdout)?;
+            },
+        }
+        Ok(())
+    }
+}
+
+/// Build a command from the top-level command line structure.
+#[must_use]
+pub fn build_cli() -> clap::Command {
+    Cli::command()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::check::{test_utils::response_with_matches, DEFAULT_TAB_WIDTH};
+
+    #[test]
+    fn test_cli() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_list_values() {
+        let cli = Cli::parse_from([
+            "ltrs",
+            "check",
+            "--enabled-rules",
+            "A,B",
+            "--enabled-rules",
+            "C",
+            "--dicts",
+            "one",
+            "--dicts",
+            "two",
+            "-t",
+            "text",
+        ]);
+        let Command::Check(cmd) = cli.command else {
+            panic!("expected the check command");
+        };
+
+        assert_eq!(
+            cmd.request.enabled_rules,
+            Some(vec!["A".to_string(), "B".to_string(), "C".to_string()])
+        );
+        assert_eq!(
+            cmd.request.dicts,
+            Some(vec!["one".to_string(), "two".to_string()])
+        );
+    }
+
+    fn review(input: &str) -> String {
+        review_text(
+            "Some phrase with a smal mistaek.",
+            &[(19, 4, &["small", "seal"]), (24, 7, &["mistake"])],
+            input,
+        )
+    }
+
+    fn review_text(text: &str, matches: &[(usize, usize, &[&str])], input: &str) -> String {
+        let response = response_with_matches(text, matches);
+        let response = CheckResponseWithContext::new(text.to_string(), response);
+
+        review_matches(
+            &response,
+            None,
+            -1,
+            false,
+            DEFAULT_TAB_WIDTH,
+            &mut input.as_bytes(),
+            &mut Vec::new(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_review_matches() {
+        assert_eq!(review("2\n1\n"), "Some phrase with a seal mistake.");
+        assert_eq!(review("s\n1\n"), "Some phrase with a smal mistake.");
+        assert_eq!(review("x\n1\nq\n"), "Some phrase with a small mistaek.");
+        assert_eq!(review("i\n"), "Some phrase with a smal mistaek.");
+        assert_eq!(review(""), "Some phrase with a smal mistaek.");
+    }
+
+    #[test]
+    fn test_review_identical_matches() {
+        let text = "A smal cat, a smal dog and a smal mistaek.";
+        let matches: &[(usize, usize, &[&str])] = &[
+            (2, 4, &["small", "seal"]),
+            (14, 4, &["small", "seal"]),
+            (29, 4, &["small", "seal"]),
+            (34, 7, &["mistake"]),
+        ];
+
+        assert_eq!(
+            review_text(text, matches, "1\na2\n1\n"),
+            "A small cat, a seal dog and a seal mistake."
+        );
+        assert_eq!(
+            review_text(text, matches, "s\na1\n"),
+            "A smal cat, a small dog and a small mistaek."
+        );
+    }
+}
+
+#[cfg(feature = "cli-complete")]
+pub(crate) mod complete {
+    //! Completion scripts generation with [`clap_complete`].
+
+    use crate::error::Result;
+    use clap::{Command, Parser};
+    use clap_complete::{generate, shells::Shell};
+    use std::io::Write;
+
+    /// Command structure to generate complete scripts.
+    #[derive(Debug, Parser)]
+    #[command(
+    about = "Generate tab-completion scripts for supported shells",
+    after_help = "Use --help for installation help.",
+    after_long_help = COMPLETIONS_HELP
+)]
+    pub struct CompleteCommand {
+        /// Shell for which to completion script is generated.
+        #[arg(value_enum, ignore_case = true)]
+        shell: Shell,
+    }
+
+    impl CompleteCommand {
+        /// Generate completion file for current shell and write to buffer.
+        pub fn generate_completion_file<F, W>(&self, build_cli: F, buffer: &mut W)
+        where
+            F: FnOnce() -> Command,
+            W: Write,
+        {
+            generate(self.shell, &mut build_cli(), "ltrs", buffer);
+        }
+
+        /// Execute command by writing completion script to stdout.
+        pub fn execute<W>(&self, stdout: &mut W) -> Result<()>
+        where
+            W: Write,
+        {
+            self.generate_completion_file(super::build_cli, stdout);
+            Ok(())
+        }
+    }
+
+    pub(crate) static COMPLETIONS_HELP: &str = r"DISCUSSION:
+    Enable tab completion for Bash, Fish, Zsh, or PowerShell
+    Elvish shell completion is currently supported, but not documented below.
+    The script is output on `stdout`, allowing one to re-direct the
+    output to the file of their choosing. Where you place the file
+    will depend on which shell, and which operating system you are
+    using. Your particular configuration may also determine where
+    these scripts need to be placed.
+    Here are some common set ups for the three supported shells under
+    Unix and similar operating systems (such as GNU/Linux).
+    BASH:
+    Completion files are commonly stored in `/etc/bash_completion.d/` for
+    system-wide commands, but can be stored in
+    `~/.local/share/bash-completion/completions` for user-specific commands.
+    Run the command:
+        $ mkdir -p ~/.local/share/bash-completion/completions
+        $ ltrs completions bash >> ~/.local/share/bash-completion/completions/ltrs
+    This installs the completion script. You may have to log out and
+    log back in to your shell session for the changes to take effect.
+    BASH (macOS/Homebrew):
+    Homebrew stores bash completion files within the Homebrew directory.
+    With the `bash-completion` brew formula installed, run the command:
+        $ mkdir -p $(brew --prefix)/etc/bash_completion.d
+        $ ltrs completions bash > $(brew --prefix)/etc/bash_completion.d/ltrs.bash-completion
+    FISH:
+    Fish completion files are commonly stored in
+    `$HOME/.config/fish/completions`. Run the command:
+        $ mkdir -p ~/.config/fish/completions
+        $ ltrs completions fish > ~/.config/fish/completions/ltrs.fish
+    This installs the completion script. You may have to log out and
+    log back in to your shell session for the changes to take effect.
+    ZSH:
+    ZSH completions are commonly stored in any directory listed in
+    your `$fpath` variable. To use these completions, you must either
+    add the generated script to one of those directories, or add your
+    own to this list.
+    Adding a custom directory is often the safest bet if you are
+    unsure of which directory to use. First create the directory; for
+    this example we'll create a hidden directory inside our `$HOME`
+    directory:
+        $ mkdir ~/.zfunc
+    Then add the following lines to your `.zshrc` just before
+    `compinit`:
+        fpath+=~/.zfunc
+    Now you can install the completions script using the following
+    command:
+        $ ltrs completions zsh > ~/.zfunc/_ltrs
+    You must then either log out and log back in, or simply run
+        $ exec zsh
+    for the new completions to take effect.
+    CUSTOM LOCATIONS:
+    Alternatively, you could save these files to the place of your
+    choosing, such as a custom directory inside your $HOME. Doing so
+    will require you to add the proper directives, such as `source`ing
+    inside your login script. Consult your shells documentation for
+    how to add such directives.
+    POWERSHELL:
+    The powershell completion scripts require PowerShell v5.0+ (which
+    comes with Windows 10, but can be downloaded separately for windows 7
+    or 8.1).
+    First, check if a profile has already been set
+        PS C:\> Test-Path $profile
+    If the above command returns `False` run the following
+        PS C:\> New-Item -path $profile -type file -force
+    Now open the file provided by `$profile` (if you used the
+    `New-Item` command it will be
+    `${env:USERPROFILE}\Documents\WindowsPowerShell\Microsoft.PowerShell_profile.ps1`
+    Next, we either save the completions file into our profile, or
+    into a separate file and source it inside our profile. To save the
+    completions into our profile simply use
+        PS C:\> ltrs completions powershell >> ${env:USERPROFILE}\Documents\WindowsPowerShell\Microsoft.PowerShell_profile.ps1
+    SOURCE:
+        This documentation is directly taken from: https://github.com/rust-lang/rustup/blob/8f6b53628ad996ad86f9c6225fa500cddf860905/src/cli/help.rs#L157";
+}
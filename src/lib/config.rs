@@ -0,0 +1,555 @@
+//! Configuration file providing default values for command line options.
+//!
+//! The configuration file is read from the path given with `--config`, from
+//! the closest [`PROJECT_CONFIG_FILENAME`] file, found by walking up from the
+//! current directory, or from `ltrs/config.toml`, inside the user's
+//! configuration directory (e.g., `~/.config/ltrs/config.toml` on Linux). Its
+//! values only replace built-in defaults: environment variables and command
+//! line flags still take precedence.
+
+use crate::error::{Error, Result};
+use clap::{Args, Parser, Subcommand};
+use serde::{Deserialize, Serialize};
+use std::{
+    ffi::OsString,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+/// Environment variable that can be used instead of `--config`.
+pub const CONFIG_ENV: &str = "LTRS_CONFIG";
+
+/// Name of project configuration files.
+pub const PROJECT_CONFIG_FILENAME: &str = ".ltrs.toml";
+
+/// Template written by `ltrs config init`.
+const TEMPLATE: &str = r#"# Default values for ltrs options.
+#
+# Command line flags and environment variables take precedence over values
+# defined here. Uncomment and edit the options you need.
+
+# hostname = "https://api.languagetoolplus.com"
+# port = ""
+# username = "me@example.com"
+# api_key = "..."
+# timeout_ms = 30000
+# headers = ["Authorization: Bearer ..."]
+# proxy = "http://proxy.example.com:3128"
+# cacert = "/etc/ssl/certs/my-ca.pem"
+# client_cert = "client.pem"
+# client_key = "client-key.pem"
+# application = "my-app/1.0"
+
+[check]
+# language = "en-US"
+# mother_tongue = "fr"
+# preferred_variants = ["en-GB", "de-AT"]
+# level = "picky"
+# enabled_rules = []
+# disabled_rules = ["WHITESPACE_RULE"]
+# enabled_categories = []
+# disabled_categories = []
+# max_suggestions = 5
+# max_length = 1500
+# split_pattern = "\n\n"
+# ignore_line_regex = ['^\|', '^:::']
+# known_words = "words.txt"
+# ignore_patterns = ['^\{\{.*\}\}$']
+# language_overrides = ["docs/fr/**=fr-FR", "*.de.md=de-DE"]
+# mother_tongue_overrides = ["docs/en/**=fr"]
+# preferred_variants_overrides = ["docs/uk/**=en-GB,de-AT"]
+"#;
+
+/// Default options for the check command.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+#[non_exhaustive]
+pub struct CheckConfig {
+    /// Language code, or `auto`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Language code of the user's native language.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mother_tongue: Option<String>,
+    /// Preferred language variants, used with `language = "auto"`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preferred_variants: Option<Vec<String>>,
+    /// Level of rules, `default` or `picky`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub level: Option<String>,
+    /// IDs of rules to be enabled.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled_rules: Option<Vec<String>>,
+    /// IDs of rules to be disabled.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disabled_rules: Option<Vec<String>>,
+    /// IDs of categories to be enabled.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled_categories: Option<Vec<String>>,
+    /// IDs of categories to be disabled.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disabled_categories: Option<Vec<String>>,
+    /// Maximum number of suggestions kept.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_suggestions: Option<isize>,
+    /// Maximum number of characters before splitting.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<usize>,
+    /// Pattern used to split long texts.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub split_pattern: Option<String>,
+    /// Regular expressions of lines on which matches are suppressed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ignore_line_regex: Option<Vec<String>>,
+    /// File listing known words, on which spelling matches are suppressed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub known_words: Option<PathBuf>,
+    /// Regular expressions of matched texts whose matches are suppressed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ignore_patterns: Option<Vec<String>>,
+    /// Languages of the files whose path matches a glob, formatted as
+    /// `GLOB=LANGUAGE`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language_overrides: Option<Vec<String>>,
+    /// Mother tongues of the files whose path matches a glob, formatted as
+    /// `GLOB=LANGUAGE`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mother_tongue_overrides: Option<Vec<String>>,
+    /// Preferred variants of the files whose path matches a glob, formatted
+    /// as `GLOB=VARIANT,...`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preferred_variants_overrides: Option<Vec<String>>,
+}
+
+/// Content of a configuration file.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+#[non_exhaustive]
+pub struct LtrsConfig {
+    /// Server's hostname.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hostname: Option<String>,
+    /// Server's port number.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<String>,
+    /// Username used to log in at languagetool.org.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    /// API key used to log in at languagetool.org.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    /// Timeout of each request, in milliseconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+    /// Extra HTTP headers sent with each request, formatted as `KEY:VALUE`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub headers: Option<Vec<String>>,
+    /// URL of the HTTP(S) proxy through which requests are sent.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+    /// PEM file with additional trusted root certificates.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cacert: Option<PathBuf>,
+    /// PEM file with the client certificate used for mutual TLS.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_cert: Option<PathBuf>,
+    /// PEM file with the private key of the client certificate.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_key: Option<PathBuf>,
+    /// Name of the application, prepended to the `User-Agent` header.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub application: Option<String>,
+    /// Default options for the check command.
+    pub check: CheckConfig,
+}
+
+impl LtrsConfig {
+    /// Return the default configuration file path, if the user's configuration
+    /// directory is known.
+    #[must_use]
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("ltrs").join("config.toml"))
+    }
+
+    /// Read a configuration file.
+    ///
+    /// # Errors
+    ///
+    /// If the file cannot be read or is not a valid configuration.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| Error::InvalidConfig(format!("{}: {e}", path.display())))?;
+        toml::from_str(&content)
+            .map_err(|e| Error::InvalidConfig(format!("{}: {}", path.display(), e.message())))
+    }
+
+    /// Find the closest project configuration file, walking up from `dir`.
+    #[must_use]
+    pub fn discover<P: AsRef<Path>>(dir: P) -> Option<PathBuf> {
+        dir.as_ref()
+            .ancestors()
+            .map(|dir| dir.join(PROJECT_CONFIG_FILENAME))
+            .find(|candidate| candidate.is_file())
+    }
+
+    /// Return the path of the configuration file used when `--config` is not
+    /// given: the closest project configuration file, or the default path.
+    #[must_use]
+    pub fn find_path() -> Option<PathBuf> {
+        std::env::current_dir()
+            .ok()
+            .and_then(Self::discover)
+            .or_else(Self::default_path)
+    }
+
+    /// Load the configuration file at `path`, or at the path returned by
+    /// [`LtrsConfig::find_path`].
+    ///
+    /// A missing file at the default path is not an error, and results in an
+    /// empty configuration.
+    ///
+    /// # Errors
+    ///
+    /// If `path` is given and does not exist, or if the file is not a valid
+    /// configuration.
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        match path {
+            Some(path) => Self::from_path(path),
+            None => {
+                match Self::find_path() {
+                    Some(path) if path.is_file() => Self::from_path(path),
+                    _ => Ok(Self::default()),
+                }
+            },
+        }
+    }
+
+    /// Return pairs of argument ids and their default values.
+    fn defaults(&self) -> Vec<(&'static str, Vec<String>)> {
+        let check = &self.check;
+        let one = |value: &Option<String>| value.clone().map(|value| vec![value]);
+        let path = |value: &Option<PathBuf>| {
+            value
+                .as_ref()
+                .map(|path| vec![path.display().to_string()])
+        };
+
+        [
+            ("hostname", one(&self.hostname)),
+            ("PRT", one(&self.port)),
+            ("username", one(&self.username)),
+            ("api_key", one(&self.api_key)),
+            ("timeout_ms", self.timeout_ms.map(|ms| vec![ms.to_string()])),
+            ("headers", self.headers.clone()),
+            ("proxy", one(&self.proxy)),
+            ("cacert", path(&self.cacert)),
+            ("client_cert", path(&self.client_cert)),
+            ("client_key", path(&self.client_key)),
+            ("application", one(&self.application)),
+            ("language", one(&check.language)),
+            ("mother_tongue", one(&check.mother_tongue)),
+            ("preferred_variants", check.preferred_variants.clone()),
+            ("level", one(&check.level)),
+            ("enabled_rules", check.enabled_rules.clone()),
+            ("disabled_rules", check.disabled_rules.clone()),
+            ("enabled_categories", check.enabled_categories.clone()),
+            ("disabled_categories", check.disabled_categories.clone()),
+            (
+                "max_suggestions",
+                check.max_suggestions.map(|n| vec![n.to_string()]),
+            ),
+            ("max_length", check.max_length.map(|n| vec![n.to_string()])),
+            ("split_pattern", one(&check.split_pattern)),
+            ("ignore_line_regex", check.ignore_line_regex.clone()),
+            ("known_words", path(&check.known_words)),
+            ("ignore_pattern", check.ignore_patterns.clone()),
+            ("language_override", check.language_overrides.clone()),
+            ("mother_tongue_override", check.mother_tongue_overrides.clone()),
+            (
+                "preferred_variants_override",
+                check.preferred_variants_overrides.clone(),
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(id, values)| values.map(|values| (id, values)))
+        .collect()
+    }
+
+    /// Use the values of this configuration as default values for the
+    /// matching arguments of `command` and of all its subcommands.
+    #[must_use]
+    pub fn apply(&self, command: clap::Command) -> clap::Command {
+        fn apply_defaults(
+            mut command: clap::Command,
+            defaults: &[(&'static str, Vec<String>)],
+        ) -> clap::Command {
+            for (id, values) in defaults {
+                if command.get_arguments().any(|arg| arg.get_id() == id) {
+                    let values = values.clone();
+                    command = command.mut_arg(id, |arg| arg.default_values(values));
+                }
+            }
+            let names: Vec<String> = command
+                .get_subcommands()
+                .map(|subcommand| subcommand.get_name().to_string())
+                .collect();
+            for name in names {
+                command =
+                    command.mut_subcommand(name, |subcommand| apply_defaults(subcommand, defaults));
+            }
+            command
+        }
+
+        apply_defaults(command, &self.defaults())
+    }
+}
+
+/// Return the configuration file path given on the command line with
+/// `--config`, or with the [`CONFIG_ENV`] environment variable.
+///
+/// This is needed before actually parsing arguments, because the
+/// configuration file changes how they are parsed.
+pub fn config_path_from_args<I, T>(args: I) -> Option<PathBuf>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString>,
+{
+    let mut args = args.into_iter().map(Into::into);
+
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            break;
+        } else if arg == "--config" {
+            return args.next().map(PathBuf::from);
+        } else if let Some(path) = arg.to_str().and_then(|arg| arg.strip_prefix("--config=")) {
+            return Some(PathBuf::from(path));
+        }
+    }
+
+    std::env::var_os(CONFIG_ENV).map(PathBuf::from)
+}
+
+/// Arguments to create a configuration file.
+#[derive(Args, Clone, Debug, PartialEq, Eq)]
+pub struct ConfigInitArgs {
+    /// Overwrite the configuration file if it already exists.
+    #[clap(long)]
+    pub force: bool,
+}
+
+/// Config's subcommand.
+#[derive(Clone, Debug, Subcommand)]
+pub enum ConfigSubcommand {
+    /// Create a configuration file with all options commented out.
+    Init(ConfigInitArgs),
+    /// Print the path and the content of the configuration file.
+    Show,
+}
+
+/// Manage the configuration file.
+#[derive(Debug, Parser)]
+pub struct ConfigCommand {
+    /// Subcommand.
+    #[command(subcommand)]
+    pub subcommand: ConfigSubcommand,
+}
+
+impl ConfigCommand {
+    /// Execute the command, using the configuration file at `path`, or, by
+    /// default, the user's configuration file to create it, and the file that
+    /// is actually loaded to show it.
+    pub fn execute<W: Write>(&self, path: Option<&Path>, stdout: &mut W) -> Result<()> {
+        let default_path = match self.subcommand {
+            ConfigSubcommand::Init(_) => LtrsConfig::default_path(),
+            ConfigSubcommand::Show => LtrsConfig::find_path(),
+        };
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => {
+                default_path.ok_or_else(|| {
+                    Error::InvalidConfig("could not find the configuration directory".to_string())
+                })?
+            },
+        };
+
+        match &self.subcommand {
+            ConfigSubcommand::Init(args) => {
+                if path.exists() && !args.force {
+                    return Err(Error::InvalidConfig(format!(
+                        "{} already exists, use --force to overwrite it",
+                        path.display()
+                    )));
+                }
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::write(&path, TEMPLATE)?;
+                writeln!(stdout, "Created {}", path.display())?;
+            },
+            ConfigSubcommand::Show => {
+                writeln!(stdout, "# {}", path.display())?;
+                if path.is_file() {
+                    let config = LtrsConfig::from_path(&path)?;
+                    let content = toml::to_string_pretty(&config)
+                        .map_err(|e| Error::InvalidConfig(e.to_string()))?;
+                    write!(stdout, "{content}")?;
+                }
+            },
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::Cli;
+    use clap::{CommandFactory, FromArgMatches};
+
+    fn parse(config: &LtrsConfig, args: &[&str]) -> Cli {
+        let matches = config.apply(Cli::command()).get_matches_from(args);
+        Cli::from_arg_matches(&matches).unwrap()
+    }
+
+    #[test]
+    fn test_template_is_valid() {
+        assert_eq!(
+            toml::from_str::<LtrsConfig>(TEMPLATE).unwrap(),
+            LtrsConfig::default()
+        );
+    }
+
+    #[test]
+    fn test_apply() {
+        let config: LtrsConfig = toml::from_str(
+            r#"
+            hostname = "http://localhost"
+            port = "8081"
+
+            [check]
+            language = "fr"
+            disabled_rules = ["A", "B"]
+            max_length = 100
+            "#,
+        )
+        .unwrap();
+
+        let cli = parse(&config, &["ltrs", "check", "-t", "text"]);
+        assert_eq!(cli.server_cli.hostname, "http://localhost");
+        assert_eq!(cli.server_cli.port, "8081");
+        let crate::cli::Command::Check(cmd) = cli.command else {
+            panic!("expected the check command");
+        };
+        assert_eq!(cmd.request.language, "fr");
+        assert_eq!(
+            cmd.request.disabled_rules,
+            Some(vec!["A".to_string(), "B".to_string()])
+        );
+        assert_eq!(cmd.max_length, 100);
+
+        let cli = parse(&config, &["ltrs", "check", "-l", "de-DE", "-t", "text"]);
+        let crate::cli::Command::Check(cmd) = cli.command else {
+            panic!("expected the check command");
+        };
+        assert_eq!(cmd.request.language, "de-DE");
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, "unknown = 1").unwrap();
+
+        assert!(LtrsConfig::from_path(file.path()).is_err());
+    }
+
+    #[test]
+    fn test_discover() {
+        let dir = tempfile::tempdir().unwrap();
+        let subdir = dir.path().join("docs");
+        std::fs::create_dir(&subdir).unwrap();
+
+        assert_eq!(LtrsConfig::discover(&subdir), None);
+
+        let path = dir.path().join(PROJECT_CONFIG_FILENAME);
+        std::fs::write(&path, "[check]\nignore_line_regex = ['^\\|']\n").unwrap();
+        assert_eq!(LtrsConfig::discover(&subdir), Some(path.clone()));
+
+        let config = LtrsConfig::from_path(&path).unwrap();
+        let cli = parse(&config, &["ltrs", "check", "-t", "text"]);
+        let crate::cli::Command::Check(cmd) = cli.command else {
+            panic!("expected the check command");
+        };
+        assert_eq!(cmd.ignore_line_regex.len(), 1);
+        assert!(cmd.ignore_line_regex[0].is_match("| a | b |"));
+    }
+
+    #[test]
+    fn test_known_words() {
+        let dir = tempfile::tempdir().unwrap();
+        let words = dir.path().join("words.txt");
+        std::fs::write(&words, "LTRS\n").unwrap();
+
+        let config = LtrsConfig {
+            check: CheckConfig {
+                known_words: Some(words),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let cli = parse(&config, &["ltrs", "check", "-t", "text"]);
+        let crate::cli::Command::Check(cmd) = cli.command else {
+            panic!("expected the check command");
+        };
+        assert!(cmd.known_words.unwrap().words.contains("LTRS"));
+    }
+
+    #[test]
+    fn test_ignore_patterns() {
+        let config: LtrsConfig =
+            toml::from_str("[check]\nignore_patterns = ['^\\{\\{.*\\}\\}$']\n").unwrap();
+        let cli = parse(&config, &["ltrs", "check", "-t", "text"]);
+        let crate::cli::Command::Check(cmd) = cli.command else {
+            panic!("expected the check command");
+        };
+        assert!(cmd.ignore_pattern[0].is_match("{{ var }}"));
+    }
+
+    #[test]
+    fn test_language_overrides() {
+        let config: LtrsConfig = toml::from_str(
+            r#"
+            [check]
+            language_overrides = ["docs/fr/**=fr-FR"]
+            mother_tongue_overrides = ["docs/en/**=fr"]
+            preferred_variants_overrides = ["docs/uk/**=en-GB,de-AT"]
+            "#,
+        )
+        .unwrap();
+        let cli = parse(&config, &["ltrs", "check", "-t", "text"]);
+        let crate::cli::Command::Check(cmd) = cli.command else {
+            panic!("expected the check command");
+        };
+        assert_eq!(cmd.language_override[0].glob, "docs/fr/**");
+        assert_eq!(cmd.language_override[0].value, "fr-FR");
+        assert_eq!(cmd.mother_tongue_override[0].value, "fr");
+        assert_eq!(cmd.preferred_variants_override[0].value.len(), 2);
+    }
+
+    #[test]
+    fn test_config_path_from_args() {
+        assert_eq!(
+            config_path_from_args(["ltrs", "--config", "a.toml", "check"]),
+            Some(PathBuf::from("a.toml"))
+        );
+        assert_eq!(
+            config_path_from_args(["ltrs", "check", "--config=b.toml"]),
+            Some(PathBuf::from("b.toml"))
+        );
+        assert_eq!(
+            config_path_from_args(["ltrs", "check", "--", "--config", "c.toml"])
+                .filter(|path| path == Path::new("c.toml")),
+            None
+        );
+    }
+}
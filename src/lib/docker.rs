@@ -1,10 +1,240 @@
 //! Structures and methods to easily manipulate Docker images, especially for
 //! LanguageTool applications.
+//!
+//! Commands are run by a [`ContainerRuntime`], i.e., Docker or an engine with a
+//! compatible command line, such as Podman or nerdctl.
+//!
+//! The options a container is started with are saved in a [`DockerState`], so
+//! that it can be restarted or upgraded later with the same options.
 
-use crate::error::{exit_status_error, Error, Result};
+use crate::{
+    error::{exit_status_error, Error, Result},
+    server::ServerClient,
+};
 #[cfg(feature = "cli")]
-use clap::{Args, Parser};
-use std::process::{Command, Output, Stdio};
+use clap::{Args, Parser, ValueEnum};
+use serde::{Deserialize, Serialize};
+use std::{
+    net::TcpListener,
+    path::{Path, PathBuf},
+    process::{Command, Output, Stdio},
+    time::Duration,
+};
+
+/// Maximum number of ports tried after a busy one, see
+/// [`Docker::resolve_port`].
+const MAX_PORT_ATTEMPTS: u16 = 100;
+
+/// Split a port mapping, like `[IP:]HOST_PORT:CONTAINER_PORT`, into its host
+/// address, host port, and container port.
+///
+/// Return `None` if the mapping does not specify a host port.
+fn parse_port_mapping(mapping: &str) -> Option<(&str, u16, &str)> {
+    let (host, container) = mapping.rsplit_once(':')?;
+    let (ip, port) = host.rsplit_once(':').unwrap_or(("", host));
+    Some((ip, port.parse().ok()?, container))
+}
+
+/// Return `true` if a TCP port can be bound on the given address.
+fn is_port_free(ip: &str, port: u16) -> bool {
+    let ip = if ip.is_empty() { "0.0.0.0" } else { ip };
+    TcpListener::bind((ip, port)).is_ok()
+}
+
+/// Interval between two requests while waiting for the server to be ready.
+const READINESS_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Number of lines of the container's logs reported when the server is not
+/// ready in time.
+const LOG_LINES: &str = "20";
+
+/// Directory where n-gram data is mounted in the container, see
+/// [`Docker::ngrams`].
+const NGRAMS_MOUNT: &str = "/ngrams";
+
+/// Path where the fastText model is mounted in the container, see
+/// [`Docker::fasttext`].
+const FASTTEXT_MOUNT: &str = "/fasttext/lid.176.bin";
+
+/// When to pull the image before starting a container, as Docker's `--pull`
+/// option.
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PullPolicy {
+    /// Always pull the image.
+    Always,
+    /// Pull the image if it is not present locally.
+    #[default]
+    Missing,
+    /// Never pull the image.
+    Never,
+}
+
+impl PullPolicy {
+    /// Return the value of Docker's `--pull` option.
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Always => "always",
+            Self::Missing => "missing",
+            Self::Never => "never",
+        }
+    }
+}
+
+/// Return the absolute path of a file mounted in the container.
+fn mount_source(path: &Path) -> Result<String> {
+    let path = path
+        .canonicalize()
+        .map_err(|_| Error::InvalidFilename(path.display().to_string()))?;
+    Ok(path.display().to_string())
+}
+
+/// Command line of a container engine, compatible with Docker's.
+///
+/// Engines only differ by the few details below, and every other argument is
+/// built by [`Docker`].
+pub trait ContainerRuntime {
+    /// Default name of the engine's binary, e.g., `docker`.
+    fn default_bin(&self) -> &str;
+
+    /// Return the reference of an image as understood by the engine, e.g., by
+    /// qualifying it with a registry.
+    fn image_reference(&self, image: &str) -> String {
+        image.to_string()
+    }
+
+    /// Return the `ps` filter matching the containers that publish a host
+    /// port, if the engine supports one.
+    fn publish_filter(&self, port: u16) -> Option<String> {
+        Some(format!("publish={port}"))
+    }
+}
+
+/// Container engines, see [`ContainerRuntime`].
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Runtime {
+    /// The first engine found in `PATH`, trying Docker, Podman, and nerdctl in
+    /// this order, or Docker if none is found.
+    #[default]
+    Auto,
+    /// Docker.
+    Docker,
+    /// Podman, which can run rootless containers.
+    Podman,
+    /// nerdctl, containerd's command line.
+    Nerdctl,
+}
+
+/// Return `true` if an executable named `bin` is in a directory of `PATH`.
+fn is_in_path(bin: &str) -> bool {
+    std::env::var_os("PATH").is_some_and(|paths| {
+        std::env::split_paths(&paths).any(|dir| {
+            dir.join(bin).is_file() || dir.join(format!("{bin}.exe")).is_file()
+        })
+    })
+}
+
+impl Runtime {
+    /// Return the engine [`Runtime::Auto`] stands for, or `self` otherwise.
+    #[must_use]
+    pub fn resolve(self) -> Self {
+        if self != Self::Auto {
+            return self;
+        }
+        [Self::Docker, Self::Podman, Self::Nerdctl]
+            .into_iter()
+            .find(|runtime| is_in_path(runtime.default_bin()))
+            .unwrap_or(Self::Docker)
+    }
+}
+
+impl ContainerRuntime for Runtime {
+    fn default_bin(&self) -> &str {
+        match self.resolve() {
+            Self::Podman => "podman",
+            Self::Nerdctl => "nerdctl",
+            _ => "docker",
+        }
+    }
+
+    fn image_reference(&self, image: &str) -> String {
+        if self.resolve() != Self::Podman {
+            return image.to_string();
+        }
+        // Podman may refuse short names, that Docker resolves on Docker Hub.
+        match image.split_once('/') {
+            Some((registry, _)) if registry.contains(['.', ':']) || registry == "localhost" => {
+                image.to_string()
+            },
+            Some(_) => format!("docker.io/{image}"),
+            None => format!("docker.io/library/{image}"),
+        }
+    }
+
+    fn publish_filter(&self, port: u16) -> Option<String> {
+        match self.resolve() {
+            Self::Podman => None,
+            _ => Some(format!("publish={port}")),
+        }
+    }
+}
+
+/// Options a container was started with, so that it can be recreated.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct DockerState {
+    /// Image reference, see [`Docker::image`].
+    pub image: String,
+    /// Published port mapping, after resolving busy ports.
+    pub port: String,
+    /// Arguments passed to `docker`, starting with `run`.
+    pub args: Vec<String>,
+}
+
+impl DockerState {
+    /// Return the default path of the state file of a container, inside the
+    /// user's cache directory (e.g., `~/.cache/ltrs/docker/languagetool.json`
+    /// on Linux).
+    #[cfg(feature = "cli")]
+    #[must_use]
+    pub fn default_path(container_name: &str) -> Option<PathBuf> {
+        dirs::cache_dir().map(|dir| {
+            dir.join("ltrs")
+                .join("docker")
+                .join(format!("{container_name}.json"))
+        })
+    }
+
+    /// Read a state file, or return `None` if it does not exist.
+    ///
+    /// # Errors
+    ///
+    /// If the file exists but cannot be read or is not a valid state.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Option<Self>> {
+        match std::fs::read_to_string(path) {
+            Ok(content) => Ok(Some(serde_json::from_str(&content)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Write the state to `path`, creating its parent directories if needed.
+    ///
+    /// # Errors
+    ///
+    /// If the file cannot be written.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
 
 /// Commands to pull, start and stop a `LanguageTool` container using Docker.
 #[cfg_attr(feature = "cli", derive(Args))]
@@ -19,17 +249,31 @@ pub struct Docker {
         )
     )]
     name: String,
-    /// Path to Docker's binaries.
+    /// Tag of the image, e.g., a LanguageTool version like `6.4`. Defaults to
+    /// the image's latest tag.
+    #[cfg_attr(
+        feature = "cli",
+        clap(short = 't', long, env = "LANGUAGETOOL_DOCKER_TAG")
+    )]
+    tag: Option<String>,
+    /// Container engine used to run LanguageTool.
     #[cfg_attr(
         feature = "cli",
         clap(
-            short = 'b',
             long,
-            default_value = "docker",
-            env = "LANGUAGETOOL_DOCKER_BIN"
+            value_enum,
+            default_value_t,
+            env = "LANGUAGETOOL_CONTAINER_RUNTIME"
         )
     )]
-    bin: String,
+    runtime: Runtime,
+    /// Path to the container engine's binary. Defaults to the binary of
+    /// `--runtime`, e.g., `docker` or `podman`.
+    #[cfg_attr(
+        feature = "cli",
+        clap(short = 'b', long, env = "LANGUAGETOOL_DOCKER_BIN")
+    )]
+    bin: Option<String>,
     /// Name assigned to the container.
     #[cfg_attr(
         feature = "cli",
@@ -47,6 +291,38 @@ pub struct Docker {
         )
     )]
     port: String,
+    /// Options passed to the server's JVM with the `JAVA_OPTS` environ
+    /// variable, e.g., `-Xms512m -Xmx2g` to set memory limits.
+    #[cfg_attr(
+        feature = "cli",
+        clap(
+            long,
+            value_name = "OPTS",
+            allow_hyphen_values = true,
+            env = "LANGUAGETOOL_DOCKER_JAVA_OPTS"
+        )
+    )]
+    java_opts: Option<String>,
+    /// Directory with n-gram data, mounted in the container to enable
+    /// n-gram based rules.
+    #[cfg_attr(
+        feature = "cli",
+        clap(long, value_name = "DIR", env = "LANGUAGETOOL_DOCKER_NGRAMS")
+    )]
+    ngrams: Option<PathBuf>,
+    /// fastText model (`lid.176.bin`), mounted in the container to improve
+    /// language detection.
+    #[cfg_attr(
+        feature = "cli",
+        clap(long, value_name = "FILE", env = "LANGUAGETOOL_DOCKER_FASTTEXT")
+    )]
+    fasttext: Option<PathBuf>,
+    /// When to pull the image before starting a container.
+    #[cfg_attr(
+        feature = "cli",
+        clap(long, value_enum, default_value_t, env = "LANGUAGETOOL_DOCKER_PULL")
+    )]
+    pull: PullPolicy,
     /// Docker action.
     #[cfg_attr(feature = "cli", clap(subcommand))]
     action: Action,
@@ -58,60 +334,320 @@ pub struct Docker {
 enum Action {
     /// Pull a docker docker image.
     ///
-    /// Alias to `{docker.bin} pull {docker.name}`.
+    /// Alias to `{docker.bin} pull {docker.name}:{docker.tag}`.
     Pull,
     /// Start a (detached) docker container.
     ///
-    /// Alias to `{docker.bin} run --rm -d -p {docker.port} {docker.name}`
-    Start,
+    /// Alias to `{docker.bin} run --rm -d -p {docker.port} {docker.name}:{docker.tag}`,
+    /// with the options set by other flags.
+    Start {
+        /// If the host port is already in use, publish the container on the
+        /// next free port instead of failing.
+        #[cfg_attr(feature = "cli", clap(long))]
+        next_free_port: bool,
+        /// If present, wait until the server answers requests before exiting.
+        #[cfg_attr(feature = "cli", clap(long))]
+        wait: bool,
+        /// Maximum time to wait for the server with `--wait`, in seconds.
+        #[cfg_attr(
+            feature = "cli",
+            clap(long, value_name = "SECONDS", default_value_t = 120, requires = "wait")
+        )]
+        wait_timeout: u64,
+    },
     /// Stop a docker container.
     ///
     /// Alias to `{docker.bin} kill $({docker.bin} ps -l -f
     /// "name={docker.container_name}")`.
     Stop,
+    /// Print the status of the container, and the options it was started
+    /// with.
+    Status,
+    /// Print the logs of the container.
+    ///
+    /// Alias to `{docker.bin} logs {docker.container_name}`.
+    Logs {
+        /// If present, follow the logs until interrupted.
+        #[cfg_attr(feature = "cli", clap(short = 'f', long))]
+        follow: bool,
+    },
+    /// Restart the container, or start it again with the options it was last
+    /// started with if it was stopped.
+    Restart,
+    /// Pull the image again, and recreate the container with the options it
+    /// was last started with.
+    Upgrade,
 }
 
 impl Docker {
+    /// Return the image reference, i.e., its name followed by its tag, if
+    /// any, as understood by the container engine.
+    #[must_use]
+    pub fn image(&self) -> String {
+        let image = match &self.tag {
+            Some(tag) => format!("{}:{tag}", self.name),
+            None => self.name.clone(),
+        };
+        self.runtime.image_reference(&image)
+    }
+
+    /// Return the path to the container engine's binary.
+    #[must_use]
+    pub fn bin(&self) -> String {
+        match &self.bin {
+            Some(bin) => bin.clone(),
+            None => self.runtime.default_bin().to_string(),
+        }
+    }
+
+    /// Return a command running the container engine's binary.
+    fn command(&self) -> Command {
+        Command::new(self.bin())
+    }
+
+    /// Return the name assigned to the container.
+    #[must_use]
+    pub fn container_name(&self) -> &str {
+        &self.container_name
+    }
+
     /// Pull a Docker image from the given repository/file/...
     pub fn pull(&self) -> Result<Output> {
-        let output = Command::new(&self.bin)
-            .args(["pull", &self.name])
+        self.pull_image(&self.image())
+    }
+
+    /// Pull the given image.
+    fn pull_image(&self, image: &str) -> Result<Output> {
+        let output = self.command()
+            .args(["pull", image])
             .stdout(Stdio::inherit())
             .stderr(Stdio::inherit())
             .output()
-            .map_err(|_| Error::CommandNotFound(self.bin.to_string()))?;
+            .map_err(|_| Error::CommandNotFound(self.bin()))?;
 
         exit_status_error(&output.status)?;
 
         Ok(output)
     }
 
+    /// Describe what is using a given host port, e.g., a container or a
+    /// process, if it can be found.
+    fn port_owner(&self, port: u16) -> Option<String> {
+        let stdout = |program: &str, args: &[&str]| {
+            Command::new(program)
+                .args(args)
+                .stderr(Stdio::null())
+                .output()
+                .ok()
+                .filter(|output| output.status.success())
+                .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+                .filter(|stdout| !stdout.is_empty())
+        };
+
+        if let Some(names) = self.runtime.publish_filter(port).and_then(|filter| {
+            stdout(
+                &self.bin(),
+                &["ps", "--filter", &filter, "--format", "{{.Names}}"],
+            )
+        }) {
+            return Some(format!("container {:?}", names.replace('\n', ", ")));
+        }
+
+        // Fields are prefixed with their name: 'p' for PID and 'c' for command.
+        let fields = stdout(
+            "lsof",
+            &["-nP", &format!("-iTCP:{port}"), "-sTCP:LISTEN", "-Fpc"],
+        )?;
+        let pid = fields.lines().find_map(|line| line.strip_prefix('p'))?;
+        let command = fields
+            .lines()
+            .find_map(|line| line.strip_prefix('c'))
+            .unwrap_or("unknown");
+        Some(format!("process {command:?} (PID {pid})"))
+    }
+
+    /// Return the port mapping to publish, after checking that its host port
+    /// is free.
+    ///
+    /// If `next_free_port` is `true`, a busy host port is replaced by the next
+    /// free one.
+    ///
+    /// # Errors
+    ///
+    /// If the host port is busy, naming what uses it when possible.
+    pub fn resolve_port(&self, next_free_port: bool) -> Result<String> {
+        let Some((ip, port, container)) = parse_port_mapping(&self.port) else {
+            return Ok(self.port.clone());
+        };
+
+        if is_port_free(ip, port) {
+            return Ok(self.port.clone());
+        }
+
+        if next_free_port {
+            let free_port = (port.saturating_add(1)..=port.saturating_add(MAX_PORT_ATTEMPTS))
+                .find(|&port| is_port_free(ip, port));
+            if let Some(free_port) = free_port {
+                return Ok(match ip {
+                    "" => format!("{free_port}:{container}"),
+                    ip => format!("{ip}:{free_port}:{container}"),
+                });
+            }
+        }
+
+        Err(Error::PortInUse {
+            port,
+            owner: self
+                .port_owner(port)
+                .unwrap_or_else(|| "another process".to_string()),
+        })
+    }
+
     /// Start a Docker container with given specifications.
+    ///
+    /// # Errors
+    ///
+    /// If the host port is already in use, see [`Docker::resolve_port`].
     pub fn start(&self) -> Result<Output> {
-        let output = Command::new(&self.bin)
-            .args([
-                "run",
-                "--rm",
-                "--name",
-                &self.container_name,
-                "-d",
-                "-p",
-                "8010:8010",
-                &self.name,
-            ])
+        self.start_with_port(&self.resolve_port(false)?)
+    }
+
+    /// Return the arguments of `docker run` to start a container publishing
+    /// the given port mapping.
+    ///
+    /// # Errors
+    ///
+    /// If a mounted path does not exist.
+    fn run_args(&self, port: &str) -> Result<Vec<String>> {
+        let mut args: Vec<String> = [
+            "run",
+            "--rm",
+            "--name",
+            &self.container_name,
+            "-d",
+            "-p",
+            port,
+        ]
+        .map(String::from)
+        .into();
+
+        if self.pull != PullPolicy::default() {
+            args.extend(["--pull".to_string(), self.pull.as_str().to_string()]);
+        }
+        if let Some(java_opts) = &self.java_opts {
+            args.extend(["-e".to_string(), format!("JAVA_OPTS={java_opts}")]);
+        }
+        if let Some(ngrams) = &self.ngrams {
+            args.extend([
+                "-v".to_string(),
+                format!("{}:{NGRAMS_MOUNT}:ro", mount_source(ngrams)?),
+                "-e".to_string(),
+                format!("langtool_languageModel={NGRAMS_MOUNT}"),
+            ]);
+        }
+        if let Some(fasttext) = &self.fasttext {
+            args.extend([
+                "-v".to_string(),
+                format!("{}:{FASTTEXT_MOUNT}:ro", mount_source(fasttext)?),
+                "-e".to_string(),
+                format!("langtool_fasttextModel={FASTTEXT_MOUNT}"),
+            ]);
+        }
+
+        args.push(self.image());
+        Ok(args)
+    }
+
+    /// Return the options to start a container publishing the given port
+    /// mapping.
+    ///
+    /// # Errors
+    ///
+    /// If a mounted path does not exist.
+    pub fn state(&self, port: &str) -> Result<DockerState> {
+        Ok(DockerState {
+            image: self.image(),
+            port: port.to_string(),
+            args: self.run_args(port)?,
+        })
+    }
+
+    /// Start a Docker container publishing the given port mapping.
+    fn start_with_port(&self, port: &str) -> Result<Output> {
+        self.run(&self.run_args(port)?)
+    }
+
+    /// Run `docker` with the given arguments, printing its output.
+    fn run(&self, args: &[String]) -> Result<Output> {
+        let output = self.command()
+            .args(args)
             .stdout(Stdio::inherit())
             .stderr(Stdio::inherit())
             .output()
-            .map_err(|_| Error::CommandNotFound(self.bin.to_string()))?;
+            .map_err(|_| Error::CommandNotFound(self.bin()))?;
 
         exit_status_error(&output.status)?;
 
         Ok(output)
     }
 
+    /// Return the last lines logged by the container, or an empty string if
+    /// they cannot be read.
+    fn last_logs(&self) -> String {
+        self.command()
+            .args(["logs", "--tail", LOG_LINES, &self.container_name])
+            .output()
+            .map(|output| {
+                // LanguageTool logs to both standard output and standard error.
+                let mut logs = String::from_utf8_lossy(&output.stdout).into_owned();
+                logs.push_str(&String::from_utf8_lossy(&output.stderr));
+                logs.trim_end().to_string()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Wait until the server of a started container answers requests to its
+    /// `languages` endpoint, for at most `timeout`.
+    ///
+    /// # Errors
+    ///
+    /// If the server is not ready in time, with the last logs of the
+    /// container.
+    pub async fn wait_ready(&self, timeout: Duration) -> Result<()> {
+        self.wait_ready_on_port(&self.port, timeout).await
+    }
+
+    /// Wait until the server published with the given port mapping is ready,
+    /// see [`Docker::wait_ready`].
+    async fn wait_ready_on_port(&self, port: &str, timeout: Duration) -> Result<()> {
+        let (ip, host_port) = match parse_port_mapping(port) {
+            Some((ip, host_port, _)) => (ip, host_port.to_string()),
+            None => ("", port.to_string()),
+        };
+        let hostname = match ip {
+            "" | "0.0.0.0" => "http://localhost".to_string(),
+            ip => format!("http://{ip}"),
+        };
+        let client = ServerClient::new(&hostname, &host_port);
+        let deadline = tokio::time::Instant::now() + timeout;
+
+        loop {
+            if client.languages().await.is_ok() {
+                return Ok(());
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return Err(Error::ServerNotReady {
+                    seconds: timeout.as_secs(),
+                    logs: self.last_logs(),
+                });
+            }
+            tokio::time::sleep(READINESS_INTERVAL).await;
+        }
+    }
+
     /// Stop the latest Docker container with the given name.
     pub fn stop(&self) -> Result<Output> {
-        let output = Command::new(&self.bin)
+        let output = self.command()
             .args([
                 "ps",
                 "-l",
@@ -121,7 +657,7 @@ impl Docker {
             ])
             .stderr(Stdio::inherit())
             .output()
-            .map_err(|_| Error::CommandNotFound(self.bin.to_string()))?;
+            .map_err(|_| Error::CommandNotFound(self.bin()))?;
 
         exit_status_error(&output.status)?;
 
@@ -130,7 +666,7 @@ impl Docker {
             .filter(|c| c.is_alphanumeric()) // This avoids newlines
             .collect();
 
-        let output = Command::new(&self.bin)
+        let output = self.command()
             .args(["kill", &docker_id])
             .stdout(Stdio::inherit())
             .stderr(Stdio::inherit())
@@ -141,12 +677,88 @@ impl Docker {
         Ok(output)
     }
 
+    /// Return the status of the container, e.g., `Up 5 minutes`, or `None`
+    /// if there is no container with the given name.
+    ///
+    /// # Errors
+    ///
+    /// If Docker cannot be run.
+    pub fn status(&self) -> Result<Option<String>> {
+        let output = self.command()
+            .args([
+                "ps",
+                "-a",
+                "-f",
+                &format!("name=^{}$", self.container_name),
+                "--format",
+                "{{.Status}}",
+            ])
+            .stderr(Stdio::inherit())
+            .output()
+            .map_err(|_| Error::CommandNotFound(self.bin()))?;
+
+        exit_status_error(&output.status)?;
+
+        let status = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        Ok((!status.is_empty()).then_some(status))
+    }
+
+    /// Print the logs of the container, and keep printing new ones if
+    /// `follow` is `true`.
+    pub fn logs(&self, follow: bool) -> Result<Output> {
+        let mut args = vec!["logs".to_string()];
+        if follow {
+            args.push("--follow".to_string());
+        }
+        args.push(self.container_name.clone());
+        self.run(&args)
+    }
+
+    /// Restart the container, or, if there is none, start one with the
+    /// options of `state`, or with the current options if `state` is `None`.
+    pub fn restart(&self, state: Option<&DockerState>) -> Result<Output> {
+        if self.status()?.is_some() {
+            return self.run(&["restart".to_string(), self.container_name.clone()]);
+        }
+        match state {
+            Some(state) => self.run(&state.args),
+            None => self.start(),
+        }
+    }
+
+    /// Pull the image of `state` again, remove the container if any, and
+    /// start a new one with the options of `state`.
+    pub fn upgrade(&self, state: &DockerState) -> Result<Output> {
+        self.pull_image(&state.image)?;
+        if self.status()?.is_some() {
+            self.run(&[
+                "rm".to_string(),
+                "--force".to_string(),
+                self.container_name.clone(),
+            ])?;
+        }
+        self.run(&state.args)
+    }
+
     /// Run a Docker command according to `self.action`.
     pub fn run_action(&self) -> Result<Output> {
         match self.action {
             Action::Pull => self.pull(),
-            Action::Start => self.start(),
+            Action::Start { next_free_port, .. } => {
+                self.start_with_port(&self.resolve_port(next_free_port)?)
+            },
             Action::Stop => self.stop(),
+            Action::Status => {
+                self.run(&[
+                    "ps".to_string(),
+                    "-a".to_string(),
+                    "-f".to_string(),
+                    format!("name=^{}$", self.container_name),
+                ])
+            },
+            Action::Logs { follow } => self.logs(follow),
+            Action::Restart => self.restart(None),
+            Action::Upgrade => self.upgrade(&self.state(&self.port)?),
         }
     }
 }
@@ -163,11 +775,282 @@ pub struct DockerCommand {
 #[cfg(feature = "cli")]
 impl DockerCommand {
     /// Execute a Docker command and write output to stdout.
-    pub fn execute<W>(&self, _stdout: &mut W) -> Result<()>
+    pub async fn execute<W>(&self, stdout: &mut W) -> Result<()>
     where
         W: std::io::Write,
     {
-        self.docker.run_action()?;
+        let docker = &self.docker;
+        let state_path = DockerState::default_path(&docker.container_name);
+        let saved_state = match &state_path {
+            Some(path) => DockerState::from_path(path)?,
+            None => None,
+        };
+
+        match docker.action {
+            Action::Start {
+                next_free_port,
+                wait,
+                wait_timeout,
+            } => {
+                let port = docker.resolve_port(next_free_port)?;
+                let state = docker.state(&port)?;
+                docker.run(&state.args)?;
+                if let Some(path) = &state_path {
+                    state.save(path)?;
+                }
+
+                if wait {
+                    docker
+                        .wait_ready_on_port(&port, Duration::from_secs(wait_timeout))
+                        .await?;
+                }
+                if let Some((_, host_port, _)) = parse_port_mapping(&port) {
+                    let status = if wait { "ready" } else { "starting" };
+                    writeln!(
+                        stdout,
+                        "LanguageTool server is {status}, use `ltrs --hostname http://localhost \
+                         --port {host_port}` to connect to it."
+                    )?;
+                }
+            },
+            Action::Status => {
+                match docker.status()? {
+                    Some(status) => {
+                        writeln!(stdout, "Container {:?}: {status}", docker.container_name)?;
+                    },
+                    None => writeln!(stdout, "Container {:?}: not found", docker.container_name)?,
+                }
+                if let Some(state) = &saved_state {
+                    writeln!(
+                        stdout,
+                        "Last started from image {:?}, publishing port {}",
+                        state.image, state.port
+                    )?;
+                }
+            },
+            Action::Restart => {
+                docker.restart(saved_state.as_ref())?;
+            },
+            Action::Upgrade => {
+                let state = match saved_state {
+                    Some(state) => state,
+                    None => docker.state(&docker.port)?,
+                };
+                docker.upgrade(&state)?;
+                if let Some(path) = &state_path {
+                    state.save(path)?;
+                }
+                writeln!(
+                    stdout,
+                    "Recreated container {:?} from image {:?}",
+                    docker.container_name, state.image
+                )?;
+            },
+            _ => {
+                docker.run_action()?;
+            },
+        }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_port_mapping() {
+        assert_eq!(parse_port_mapping("8010:8010"), Some(("", 8010, "8010")));
+        assert_eq!(
+            parse_port_mapping("127.0.0.1:8081:8010"),
+            Some(("127.0.0.1", 8081, "8010"))
+        );
+        assert_eq!(parse_port_mapping("8010"), None);
+    }
+
+    fn docker(port: &str) -> Docker {
+        Docker {
+            name: "erikvl87/languagetool".to_string(),
+            tag: None,
+            runtime: Runtime::Docker,
+            bin: None,
+            container_name: "languagetool".to_string(),
+            port: port.to_string(),
+            java_opts: None,
+            ngrams: None,
+            fasttext: None,
+            pull: PullPolicy::default(),
+            action: Action::Pull,
+        }
+    }
+
+    #[test]
+    fn test_resolve_port() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let docker = docker(&format!("127.0.0.1:{port}:8010"));
+
+        match docker.resolve_port(false) {
+            Err(Error::PortInUse { port: busy, .. }) => assert_eq!(busy, port),
+            other => panic!("expected the port to be in use, got {other:?}"),
+        }
+
+        let resolved = docker.resolve_port(true).unwrap();
+        let (_, free_port, container) = parse_port_mapping(&resolved).unwrap();
+        assert!(free_port > port);
+        assert_eq!(container, "8010");
+    }
+
+    #[tokio::test]
+    async fn test_wait_ready() {
+        use std::io::{Read, Write};
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        // The server is unavailable at first, as while LanguageTool loads
+        let server = std::thread::spawn(move || {
+            for (i, stream) in listener.incoming().take(2).enumerate() {
+                let mut stream = stream.unwrap();
+                let _ = stream.read(&mut [0; 4096]).unwrap();
+                let status = if i == 0 {
+                    "503 Service Unavailable"
+                } else {
+                    "200 OK"
+                };
+                write!(
+                    stream,
+                    "HTTP/1.1 {status}\r\ncontent-length: 2\r\nconnection: close\r\n\r\n[]"
+                )
+                .unwrap();
+            }
+        });
+
+        let docker = docker(&format!("127.0.0.1:{port}:8010"));
+        docker.wait_ready(Duration::from_secs(10)).await.unwrap();
+        server.join().unwrap();
+
+        // Nothing listens on the port anymore
+        assert!(matches!(
+            docker.wait_ready(Duration::ZERO).await,
+            Err(Error::ServerNotReady { seconds: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn test_run_args() {
+        assert_eq!(
+            docker("8010:8010").run_args("8010:8010").unwrap(),
+            [
+                "run",
+                "--rm",
+                "--name",
+                "languagetool",
+                "-d",
+                "-p",
+                "8010:8010",
+                "erikvl87/languagetool"
+            ]
+        );
+
+        let ngrams = tempfile::tempdir().unwrap();
+        let docker = Docker {
+            tag: Some("6.4".to_string()),
+            java_opts: Some("-Xmx2g".to_string()),
+            ngrams: Some(ngrams.path().to_path_buf()),
+            pull: PullPolicy::Always,
+            ..docker("8010:8010")
+        };
+        let args = docker.run_args("8081:8010").unwrap().join(" ");
+        let source = ngrams.path().canonicalize().unwrap();
+
+        assert!(args.contains("-p 8081:8010 --pull always -e JAVA_OPTS=-Xmx2g"));
+        assert!(args.contains(&format!("-v {}:/ngrams:ro", source.display())));
+        assert!(args.ends_with("-e langtool_languageModel=/ngrams erikvl87/languagetool:6.4"));
+
+        let docker = Docker {
+            fasttext: Some(ngrams.path().join("missing.bin")),
+            ..docker
+        };
+        assert!(matches!(
+            docker.run_args("8010:8010"),
+            Err(Error::InvalidFilename(_))
+        ));
+    }
+
+    #[test]
+    fn test_state_save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("docker").join("languagetool.json");
+        let state = docker("8010:8010").state("8081:8010").unwrap();
+
+        assert_eq!(DockerState::from_path(&path).unwrap(), None);
+        state.save(&path).unwrap();
+        assert_eq!(DockerState::from_path(&path).unwrap(), Some(state));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_restart_and_upgrade() {
+        use std::os::unix::fs::PermissionsExt;
+
+        // Fake Docker binary, logging its arguments, without any container
+        let dir = tempfile::tempdir().unwrap();
+        let bin = dir.path().join("docker");
+        let log = dir.path().join("log");
+        std::fs::write(
+            &bin,
+            format!("#!/bin/sh\necho \"$*\" >> {}\n", log.display()),
+        )
+        .unwrap();
+        std::fs::set_permissions(&bin, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let docker = Docker {
+            bin: Some(bin.display().to_string()),
+            ..docker("8010:8010")
+        };
+        let state = Docker {
+            tag: Some("6.4".to_string()),
+            ..docker.clone()
+        }
+        .state("8081:8010")
+        .unwrap();
+
+        assert_eq!(docker.status().unwrap(), None);
+        docker.restart(Some(&state)).unwrap();
+        docker.upgrade(&state).unwrap();
+
+        let log = std::fs::read_to_string(log).unwrap();
+        let commands: Vec<&str> = log
+            .lines()
+            .map(|line| line.split(' ').next().unwrap())
+            .collect();
+        assert_eq!(commands, ["ps", "ps", "run", "pull", "ps", "run"]);
+        assert!(log.contains("pull erikvl87/languagetool:6.4"));
+        assert!(log.contains("-p 8081:8010"));
+    }
+
+    #[test]
+    fn test_podman_image_reference() {
+        let podman = Runtime::Podman;
+
+        assert_eq!(podman.default_bin(), "podman");
+        assert_eq!(
+            podman.image_reference("erikvl87/languagetool:6.4"),
+            "docker.io/erikvl87/languagetool:6.4"
+        );
+        assert_eq!(podman.image_reference("alpine"), "docker.io/library/alpine");
+        assert_eq!(
+            podman.image_reference("ghcr.io/owner/languagetool"),
+            "ghcr.io/owner/languagetool"
+        );
+        assert_eq!(
+            podman.image_reference("localhost/languagetool"),
+            "localhost/languagetool"
+        );
+        assert_eq!(
+            Runtime::Docker.image_reference("erikvl87/languagetool"),
+            "erikvl87/languagetool"
+        );
+        assert_ne!(Runtime::Auto.resolve(), Runtime::Auto);
+    }
+}
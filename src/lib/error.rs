@@ -9,14 +9,54 @@ pub enum Error {
     #[error(transparent)]
     Cli(#[from] clap::Error),
 
+    /// Error when a request was not sent because the client was cancelled,
+    /// see [`ServerClient::cancel`](crate::server::ServerClient::cancel).
+    #[error("request was cancelled")]
+    Cancelled,
+
+    /// Error when a downloaded file does not have the expected SHA-256
+    /// digest, see
+    /// [`LocalServer::download`](crate::local_server::LocalServer::download).
+    #[cfg(feature = "local-server")]
+    #[error("checksum mismatch for {url} (expected {expected}, got {actual})")]
+    ChecksumMismatch {
+        /// URL of the downloaded file.
+        url: String,
+        /// Expected digest, in hexadecimal.
+        expected: String,
+        /// Digest of the downloaded file, in hexadecimal.
+        actual: String,
+    },
+
     /// Error when a process command was not found.
     #[error("command not found: {0}")]
     CommandNotFound(String),
 
+    /// Error when a deadline elapsed before any response was received.
+    #[error("deadline exceeded before any response was received")]
+    DeadlineExceeded,
+
     /// Error from a command line process (see [`std::process::Command`]).
     #[error("command failed: {0:?}")]
     ExitStatus(String),
 
+    /// Error when some files were skipped, e.g., because checking them took
+    /// longer than `--per-file-timeout`.
+    #[cfg(feature = "cli")]
+    #[error("{0} file(s) were skipped, see the warnings above")]
+    FilesSkipped(usize),
+
+    /// Error when the run was stopped after reporting the maximum number of
+    /// matches, see `--max-errors`.
+    #[cfg(feature = "cli")]
+    #[error("stopped after reporting {0} match(es) (--max-errors)")]
+    MaxErrorsReached(usize),
+
+    /// Error from reading or parsing a configuration file.
+    #[cfg(feature = "cli")]
+    #[error("invalid configuration: {0}")]
+    InvalidConfig(String),
+
     /// Error specifying an invalid
     /// [`DataAnnotation`](`crate::check::DataAnnotation`).
     #[error("invalid request: {0}")]
@@ -30,6 +70,12 @@ pub enum Error {
     #[error("invalid request: {0}")]
     InvalidRequest(String),
 
+    /// Error from importing rules that are not well-formed, see
+    /// [`RuleSet::from_grammar_xml`](crate::rules::RuleSet::from_grammar_xml).
+    #[cfg(feature = "rules")]
+    #[error("invalid rules: {0}")]
+    InvalidRules(String),
+
     /// Error specifying an invalid value.
     #[error("invalid value: {0:?}")]
     InvalidValue(String),
@@ -43,6 +89,11 @@ pub enum Error {
     #[error(transparent)]
     JoinError(#[from] tokio::task::JoinError),
 
+    /// Error from accessing the system's keychain (see [`keyring::Error`]).
+    #[cfg(feature = "keyring")]
+    #[error(transparent)]
+    Keyring(#[from] keyring::Error),
+
     /// Error from parsing JSON (see [`serde_json::Error`]).
     #[error(transparent)]
     JSON(#[from] serde_json::Error),
@@ -55,17 +106,223 @@ pub enum Error {
     #[error("request could not be properly encoded: {0}")]
     RequestEncode(reqwest::Error),
 
+    /// Error when the host port of a container is already in use.
+    #[cfg(feature = "docker")]
+    #[error("port {port} is already in use by {owner}")]
+    PortInUse {
+        /// Host port.
+        port: u16,
+        /// Description of what uses the port.
+        owner: String,
+    },
+
     /// Any other error from requests (see [`reqwest::Error`]).
     #[error(transparent)]
-    Reqwest(#[from] reqwest::Error),
+    Reqwest(reqwest::Error),
 
     /// Error from request decoding.
     #[error("response could not be properly decoded: {0}")]
     ResponseDecode(reqwest::Error),
 
+    /// Error response of the server, i.e., with a non-success HTTP status,
+    /// see [`Error::server`].
+    #[error("server error ({status}): {message}")]
+    Server {
+        /// HTTP status of the response.
+        status: reqwest::StatusCode,
+        /// Error message, parsed from the body.
+        message: String,
+        /// Body of the response.
+        body: String,
+        /// Delay the server asked to wait before retrying, read from the
+        /// `Retry-After` header, if any.
+        retry_after: Option<std::time::Duration>,
+    },
+
+    /// Error when a container's server did not respond in time, see
+    /// [`Docker::wait_ready`](crate::docker::Docker::wait_ready).
+    #[cfg(feature = "docker")]
+    #[error("server was not ready after {seconds}s, last logs of the container:\n{logs}")]
+    ServerNotReady {
+        /// Time waited, in seconds.
+        seconds: u64,
+        /// Last lines logged by the container.
+        logs: String,
+    },
+
+    /// Error when a request did not complete in time, see
+    /// [`ServerClient::with_timeout`](crate::server::ServerClient::with_timeout).
+    #[error("request timed out: {0}")]
+    Timeout(reqwest::Error),
+
+    /// Error when a language code is not supported by the server, see
+    /// [`ServerClient::validate_language`](crate::server::ServerClient::validate_language).
+    #[error(
+        "language code {code:?} is not supported by the server{}",
+        did_you_mean(.suggestions)
+    )]
+    UnsupportedLanguage {
+        /// Language code.
+        code: String,
+        /// Supported language codes that are close to `code`.
+        suggestions: Vec<String>,
+    },
+
     /// Error from reading environ variable (see [`std::env::VarError`]).
     #[error(transparent)]
     VarError(#[from] std::env::VarError),
+
+    /// Error from walking directories or parsing globs (see
+    /// [`ignore::Error`](::ignore::Error)).
+    #[cfg(feature = "cli")]
+    #[error(transparent)]
+    Walk(#[from] ::ignore::Error),
+}
+
+impl From<reqwest::Error> for Error {
+    /// Convert timeouts into [`Error::Timeout`], and other errors into
+    /// [`Error::Reqwest`].
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_timeout() {
+            Self::Timeout(e)
+        } else {
+            Self::Reqwest(e)
+        }
+    }
+}
+
+impl Error {
+    /// Build an [`Error::Server`] from the status and the body of an error
+    /// response of the server.
+    ///
+    /// LanguageTool answers with plain text, e.g., `"Error: Access denied:
+    /// ..."`, possibly naming the Java exception that was raised, e.g.,
+    /// `AuthException`, which are stripped from the message. Bodies that are
+    /// JSON objects with a `"message"` or an `"error"` field are also
+    /// understood.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use languagetool_rust::error::Error;
+    /// use reqwest::StatusCode;
+    ///
+    /// let error = Error::server(
+    ///     StatusCode::FORBIDDEN,
+    ///     "Error: org.languagetool.server.AuthException: Invalid API key".to_string(),
+    /// );
+    ///
+    /// assert!(error.is_auth_error());
+    /// assert_eq!(error.to_string(), "server error (403 Forbidden): Invalid API key");
+    /// ```
+    #[must_use]
+    pub fn server(status: reqwest::StatusCode, body: String) -> Self {
+        let json = serde_json::from_str::<serde_json::Value>(&body).ok();
+        let message = json
+            .as_ref()
+            .and_then(|json| json.get("message").or_else(|| json.get("error")))
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or(&body)
+            .trim();
+        let message = message.strip_prefix("Error:").unwrap_or(message).trim_start();
+        // Strip exception names, e.g., `org.languagetool.server.AuthException: `
+        let message = match message.split_once(": ") {
+            Some((exception, rest))
+                if exception.ends_with("Exception") && !exception.contains(char::is_whitespace) =>
+            {
+                rest
+            },
+            _ => message,
+        };
+        let message = match message {
+            "" => status.canonical_reason().unwrap_or_default(),
+            message => message,
+        };
+
+        Self::Server {
+            status,
+            message: message.to_string(),
+            body,
+            retry_after: None,
+        }
+    }
+
+    /// Set the delay the server asked to wait before retrying, of an
+    /// [`Error::Server`].
+    #[must_use]
+    pub fn with_retry_after(mut self, delay: Option<std::time::Duration>) -> Self {
+        if let Self::Server { retry_after, .. } = &mut self {
+            *retry_after = delay;
+        }
+        self
+    }
+
+    /// Return the delay the server asked to wait before retrying, if any.
+    #[must_use]
+    pub fn retry_after(&self) -> Option<std::time::Duration> {
+        match self {
+            Self::Server { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
+
+    /// Return the HTTP status of an [`Error::Server`].
+    #[must_use]
+    pub fn status(&self) -> Option<reqwest::StatusCode> {
+        match self {
+            Self::Server { status, .. } => Some(*status),
+            _ => None,
+        }
+    }
+
+    /// Return `true` if the server rejected the credentials, or their
+    /// absence, e.g., with an invalid API key.
+    #[must_use]
+    pub fn is_auth_error(&self) -> bool {
+        match self {
+            Self::Server { status, body, .. } => {
+                matches!(status.as_u16(), 401 | 403) || body.contains("AuthException")
+            },
+            _ => false,
+        }
+    }
+
+    /// Return `true` if the server refused a request because too many were
+    /// sent, e.g., with `429 Too Many Requests`.
+    #[must_use]
+    pub fn is_rate_limited(&self) -> bool {
+        match self {
+            Self::Server { status, body, .. } => {
+                status.as_u16() == 429 || body.to_lowercase().contains("too many requests")
+            },
+            _ => false,
+        }
+    }
+
+    /// Return `true` if the server refused a check request because its text
+    /// exceeds the server's `maxTextLength`.
+    #[must_use]
+    pub fn is_text_too_long(&self) -> bool {
+        match self {
+            Self::Server { status, body, .. } => {
+                let body = body.to_lowercase();
+                status.as_u16() == 413
+                    || body.contains("exceeds the limit")
+                    || body.contains("text is too long")
+                    || body.contains("maxtextlength")
+            },
+            _ => false,
+        }
+    }
+}
+
+/// Format suggestions as the end of an error message.
+fn did_you_mean(suggestions: &[String]) -> String {
+    match suggestions {
+        [] => String::new(),
+        [suggestion] => format!(", did you mean {suggestion}?"),
+        suggestions => format!(", did you mean one of: {}?", suggestions.join(", ")),
+    }
 }
 
 /// Result type alias with error type defined above (see [`Error`]]).
@@ -142,6 +399,33 @@ mod tests {
         assert!(matches!(error, Error::InvalidRequest(_)));
     }
 
+    #[test]
+    fn test_error_server() {
+        use reqwest::StatusCode;
+
+        let error = Error::server(StatusCode::TOO_MANY_REQUESTS, String::new());
+        assert!(error.is_rate_limited());
+        assert!(!error.is_auth_error());
+        assert_eq!(error.to_string(), "server error (429 Too Many Requests): Too Many Requests");
+
+        let too_long = "Your text exceeds the limit of 20000 characters";
+        let body = serde_json::json!({ "message": too_long }).to_string();
+        let error = Error::server(StatusCode::BAD_REQUEST, body);
+        assert!(error.is_text_too_long());
+        assert!(matches!(&error, Error::Server { message, .. } if message == too_long));
+        assert_eq!(error.status(), Some(StatusCode::BAD_REQUEST));
+
+        let error = Error::server(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Error: Server overloaded, please try again later".to_string(),
+        );
+        assert!(matches!(
+            &error,
+            Error::Server { message, .. } if message == "Server overloaded, please try again later"
+        ));
+        assert!(!error.is_rate_limited());
+    }
+
     #[ignore]
     #[test]
     fn test_error_invalid_value() {
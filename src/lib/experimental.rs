@@ -0,0 +1,237 @@
+//! Experimental fields of check requests and responses.
+//!
+//! Newer servers, especially premium ones, may accept request fields and
+//! return response fields that are not part of the documented API, e.g., to
+//! enable AI-assisted rewriting suggestions or to list hidden matches. This
+//! module lets users evaluate them without waiting for proper support:
+//! [`ExperimentalFields`] are passed through as is with each request, and
+//! unknown response fields are kept as raw JSON, and moved to the
+//! [`ExperimentalResponse`] of a [`CheckReport`](crate::check::CheckReport),
+//! from which [`Suggestion`]s can be extracted.
+//!
+//! Nothing here is stable: fields may disappear from servers, or move to
+//! proper types in future versions.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+/// Extra fields sent, as is, with each check request.
+pub type ExperimentalFields = BTreeMap<String, String>;
+
+/// Response fields that are not part of the documented API, as raw JSON.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct ExperimentalResponse {
+    /// Raw fields, by name.
+    pub fields: Map<String, Value>,
+}
+
+/// Suggestion found in an experimental response field.
+///
+/// Only a few fields are typed, and the raw suggestion is always kept.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct Suggestion {
+    /// Name of the response field the suggestion was found in, e.g.,
+    /// `"hiddenMatches"`.
+    pub field: String,
+    /// Offset of the suggestion in the checked text, in characters.
+    pub offset: usize,
+    /// Length of the suggestion in the checked text, in characters.
+    pub length: usize,
+    /// Message explaining the suggestion, if any.
+    pub message: Option<String>,
+    /// Suggested replacements, if any.
+    pub replacements: Vec<String>,
+    /// Raw suggestion.
+    pub raw: Value,
+}
+
+impl Suggestion {
+    /// Try to read a suggestion from a raw `value`, which must at least have
+    /// an offset and a length.
+    fn from_value(field: &str, value: &Value) -> Option<Self> {
+        let offset = value.get("offset")?.as_u64()? as usize;
+        let length = value.get("length")?.as_u64()? as usize;
+        let message = ["message", "shortMessage", "description"]
+            .iter()
+            .find_map(|key| value.get(key)?.as_str())
+            .map(ToString::to_string);
+        let replacements = ["replacements", "suggestions", "rewrites"]
+            .iter()
+            .find_map(|key| value.get(key)?.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|replacement| {
+                // Either plain strings or objects like `{"value": "..."}`
+                replacement
+                    .as_str()
+                    .or_else(|| replacement.get("value")?.as_str())
+                    .map(ToString::to_string)
+            })
+            .collect();
+
+        Some(Self {
+            field: field.to_string(),
+            offset,
+            length,
+            message,
+            replacements,
+            raw: value.clone(),
+        })
+    }
+}
+
+impl std::fmt::Display for Suggestion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} at {}..{}: {}",
+            self.field,
+            self.offset,
+            self.offset + self.length,
+            self.message.as_deref().unwrap_or("(no message)")
+        )?;
+        if !self.replacements.is_empty() {
+            write!(f, " (suggestions: {})", self.replacements.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl ExperimentalResponse {
+    /// Return whether no experimental field was returned.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// Return the suggestions found in experimental fields, i.e., objects with
+    /// an offset and a length, listed directly in array fields.
+    #[must_use]
+    pub fn suggestions(&self) -> Vec<Suggestion> {
+        self.fields
+            .iter()
+            .filter_map(|(field, value)| Some((field, value.as_array()?)))
+            .flat_map(|(field, values)| {
+                values
+                    .iter()
+                    .filter_map(|value| Suggestion::from_value(field, value))
+            })
+            .collect()
+    }
+}
+
+/// Append the unknown fields of `other` to `fields`, shifting the offsets of
+/// its suggestions by `offset` characters, see
+/// [`CheckResponse::append_with_offset`](crate::check::CheckResponse::append_with_offset).
+///
+/// Array fields are concatenated, and other fields are kept from `fields`
+/// if present in both.
+pub(crate) fn append_with_offset(
+    fields: &mut Map<String, Value>,
+    other: Map<String, Value>,
+    offset: usize,
+) {
+    for (field, value) in other {
+        match (fields.get_mut(&field), value) {
+            (Some(Value::Array(values)), Value::Array(others)) => {
+                values.extend(others.into_iter().map(|mut value| {
+                    if let Some(Value::Number(n)) = value.get_mut("offset") {
+                        if let Some(shifted) = n.as_u64().map(|n| n + offset as u64) {
+                            *n = shifted.into();
+                        }
+                    }
+                    value
+                }));
+            },
+            (Some(_), _) => {},
+            (None, value) => {
+                fields.insert(field, value);
+            },
+        }
+    }
+}
+
+/// Remove the values of array fields that start in the first `length`
+/// characters of the checked text, and shift the offsets of the others back,
+/// like [`from_fragment`](crate::check::CheckResponseWithContext::from_fragment)
+/// does with matches.
+pub(crate) fn remove_prefix(fields: &mut Map<String, Value>, length: usize) {
+    for value in fields.values_mut() {
+        if let Value::Array(values) = value {
+            values.retain_mut(|value| {
+                match value.get_mut("offset") {
+                    Some(Value::Number(n)) => {
+                        match n.as_u64() {
+                            Some(offset) if offset >= length as u64 => {
+                                *n = (offset - length as u64).into();
+                                true
+                            },
+                            Some(_) => false,
+                            None => true,
+                        }
+                    },
+                    _ => true,
+                }
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn response(value: Value) -> ExperimentalResponse {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_suggestions() {
+        let response = response(json!({
+            "hiddenMatches": [
+                {"offset": 5, "length": 3, "message": "Premium rule", "replacements": [{"value": "a"}]},
+                {"from": 0, "to": 3},
+            ],
+            "rewrites": [{"offset": 0, "length": 4, "suggestions": ["Some", "Any"]}],
+            "warnings": {"incompleteResults": false},
+        }));
+        let suggestions = response.suggestions();
+
+        assert_eq!(suggestions.len(), 2);
+        assert_eq!(
+            suggestions[0].to_string(),
+            "hiddenMatches at 5..8: Premium rule (suggestions: a)"
+        );
+        assert_eq!(
+            suggestions[1].to_string(),
+            "rewrites at 0..4: (no message) (suggestions: Some, Any)"
+        );
+    }
+
+    #[test]
+    fn test_append_with_offset() {
+        let mut first = response(json!({
+            "hiddenMatches": [{"offset": 1, "length": 1}],
+            "model": "first",
+        }));
+        let second = response(json!({
+            "hiddenMatches": [{"offset": 2, "length": 1}],
+            "model": "second",
+            "other": true,
+        }));
+        append_with_offset(&mut first.fields, second.fields, 10);
+
+        assert_eq!(
+            serde_json::to_value(&first).unwrap(),
+            json!({
+                "hiddenMatches": [{"offset": 1, "length": 1}, {"offset": 12, "length": 1}],
+                "model": "first",
+                "other": true,
+            })
+        );
+    }
+}
@@ -0,0 +1,227 @@
+//! Lines changed according to git, so that only the matches on these lines
+//! are reported, e.g., in pre-commit hooks with `ltrs check --diff --staged`.
+//!
+//! Changed lines are read from `git diff --unified=0`, whose hunk headers give
+//! the ranges of lines added or modified in the new version of each file.
+
+use crate::{
+    check::CheckResponse,
+    error::{exit_status_error, Error, Result},
+};
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+/// Parse the new side of a hunk header, e.g., `@@ -3,2 +4,3 @@`, as the
+/// first line number and the number of lines.
+fn parse_hunk_header(line: &str) -> Option<(usize, usize)> {
+    let new = line
+        .strip_prefix("@@ ")?
+        .split(' ')
+        .find_map(|range| range.strip_prefix('+'))?;
+    match new.split_once(',') {
+        Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+        None => Some((new.parse().ok()?, 1)),
+    }
+}
+
+/// Run git with `args`, and return its standard output.
+fn git(args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .stderr(Stdio::inherit())
+        .output()
+        .map_err(|_| Error::CommandNotFound("git".to_string()))?;
+    exit_status_error(&output.status)?;
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Lines added or modified in each file, as ranges of line numbers starting
+/// at 1.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ChangedLines {
+    /// Ranges of changed lines, as first line and number of lines, by file.
+    pub files: HashMap<PathBuf, Vec<(usize, usize)>>,
+}
+
+impl ChangedLines {
+    /// Parse the output of `git diff --unified=0`, where file paths are
+    /// relative to `root`.
+    ///
+    /// Deleted files, and hunks that only remove lines, are skipped.
+    #[must_use]
+    pub fn parse(diff: &str, root: &Path) -> Self {
+        let mut files: HashMap<PathBuf, Vec<(usize, usize)>> = HashMap::new();
+        let mut file = None;
+
+        for line in diff.lines() {
+            if let Some(path) = line.strip_prefix("+++ ") {
+                file = path
+                    .strip_prefix("b/")
+                    .map(|path| root.join(path.trim_end()));
+            } else if let (Some(file), Some((start, count))) = (&file, parse_hunk_header(line)) {
+                if count > 0 {
+                    files.entry(file.clone()).or_default().push((start, count));
+                }
+            }
+        }
+        Self { files }
+    }
+
+    /// Read the lines changed in the working tree, or in the index if
+    /// `staged` is `true`, compared to the index, or to revision `since` if
+    /// set.
+    ///
+    /// # Errors
+    ///
+    /// If git is not found, or if it fails, e.g., outside of a repository.
+    pub fn from_git(staged: bool, since: Option<&str>) -> Result<Self> {
+        let root = git(&["rev-parse", "--show-toplevel"])?;
+        let mut args = vec!["diff", "--unified=0", "--no-color", "--no-ext-diff"];
+        if staged {
+            args.push("--cached");
+        }
+        args.extend(since);
+        // Canonical, so that files given with any path are found
+        let root = Path::new(root.trim_end()).canonicalize()?;
+        Ok(Self::parse(&git(&args)?, &root))
+    }
+
+    /// Return the files with changed lines that still exist, in a
+    /// deterministic order.
+    #[must_use]
+    pub fn existing_files(&self) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = self
+            .files
+            .keys()
+            .filter(|file| file.is_file())
+            .cloned()
+            .collect();
+        files.sort();
+        files
+    }
+
+    /// Return the ranges of changed lines of `file`, if any.
+    fn ranges(&self, file: &Path) -> Option<&[(usize, usize)]> {
+        self.files
+            .get(file)
+            .or_else(|| self.files.get(&file.canonicalize().ok()?))
+            .map(Vec::as_slice)
+    }
+
+    /// Remove the matches, from a response to a check of `text`, read from
+    /// `file`, that do not overlap any changed line.
+    ///
+    /// Like [`filter_lines`](crate::ignore::filter_lines), line numbers are
+    /// read from [`Match::more_context`](crate::check::Match::more_context)
+    /// when available, and computed from `text` otherwise.
+    pub fn filter(&self, file: &Path, text: &str, response: &mut CheckResponse) {
+        let Some(ranges) = self.ranges(file) else {
+            response.matches.clear();
+            return;
+        };
+
+        response.matches.retain(|m| {
+            let first = match &m.more_context {
+                Some(more_context) => more_context.line_number,
+                None => 1 + text.chars().take(m.offset).filter(|c| *c == '\n').count(),
+            };
+            let last = first
+                + text
+                    .chars()
+                    .skip(m.offset)
+                    .take(m.length)
+                    .filter(|c| *c == '\n')
+                    .count();
+            ranges
+                .iter()
+                .any(|(start, count)| first < start + count && *start <= last)
+        });
+    }
+}
+
+/// Files changed since a revision, i.e., tracked files modified since then,
+/// and files not tracked by git.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ChangedFiles {
+    /// Canonical paths of the files tracked by git.
+    pub tracked: HashSet<PathBuf>,
+    /// Canonical paths of the tracked files modified since the revision.
+    pub modified: HashSet<PathBuf>,
+}
+
+impl ChangedFiles {
+    /// Read the files changed in the working tree since revision `since`.
+    ///
+    /// # Errors
+    ///
+    /// If git is not found, or if it fails, e.g., outside of a repository, or
+    /// if `since` cannot be resolved.
+    pub fn from_git(since: &str) -> Result<Self> {
+        let commit = git(&["rev-parse", "--verify", &format!("{since}^{{commit}}")])?;
+        let root = git(&["rev-parse", "--show-toplevel"])?;
+        // Canonical, so that files given with any path are found
+        let root = Path::new(root.trim_end()).canonicalize()?;
+        let paths = |output: String| -> HashSet<PathBuf> {
+            output
+                .split('\0')
+                .filter(|path| !path.is_empty())
+                .map(|path| root.join(path))
+                .collect()
+        };
+        // Paths are relative to the root, see `:/`
+        Ok(Self {
+            tracked: paths(git(&["ls-files", "-z", "--full-name", "--", ":/"])?),
+            modified: paths(git(&["diff", "--name-only", "-z", commit.trim_end()])?),
+        })
+    }
+
+    /// Return `true` if `file` is not tracked, or was modified. Files that
+    /// cannot be found are considered changed.
+    #[must_use]
+    pub fn contains(&self, file: &Path) -> bool {
+        file.canonicalize()
+            .map_or(true, |path| !self.tracked.contains(&path) || self.modified.contains(&path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::check::test_utils::response_with_matches;
+
+    #[test]
+    fn test_parse() {
+        let diff = "diff --git a/docs/a.md b/docs/a.md\nindex 1..2 100644\n--- a/docs/a.md\n+++ \
+                    b/docs/a.md\n@@ -1 +1 @@\n-old\n+new\n@@ -5,2 +5,0 @@\n-x\n-y\n@@ -8,0 +7,3 \
+                    @@\n+a\n+b\n+c\ndiff --git a/gone.md b/gone.md\n--- a/gone.md\n+++ \
+                    /dev/null\n@@ -1 +0,0 @@\n-gone\n";
+        let changed = ChangedLines::parse(diff, Path::new("/repo"));
+
+        assert_eq!(changed.files.len(), 1);
+        assert_eq!(
+            changed.files[Path::new("/repo/docs/a.md")],
+            vec![(1, 1), (7, 3)]
+        );
+    }
+
+    #[test]
+    fn test_filter() {
+        let changed = ChangedLines::parse("+++ b/a.md\n@@ -2 +2,2 @@\n", Path::new("/repo"));
+        let text = "A smal line.\nA smal\nchange.\nA last smal line.\n";
+        let response = response_with_matches(text, &[(2, 4, &[]), (15, 8, &[]), (37, 4, &[])]);
+
+        let mut filtered = response.clone();
+        changed.filter(Path::new("/repo/a.md"), text, &mut filtered);
+        let offsets: Vec<usize> = filtered.matches.iter().map(|m| m.offset).collect();
+        assert_eq!(offsets, vec![15]);
+
+        let mut filtered = response;
+        changed.filter(Path::new("/repo/b.md"), text, &mut filtered);
+        assert!(filtered.matches.is_empty());
+    }
+}
@@ -0,0 +1,725 @@
+//! Project-level ignore files, used to suppress matches.
+//!
+//! An ignore file, named [`IGNORE_FILENAME`], lists one entry per line:
+//!
+//! ```text
+//! # Lines starting with '#' are comments.
+//! rule WHITESPACE_RULE
+//! category TYPOGRAPHY
+//! match docs/index.md MORFOLOGIK_RULE_EN_US LTRS
+//! match * MORFOLOGIK_RULE_EN_US LanguageTool
+//! ```
+//!
+//! `rule` and `category` entries suppress all matches from a given rule or
+//! category. `match` entries suppress matches of a given rule on a given text
+//! (the rest of the line), in a given file, relative to the ignore file's
+//! directory, or in any file with `*`.
+//!
+//! Matches can also be suppressed from the checked text itself, using
+//! directives inside comments, see [`InlineIgnores`], on lines selected by
+//! their content, see [`filter_lines`], or by their rule, category or issue
+//! type, see [`MatchFilter`]. Spelling matches on words of a local word list
+//! are suppressed by [`KnownWords`].
+
+use crate::{
+    check::{CheckResponse, Match},
+    error::{Error, Result},
+};
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+/// Name of ignore files.
+pub const IGNORE_FILENAME: &str = ".ltrsignore";
+
+/// Suppression of a specific match.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct IgnoredMatch {
+    /// File in which the match is suppressed, or `None` for any file.
+    pub path: Option<PathBuf>,
+    /// Rule id.
+    pub rule: String,
+    /// Matched text.
+    pub text: String,
+}
+
+/// Content of an ignore file.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct IgnoreFile {
+    /// Suppressed rule ids.
+    pub rules: Vec<String>,
+    /// Suppressed category ids.
+    pub categories: Vec<String>,
+    /// Suppressed matches.
+    pub matches: Vec<IgnoredMatch>,
+}
+
+impl IgnoreFile {
+    /// Parse the content of an ignore file, where `match` paths are relative
+    /// to `base_dir`.
+    ///
+    /// # Errors
+    ///
+    /// If a line is not a valid entry.
+    pub fn parse(content: &str, base_dir: &Path) -> Result<Self> {
+        let mut ignore = Self::default();
+
+        for (i, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = || Error::InvalidValue(format!("line {}: {line}", i + 1));
+            let (kind, rest) = line.split_once(char::is_whitespace).ok_or_else(invalid)?;
+            let rest = rest.trim();
+
+            match kind {
+                "rule" => ignore.rules.push(rest.to_string()),
+                "category" => ignore.categories.push(rest.to_string()),
+                "match" => {
+                    let mut parts = rest.splitn(3, char::is_whitespace);
+                    let (Some(path), Some(rule), Some(text)) =
+                        (parts.next(), parts.next(), parts.next())
+                    else {
+                        return Err(invalid());
+                    };
+                    ignore.matches.push(IgnoredMatch {
+                        path: (path != "*").then(|| base_dir.join(path)),
+                        rule: rule.to_string(),
+                        text: text.trim().to_string(),
+                    });
+                },
+                _ => return Err(invalid()),
+            }
+        }
+
+        Ok(ignore)
+    }
+
+    /// Read an ignore file.
+    ///
+    /// # Errors
+    ///
+    /// If the file cannot be read or is not valid.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)?;
+        let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+
+        Self::parse(&content, base_dir).map_err(|e| {
+            match e {
+                Error::InvalidValue(msg) => {
+                    Error::InvalidValue(format!("{}, {msg}", path.display()))
+                },
+                e => e,
+            }
+        })
+    }
+
+    /// Find and read the closest ignore file, walking up from `path` (a file
+    /// or a directory), which may not exist, e.g., with `--stdin-filepath`.
+    ///
+    /// # Errors
+    ///
+    /// If the ignore file that was found cannot be read or is not valid.
+    pub fn discover<P: AsRef<Path>>(path: P) -> Result<Option<Self>> {
+        let path = std::env::current_dir()?.join(path);
+        let path = match path.ancestors().find_map(|p| p.canonicalize().ok()) {
+            Some(path) => path,
+            None => return Ok(None),
+        };
+        let start = if path.is_dir() {
+            path.as_path()
+        } else {
+            path.parent().unwrap_or(&path)
+        };
+
+        start
+            .ancestors()
+            .map(|dir| dir.join(IGNORE_FILENAME))
+            .find(|candidate| candidate.is_file())
+            .map(Self::from_path)
+            .transpose()
+    }
+
+    /// Return `true` if a match, found in `file`, is suppressed.
+    #[must_use]
+    pub fn is_ignored(&self, file: Option<&Path>, m: &Match) -> bool {
+        if self.rules.contains(&m.rule.id) || self.categories.contains(&m.rule.category.id) {
+            return true;
+        }
+
+        let file = file.and_then(|file| file.canonicalize().ok());
+        let mut text = None;
+
+        self.matches.iter().any(|ignored| {
+            ignored.rule == m.rule.id
+                && match &ignored.path {
+                    None => true,
+                    Some(path) => {
+                        file.is_some() && path.canonicalize().ok().as_deref() == file.as_deref()
+                    },
+                }
+                && *text.get_or_insert_with(|| m.matched_text()) == ignored.text
+        })
+    }
+
+    /// Remove suppressed matches, found in `file`, from a response, and
+    /// return the number of removed matches.
+    pub fn filter(&self, file: Option<&Path>, response: &mut CheckResponse) -> usize {
+        let len = response.matches.len();
+
+        response.matches.retain(|m| !self.is_ignored(file, m));
+        len - response.matches.len()
+    }
+}
+
+/// Rules suppressed by a directive, where an empty list means all rules.
+type Rules = Vec<String>;
+
+/// Suppressions defined by directives found in a checked text.
+///
+/// Directives are recognized inside any kind of comment, e.g.,
+/// `<!-- ltrs-disable-next-line RULE_ID -->` or `// ltrs-disable-file`, and
+/// can be followed by the ids of the rules to suppress, or by nothing to
+/// suppress all rules:
+///
+/// - `ltrs-disable-file` suppresses matches in the whole text;
+/// - `ltrs-disable-line` suppresses matches on the same line;
+/// - `ltrs-disable-next-line` suppresses matches on the following line.
+///
+/// Matches on directives themselves are always suppressed.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct InlineIgnores {
+    /// Rules suppressed in the whole text, if any.
+    pub file: Option<Rules>,
+    /// Rules suppressed on given line numbers, starting at 1.
+    pub lines: HashMap<usize, Rules>,
+    /// Char ranges (start and end offsets) of the directives.
+    pub directives: Vec<(usize, usize)>,
+}
+
+/// Return `true` if `token` looks like a rule id, e.g., `UPPERCASE_RULE`.
+fn is_rule_id(token: &str) -> bool {
+    !token.is_empty()
+        && token
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+}
+
+impl InlineIgnores {
+    /// Find directives in `text`.
+    #[must_use]
+    pub fn parse(text: &str) -> Self {
+        let mut ignores = Self::default();
+        let mut line_offset = 0;
+
+        for (i, line) in text.split_inclusive('\n').enumerate() {
+            let line_number = i + 1;
+            let line_start = line_offset;
+            line_offset += line.chars().count();
+
+            let Some(start) = line.find("ltrs-disable-") else {
+                continue;
+            };
+            let directive = &line[start..];
+            let mut tokens = directive.split_whitespace();
+            let kind = tokens.next().unwrap_or_default();
+            let rules: Rules = tokens
+                .take_while(|token| is_rule_id(token))
+                .map(ToString::to_string)
+                .collect();
+
+            match kind {
+                "ltrs-disable-file" => {
+                    ignores
+                        .file
+                        .get_or_insert_with(Vec::new)
+                        .extend(rules.clone())
+                },
+                "ltrs-disable-line" | "ltrs-disable-next-line" => {
+                    let line_number = if kind == "ltrs-disable-line" {
+                        line_number
+                    } else {
+                        line_number + 1
+                    };
+                    ignores
+                        .lines
+                        .entry(line_number)
+                        .or_default()
+                        .extend(rules.clone());
+                },
+                _ => continue,
+            }
+
+            let mut end = kind.len();
+            for rule in &rules {
+                end += directive[end..].find(rule.as_str()).unwrap_or_default() + rule.len();
+            }
+            let start = line_start + line[..start].chars().count();
+            ignores
+                .directives
+                .push((start, start + directive[..end].chars().count()));
+        }
+
+        ignores
+    }
+
+    /// Return `true` if there is no directive.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.file.is_none() && self.lines.is_empty() && self.directives.is_empty()
+    }
+
+    /// Return `true` if a match, starting on `line_number`, is suppressed.
+    #[must_use]
+    pub fn is_ignored(&self, line_number: usize, m: &Match) -> bool {
+        let suppresses = |rules: &Rules| rules.is_empty() || rules.contains(&m.rule.id);
+
+        self.file.as_ref().is_some_and(suppresses)
+            || self.lines.get(&line_number).is_some_and(suppresses)
+            || self
+                .directives
+                .iter()
+                .any(|(start, end)| m.offset < *end && *start < m.offset + m.length.max(1))
+    }
+
+    /// Remove suppressed matches from a response to a check of `text`, and
+    /// return the number of removed matches.
+    ///
+    /// Line numbers are read from [`Match::more_context`] when available, and
+    /// computed from `text` otherwise.
+    pub fn filter(&self, text: &str, response: &mut CheckResponse) -> usize {
+        if self.is_empty() {
+            return 0;
+        }
+        let len = response.matches.len();
+
+        response.matches.retain(|m| {
+            let line_number = match &m.more_context {
+                Some(more_context) => more_context.line_number,
+                None => 1 + text.chars().take(m.offset).filter(|c| *c == '\n').count(),
+            };
+            !self.is_ignored(line_number, m)
+        });
+        len - response.matches.len()
+    }
+}
+
+/// Remove matches, from a response to a check of `text`, that start on a line
+/// for which `is_ignored` returns `true`, e.g., table rows starting with `|`,
+/// and return the number of removed matches.
+///
+/// Like [`InlineIgnores::filter`], line numbers are read from
+/// [`Match::more_context`] when available, and computed from `text` otherwise.
+pub fn filter_lines<F>(text: &str, response: &mut CheckResponse, mut is_ignored: F) -> usize
+where
+    F: FnMut(&str) -> bool,
+{
+    let mut ignored_lines: HashMap<usize, bool> = HashMap::new();
+    let lines: Vec<&str> = text.lines().collect();
+    let len = response.matches.len();
+
+    response.matches.retain(|m| {
+        let line_number = match &m.more_context {
+            Some(more_context) => more_context.line_number,
+            None => 1 + text.chars().take(m.offset).filter(|c| *c == '\n').count(),
+        };
+        !*ignored_lines.entry(line_number).or_insert_with(|| {
+            lines
+                .get(line_number - 1)
+                .is_some_and(|line| is_ignored(line))
+        })
+    });
+    len - response.matches.len()
+}
+
+/// Client-side filter of matches, applied to a response after the request.
+///
+/// Unlike disabling rules on the server, this also works with cached
+/// responses, and with matches that cannot be disabled, such as hints about
+/// premium rules.
+///
+/// # Examples
+///
+/// ```
+/// # use languagetool_rust::{check::CheckResponse, ignore::MatchFilter};
+/// # fn prune(response: &mut CheckResponse) {
+/// let filter = MatchFilter::default()
+///     .with_ignore_rules(["WHITESPACE_RULE"])
+///     .with_ignore_spelling(true);
+///
+/// filter.filter(response);
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct MatchFilter {
+    /// Suppressed rule ids.
+    pub ignore_rules: Vec<String>,
+    /// Kept category ids, ignoring case, or all categories if empty.
+    pub only_categories: Vec<String>,
+    /// If `true`, suppress spelling matches, i.e., matches whose issue type is
+    /// `"misspelling"`.
+    pub ignore_spelling: bool,
+    /// If `true`, only keep matches from rules tagged as `"picky"`, i.e.,
+    /// rules only enabled with [`Level::Picky`](crate::check::Level::Picky).
+    pub picky_only: bool,
+    /// Suppress matches whose matched text matches any of these patterns,
+    /// e.g., product names or templating placeholders like `{{ var }}`.
+    #[cfg(feature = "cli")]
+    pub ignore_patterns: Vec<regex::Regex>,
+}
+
+impl MatchFilter {
+    /// Set the suppressed rule ids.
+    #[must_use]
+    pub fn with_ignore_rules<I, S>(mut self, rules: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.ignore_rules = rules.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Set the kept category ids.
+    #[must_use]
+    pub fn with_only_categories<I, S>(mut self, categories: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.only_categories = categories.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Set whether spelling matches are suppressed.
+    #[must_use]
+    pub fn with_ignore_spelling(mut self, ignore_spelling: bool) -> Self {
+        self.ignore_spelling = ignore_spelling;
+        self
+    }
+
+    /// Set whether only matches from picky rules are kept.
+    #[must_use]
+    pub fn with_picky_only(mut self, picky_only: bool) -> Self {
+        self.picky_only = picky_only;
+        self
+    }
+
+    /// Set the patterns of suppressed matched texts.
+    #[cfg(feature = "cli")]
+    #[must_use]
+    pub fn with_ignore_patterns<I>(mut self, patterns: I) -> Self
+    where
+        I: IntoIterator<Item = regex::Regex>,
+    {
+        self.ignore_patterns = patterns.into_iter().collect();
+        self
+    }
+
+    /// Return `true` if this filter keeps all matches.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        let is_empty = self.ignore_rules.is_empty()
+            && self.only_categories.is_empty()
+            && !self.ignore_spelling
+            && !self.picky_only;
+        #[cfg(feature = "cli")]
+        let is_empty = is_empty && self.ignore_patterns.is_empty();
+        is_empty
+    }
+
+    /// Return `true` if a match is kept by this filter.
+    #[must_use]
+    pub fn keeps(&self, m: &Match) -> bool {
+        let rule = &m.rule;
+
+        !self.ignore_rules.contains(&rule.id)
+            && (self.only_categories.is_empty()
+                || self
+                    .only_categories
+                    .iter()
+                    .any(|category| category.eq_ignore_ascii_case(&rule.category.id)))
+            && !(self.ignore_spelling && rule.issue_type == "misspelling")
+            && (!self.picky_only
+                || rule
+                    .tags
+                    .as_ref()
+                    .is_some_and(|tags| tags.iter().any(|tag| tag == "picky")))
+            && !self.is_ignored_pattern(m)
+    }
+
+    /// Return `true` if the matched text of `m` matches an ignored pattern.
+    #[cfg(feature = "cli")]
+    fn is_ignored_pattern(&self, m: &Match) -> bool {
+        if self.ignore_patterns.is_empty() {
+            return false;
+        }
+        let text = m.matched_text();
+        self.ignore_patterns
+            .iter()
+            .any(|pattern| pattern.is_match(&text))
+    }
+
+    /// Return `true` if the matched text of `m` matches an ignored pattern.
+    #[cfg(not(feature = "cli"))]
+    fn is_ignored_pattern(&self, _: &Match) -> bool {
+        false
+    }
+
+    /// Remove the matches that are not kept by this filter from a response,
+    /// and return the number of removed matches.
+    pub fn filter(&self, response: &mut CheckResponse) -> usize {
+        if self.is_empty() {
+            return 0;
+        }
+        let len = response.matches.len();
+
+        response.matches.retain(|m| self.keeps(m));
+        len - response.matches.len()
+    }
+
+    /// Only remove the matches whose matched text matches an ignored pattern
+    /// from a response, and return the number of removed matches.
+    pub fn filter_patterns(&self, response: &mut CheckResponse) -> usize {
+        let len = response.matches.len();
+
+        response.matches.retain(|m| !self.is_ignored_pattern(m));
+        len - response.matches.len()
+    }
+}
+
+/// Local list of known words, used as a personal dictionary that does not
+/// require a Premium account.
+///
+/// A word list contains one word per line. Empty lines and lines starting
+/// with `#` are skipped.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct KnownWords {
+    /// Known words.
+    pub words: HashSet<String>,
+}
+
+impl KnownWords {
+    /// Parse the content of a word list.
+    #[must_use]
+    pub fn parse(content: &str) -> Self {
+        let words = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(ToString::to_string)
+            .collect();
+        Self { words }
+    }
+
+    /// Read a word list from a file.
+    ///
+    /// # Errors
+    ///
+    /// If the file cannot be read.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Ok(Self::parse(&std::fs::read_to_string(path)?))
+    }
+
+    /// Return `true` if `m` is a spelling match on a known word.
+    ///
+    /// Spelling matches are matches whose issue type is `"misspelling"`, or
+    /// whose rule is a Morfologik or Hunspell speller rule.
+    #[must_use]
+    pub fn is_ignored(&self, m: &Match) -> bool {
+        let rule = &m.rule;
+        let is_spelling = rule.issue_type == "misspelling"
+            || rule.id.starts_with("MORFOLOGIK_RULE")
+            || rule.id.contains("HUNSPELL");
+
+        is_spelling && self.words.contains(&m.matched_text())
+    }
+
+    /// Remove spelling matches on known words from a response, and return
+    /// the number of removed matches.
+    pub fn filter(&self, response: &mut CheckResponse) -> usize {
+        let len = response.matches.len();
+
+        response.matches.retain(|m| !self.is_ignored(m));
+        len - response.matches.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::check::test_utils::response_with_matches;
+
+    #[test]
+    fn test_parse() {
+        let ignore = IgnoreFile::parse(
+            "# comment\n\nrule A\ncategory B\nmatch * C some text\nmatch doc.md D x\n",
+            Path::new("/project"),
+        )
+        .unwrap();
+
+        assert_eq!(ignore.rules, vec!["A"]);
+        assert_eq!(ignore.categories, vec!["B"]);
+        assert_eq!(
+            ignore.matches,
+            vec![
+                IgnoredMatch {
+                    path: None,
+                    rule: "C".to_string(),
+                    text: "some text".to_string(),
+                },
+                IgnoredMatch {
+                    path: Some(PathBuf::from("/project/doc.md")),
+                    rule: "D".to_string(),
+                    text: "x".to_string(),
+                },
+            ]
+        );
+
+        assert!(IgnoreFile::parse("unknown A", Path::new(".")).is_err());
+        assert!(IgnoreFile::parse("match * A", Path::new(".")).is_err());
+    }
+
+    #[test]
+    fn test_discover_and_filter() {
+        let dir = tempfile::tempdir().unwrap();
+        let subdir = dir.path().join("docs");
+        std::fs::create_dir(&subdir).unwrap();
+        let file = subdir.join("index.md");
+        std::fs::write(&file, "").unwrap();
+        std::fs::write(
+            dir.path().join(IGNORE_FILENAME),
+            "match docs/index.md MORFOLOGIK_RULE_EN_US smal\n",
+        )
+        .unwrap();
+
+        let ignore = IgnoreFile::discover(&file).unwrap().unwrap();
+        let text = "A smal and a smal.";
+        let mut response = response_with_matches(text, &[(2, 4, &[]), (11, 1, &[])]);
+
+        ignore.filter(Some(&file), &mut response);
+        assert_eq!(response.matches.len(), 1);
+        assert_eq!(response.matches[0].offset, 11);
+
+        let mut response = response_with_matches(text, &[(2, 4, &[])]);
+        ignore.filter(Some(&dir.path().join(IGNORE_FILENAME)), &mut response);
+        assert_eq!(response.matches.len(), 1);
+    }
+
+    #[test]
+    fn test_inline_ignores() {
+        let text = "<!-- ltrs-disable-next-line MORFOLOGIK_RULE_EN_US -->\nA smal \
+                    mistake.\nAnother smal one. // ltrs-disable-line OTHER_RULE\nA last smal one.";
+        let ignores = InlineIgnores::parse(text);
+
+        assert_eq!(ignores.file, None);
+        assert_eq!(
+            ignores.lines.get(&2),
+            Some(&vec!["MORFOLOGIK_RULE_EN_US".to_string()])
+        );
+
+        assert_eq!(ignores.directives[0], (5, 49));
+
+        let mut response = response_with_matches(
+            text,
+            &[
+                (0, 4, &[]),
+                (5, 22, &[]),
+                (56, 4, &[]),
+                (78, 4, &[]),
+                (127, 4, &[]),
+            ],
+        );
+        ignores.filter(text, &mut response);
+
+        let offsets: Vec<usize> = response.matches.iter().map(|m| m.offset).collect();
+        assert_eq!(offsets, vec![0, 78, 127]);
+
+        let ignores = InlineIgnores::parse("# ltrs-disable-file\nA smal mistake.");
+        let mut response = response_with_matches(text, &[(22, 4, &[])]);
+        ignores.filter(text, &mut response);
+        assert!(response.matches.is_empty());
+    }
+
+    #[test]
+    fn test_filter_lines() {
+        let text = "A smal table:\n| teh | cell |\n::: warning\nA last smal one.";
+        let mut response = response_with_matches(
+            text,
+            &[(2, 4, &[]), (16, 3, &[]), (33, 7, &[]), (48, 4, &[])],
+        );
+
+        filter_lines(text, &mut response, |line| {
+            line.starts_with('|') || line.starts_with(":::")
+        });
+
+        let offsets: Vec<usize> = response.matches.iter().map(|m| m.offset).collect();
+        assert_eq!(offsets, vec![2, 48]);
+    }
+
+    #[test]
+    fn test_match_filter() {
+        let text = "A smal and a smal.";
+        let mut response = response_with_matches(text, &[(2, 4, &[]), (13, 4, &[])]);
+        response.matches[1].rule.id = "PICKY_RULE".to_string();
+        response.matches[1].rule.issue_type = "style".to_string();
+        response.matches[1].rule.category.id = "STYLE".to_string();
+        response.matches[1].rule.tags = Some(vec!["picky".to_string()]);
+
+        let offsets = |filter: MatchFilter| {
+            let mut response = response.clone();
+            filter.filter(&mut response);
+            response.matches.iter().map(|m| m.offset).collect::<Vec<_>>()
+        };
+
+        assert!(MatchFilter::default().is_empty());
+        assert_eq!(offsets(MatchFilter::default()), vec![2, 13]);
+        assert_eq!(
+            offsets(MatchFilter::default().with_ignore_rules(["PICKY_RULE"])),
+            vec![2]
+        );
+        assert_eq!(
+            offsets(MatchFilter::default().with_only_categories(["typos"])),
+            vec![2]
+        );
+        assert_eq!(
+            offsets(MatchFilter::default().with_ignore_spelling(true)),
+            vec![13]
+        );
+        assert_eq!(
+            offsets(MatchFilter::default().with_picky_only(true)),
+            vec![13]
+        );
+        #[cfg(feature = "cli")]
+        assert_eq!(
+            offsets(
+                MatchFilter::default().with_ignore_patterns([regex::Regex::new("^sm").unwrap()])
+            ),
+            Vec::<usize>::new()
+        );
+    }
+
+    #[test]
+    fn test_known_words() {
+        let words = KnownWords::parse("# comment\n\nsmal\n  LTRS  \n");
+        assert_eq!(words.words.len(), 2);
+
+        let text = "A smal and a smal LTRS.";
+        let mut response =
+            response_with_matches(text, &[(2, 4, &[]), (13, 4, &[]), (18, 4, &[])]);
+        response.matches[1].rule.id = "OTHER_RULE".to_string();
+        response.matches[1].rule.issue_type = "grammar".to_string();
+
+        assert_eq!(words.filter(&mut response), 2);
+        assert_eq!(response.matches.len(), 1);
+        assert_eq!(response.matches[0].offset, 13);
+    }
+}
@@ -1,6 +1,269 @@
 //! Structures for `languages` requests and responses.
 
-use serde::{Deserialize, Serialize};
+use crate::error::{Error, Result};
+#[cfg(feature = "cli")]
+use clap::{Parser, ValueEnum};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{borrow::Cow, fmt, str::FromStr};
+
+/// Language code, e.g., `en-US`, or `auto` to guess the language
+/// automatically.
+///
+/// A code is made of a primary language of two or three letters, optionally
+/// followed by a region of two letters, and then by any number of variants made
+/// of letters, all separated by `-`. Codes are case-normalized when parsed: the
+/// region is uppercased, and the rest is lowercased.
+///
+/// # Examples
+///
+/// ```
+/// # use languagetool_rust::languages::LanguageCode;
+/// let code: LanguageCode = "EN-us".parse().unwrap();
+/// assert_eq!(code, LanguageCode::EN_US);
+/// assert_eq!(code.to_string(), "en-US");
+/// assert_eq!(code.primary(), "en");
+///
+/// let code: LanguageCode = "ca-es-Valencia".parse().unwrap();
+/// assert_eq!(code, "ca-ES-valencia");
+///
+/// assert!("en_US".parse::<LanguageCode>().is_err());
+/// assert!("fr-french".parse::<LanguageCode>().is_err());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LanguageCode(Cow<'static, str>);
+
+impl LanguageCode {
+    /// Let the server guess the language, see [`crate::check::CheckRequest`].
+    pub const AUTO: Self = Self(Cow::Borrowed("auto"));
+    /// German.
+    pub const DE: Self = Self(Cow::Borrowed("de"));
+    /// German (Germany).
+    pub const DE_DE: Self = Self(Cow::Borrowed("de-DE"));
+    /// English.
+    pub const EN: Self = Self(Cow::Borrowed("en"));
+    /// English (United Kingdom).
+    pub const EN_GB: Self = Self(Cow::Borrowed("en-GB"));
+    /// English (United States).
+    pub const EN_US: Self = Self(Cow::Borrowed("en-US"));
+    /// Spanish.
+    pub const ES: Self = Self(Cow::Borrowed("es"));
+    /// French.
+    pub const FR: Self = Self(Cow::Borrowed("fr"));
+    /// Italian.
+    pub const IT: Self = Self(Cow::Borrowed("it"));
+    /// Dutch.
+    pub const NL: Self = Self(Cow::Borrowed("nl"));
+    /// Portuguese.
+    pub const PT: Self = Self(Cow::Borrowed("pt"));
+    /// Portuguese (Brazil).
+    pub const PT_BR: Self = Self(Cow::Borrowed("pt-BR"));
+    /// Portuguese (Portugal).
+    pub const PT_PT: Self = Self(Cow::Borrowed("pt-PT"));
+
+    /// Return the code as a string slice.
+    #[inline]
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Return `true` if this is [`LanguageCode::AUTO`].
+    #[inline]
+    #[must_use]
+    pub fn is_auto(&self) -> bool {
+        *self == Self::AUTO
+    }
+
+    /// Return the primary language, e.g., `"en"` for `en-US`.
+    #[must_use]
+    pub fn primary(&self) -> &str {
+        self.0.split('-').next().unwrap_or_default()
+    }
+}
+
+impl Default for LanguageCode {
+    #[inline]
+    fn default() -> Self {
+        Self::AUTO
+    }
+}
+
+impl FromStr for LanguageCode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if s.eq_ignore_ascii_case("auto") {
+            return Ok(Self::AUTO);
+        }
+
+        let mut code = String::with_capacity(s.len());
+        let mut valid = true;
+
+        for (i, subtag) in s.split('-').enumerate() {
+            let letters = !subtag.is_empty() && subtag.chars().all(|c| c.is_ascii_alphabetic());
+            valid &= match i {
+                0 => letters && (subtag.len() == 2 || subtag.len() == 3),
+                1 => letters && subtag.len() == 2,
+                _ => letters,
+            };
+            if i > 0 {
+                code.push('-');
+            }
+            if i == 1 {
+                code.push_str(&subtag.to_ascii_uppercase());
+            } else {
+                code.push_str(&subtag.to_ascii_lowercase());
+            }
+        }
+
+        if valid {
+            Ok(Self(Cow::Owned(code)))
+        } else {
+            Err(Error::InvalidValue(
+                "The value should be `\"auto\"` or match regex pattern: \
+                 ^[a-zA-Z]{2,3}(-[a-zA-Z]{2}(-[a-zA-Z]+)*)?$"
+                    .to_string(),
+            ))
+        }
+    }
+}
+
+impl TryFrom<String> for LanguageCode {
+    type Error = Error;
+
+    #[inline]
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl From<LanguageCode> for String {
+    #[inline]
+    fn from(code: LanguageCode) -> Self {
+        code.0.into_owned()
+    }
+}
+
+impl AsRef<str> for LanguageCode {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl PartialEq<str> for LanguageCode {
+    #[inline]
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for LanguageCode {
+    #[inline]
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl fmt::Display for LanguageCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for LanguageCode {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for LanguageCode {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// Native names of the languages supported by LanguageTool, from the Unicode
+/// Common Locale Data Repository (CLDR), indexed by language code.
+const NATIVE_NAMES: &[(&str, &str)] = &[
+    ("ar", "العربية"),
+    ("ast", "asturianu"),
+    ("be", "беларуская"),
+    ("br", "brezhoneg"),
+    ("ca", "català"),
+    ("ca-ES-valencia", "valencià"),
+    ("da", "dansk"),
+    ("de", "Deutsch"),
+    ("de-AT", "Österreichisches Deutsch"),
+    ("de-CH", "Schweizer Hochdeutsch"),
+    ("el", "Ελληνικά"),
+    ("en", "English"),
+    ("en-AU", "Australian English"),
+    ("en-CA", "Canadian English"),
+    ("en-GB", "British English"),
+    ("en-NZ", "English (New Zealand)"),
+    ("en-US", "American English"),
+    ("en-ZA", "English (South Africa)"),
+    ("eo", "esperanto"),
+    ("es", "español"),
+    ("es-AR", "español (Argentina)"),
+    ("fa", "فارسی"),
+    ("fr", "français"),
+    ("ga", "Gaeilge"),
+    ("gl", "galego"),
+    ("it", "italiano"),
+    ("ja", "日本語"),
+    ("km", "ខ្មែរ"),
+    ("nl", "Nederlands"),
+    ("nl-BE", "Vlaams"),
+    ("pl", "polski"),
+    ("pt", "português"),
+    ("pt-AO", "português (Angola)"),
+    ("pt-BR", "português (Brasil)"),
+    ("pt-MZ", "português (Moçambique)"),
+    ("pt-PT", "português europeu"),
+    ("ro", "română"),
+    ("ru", "русский"),
+    ("sk", "slovenčina"),
+    ("sl", "slovenščina"),
+    ("sv", "svenska"),
+    ("ta", "தமிழ்"),
+    ("tl", "Tagalog"),
+    ("uk", "українська"),
+    ("zh", "中文"),
+];
+
+/// Return the native name of a language, e.g., `"British English"` for
+/// `"en-GB"`, falling back to the name of its short code, e.g., `"English"`
+/// for `"en-IE"`.
+///
+/// # Examples
+///
+/// ```
+/// # use languagetool_rust::languages::native_name;
+/// assert_eq!(native_name("de-AT"), Some("Österreichisches Deutsch"));
+/// assert_eq!(native_name("de-LU"), Some("Deutsch"));
+/// assert_eq!(native_name("xx"), None);
+/// ```
+#[must_use]
+pub fn native_name(code: &str) -> Option<&'static str> {
+    let find = |code: &str| {
+        NATIVE_NAMES
+            .iter()
+            .find(|(c, _)| c.eq_ignore_ascii_case(code))
+            .map(|(_, name)| *name)
+    };
+
+    find(code).or_else(|| find(code.split('-').next().unwrap_or(code)))
+}
 
 #[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -10,12 +273,335 @@ pub struct Language {
     /// Language name, e.g., `"Ukrainian"`.
     pub name: String,
     /// Language (short) code, e.g., `"uk"`.
-    pub code: String,
+    pub code: LanguageCode,
     /// Language long code, e.g., `"uk-UA"`.
-    pub long_code: String,
+    pub long_code: LanguageCode,
+    /// Native language name, e.g., `"українська"`.
+    ///
+    /// This is never returned by the server, but it can be set with
+    /// [`Language::with_native_name`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub native_name: Option<String>,
+}
+
+impl Language {
+    /// Set the native name of this language, if known, see [`native_name`].
+    #[must_use]
+    pub fn with_native_name(mut self) -> Self {
+        self.native_name = native_name(self.long_code.as_str()).map(ToString::to_string);
+        self
+    }
 }
 
 /// LanguageTool GET languages response.
 ///
-/// List of all supported languages.
-pub type LanguagesResponse = Vec<Language>;
+/// List of all supported languages, in the order returned by the server.
+///
+/// # Examples
+///
+/// ```
+/// # use languagetool_rust::languages::LanguagesResponse;
+/// let languages: LanguagesResponse = serde_json::from_str(
+///     r#"[
+///         {"name": "English (GB)", "code": "en", "longCode": "en-GB"},
+///         {"name": "English (US)", "code": "en", "longCode": "en-US"},
+///         {"name": "French", "code": "fr", "longCode": "fr"}
+///     ]"#,
+/// )
+/// .unwrap();
+///
+/// assert_eq!(languages.find("en-us").unwrap().name, "English (US)");
+/// assert_eq!(languages.variants_of("en").count(), 2);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(transparent)]
+#[non_exhaustive]
+pub struct LanguagesResponse {
+    /// Supported languages.
+    pub languages: Vec<Language>,
+}
+
+impl LanguagesResponse {
+    /// Iterate over the languages.
+    pub fn iter(&self) -> std::slice::Iter<'_, Language> {
+        self.languages.iter()
+    }
+
+    /// Return the language whose long code is `code`, ignoring case.
+    #[must_use]
+    pub fn find(&self, code: &str) -> Option<&Language> {
+        self.iter()
+            .find(|language| language.long_code.as_str().eq_ignore_ascii_case(code))
+    }
+
+    /// Iterate over the languages whose (short) code is the primary language
+    /// of `code`, ignoring case, e.g., all English variants for `"en"` or
+    /// `"en-US"`.
+    pub fn variants_of<'a>(&'a self, code: &'a str) -> impl Iterator<Item = &'a Language> + 'a {
+        let primary = code.split('-').next().unwrap_or_default();
+        self.iter()
+            .filter(move |language| language.code.as_str().eq_ignore_ascii_case(primary))
+    }
+}
+
+impl From<Vec<Language>> for LanguagesResponse {
+    fn from(languages: Vec<Language>) -> Self {
+        Self { languages }
+    }
+}
+
+impl FromIterator<Language> for LanguagesResponse {
+    fn from_iter<I: IntoIterator<Item = Language>>(iter: I) -> Self {
+        Self {
+            languages: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl IntoIterator for LanguagesResponse {
+    type Item = Language;
+    type IntoIter = std::vec::IntoIter<Language>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.languages.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a LanguagesResponse {
+    type Item = &'a Language;
+    type IntoIter = std::slice::Iter<'a, Language>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Order of the languages listed by the languages command.
+#[cfg(feature = "cli")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+#[non_exhaustive]
+pub enum LanguagesSort {
+    /// Sort by long code, e.g., `en-GB`.
+    Code,
+    /// Sort by name, or by native name if `--native` is used.
+    Name,
+}
+
+/// Return list of supported languages.
+#[cfg(feature = "cli")]
+#[derive(Debug, Parser)]
+pub struct LanguagesCommand {
+    /// If present, add the native name of each language, e.g., `"Deutsch"`.
+    #[clap(long)]
+    pub native: bool,
+    /// Order of the listed languages. By default, they are listed in the
+    /// order returned by the server.
+    #[clap(long, ignore_case = true, value_enum)]
+    pub sort: Option<LanguagesSort>,
+    /// If present, only list the variants of this language, e.g., `en` for
+    /// all English variants.
+    #[clap(long, value_name = "CODE")]
+    pub code: Option<String>,
+    /// If present, print one language per line, as its long code and its name
+    /// (or native name if `--native` is used) separated by a tab, instead of
+    /// JSON.
+    #[clap(long)]
+    pub long: bool,
+}
+
+#[cfg(feature = "cli")]
+impl LanguagesCommand {
+    /// Filter, add native names and sort `languages`, as requested.
+    #[must_use]
+    pub fn apply(&self, languages: LanguagesResponse) -> LanguagesResponse {
+        let languages = match &self.code {
+            Some(code) => languages.variants_of(code).cloned().collect(),
+            None => languages,
+        };
+        let mut languages: LanguagesResponse = if self.native {
+            languages
+                .into_iter()
+                .map(Language::with_native_name)
+                .collect()
+        } else {
+            languages
+        };
+
+        match self.sort {
+            Some(LanguagesSort::Code) => {
+                languages
+                    .languages
+                    .sort_by(|a, b| a.long_code.cmp(&b.long_code));
+            },
+            Some(LanguagesSort::Name) => {
+                languages.languages.sort_by_cached_key(|language| {
+                    language
+                        .native_name
+                        .as_deref()
+                        .unwrap_or(&language.name)
+                        .to_lowercase()
+                });
+            },
+            None => {},
+        }
+        languages
+    }
+
+    /// Format `languages` as requested, i.e., as pretty JSON or, with
+    /// `--long`, one language per line.
+    ///
+    /// # Errors
+    ///
+    /// If the languages cannot be serialized.
+    pub fn format(&self, languages: &LanguagesResponse) -> serde_json::Result<String> {
+        if !self.long {
+            return serde_json::to_string_pretty(languages);
+        }
+        Ok(languages
+            .iter()
+            .map(|language| {
+                format!(
+                    "{}\t{}",
+                    language.long_code,
+                    language.native_name.as_deref().unwrap_or(&language.name)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn language(name: &str, long_code: &str) -> Language {
+        Language {
+            name: name.to_string(),
+            code: long_code.split('-').next().unwrap().parse().unwrap(),
+            long_code: long_code.parse().unwrap(),
+            native_name: None,
+        }
+    }
+
+    #[test]
+    fn test_language_code() {
+        for (code, normalized) in [
+            ("auto", "auto"),
+            ("AUTO", "auto"),
+            ("FR", "fr"),
+            ("en-us", "en-US"),
+            ("crh-ua", "crh-UA"),
+            ("de-DE-x-simple-language", "de-DE-x-simple-language"),
+        ] {
+            assert_eq!(code.parse::<LanguageCode>().unwrap(), normalized);
+        }
+        for code in ["", "e", "abcd", "en-", "en-USA", "en-U5", "fr-french", "-en"] {
+            assert!(code.parse::<LanguageCode>().is_err(), "{code:?}");
+        }
+
+        assert!(LanguageCode::default().is_auto());
+        assert_eq!(LanguageCode::PT_BR.primary(), "pt");
+        assert_eq!(
+            serde_json::to_string(&LanguageCode::EN_GB).unwrap(),
+            r#""en-GB""#
+        );
+        assert_eq!(
+            serde_json::from_str::<LanguageCode>(r#""en-gb""#).unwrap(),
+            LanguageCode::EN_GB
+        );
+        assert!(serde_json::from_str::<LanguageCode>(r#""en_GB""#).is_err());
+    }
+
+    #[test]
+    fn test_native_name() {
+        assert_eq!(native_name("en-gb"), Some("British English"));
+        assert_eq!(native_name("ca-ES-valencia"), Some("valencià"));
+        assert_eq!(native_name("uk-UA"), Some("українська"));
+        assert_eq!(native_name(""), None);
+
+        let json = serde_json::to_string(&language("German", "de-DE")).unwrap();
+        assert!(!json.contains("nativeName"));
+        let json = serde_json::to_string(&language("German", "de-DE").with_native_name()).unwrap();
+        assert!(json.contains(r#""nativeName":"Deutsch""#));
+    }
+
+    #[cfg(feature = "cli")]
+    #[test]
+    fn test_languages_command() {
+        let languages = LanguagesResponse::from(vec![
+            language("German", "de-DE"),
+            language("Dutch", "nl"),
+            language("English (US)", "en-US"),
+        ]);
+        let codes = |languages: &LanguagesResponse| -> Vec<String> {
+            languages.iter().map(|l| l.long_code.to_string()).collect()
+        };
+
+        let cmd = LanguagesCommand {
+            native: false,
+            sort: Some(LanguagesSort::Code),
+            code: None,
+            long: false,
+        };
+        assert_eq!(
+            codes(&cmd.apply(languages.clone())),
+            ["de-DE", "en-US", "nl"]
+        );
+
+        let cmd = LanguagesCommand {
+            native: false,
+            sort: Some(LanguagesSort::Name),
+            code: None,
+            long: false,
+        };
+        assert_eq!(
+            codes(&cmd.apply(languages.clone())),
+            ["nl", "en-US", "de-DE"]
+        );
+
+        // "American English" < "Deutsch" < "Nederlands"
+        let cmd = LanguagesCommand {
+            native: true,
+            sort: Some(LanguagesSort::Name),
+            code: None,
+            long: false,
+        };
+        let sorted = cmd.apply(languages.clone());
+        assert_eq!(codes(&sorted), ["en-US", "de-DE", "nl"]);
+        assert_eq!(
+            sorted.languages[2].native_name.as_deref(),
+            Some("Nederlands")
+        );
+
+        let cmd = LanguagesCommand {
+            native: false,
+            sort: None,
+            code: Some("DE".to_string()),
+            long: true,
+        };
+        let filtered = cmd.apply(languages);
+        assert_eq!(codes(&filtered), ["de-DE"]);
+        assert_eq!(cmd.format(&filtered).unwrap(), "de-DE\tGerman");
+    }
+
+    #[test]
+    fn test_languages_response() {
+        let languages = LanguagesResponse::from(vec![
+            language("English", "en"),
+            language("English (GB)", "en-GB"),
+            language("Esperanto", "eo"),
+        ]);
+
+        assert_eq!(languages.find("EN-gb").unwrap().name, "English (GB)");
+        assert!(languages.find("en-US").is_none());
+        assert_eq!(
+            languages
+                .variants_of("en-US")
+                .map(|l| l.long_code.as_str())
+                .collect::<Vec<_>>(),
+            ["en", "en-GB"]
+        );
+        assert_eq!(languages.variants_of("fr").count(), 0);
+    }
+}
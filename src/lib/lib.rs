@@ -17,16 +17,55 @@
 //! that cannot be controlled and (possible) breaking changes are to be
 //! expected.
 
+pub mod build_info;
+pub mod cache;
 pub mod check;
 #[cfg(feature = "cli")]
 pub mod cli;
+#[cfg(feature = "cli")]
+pub mod config;
 #[cfg(feature = "docker")]
 pub mod docker;
 pub mod error;
+pub mod experimental;
+pub mod git;
+pub mod ignore;
 pub mod languages;
+#[cfg(feature = "local-server")]
+pub mod local_server;
+#[cfg(feature = "lsp")]
+pub mod lsp;
+pub mod output;
+pub mod parsers;
+pub mod rule_info;
+#[cfg(feature = "rules")]
+pub mod rules;
 pub mod server;
+pub mod text_stats;
+#[cfg(feature = "cli")]
+pub mod walk;
 pub mod words;
 
+// Features enabled by other features in `Cargo.toml`, checked here so that
+// editing the manifest cannot break the code that relies on them.
+#[cfg(all(
+    feature = "cli",
+    not(all(feature = "color", feature = "multithreaded"))
+))]
+compile_error!("feature `cli` requires features `color` and `multithreaded`");
+#[cfg(all(feature = "cli-complete", not(feature = "cli")))]
+compile_error!("feature `cli-complete` requires feature `cli`");
+#[cfg(all(feature = "docker", not(feature = "multithreaded")))]
+compile_error!("feature `docker` requires feature `multithreaded`");
+#[cfg(all(feature = "keyring", not(feature = "cli")))]
+compile_error!("feature `keyring` requires feature `cli`");
+#[cfg(all(feature = "local-server", not(feature = "multithreaded")))]
+compile_error!("feature `local-server` requires feature `multithreaded`");
+#[cfg(all(feature = "lsp", not(feature = "multithreaded")))]
+compile_error!("feature `lsp` requires feature `multithreaded`");
+#[cfg(all(feature = "minimal", not(feature = "native-tls")))]
+compile_error!("feature `minimal` requires feature `native-tls`");
+
 #[cfg(feature = "docker")]
 pub use crate::docker::Docker;
 pub use crate::{
@@ -0,0 +1,326 @@
+//! Run a LanguageTool server locally, as a Java child process, so that text can
+//! be checked without Docker or the public API.
+//!
+//! A [`LocalServer`] points to a LanguageTool installation, i.e., a directory
+//! with `languagetool-server.jar`, which [`LocalServer::download`] can fetch.
+//! Starting it returns a [`RunningLocalServer`], that stops the server when
+//! dropped.
+
+use crate::{
+    error::{exit_status_error, Error, Result},
+    server::{ConfigFile, ServerClient},
+};
+use sha2::{Digest, Sha256};
+use std::{
+    io::{BufWriter, Write},
+    net::TcpListener,
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
+    time::Duration,
+};
+use tempfile::NamedTempFile;
+
+/// Version of LanguageTool downloaded by [`LocalServer::download`].
+pub const LANGUAGETOOL_VERSION: &str = "6.4";
+
+/// SHA-256 digest, in hexadecimal, of the archive of
+/// [`LANGUAGETOOL_VERSION`], checked by [`LocalServer::download`].
+///
+/// It must be updated together with the version, from the archive published
+/// on <https://languagetool.org/download/>.
+pub const LANGUAGETOOL_SHA256: &str = "";
+
+/// Default time given to the server to be ready, see [`LocalServer::start`].
+pub const DEFAULT_STARTUP_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Interval between two pings while waiting for the server to be ready.
+const READINESS_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Name of the server's JAR in a LanguageTool installation.
+const SERVER_JAR: &str = "languagetool-server.jar";
+
+/// Return a free TCP port on the loopback interface.
+fn free_port() -> Result<u16> {
+    Ok(TcpListener::bind("127.0.0.1:0")?.local_addr()?.port())
+}
+
+/// A LanguageTool installation that can be started as a local server.
+#[derive(Clone, Debug)]
+pub struct LocalServer {
+    /// Directory containing `languagetool-server.jar`.
+    home: PathBuf,
+    /// Java executable.
+    java: String,
+    /// Port to listen on, or a free port if `None`.
+    port: Option<u16>,
+    /// Server configuration.
+    config: ConfigFile,
+}
+
+impl LocalServer {
+    /// Create a local server from a LanguageTool installation directory.
+    #[must_use]
+    pub fn new<P: Into<PathBuf>>(home: P) -> Self {
+        Self {
+            home: home.into(),
+            java: "java".to_string(),
+            port: None,
+            config: ConfigFile::default(),
+        }
+    }
+
+    /// Return the directory where [`LocalServer::download`] installs
+    /// LanguageTool by default, in the user's cache directory.
+    #[must_use]
+    pub fn default_home() -> Option<PathBuf> {
+        dirs::cache_dir().map(|dir| {
+            dir.join("ltrs")
+                .join(format!("LanguageTool-{LANGUAGETOOL_VERSION}"))
+        })
+    }
+
+    /// Download and unzip LanguageTool [`LANGUAGETOOL_VERSION`] into
+    /// `home`'s parent directory, unless `home` is already an installation.
+    ///
+    /// The archive is written to a temporary file while it is downloaded, and
+    /// is only unzipped if its digest is [`LANGUAGETOOL_SHA256`]. Unzipping
+    /// relies on the `unzip` command.
+    ///
+    /// # Errors
+    ///
+    /// If the download fails, if the archive's digest does not match, if
+    /// `unzip` is not found or fails, or if the archive does not contain an
+    /// installation named like `home`.
+    pub async fn download<P: AsRef<Path>>(home: P) -> Result<Self> {
+        let server = Self::new(home.as_ref());
+        if server.is_installed() {
+            return Ok(server);
+        }
+
+        let dir = home.as_ref().parent().unwrap_or_else(|| Path::new("."));
+        std::fs::create_dir_all(dir)?;
+        let url =
+            format!("https://languagetool.org/download/LanguageTool-{LANGUAGETOOL_VERSION}.zip");
+        let mut archive = tempfile::Builder::new()
+            .prefix("LanguageTool-")
+            .suffix(".zip")
+            .tempfile_in(dir)?;
+        let mut hasher = Sha256::new();
+
+        let mut response = reqwest::get(&url).await?.error_for_status()?;
+        while let Some(chunk) = response.chunk().await? {
+            hasher.update(&chunk);
+            archive.write_all(&chunk)?;
+        }
+        archive.flush()?;
+
+        let actual: String = hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect();
+        if actual != LANGUAGETOOL_SHA256 {
+            return Err(Error::ChecksumMismatch {
+                url,
+                expected: LANGUAGETOOL_SHA256.to_string(),
+                actual,
+            });
+        }
+
+        let output = Command::new("unzip")
+            .args(["-q", "-o"])
+            .arg(archive.path())
+            .arg("-d")
+            .arg(dir)
+            .stderr(Stdio::inherit())
+            .output()
+            .map_err(|_| Error::CommandNotFound("unzip".to_string()))?;
+        archive.close()?;
+        exit_status_error(&output.status)?;
+
+        if !server.is_installed() {
+            return Err(Error::InvalidFilename(
+                server.home.join(SERVER_JAR).display().to_string(),
+            ));
+        }
+        Ok(server)
+    }
+
+    /// Set the Java executable (default: `java`).
+    #[must_use]
+    pub fn with_java<S: Into<String>>(mut self, java: S) -> Self {
+        self.java = java.into();
+        self
+    }
+
+    /// Set the port to listen on (default: any free port).
+    #[must_use]
+    pub fn with_port(mut self, port: u16) -> Self {
+        self.port = Some(port);
+        self
+    }
+
+    /// Set the server configuration.
+    #[must_use]
+    pub fn with_config(mut self, config: ConfigFile) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Return `true` if the installation directory contains the server's JAR.
+    #[must_use]
+    pub fn is_installed(&self) -> bool {
+        self.home.join(SERVER_JAR).is_file()
+    }
+
+    /// Start the server, and wait for at most `timeout` until it answers
+    /// pings.
+    ///
+    /// # Errors
+    ///
+    /// If LanguageTool is not installed, if Java is not found, if the server
+    /// exits, or if it is not ready in time.
+    pub async fn start(&self, timeout: Duration) -> Result<RunningLocalServer> {
+        if !self.is_installed() {
+            return Err(Error::InvalidFilename(
+                self.home.join(SERVER_JAR).display().to_string(),
+            ));
+        }
+
+        let port = match self.port {
+            Some(port) => port,
+            None => free_port()?,
+        };
+        let config_file = tempfile::Builder::new()
+            .prefix("ltrs-languagetool-")
+            .suffix(".properties")
+            .tempfile()?;
+        let mut writer = BufWriter::new(config_file.as_file());
+        self.config.write_to(&mut writer)?;
+        writer.flush()?;
+        drop(writer);
+
+        let child = Command::new(&self.java)
+            .arg("-cp")
+            .arg(self.home.join(SERVER_JAR))
+            .arg("org.languagetool.server.HTTPServer")
+            .arg("--config")
+            .arg(config_file.path())
+            .args(["--port", &port.to_string()])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|_| Error::CommandNotFound(self.java.clone()))?;
+
+        let mut server = RunningLocalServer {
+            child,
+            client: ServerClient::new("http://127.0.0.1", &port.to_string()),
+            _config_file: config_file,
+        };
+        server.wait_until_ready(timeout).await?;
+        Ok(server)
+    }
+}
+
+/// A LanguageTool server running as a child process, stopped when dropped.
+#[derive(Debug)]
+pub struct RunningLocalServer {
+    child: Child,
+    client: ServerClient,
+    /// Configuration file, removed when dropped.
+    _config_file: NamedTempFile,
+}
+
+impl RunningLocalServer {
+    /// Return a client that sends requests to this server.
+    #[must_use]
+    pub fn client(&self) -> &ServerClient {
+        &self.client
+    }
+
+    /// Ping the server until it answers, or until it exits or `timeout`
+    /// elapses.
+    async fn wait_until_ready(&mut self, timeout: Duration) -> Result<()> {
+        let deadline = tokio::time::Instant::now() + timeout;
+
+        loop {
+            if let Some(status) = self.child.try_wait()? {
+                exit_status_error(&status)?;
+                return Err(Error::ExitStatus(
+                    "LanguageTool server exited before being ready".to_string(),
+                ));
+            }
+            if self.client.ping().await.is_ok() {
+                return Ok(());
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return Err(Error::DeadlineExceeded);
+            }
+            tokio::time::sleep(READINESS_INTERVAL).await;
+        }
+    }
+
+    /// Stop the server.
+    ///
+    /// # Errors
+    ///
+    /// If the process cannot be killed.
+    pub fn stop(mut self) -> Result<()> {
+        self.kill()
+    }
+
+    /// Kill the process.
+    fn kill(&mut self) -> Result<()> {
+        if self.child.try_wait()?.is_none() {
+            self.child.kill()?;
+            self.child.wait()?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for RunningLocalServer {
+    fn drop(&mut self) {
+        let _ = self.kill();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_home() {
+        if let Some(home) = LocalServer::default_home() {
+            assert!(home.ends_with(format!("LanguageTool-{LANGUAGETOOL_VERSION}")));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_start_not_installed() {
+        let dir = tempfile::tempdir().unwrap();
+        let server = LocalServer::new(dir.path());
+
+        assert!(!server.is_installed());
+        assert!(matches!(
+            server.start(DEFAULT_STARTUP_TIMEOUT).await,
+            Err(Error::InvalidFilename(_))
+        ));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_start_server_exits() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(SERVER_JAR), b"").unwrap();
+        // `false` exits right away, like Java with an invalid JAR
+        let server = LocalServer::new(dir.path()).with_java("false");
+
+        assert!(server.is_installed());
+        assert!(matches!(
+            server.start(DEFAULT_STARTUP_TIMEOUT).await,
+            Err(Error::ExitStatus(_))
+        ));
+    }
+}
@@ -0,0 +1,329 @@
+//! Language Server Protocol (LSP) server publishing LanguageTool matches as
+//! diagnostics.
+//!
+//! Documents are checked when opened or saved, and after a short delay
+//! (debouncing) when changed. Replacements are offered as quick-fix code
+//! actions.
+
+#[cfg(feature = "cli")]
+use crate::error::Result;
+use crate::{
+    check::{CheckRequest, Match},
+    server::ServerClient,
+};
+#[cfg(feature = "cli")]
+use clap::Parser;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tower_lsp::{
+    jsonrpc,
+    lsp_types::{
+        CodeAction, CodeActionKind, CodeActionOrCommand, CodeActionParams,
+        CodeActionProviderCapability, CodeActionResponse, Diagnostic, DiagnosticSeverity,
+        DidChangeTextDocumentParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams,
+        DidSaveTextDocumentParams, InitializeParams, InitializeResult, InitializedParams,
+        MessageType, NumberOrString, Position, Range, SaveOptions, ServerCapabilities, ServerInfo,
+        TextDocumentSyncCapability, TextDocumentSyncKind, TextDocumentSyncOptions,
+        TextDocumentSyncSaveOptions, TextEdit, Url, WorkspaceEdit,
+    },
+    Client, LanguageServer, LspService, Server,
+};
+
+/// Name used as diagnostics source.
+const SOURCE: &str = "languagetool";
+
+/// Return the LSP position (line and UTF-16 column) of the char at index
+/// `offset` in `text`.
+#[must_use]
+pub fn position_at(text: &str, offset: usize) -> Position {
+    let mut line = 0;
+    let mut character = 0;
+
+    for c in text.chars().take(offset) {
+        if c == '\n' {
+            line += 1;
+            character = 0;
+        } else {
+            character += c.len_utf16() as u32;
+        }
+    }
+
+    Position::new(line, character)
+}
+
+/// Convert a match into a diagnostic, storing its replacements in the
+/// diagnostic's data so that they can be offered as code actions.
+#[must_use]
+pub fn match_to_diagnostic(text: &str, m: &Match) -> Diagnostic {
+    let severity = match m.rule.issue_type.as_str() {
+        "misspelling" => DiagnosticSeverity::ERROR,
+        "style" => DiagnosticSeverity::INFORMATION,
+        _ => DiagnosticSeverity::WARNING,
+    };
+    let replacements: Vec<&str> = m.replacements.iter().map(|r| r.value.as_str()).collect();
+
+    Diagnostic {
+        range: Range::new(
+            position_at(text, m.offset),
+            position_at(text, m.offset + m.length),
+        ),
+        severity: Some(severity),
+        code: Some(NumberOrString::String(m.rule.id.clone())),
+        source: Some(SOURCE.to_string()),
+        message: m.message.clone(),
+        data: Some(serde_json::json!(replacements)),
+        ..Default::default()
+    }
+}
+
+/// LanguageTool language server.
+#[derive(Debug)]
+pub struct Backend {
+    client: Client,
+    server_client: ServerClient,
+    request: CheckRequest,
+    debounce: Duration,
+    max_length: usize,
+    split_pattern: String,
+    /// Latest known version of each open document.
+    versions: Arc<Mutex<HashMap<Url, i32>>>,
+}
+
+impl Backend {
+    /// Create a new backend that checks documents with `server_client`, using
+    /// `request` as a template for check requests.
+    #[must_use]
+    pub fn new(
+        client: Client,
+        server_client: ServerClient,
+        request: CheckRequest,
+        debounce: Duration,
+    ) -> Self {
+        Self {
+            client,
+            server_client,
+            request,
+            debounce,
+            max_length: 1500,
+            split_pattern: "\n\n".to_string(),
+            versions: Default::default(),
+        }
+    }
+
+    /// Check a document after `delay`, unless a newer version was received in
+    /// the meantime, and publish the resulting diagnostics.
+    fn schedule_check(&self, uri: Url, version: i32, text: String, delay: Duration) {
+        self.versions.lock().unwrap().insert(uri.clone(), version);
+
+        let client = self.client.clone();
+        let server_client = self.server_client.clone();
+        let requests = self
+            .request
+            .clone()
+            .with_text(text.clone())
+            .split(self.max_length, self.split_pattern.as_str());
+        let versions = self.versions.clone();
+        let is_latest = move |uri: &Url| versions.lock().unwrap().get(uri) == Some(&version);
+
+        tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+
+            if !is_latest(&uri) {
+                return;
+            }
+
+            match server_client.check_multiple_and_join(requests).await {
+                Ok(response) if is_latest(&uri) => {
+                    let diagnostics = response
+                        .iter_matches()
+                        .map(|m| match_to_diagnostic(&text, m))
+                        .collect();
+                    client
+                        .publish_diagnostics(uri, diagnostics, Some(version))
+                        .await;
+                },
+                Ok(_) => {},
+                Err(e) => {
+                    client
+                        .log_message(MessageType::ERROR, format!("could not check {uri}: {e}"))
+                        .await;
+                },
+            }
+        });
+    }
+}
+
+#[tower_lsp::async_trait]
+impl LanguageServer for Backend {
+    async fn initialize(&self, _: InitializeParams) -> jsonrpc::Result<InitializeResult> {
+        Ok(InitializeResult {
+            capabilities: ServerCapabilities {
+                text_document_sync: Some(TextDocumentSyncCapability::Options(
+                    TextDocumentSyncOptions {
+                        open_close: Some(true),
+                        change: Some(TextDocumentSyncKind::FULL),
+                        save: Some(TextDocumentSyncSaveOptions::SaveOptions(SaveOptions {
+                            include_text: Some(true),
+                        })),
+                        ..Default::default()
+                    },
+                )),
+                code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
+                ..Default::default()
+            },
+            server_info: Some(ServerInfo {
+                name: env!("CARGO_PKG_NAME").to_string(),
+                version: Some(env!("CARGO_PKG_VERSION").to_string()),
+            }),
+        })
+    }
+
+    async fn initialized(&self, _: InitializedParams) {
+        self.client
+            .log_message(MessageType::INFO, "LanguageTool server initialized")
+            .await;
+    }
+
+    async fn shutdown(&self) -> jsonrpc::Result<()> {
+        Ok(())
+    }
+
+    async fn did_open(&self, params: DidOpenTextDocumentParams) {
+        let document = params.text_document;
+        self.schedule_check(
+            document.uri,
+            document.version,
+            document.text,
+            Duration::ZERO,
+        );
+    }
+
+    async fn did_change(&self, mut params: DidChangeTextDocumentParams) {
+        // With full synchronization, the last change contains the whole text.
+        if let Some(change) = params.content_changes.pop() {
+            let document = params.text_document;
+            self.schedule_check(document.uri, document.version, change.text, self.debounce);
+        }
+    }
+
+    async fn did_save(&self, params: DidSaveTextDocumentParams) {
+        let uri = params.text_document.uri;
+        let version = self.versions.lock().unwrap().get(&uri).copied();
+
+        if let (Some(text), Some(version)) = (params.text, version) {
+            self.schedule_check(uri, version, text, Duration::ZERO);
+        }
+    }
+
+    async fn did_close(&self, params: DidCloseTextDocumentParams) {
+        let uri = params.text_document.uri;
+        self.versions.lock().unwrap().remove(&uri);
+        self.client.publish_diagnostics(uri, vec![], None).await;
+    }
+
+    async fn code_action(
+        &self,
+        params: CodeActionParams,
+    ) -> jsonrpc::Result<Option<CodeActionResponse>> {
+        let uri = params.text_document.uri;
+        let mut actions = CodeActionResponse::new();
+
+        for diagnostic in params.context.diagnostics {
+            if diagnostic.source.as_deref() != Some(SOURCE) {
+                continue;
+            }
+            let replacements: Vec<String> = diagnostic
+                .data
+                .clone()
+                .and_then(|data| serde_json::from_value(data).ok())
+                .unwrap_or_default();
+
+            for replacement in replacements {
+                let edit = TextEdit::new(diagnostic.range, replacement.clone());
+                actions.push(CodeActionOrCommand::CodeAction(CodeAction {
+                    title: format!("Replace with \"{replacement}\""),
+                    kind: Some(CodeActionKind::QUICKFIX),
+                    diagnostics: Some(vec![diagnostic.clone()]),
+                    edit: Some(WorkspaceEdit::new(HashMap::from([(
+                        uri.clone(),
+                        vec![edit],
+                    )]))),
+                    ..Default::default()
+                }));
+            }
+        }
+
+        Ok(Some(actions))
+    }
+}
+
+/// Start a language server communicating over standard input and output.
+pub async fn serve(server_client: ServerClient, request: CheckRequest, debounce: Duration) {
+    let (service, socket) =
+        LspService::new(|client| Backend::new(client, server_client, request, debounce));
+    Server::new(tokio::io::stdin(), tokio::io::stdout(), socket)
+        .serve(service)
+        .await;
+}
+
+/// Start a Language Server Protocol server over standard input and output.
+#[cfg(feature = "cli")]
+#[derive(Debug, Parser)]
+pub struct LspCommand {
+    /// A language code like `en-US`, `de-DE`, `fr`, or `auto` to guess the
+    /// language automatically.
+    #[clap(
+        short = 'l',
+        long,
+        default_value = "auto",
+        value_parser = crate::check::parse_language_code
+    )]
+    pub language: crate::languages::LanguageCode,
+    /// Delay, in milliseconds, after the last change before a document is
+    /// checked.
+    #[clap(long, default_value_t = 500)]
+    pub debounce: u64,
+}
+
+#[cfg(feature = "cli")]
+impl LspCommand {
+    /// Run the language server until the client exits.
+    pub async fn execute(self, server_client: ServerClient) -> Result<()> {
+        let request = CheckRequest::default().with_language(self.language);
+        serve(server_client, request, Duration::from_millis(self.debounce)).await;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::check::test_utils::response_with_matches;
+
+    #[test]
+    fn test_position_at() {
+        let text = "first line\nsecond 🦀 line";
+
+        assert_eq!(position_at(text, 0), Position::new(0, 0));
+        assert_eq!(position_at(text, 6), Position::new(0, 6));
+        assert_eq!(position_at(text, 11), Position::new(1, 0));
+        assert_eq!(position_at(text, 20), Position::new(1, 10));
+    }
+
+    #[test]
+    fn test_match_to_diagnostic() {
+        let text = "Some text\nwith a smal mistake.";
+        let response = response_with_matches(text, &[(17, 4, &["small", "seal"])]);
+        let diagnostic = match_to_diagnostic(text, &response.matches[0]);
+
+        assert_eq!(
+            diagnostic.range,
+            Range::new(Position::new(1, 7), Position::new(1, 11))
+        );
+        assert_eq!(diagnostic.severity, Some(DiagnosticSeverity::ERROR));
+        assert_eq!(diagnostic.data, Some(serde_json::json!(["small", "seal"])));
+    }
+}
@@ -0,0 +1,91 @@
+//! [Checkstyle](https://checkstyle.org) XML reports, understood by most CI
+//! annotators.
+
+use super::{position, severity, OutputFormatter, RunResults};
+use crate::check::CheckResponse;
+use std::fmt::Write;
+
+/// Escape a string so that it can be used as an XML attribute value.
+fn escape_xml(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\n' => escaped.push_str("&#10;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Build a Checkstyle XML report with one `<file>` element per response.
+#[must_use]
+pub fn to_checkstyle<'a, I>(responses: I) -> String
+where
+    I: IntoIterator<Item = (&'a str, &'a CheckResponse)>,
+{
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str("<checkstyle version=\"4.3\">\n");
+
+    for (origin, response) in responses {
+        let _ = writeln!(xml, "  <file name=\"{}\">", escape_xml(origin));
+        for m in response.iter_matches() {
+            let (line, column) = position(m);
+            let _ = writeln!(
+                xml,
+                "    <error line=\"{line}\" column=\"{column}\" severity=\"{}\" message=\"{}\" \
+                 source=\"languagetool.{}\"/>",
+                severity(m),
+                escape_xml(&m.message),
+                escape_xml(&m.rule.id)
+            );
+        }
+        xml.push_str("  </file>\n");
+    }
+
+    xml.push_str("</checkstyle>");
+    xml
+}
+
+/// Formatter printing a Checkstyle XML report, see [`to_checkstyle`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CheckstyleFormatter;
+
+impl OutputFormatter for CheckstyleFormatter {
+    fn name(&self) -> &str {
+        "checkstyle"
+    }
+
+    fn mime(&self) -> &str {
+        "application/xml"
+    }
+
+    fn format(&self, results: &RunResults) -> Vec<u8> {
+        let mut xml = to_checkstyle(results.iter());
+        xml.push('\n');
+        xml.into_bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::tests::response;
+
+    #[test]
+    fn test_to_checkstyle() {
+        let response = response();
+        let xml = to_checkstyle([("a \"quoted\" file.txt", &response)]);
+
+        assert!(xml.contains("<file name=\"a &quot;quoted&quot; file.txt\">"));
+        assert!(xml.contains(
+            "<error line=\"2\" column=\"8\" severity=\"error\" message=\"\" \
+             source=\"languagetool.MORFOLOGIK_RULE_EN_US\"/>"
+        ));
+        assert!(xml.ends_with("</checkstyle>"));
+    }
+}
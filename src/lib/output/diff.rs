@@ -0,0 +1,169 @@
+//! Unified diff of the first suggestion of each match, which can be applied
+//! with `patch -p1` or read by code review tools.
+//!
+//! Lines changed by overlapping or adjacent matches are gathered in a single
+//! hunk, so that hunks never conflict.
+
+use crate::check::{CheckResponse, Match};
+
+/// Return the lines of `text`, without their line feed, and the character
+/// offset at which each line starts.
+fn lines_with_offsets(text: &str) -> (Vec<&str>, Vec<usize>) {
+    let lines: Vec<&str> = text.split('\n').collect();
+    let mut offsets = Vec::with_capacity(lines.len());
+    let mut offset = 0;
+    for line in &lines {
+        offsets.push(offset);
+        offset += line.chars().count() + 1;
+    }
+    (lines, offsets)
+}
+
+/// Return the index of the line containing the character at `offset`.
+fn line_of(offsets: &[usize], offset: usize) -> usize {
+    offsets.partition_point(|&start| start <= offset).max(1) - 1
+}
+
+/// Write the lines of one side of a hunk, prefixed with `prefix`.
+fn push_lines(diff: &mut String, prefix: char, lines: &str, no_newline_at_end: bool) {
+    for line in lines.split('\n') {
+        diff.push(prefix);
+        diff.push_str(line);
+        diff.push('\n');
+    }
+    if no_newline_at_end {
+        diff.push_str("\\ No newline at end of file\n");
+    }
+}
+
+/// Render the first suggestion of each match as a unified diff between
+/// `text` and the text with these suggestions applied.
+///
+/// Matches without suggestions, and matches overlapping a previous one, are
+/// skipped. The diff is empty if there is nothing to change.
+///
+/// # Examples
+///
+/// ```
+/// # use languagetool_rust::{check::CheckResponse, output::to_diff};
+/// # fn print(response: &CheckResponse) {
+/// let text = "Some text\nwith a smal mistake.\n";
+/// // With a match on "smal", suggesting "small"
+/// assert_eq!(
+///     to_diff("file.txt", text, response),
+///     "--- a/file.txt\n+++ b/file.txt\n@@ -2,1 +2,1 @@\n-with a smal mistake.\n+with a small \
+///      mistake.\n"
+/// );
+/// # }
+/// ```
+#[must_use]
+pub fn to_diff(origin: &str, text: &str, response: &CheckResponse) -> String {
+    let mut matches: Vec<&Match> = response
+        .iter_matches()
+        .filter(|m| !m.replacements.is_empty())
+        .collect();
+    matches.sort_by_key(|m| m.offset);
+    let mut end = 0;
+    matches.retain(|m| {
+        let kept = m.offset >= end;
+        if kept {
+            end = m.offset + m.length;
+        }
+        kept
+    });
+
+    let (lines, offsets) = lines_with_offsets(text);
+    let last_line = lines.len() - 1;
+    // A trailing line feed leaves an empty last "line", that is never changed.
+    let no_newline_at_end = !text.ends_with('\n');
+
+    // Ranges of lines, and the matches they contain.
+    let mut hunks: Vec<(usize, usize, Vec<&Match>)> = Vec::new();
+    for m in matches {
+        let first = line_of(&offsets, m.offset);
+        let last = line_of(&offsets, m.offset + m.length.saturating_sub(1)).max(first);
+        match hunks.last_mut() {
+            Some((_, hunk_last, hunk_matches)) if first <= *hunk_last + 1 => {
+                *hunk_last = (*hunk_last).max(last);
+                hunk_matches.push(m);
+            },
+            _ => hunks.push((first, last, vec![m])),
+        }
+    }
+
+    let mut diff = String::new();
+    if hunks.is_empty() {
+        return diff;
+    }
+    diff.push_str(&format!("--- a/{origin}\n+++ b/{origin}\n"));
+
+    // Difference between the line numbers of the fixed and of the original text.
+    let mut delta: isize = 0;
+    for (first, last, hunk_matches) in hunks {
+        let original = lines[first..=last].join("\n");
+        // Replacing from the end keeps the offsets of remaining matches valid
+        let fixed = hunk_matches.iter().rev().fold(original.clone(), |fixed, m| {
+            let mut m = (*m).clone();
+            m.offset -= offsets[first];
+            m.apply_to(&fixed, 0).map_or(fixed, |(edited, _)| edited)
+        });
+        if fixed == original {
+            continue;
+        }
+
+        let original_len = last - first + 1;
+        let fixed_len = fixed.split('\n').count();
+        let fixed_first = first as isize + 1 + delta;
+        diff.push_str(&format!(
+            "@@ -{},{original_len} +{fixed_first},{fixed_len} @@\n",
+            first + 1
+        ));
+        let at_end = no_newline_at_end && last == last_line;
+        push_lines(&mut diff, '-', &original, at_end);
+        push_lines(&mut diff, '+', &fixed, at_end);
+        delta += fixed_len as isize - original_len as isize;
+    }
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::check::test_utils::response_with_matches;
+
+    #[test]
+    fn test_to_diff() {
+        let text = "Some text\nwith a smal mistake\nand anothr one.\n";
+        let response = response_with_matches(
+            text,
+            &[(17, 4, &["small"]), (30, 3, &["ann"]), (34, 6, &["another", "other"])],
+        );
+
+        assert_eq!(
+            to_diff("file.txt", text, &response),
+            "--- a/file.txt\n+++ b/file.txt\n@@ -2,2 +2,2 @@\n-with a smal mistake\n-and anothr \
+             one.\n+with a small mistake\n+ann another one.\n"
+        );
+    }
+
+    #[test]
+    fn test_to_diff_line_delta_and_no_newline() {
+        let text = "A smal text\n\nA smal end";
+        let response = response_with_matches(text, &[(2, 4, &["small\n"]), (15, 4, &["small"])]);
+
+        assert_eq!(
+            to_diff("-", text, &response),
+            "--- a/-\n+++ b/-\n@@ -1,1 +1,2 @@\n-A smal text\n+A small\n+ text\n@@ -3,1 +4,1 \
+             @@\n-A smal end\n\\ No newline at end of file\n+A small end\n\\ No newline at end \
+             of file\n"
+        );
+    }
+
+    #[test]
+    fn test_to_diff_without_suggestions() {
+        let text = "A smal text";
+        let response = response_with_matches(text, &[(2, 4, &[])]);
+
+        assert_eq!(to_diff("file.txt", text, &response), "");
+    }
+}
@@ -0,0 +1,338 @@
+//! Serialize check responses into report formats understood by other tools,
+//! such as code scanning services or CI annotators.
+//!
+//! Each report is built from a list of `(origin, response)` pairs, where
+//! `origin` is the file that was checked. Match positions are read from
+//! [`Match::more_context`] when available.
+//!
+//! Report formats implement [`OutputFormatter`] and live in their own module,
+//! e.g., [`sarif`]. They are looked up by name in a [`FormatterRegistry`], to
+//! which crates embedding the command line interface can add their own
+//! formatters.
+
+use crate::{
+    check::{CheckResponse, Match},
+    error::{Error, Result},
+};
+#[cfg(feature = "cli")]
+use clap::ValueEnum;
+
+pub mod checkstyle;
+pub mod diff;
+pub mod sarif;
+
+pub use checkstyle::{to_checkstyle, CheckstyleFormatter};
+pub use diff::to_diff;
+pub use sarif::{to_sarif, SarifFormatter};
+
+/// Format used to print check results.
+///
+/// Report formats, like [`OutputFormat::Sarif`], are printed by the
+/// [`OutputFormatter`] of the same name.
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum OutputFormat {
+    /// Annotated text, meant to be read by humans.
+    #[default]
+    Text,
+    /// Raw JSON responses.
+    Json,
+    /// [SARIF 2.1.0](https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html) log.
+    Sarif,
+    /// [Checkstyle](https://checkstyle.org) XML report.
+    Checkstyle,
+    /// Fingerprint and rule id of each match, one per line, see
+    /// [`Match::fingerprint`].
+    Fingerprints,
+    /// Unified diff applying the first suggestion of each match, see
+    /// [`to_diff`].
+    Diff,
+}
+
+/// Names of the formats printed while files are checked, which cannot be used
+/// by an [`OutputFormatter`].
+pub const RESERVED_FORMAT_NAMES: &[&str] = &["text", "json", "fingerprints", "diff"];
+
+/// Severity of a match, derived from its rule's issue type.
+fn severity(m: &Match) -> &'static str {
+    match m.rule.issue_type.as_str() {
+        "misspelling" => "error",
+        "style" => "info",
+        _ => "warning",
+    }
+}
+
+/// Return the one-based line and column of a match.
+fn position(m: &Match) -> (usize, usize) {
+    match &m.more_context {
+        Some(more_context) => (more_context.line_number, more_context.line_offset + 1),
+        None => (1, m.offset + 1),
+    }
+}
+
+/// List the fingerprint and rule id of each match, separated by a space, one
+/// match per line.
+///
+/// The output is meant for scripts, e.g., to diff two runs or to count
+/// occurrences of each rule.
+#[must_use]
+pub fn to_fingerprints(response: &CheckResponse) -> String {
+    response
+        .iter_matches()
+        .map(|m| format!("{} {}\n", m.fingerprint(), m.rule.id))
+        .collect()
+}
+
+/// Results of a whole run, i.e., the response of each checked file, in the
+/// order files were checked.
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct RunResults {
+    /// Origin of each response, i.e., the checked filename or `-` for
+    /// standard input, and the response itself.
+    pub reports: Vec<(String, CheckResponse)>,
+}
+
+impl RunResults {
+    /// Iterate over `(origin, response)` pairs.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &CheckResponse)> {
+        self.reports
+            .iter()
+            .map(|(origin, response)| (origin.as_str(), response))
+    }
+}
+
+impl From<Vec<(String, CheckResponse)>> for RunResults {
+    fn from(reports: Vec<(String, CheckResponse)>) -> Self {
+        Self { reports }
+    }
+}
+
+/// A report format, printed once all files are checked.
+///
+/// This is the interface third-party crates implement to add formats to the
+/// command line interface, see [`FormatterRegistry::register`]. Its methods
+/// must not change between two calls, and [`verify_formatter`] checks the
+/// values they return.
+pub trait OutputFormatter: Send + Sync {
+    /// Name used to select this format, e.g., with `--format NAME`.
+    ///
+    /// It must be made of lowercase ASCII letters, digits, `-` and `_`, and
+    /// must not be one of [`RESERVED_FORMAT_NAMES`].
+    fn name(&self) -> &str;
+
+    /// MIME type of the produced report, e.g., `application/xml`.
+    fn mime(&self) -> &str;
+
+    /// Format the results of a run into a report.
+    fn format(&self, results: &RunResults) -> Vec<u8>;
+}
+
+/// Check that a formatter respects the requirements of [`OutputFormatter`].
+///
+/// # Errors
+///
+/// If its name or MIME type is invalid.
+pub fn verify_formatter(formatter: &dyn OutputFormatter) -> Result<()> {
+    let name = formatter.name();
+    if name.is_empty()
+        || !name
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-' || b == b'_')
+    {
+        return Err(Error::InvalidValue(format!(
+            "output format name {name:?} must be made of lowercase ASCII letters, digits, '-' \
+             and '_'"
+        )));
+    }
+    if RESERVED_FORMAT_NAMES.contains(&name) {
+        return Err(Error::InvalidValue(format!(
+            "output format name {name:?} is reserved"
+        )));
+    }
+
+    let mime = formatter.mime();
+    let valid_mime = match mime.split_once('/') {
+        Some((kind, subtype)) => {
+            !kind.is_empty()
+                && !subtype.is_empty()
+                && !mime.contains(|c: char| c.is_whitespace() || c.is_control())
+                && !subtype.contains('/')
+        },
+        None => false,
+    };
+    if !valid_mime {
+        return Err(Error::InvalidValue(format!(
+            "output format {name:?} has an invalid MIME type {mime:?}"
+        )));
+    }
+
+    Ok(())
+}
+
+/// Registry of the report formats that can be selected by name.
+///
+/// [`FormatterRegistry::default`] contains the formats of this crate, i.e.,
+/// [`SarifFormatter`] and [`CheckstyleFormatter`].
+///
+/// # Examples
+///
+/// ```
+/// # use languagetool_rust::output::{FormatterRegistry, OutputFormatter, RunResults};
+/// struct Count;
+///
+/// impl OutputFormatter for Count {
+///     fn name(&self) -> &str {
+///         "count"
+///     }
+///
+///     fn mime(&self) -> &str {
+///         "text/plain"
+///     }
+///
+///     fn format(&self, results: &RunResults) -> Vec<u8> {
+///         let count: usize = results.iter().map(|(_, r)| r.matches.len()).sum();
+///         format!("{count}\n").into_bytes()
+///     }
+/// }
+///
+/// let mut formatters = FormatterRegistry::default();
+/// formatters.register(Count).unwrap();
+/// assert!(formatters.get("count").is_some());
+/// ```
+pub struct FormatterRegistry {
+    formatters: Vec<Box<dyn OutputFormatter>>,
+}
+
+impl std::fmt::Debug for FormatterRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.names()).finish()
+    }
+}
+
+impl Default for FormatterRegistry {
+    fn default() -> Self {
+        Self {
+            formatters: vec![Box::new(SarifFormatter), Box::new(CheckstyleFormatter)],
+        }
+    }
+}
+
+impl FormatterRegistry {
+    /// Return an empty registry, without the formats of this crate.
+    #[must_use]
+    pub fn empty() -> Self {
+        Self {
+            formatters: Vec::new(),
+        }
+    }
+
+    /// Add a formatter, after checking it with [`verify_formatter`].
+    ///
+    /// # Errors
+    ///
+    /// If the formatter is invalid, or if a formatter with the same name is
+    /// already registered.
+    pub fn register<F: OutputFormatter + 'static>(&mut self, formatter: F) -> Result<()> {
+        verify_formatter(&formatter)?;
+        if self.get(formatter.name()).is_some() {
+            return Err(Error::InvalidValue(format!(
+                "output format {:?} is already registered",
+                formatter.name()
+            )));
+        }
+        self.formatters.push(Box::new(formatter));
+        Ok(())
+    }
+
+    /// Return the formatter with the given name, ignoring case.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&dyn OutputFormatter> {
+        self.formatters
+            .iter()
+            .find(|formatter| formatter.name().eq_ignore_ascii_case(name))
+            .map(AsRef::as_ref)
+    }
+
+    /// Iterate over the names of registered formatters, in registration order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.formatters.iter().map(|formatter| formatter.name())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::check::{test_utils::response_with_matches, CheckResponseWithContext};
+
+    pub(crate) fn response() -> CheckResponse {
+        let text = "Some text\nwith a smal mistake.";
+        let response = response_with_matches(text, &[(17, 4, &["small"])]);
+        CheckResponseWithContext::new(text.to_string(), response).into()
+    }
+
+    struct Named(&'static str, &'static str);
+
+    impl OutputFormatter for Named {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn mime(&self) -> &str {
+            self.1
+        }
+
+        fn format(&self, _: &RunResults) -> Vec<u8> {
+            Vec::new()
+        }
+    }
+
+    #[test]
+    fn test_to_fingerprints() {
+        let text = "A smal and smal mistake.";
+        let response = response_with_matches(text, &[(2, 4, &["small"]), (11, 4, &["small"])]);
+        let fingerprints = to_fingerprints(&response);
+        let lines: Vec<&str> = fingerprints.lines().collect();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], lines[1]);
+        assert_eq!(lines[0], "f0595450e82c1de5 MORFOLOGIK_RULE_EN_US");
+    }
+
+    #[test]
+    fn test_builtin_formatters() {
+        let formatters = FormatterRegistry::default();
+        let results = RunResults::from(vec![("file.txt".to_string(), response())]);
+
+        assert_eq!(
+            formatters.names().collect::<Vec<_>>(),
+            ["sarif", "checkstyle"]
+        );
+        for name in formatters.names() {
+            let formatter = formatters.get(name).unwrap();
+            assert!(verify_formatter(formatter).is_ok());
+            assert!(formatter.format(&results).ends_with(b"\n"));
+        }
+        assert!(formatters.get("SARIF").is_some());
+    }
+
+    #[test]
+    fn test_register_formatter() {
+        let mut formatters = FormatterRegistry::empty();
+
+        assert!(formatters.register(Named("junit", "application/xml")).is_ok());
+        assert!(formatters.get("junit").is_some());
+        assert!(formatters.register(Named("junit", "text/html")).is_err());
+        assert!(formatters.register(Named("JUnit2", "application/xml")).is_err());
+        assert!(formatters.register(Named("", "application/xml")).is_err());
+        assert!(formatters.register(Named("json", "application/json")).is_err());
+        assert!(formatters.register(Named("html", "html")).is_err());
+        assert!(formatters.register(Named("html", "text/ html")).is_err());
+        assert!(formatters.register(Named("quickfix", "text/plain")).is_ok());
+        assert_eq!(
+            formatters.names().collect::<Vec<_>>(),
+            ["junit", "quickfix"]
+        );
+    }
+}
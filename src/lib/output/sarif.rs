@@ -0,0 +1,126 @@
+//! [SARIF 2.1.0](https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html)
+//! logs, understood by code scanning services.
+
+use super::{position, severity, OutputFormatter, RunResults};
+use crate::{build_info::BUILD_INFO, check::CheckResponse};
+use serde_json::{json, Value};
+
+/// Build a SARIF 2.1.0 log with one result per match.
+///
+/// Columns are counted in Unicode code points.
+#[must_use]
+pub fn to_sarif<'a, I>(responses: I) -> Value
+where
+    I: IntoIterator<Item = (&'a str, &'a CheckResponse)>,
+{
+    let mut rules: Vec<Value> = Vec::new();
+    let mut rule_ids: Vec<&str> = Vec::new();
+    let mut results: Vec<Value> = Vec::new();
+
+    for (origin, response) in responses {
+        for m in response.iter_matches() {
+            let rule_index = match rule_ids.iter().position(|id| *id == m.rule.id) {
+                Some(index) => index,
+                None => {
+                    rule_ids.push(&m.rule.id);
+                    rules.push(json!({
+                        "id": m.rule.id,
+                        "shortDescription": {"text": m.rule.description},
+                    }));
+                    rule_ids.len() - 1
+                },
+            };
+            let (line, column) = position(m);
+            let level = match severity(m) {
+                "info" => "note",
+                level => level,
+            };
+
+            results.push(json!({
+                "ruleId": m.rule.id,
+                "ruleIndex": rule_index,
+                "level": level,
+                "message": {"text": m.message},
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": {"uri": origin},
+                        "region": {
+                            "startLine": line,
+                            "startColumn": column,
+                            "charOffset": m.offset,
+                            "charLength": m.length,
+                        },
+                    },
+                }],
+            }));
+        }
+    }
+
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": BUILD_INFO.version,
+                    "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                    "rules": rules,
+                },
+            },
+            "columnKind": "unicodeCodePoints",
+            "results": results,
+        }],
+    })
+}
+
+/// Formatter printing a pretty SARIF log, see [`to_sarif`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SarifFormatter;
+
+impl OutputFormatter for SarifFormatter {
+    fn name(&self) -> &str {
+        "sarif"
+    }
+
+    fn mime(&self) -> &str {
+        "application/sarif+json"
+    }
+
+    fn format(&self, results: &RunResults) -> Vec<u8> {
+        let mut bytes = serde_json::to_vec_pretty(&to_sarif(results.iter()))
+            .expect("a JSON value is always serializable");
+        bytes.push(b'\n');
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::tests::response;
+
+    #[test]
+    fn test_to_sarif() {
+        let response = response();
+        let sarif = to_sarif([("file.txt", &response)]);
+        let run = &sarif["runs"][0];
+
+        assert_eq!(sarif["version"], "2.1.0");
+        assert_eq!(
+            run["tool"]["driver"]["rules"][0]["id"],
+            "MORFOLOGIK_RULE_EN_US"
+        );
+
+        let result = &run["results"][0];
+        let region = &result["locations"][0]["physicalLocation"]["region"];
+
+        assert_eq!(result["level"], "error");
+        assert_eq!(
+            result["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "file.txt"
+        );
+        assert_eq!(region["startLine"], 2);
+        assert_eq!(region["startColumn"], 8);
+    }
+}
@@ -0,0 +1,320 @@
+//! Convert AsciiDoc sources into [`Data`] annotations.
+//!
+//! The parser works line by line: attribute entries, comments, block
+//! attributes, block macros and delimiters are sent as markup, and so is the
+//! content of listing, literal, passthrough and comment blocks. Section titles,
+//! list items and admonitions have their marker sent as markup and their
+//! content checked as text.
+//!
+//! Inside text, monospace spans, attribute references and cross references
+//! are interpreted as [`PLACEHOLDER`], and only the text of links is checked.
+
+use crate::check::{Data, DataBuilder};
+
+/// Token that code spans, attribute references and cross references are
+/// interpreted as.
+pub const PLACEHOLDER: &str = "X";
+
+/// Admonition labels, that can start a paragraph.
+const ADMONITIONS: &[&str] = &["CAUTION", "IMPORTANT", "NOTE", "TIP", "WARNING"];
+
+/// Prefixes of links, whose text is checked.
+const LINK_PREFIXES: &[&str] = &["https://", "http://", "link:", "mailto:", "xref:"];
+
+/// Convert an AsciiDoc `source` into data annotations.
+///
+/// # Examples
+///
+/// ```
+/// # use languagetool_rust::check::DataAnnotation;
+/// # use languagetool_rust::parsers::asciidoc;
+/// let data = asciidoc::parse("= Title\n:toc:\n\nRun `ltrs` now.\n");
+///
+/// assert_eq!(
+///     data.annotation,
+///     vec![
+///         DataAnnotation::new_markup("= ".to_string()),
+///         DataAnnotation::new_text("Title\n".to_string()),
+///         DataAnnotation::new_markup(":toc:\n".to_string()),
+///         DataAnnotation::new_text("\nRun ".to_string()),
+///         DataAnnotation::new_interpreted_markup("`ltrs`".to_string(), "X".to_string()),
+///         DataAnnotation::new_text(" now.\n".to_string()),
+///     ]
+/// );
+/// ```
+#[must_use]
+pub fn parse(source: &str) -> Data {
+    let mut builder = DataBuilder::default();
+    let mut lines = source.split_inclusive('\n');
+    let mut in_table = false;
+
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim_end();
+
+        if is_verbatim_delimiter(trimmed) {
+            builder.push_markup(line);
+            for line in lines.by_ref() {
+                builder.push_markup(line);
+                if line.trim_end() == trimmed {
+                    break;
+                }
+            }
+        } else if trimmed.starts_with("|===") {
+            in_table = !in_table;
+            builder.push_markup(line);
+        } else if is_markup_line(trimmed) {
+            builder.push_markup(line);
+        } else {
+            let prefix_len = marker_len(trimmed);
+            builder.push_markup(&line[..prefix_len]);
+            push_inline(&mut builder, &line[prefix_len..], in_table);
+        }
+    }
+
+    builder.build()
+}
+
+/// Return whether `line` opens (or closes) a block whose content is never
+/// checked: comment, listing, literal, passthrough, or fenced code block.
+fn is_verbatim_delimiter(line: &str) -> bool {
+    line.starts_with("```")
+        || (line.len() >= 4
+            && ['/', '-', '.', '+']
+                .into_iter()
+                .any(|c| line.chars().all(|d| d == c)))
+}
+
+/// Return whether `line` is entirely markup.
+fn is_markup_line(line: &str) -> bool {
+    // Compound block delimiters, thematic and page breaks
+    let is_delimiter = line == "--"
+        || line == "'''"
+        || line == "<<<"
+        || (line.len() >= 4
+            && ['=', '*', '_']
+                .into_iter()
+                .any(|c| line.chars().all(|d| d == c)));
+
+    is_delimiter
+        || line.starts_with("//")
+        || is_attribute_entry(line)
+        || (line.starts_with('[') && line.ends_with(']'))
+        || is_block_macro(line)
+}
+
+/// Return whether `line` is an attribute entry, e.g., `:toc: left`.
+fn is_attribute_entry(line: &str) -> bool {
+    let Some(rest) = line.strip_prefix(':') else {
+        return false;
+    };
+    let name = rest.split(':').next().unwrap_or_default();
+    let name = name.trim_start_matches('!').trim_end_matches('!');
+
+    rest.len() > name.len()
+        && !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Return whether `line` is a block macro, e.g., `image::logo.png[Logo]`.
+fn is_block_macro(line: &str) -> bool {
+    let Some((name, target)) = line.split_once("::") else {
+        return false;
+    };
+
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        && !target.contains(char::is_whitespace)
+        && line.ends_with(']')
+        && target.contains('[')
+}
+
+/// Return the length of the marker that starts `line`, if it is a section
+/// title, a list item, an admonition paragraph or a block title.
+fn marker_len(line: &str) -> usize {
+    let indented = line.trim_start();
+    let indent = line.len() - indented.len();
+
+    let section = indented.len() - indented.trim_start_matches('=').len();
+    if (1..=6).contains(&section) && indented[section..].starts_with(' ') {
+        return indent + section + 1;
+    }
+
+    let bullet = indented.len() - indented.trim_start_matches(['*', '.', '-']).len();
+    if bullet > 0 && indented[bullet..].starts_with(' ') {
+        return indent + bullet + 1;
+    }
+
+    let digits = indented.len()
+        - indented
+            .trim_start_matches(|c: char| c.is_ascii_digit())
+            .len();
+    if digits > 0 && indented[digits..].starts_with(". ") {
+        return indent + digits + 2;
+    }
+
+    for admonition in ADMONITIONS {
+        if indented
+            .strip_prefix(admonition)
+            .is_some_and(|rest| rest.starts_with(": "))
+        {
+            return indent + admonition.len() + 2;
+        }
+    }
+
+    if indent == 0 && line.len() > 1 && line.starts_with('.') && !line[1..].starts_with(['.', ' '])
+    {
+        return 1;
+    }
+
+    0
+}
+
+/// Push a line of text, handling inline markup.
+fn push_inline(builder: &mut DataBuilder, line: &str, in_table: bool) {
+    let mut text_start = 0;
+    let mut i = 0;
+
+    while i < line.len() {
+        let rest = &line[i..];
+        let preceded_by_space = line[..i]
+            .chars()
+            .next_back()
+            .map_or(true, |c| c.is_whitespace() || c == '(');
+
+        // Length of inline markup starting at `i`, and how it is pushed
+        let markup: Option<(usize, Inline)> = if in_table && rest.starts_with('|') {
+            Some((1, Inline::Markup))
+        } else if let Some(code) = rest.strip_prefix('`') {
+            code.find('`').map(|end| (end + 2, Inline::Placeholder))
+        } else if rest.starts_with('{') {
+            rest.find('}')
+                .filter(|&end| {
+                    end > 1
+                        && rest[1..end]
+                            .chars()
+                            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+                })
+                .map(|end| (end + 1, Inline::Placeholder))
+        } else if rest.starts_with("<<") {
+            rest.find(">>").map(|end| (end + 2, Inline::Placeholder))
+        } else if preceded_by_space && LINK_PREFIXES.iter().any(|p| rest.starts_with(p)) {
+            let target_len = rest
+                .find(|c: char| c.is_whitespace() || c == '[')
+                .unwrap_or(rest.len());
+            if rest[target_len..].starts_with('[') {
+                rest[target_len..]
+                    .find(']')
+                    .map(|end| (target_len + end + 1, Inline::Link(target_len + 1)))
+            } else {
+                Some((target_len, Inline::Placeholder))
+            }
+        } else {
+            None
+        };
+
+        let Some((len, kind)) = markup else {
+            i += rest.chars().next().map_or(1, char::len_utf8);
+            continue;
+        };
+
+        builder.push_text(&line[text_start..i]);
+        let markup = &rest[..len];
+        match kind {
+            Inline::Markup => builder.push_markup(markup),
+            Inline::Placeholder => builder.push_interpreted_markup(markup, PLACEHOLDER),
+            Inline::Link(open) if open + 1 == len => {
+                builder.push_interpreted_markup(markup, PLACEHOLDER);
+            },
+            Inline::Link(open) => {
+                builder.push_markup(&markup[..open]);
+                builder.push_text(&markup[open..len - 1]);
+                builder.push_markup(&markup[len - 1..]);
+            },
+        }
+        i += len;
+        text_start = i;
+    }
+
+    builder.push_text(&line[text_start..]);
+}
+
+/// Kind of inline markup.
+enum Inline {
+    /// Plain markup.
+    Markup,
+    /// Markup interpreted as [`PLACEHOLDER`].
+    Placeholder,
+    /// Link whose text starts at the given byte index.
+    Link(usize),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::test_utils::{checked_text, source_of};
+
+    #[test]
+    fn test_parse_roundtrip() {
+        let source = "\
+= Document Title
+Author Name
+:toc: left
+:!sectnums:
+
+// A comment
+== Introduction
+
+NOTE: \
+                      See https://example.com[the website] or <<install>>.
+
+.Example
+[source,rust]
+----
+fn main() { teh(); }
+----
+
+* First item with {product}
+** Nested item
+1. Numbered item
+
+image::logo.png[Logo]
+
+|===
+| Cell one | Cell two
+|===
+";
+        let data = parse(source);
+
+        assert_eq!(source_of(&data), source);
+        assert_eq!(
+            checked_text(&data),
+            "Document Title\nAuthor Name\n\nIntroduction\n\nSee the website or \
+             X.\n\nExample\n\nFirst item with X\nNested item\nNumbered item\n\n\n Cell one  Cell \
+             two\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_links() {
+        let data = parse("Visit https://example.com or link:docs.html[] today.");
+
+        assert_eq!(checked_text(&data), "Visit X or X today.");
+    }
+
+    #[test]
+    fn test_parse_unclosed() {
+        for source in [
+            "----\nnever closed",
+            "`code",
+            "{attr",
+            "<<ref",
+            "link:x[text",
+        ] {
+            assert_eq!(source_of(&parse(source)), source);
+        }
+    }
+}
@@ -0,0 +1,347 @@
+//! Convert BibTeX sources into [`Data`] annotations.
+//!
+//! Only the values of [`TEXT_FIELDS`] are checked, each as a separate
+//! paragraph, and everything else (entry types, citation keys, other fields,
+//! `@string`, `@preamble` and `@comment` entries) is sent as markup.
+//!
+//! Inside checked values, braces protecting words, e.g., `{DNA}`, are sent as
+//! markup so that the protected words are checked as is. LaTeX accents, e.g.,
+//! `\"o`, are interpreted as the character they stand for, other commands are
+//! sent as markup, and math is interpreted as [`PLACEHOLDER`].
+
+use crate::check::{Data, DataBuilder};
+
+/// Token that math is interpreted as.
+pub const PLACEHOLDER: &str = "X";
+
+/// Fields whose value is checked.
+pub const TEXT_FIELDS: &[&str] = &["abstract", "note", "title"];
+
+/// Accented letters, by accent command and base letter.
+const ACCENTS: &[(char, &str, &str)] = &[
+    ('\'', "aeiouyAEIOUYcnszCNSZ", "áéíóúýÁÉÍÓÚÝćńśźĆŃŚŹ"),
+    ('`', "aeiouAEIOU", "àèìòùÀÈÌÒÙ"),
+    ('^', "aeiouAEIOU", "âêîôûÂÊÎÔÛ"),
+    ('"', "aeiouyAEIOU", "äëïöüÿÄËÏÖÜ"),
+    ('~', "anoANO", "ãñõÃÑÕ"),
+];
+
+/// Commands that stand for a letter.
+const LETTERS: &[(&str, &str)] = &[
+    ("AA", "Å"),
+    ("AE", "Æ"),
+    ("L", "Ł"),
+    ("O", "Ø"),
+    ("OE", "Œ"),
+    ("aa", "å"),
+    ("ae", "æ"),
+    ("l", "ł"),
+    ("o", "ø"),
+    ("oe", "œ"),
+    ("ss", "ß"),
+];
+
+/// Convert a BibTeX `source` into data annotations.
+///
+/// # Examples
+///
+/// ```
+/// # use languagetool_rust::check::DataAnnotation;
+/// # use languagetool_rust::parsers::bibtex;
+/// let data = bibtex::parse("@article{key, title = {The {DNA} helix}, year = 1953}");
+///
+/// assert_eq!(
+///     data.annotation,
+///     vec![
+///         DataAnnotation::new_interpreted_markup(
+///             "@article{key, title = {".to_string(),
+///             "\n\n".to_string()
+///         ),
+///         DataAnnotation::new_text("The ".to_string()),
+///         DataAnnotation::new_markup("{".to_string()),
+///         DataAnnotation::new_text("DNA".to_string()),
+///         DataAnnotation::new_markup("}".to_string()),
+///         DataAnnotation::new_text(" helix".to_string()),
+///         DataAnnotation::new_markup("}, year = 1953}".to_string()),
+///     ]
+/// );
+/// ```
+#[must_use]
+pub fn parse(source: &str) -> Data {
+    let mut builder = DataBuilder::default();
+    // Start of the markup that was not pushed yet
+    let mut last = 0;
+    let mut pos = 0;
+
+    while let Some(at) = source[pos..].find('@') {
+        let start = pos + at + 1;
+        let kind_len = source[start..]
+            .find(|c: char| !c.is_ascii_alphanumeric())
+            .unwrap_or(source.len() - start);
+        let kind = source[start..start + kind_len].to_ascii_lowercase();
+        let mut i = skip_whitespace(source, start + kind_len);
+
+        let Some(close) = (match source[i..].chars().next() {
+            Some('{') => Some('}'),
+            Some('(') => Some(')'),
+            _ => None,
+        }) else {
+            pos = i;
+            continue;
+        };
+        if matches!(kind.as_str(), "comment" | "preamble" | "string") {
+            pos = group_end(source, i);
+            continue;
+        }
+
+        // Citation key
+        i += 1;
+        i += source[i..].find([',', close]).unwrap_or(source.len() - i);
+
+        // Fields
+        loop {
+            i = skip_whitespace(source, i);
+            match source[i..].chars().next() {
+                Some(',') => {
+                    i += 1;
+                    continue;
+                },
+                Some(c) if c == close => {
+                    i += 1;
+                    break;
+                },
+                None => break,
+                _ => {},
+            }
+
+            let name_len = source[i..]
+                .find(|c: char| !(c.is_alphanumeric() || "-_:.".contains(c)))
+                .unwrap_or(source.len() - i);
+            let name = source[i..i + name_len].to_ascii_lowercase();
+            i = skip_whitespace(source, i + name_len);
+            if name_len == 0 || !source[i..].starts_with('=') {
+                // Malformed entry, look for the next one
+                break;
+            }
+            i = skip_whitespace(source, i + 1);
+
+            let checked = TEXT_FIELDS.contains(&name.as_str());
+            let mut first = true;
+            loop {
+                let end = match source[i..].chars().next() {
+                    Some('{') => group_end(source, i),
+                    Some('"') => quoted_end(source, i),
+                    _ => {
+                        i + source[i..]
+                            .find(|c: char| c == ',' || c == '#' || c == close || c.is_whitespace())
+                            .unwrap_or(source.len() - i)
+                    },
+                };
+                let delimited = source[i..].starts_with(['{', '"']);
+
+                if checked && delimited && end > i + 1 {
+                    let closed = source[..end].ends_with(['}', '"']);
+                    let content_end = if closed { end - 1 } else { end };
+                    // Concatenated values, joined with `#`, are checked as one
+                    if first {
+                        builder.push_paragraph_markup(&source[last..i + 1]);
+                    } else {
+                        builder.push_markup(&source[last..i + 1]);
+                    }
+                    push_field_text(&mut builder, &source[i + 1..content_end]);
+                    last = content_end;
+                    first = false;
+                }
+
+                i = skip_whitespace(source, end);
+                if source[i..].starts_with('#') {
+                    i = skip_whitespace(source, i + 1);
+                } else {
+                    break;
+                }
+            }
+        }
+        pos = i;
+    }
+
+    builder.push_markup(&source[last..]);
+    builder.build()
+}
+
+/// Return the byte index of the first non-whitespace character at or after
+/// `i`.
+fn skip_whitespace(source: &str, i: usize) -> usize {
+    source.len() - source[i..].trim_start().len()
+}
+
+/// Return the byte index after the group opened at `i`, i.e., after its
+/// matching closing brace or parenthesis, or the end of `source`.
+fn group_end(source: &str, i: usize) -> usize {
+    let (open, close) = match source[i..].chars().next() {
+        Some('(') => ('(', ')'),
+        _ => ('{', '}'),
+    };
+    let mut depth = 0;
+
+    for (j, c) in source[i..].char_indices() {
+        if c == open {
+            depth += 1;
+        } else if c == close {
+            depth -= 1;
+            if depth == 0 {
+                return i + j + 1;
+            }
+        }
+    }
+    source.len()
+}
+
+/// Return the byte index after the quoted value starting at `i`, where
+/// quotes inside braces, or escaped as in `\"o`, do not end the value.
+fn quoted_end(source: &str, i: usize) -> usize {
+    let mut depth = 0;
+    let mut escaped = false;
+
+    for (j, c) in source[i + 1..].char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            '"' if depth <= 0 && !escaped => return i + 1 + j + 1,
+            _ => {},
+        }
+        escaped = c == '\\' && !escaped;
+    }
+    source.len()
+}
+
+/// Push the content of a checked field.
+fn push_field_text(builder: &mut DataBuilder, text: &str) {
+    let mut text_start = 0;
+    let mut i = 0;
+
+    while i < text.len() {
+        let rest = &text[i..];
+        let (len, interpret_as): (usize, Option<String>) = match rest.chars().next() {
+            Some('{' | '}') => (1, None),
+            Some('~') => (1, Some(" ".to_string())),
+            Some('$') => {
+                match rest[1..].find('$') {
+                    Some(end) => (end + 2, Some(PLACEHOLDER.to_string())),
+                    None => (rest.len(), Some(PLACEHOLDER.to_string())),
+                }
+            },
+            Some('\\') => command(rest),
+            Some(c) => {
+                i += c.len_utf8();
+                continue;
+            },
+            None => break,
+        };
+
+        builder.push_text(&text[text_start..i]);
+        match interpret_as {
+            Some(interpret_as) => builder.push_interpreted_markup(&rest[..len], &interpret_as),
+            None => builder.push_markup(&rest[..len]),
+        }
+        i += len;
+        text_start = i;
+    }
+
+    builder.push_text(&text[text_start..]);
+}
+
+/// Return the length of the command starting `rest` and what it is
+/// interpreted as, if anything.
+fn command(rest: &str) -> (usize, Option<String>) {
+    let after = &rest[1..];
+    match after.chars().next() {
+        Some(c) if c.is_ascii_alphabetic() => {
+            let name_len = after
+                .find(|c: char| !c.is_ascii_alphabetic())
+                .unwrap_or(after.len());
+            let name = &after[..name_len];
+            let letter = LETTERS
+                .iter()
+                .find(|(command, _)| *command == name)
+                .map(|(_, letter)| letter.to_string());
+            (1 + name_len, letter)
+        },
+        Some(accent) if ACCENTS.iter().any(|(a, ..)| *a == accent) => {
+            // The letter follows the accent, possibly inside braces
+            let letter = &after[1..];
+            let (letter_len, base) = match letter.strip_prefix('{') {
+                Some(braced) => {
+                    match braced.chars().next() {
+                        Some(base) if braced[base.len_utf8()..].starts_with('}') => {
+                            (base.len_utf8() + 2, Some(base))
+                        },
+                        _ => (0, None),
+                    }
+                },
+                None => {
+                    letter
+                        .chars()
+                        .next()
+                        .map_or((0, None), |base| (base.len_utf8(), Some(base)))
+                },
+            };
+            let Some(base) = base else {
+                return (2, None);
+            };
+            let accented = ACCENTS
+                .iter()
+                .find(|(a, ..)| *a == accent)
+                .and_then(|(_, bases, accented)| {
+                    let index = bases.chars().position(|c| c == base)?;
+                    accented.chars().nth(index)
+                })
+                .unwrap_or(base);
+            (2 + letter_len, Some(accented.to_string()))
+        },
+        // Escaped characters, e.g., `\&` or `\%`
+        Some(c) => (1 + c.len_utf8(), Some(c.to_string())),
+        None => (1, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::test_utils::{checked_text, source_of};
+
+    #[test]
+    fn test_parse() {
+        let source = concat!(
+            "% A comment, with a title = {Not checked}\n",
+            "@string{jan = \"January\"}\n",
+            "@Article{schrodinger1935,\n",
+            "  author = {Schr\\\"{o}dinger, E.},\n",
+            "  title = \"Die gegenw\\\"artige {Situation} in der Quantenmechanik\",\n",
+            "  month = jan,\n",
+            "  note = {Part~1 of 3 \\& $n=3$} # \" parts\",\n",
+            "}\n",
+            "@book(key, abstract = {An {\\ss}ay \\emph{about} it.})\n",
+        );
+        let data = parse(source);
+
+        assert_eq!(source_of(&data), source);
+        assert_eq!(
+            checked_text(&data),
+            "\n\nDie gegenwärtige Situation in der Quantenmechanik\n\nPart 1 of 3 & X parts\n\nAn \
+             ßay about it."
+        );
+    }
+
+    #[test]
+    fn test_parse_unclosed() {
+        for source in [
+            "@article{key, title = {never",
+            "@article{key, title = \"never",
+            "@article{key, title",
+            "@misc{key, note = {\\\"",
+            "@",
+        ] {
+            assert_eq!(source_of(&parse(source)), source);
+        }
+    }
+}
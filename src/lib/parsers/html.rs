@@ -0,0 +1,323 @@
+//! Convert HTML sources into [`Data`] annotations.
+//!
+//! Tags, including their attributes, comments and declarations are sent as
+//! markup. Tags of [`BLOCK_ELEMENTS`] are interpreted as paragraph breaks,
+//! and `<br>` as a line break, so that text of different blocks is never
+//! joined. The content of [`RAW_ELEMENTS`], such as scripts, is sent as markup,
+//! and the content of [`CODE_ELEMENTS`] is interpreted as [`PLACEHOLDER`].
+//!
+//! Character references, such as `&amp;` or `&#233;`, are interpreted as the
+//! character they stand for.
+
+use crate::check::{Data, DataBuilder};
+
+/// Token that code elements are interpreted as.
+pub const PLACEHOLDER: &str = "X";
+
+/// Elements that separate paragraphs.
+pub const BLOCK_ELEMENTS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "body",
+    "caption",
+    "dd",
+    "details",
+    "div",
+    "dl",
+    "dt",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "head",
+    "header",
+    "hr",
+    "html",
+    "li",
+    "main",
+    "nav",
+    "ol",
+    "p",
+    "section",
+    "summary",
+    "table",
+    "td",
+    "th",
+    "title",
+    "tr",
+    "ul",
+];
+
+/// Elements whose content is never checked.
+pub const RAW_ELEMENTS: &[&str] = &["noscript", "pre", "script", "style", "svg", "template"];
+
+/// Elements whose content is interpreted as [`PLACEHOLDER`].
+pub const CODE_ELEMENTS: &[&str] = &["code", "kbd", "samp"];
+
+/// Named character references that are interpreted, with the character they
+/// stand for.
+const ENTITIES: &[(&str, &str)] = &[
+    ("amp", "&"),
+    ("apos", "'"),
+    ("copy", "©"),
+    ("gt", ">"),
+    ("hellip", "…"),
+    ("laquo", "«"),
+    ("ldquo", "“"),
+    ("lsquo", "‘"),
+    ("lt", "<"),
+    ("mdash", "—"),
+    ("nbsp", "\u{a0}"),
+    ("ndash", "–"),
+    ("quot", "\""),
+    ("raquo", "»"),
+    ("rdquo", "”"),
+    ("reg", "®"),
+    ("rsquo", "’"),
+    ("shy", ""),
+];
+
+/// Convert an HTML `source` into data annotations.
+///
+/// # Examples
+///
+/// ```
+/// # use languagetool_rust::check::DataAnnotation;
+/// # use languagetool_rust::parsers::html;
+/// let data = html::parse("<p class=\"intro\">Fish &amp; <em>chips</em></p>");
+///
+/// assert_eq!(
+///     data.annotation,
+///     vec![
+///         DataAnnotation::new_interpreted_markup(
+///             "<p class=\"intro\">".to_string(),
+///             "\n\n".to_string()
+///         ),
+///         DataAnnotation::new_text("Fish ".to_string()),
+///         DataAnnotation::new_interpreted_markup("&amp;".to_string(), "&".to_string()),
+///         DataAnnotation::new_text(" ".to_string()),
+///         DataAnnotation::new_markup("<em>".to_string()),
+///         DataAnnotation::new_text("chips".to_string()),
+///         DataAnnotation::new_markup("</em>".to_string()),
+///         DataAnnotation::new_interpreted_markup("</p>".to_string(), "\n\n".to_string()),
+///     ]
+/// );
+/// ```
+#[must_use]
+pub fn parse(source: &str) -> Data {
+    let mut builder = DataBuilder::default();
+    let mut rest = source;
+
+    while let Some(i) = rest.find(['<', '&']) {
+        builder.push_text(&rest[..i]);
+        rest = &rest[i..];
+
+        if rest.starts_with('&') {
+            let (len, interpret_as) = character_reference(rest).unwrap_or((1, None));
+            match interpret_as {
+                Some(interpret_as) => builder.push_interpreted_markup(&rest[..len], &interpret_as),
+                None => builder.push_text(&rest[..len]),
+            }
+            rest = &rest[len..];
+            continue;
+        }
+
+        let len = if let Some(comment) = rest.strip_prefix("<!--") {
+            comment.find("-->").map_or(rest.len(), |end| end + 7)
+        } else if rest.starts_with("<!") || rest.starts_with("<?") {
+            rest.find('>').map_or(rest.len(), |end| end + 1)
+        } else if let Some((len, name, closing)) = tag(rest) {
+            let tag = &rest[..len];
+            if !closing && is_one_of(name, RAW_ELEMENTS) {
+                let end = element_end(rest, len, name);
+                builder.push_markup(&rest[..end]);
+                rest = &rest[end..];
+                continue;
+            } else if !closing && is_one_of(name, CODE_ELEMENTS) {
+                let end = element_end(rest, len, name);
+                builder.push_interpreted_markup(&rest[..end], PLACEHOLDER);
+                rest = &rest[end..];
+                continue;
+            } else if name.eq_ignore_ascii_case("br") {
+                builder.push_interpreted_markup(tag, "\n");
+            } else if is_one_of(name, BLOCK_ELEMENTS) {
+                builder.push_paragraph_markup(tag);
+            } else {
+                builder.push_markup(tag);
+            }
+            rest = &rest[len..];
+            continue;
+        } else {
+            // A lone `<`, e.g., in `a < b`
+            builder.push_text("<");
+            rest = &rest[1..];
+            continue;
+        };
+
+        builder.push_markup(&rest[..len]);
+        rest = &rest[len..];
+    }
+
+    builder.push_text(rest);
+    builder.build()
+}
+
+/// Return whether the element `name` is one of `elements`, ignoring case.
+fn is_one_of(name: &str, elements: &[&str]) -> bool {
+    elements
+        .iter()
+        .any(|element| element.eq_ignore_ascii_case(name))
+}
+
+/// Return the length, name and whether it is a closing tag, of the tag that
+/// starts `rest`, e.g., `<a href="https://example.com">`.
+fn tag(rest: &str) -> Option<(usize, &str, bool)> {
+    let after = rest.strip_prefix('<')?;
+    let closing = after.starts_with('/');
+    let after = after.strip_prefix('/').unwrap_or(after);
+    if !after.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let name_len = after
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == ':'))
+        .unwrap_or(after.len());
+
+    // Attribute values may contain `>`
+    let mut quote = None;
+    for (i, c) in after[name_len..].char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            (None, '>') => {
+                let len = rest.len() - after.len() + name_len + i + 1;
+                return Some((len, &after[..name_len], closing));
+            },
+            _ => {},
+        }
+    }
+    None
+}
+
+/// Return the index, in `rest`, of the end of the closing tag of element
+/// `name`, whose opening tag is `rest[..start]`, or the length of `rest` if
+/// it is not closed.
+fn element_end(rest: &str, start: usize, name: &str) -> usize {
+    // Self-closing elements, e.g., `<svg/>`, have no content
+    if rest[..start].ends_with("/>") {
+        return start;
+    }
+    let mut i = start;
+
+    while let Some(j) = rest[i..].find("</") {
+        let end = i + j;
+        if let Some((len, closing_name, true)) = tag(&rest[end..]) {
+            if closing_name.eq_ignore_ascii_case(name) {
+                return end + len;
+            }
+        }
+        i = end + 2;
+    }
+    rest.len()
+}
+
+/// Return the length of the character reference that starts `rest`, e.g.,
+/// `&amp;` or `&#x2014;`, and the character it stands for.
+///
+/// Unknown named references are returned without interpretation.
+fn character_reference(rest: &str) -> Option<(usize, Option<String>)> {
+    let end = rest.get(1..)?.find(';')? + 1;
+    let name = &rest[1..end];
+    if name.is_empty() || name.len() > 32 {
+        return None;
+    }
+
+    let interpret_as = if let Some(number) = name.strip_prefix('#') {
+        let code = match number.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok(),
+            None => number.parse().ok(),
+        };
+        Some(char::from_u32(code?)?.to_string())
+    } else if name.chars().all(|c| c.is_ascii_alphanumeric()) {
+        ENTITIES
+            .iter()
+            .find(|(entity, _)| *entity == name)
+            .map(|(_, c)| c.to_string())
+    } else {
+        return None;
+    };
+    Some((end + 1, interpret_as))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::test_utils::{checked_text, source_of};
+
+    #[test]
+    fn test_parse_roundtrip() {
+        let source = concat!(
+            "<!DOCTYPE html>\n",
+            "<html lang=\"en\">\n",
+            "<head><title>A title</title><style>p { color: red; }</style></head>\n",
+            "<!-- A comment with a error -->\n",
+            "<body>\n",
+            "<h1>Heading</h1>Text after\n",
+            "<p title='a > b'>Run <code>teh</code> with a&nbsp;space&#x2014;or&#233; 1 < \
+             2.<br/>Next line</p>\n",
+            "<script>\nif (a < b) { teh(); }\n</script>\n",
+            "<pre>fn main() {}</pre>\n",
+            "</body>\n",
+            "</html>\n",
+        );
+        let data = parse(source);
+
+        assert_eq!(source_of(&data), source);
+        assert_eq!(
+            checked_text(&data),
+            concat!(
+                "\n",
+                "\n\n\n",
+                "\n\n\n\nA title\n\n\n\n\n",
+                "\n",
+                "\n\n\n",
+                "\n\nHeading\n\nText after\n",
+                "\n\nRun X with a\u{a0}space—oré 1 < 2.\nNext line\n\n\n",
+                "\n",
+                "\n",
+                "\n\n\n",
+                "\n\n\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_unknown_references() {
+        let data = parse("Fish &chips; &amp chips & more");
+
+        assert_eq!(checked_text(&data), "Fish &chips; &amp chips & more");
+    }
+
+    #[test]
+    fn test_parse_unclosed() {
+        for source in [
+            "<!-- never closed",
+            "<p class=\"never closed>",
+            "<script>never closed",
+            "<code>x",
+            "&#xZZ;",
+            "<",
+            "&",
+        ] {
+            assert_eq!(source_of(&parse(source)), source);
+        }
+    }
+}
@@ -0,0 +1,440 @@
+//! Convert LaTeX sources into [`Data`] annotations.
+//!
+//! Commands, environments and comments are sent as markup, so that only the
+//! prose is checked. The arguments of text macros, such as `\section` or
+//! `\emph`, are parsed as text, and math is interpreted as
+//! [`MATH_PLACEHOLDER`] so that sentences containing formulas stay
+//! grammatical.
+
+use crate::check::{find_unescaped, Data, DataBuilder};
+
+/// Token that math, citations and references are interpreted as.
+pub const MATH_PLACEHOLDER: &str = "X";
+
+/// Commands whose (last) braced argument is checked as text.
+const TEXT_COMMANDS: &[&str] = &[
+    "author",
+    "caption",
+    "chapter",
+    "emph",
+    "footnote",
+    "mbox",
+    "paragraph",
+    "part",
+    "section",
+    "subparagraph",
+    "subsection",
+    "subsubsection",
+    "text",
+    "textbf",
+    "textit",
+    "textrm",
+    "textsc",
+    "textsf",
+    "textsl",
+    "texttt",
+    "textup",
+    "title",
+    "underline",
+];
+
+/// Commands that stand for a word in the sentence, and are interpreted as
+/// [`MATH_PLACEHOLDER`].
+const PLACEHOLDER_COMMANDS: &[&str] = &[
+    "autoref", "cite", "citep", "citet", "Cref", "cref", "eqref", "pageref", "ref", "url",
+];
+
+/// Environments whose content is interpreted as [`MATH_PLACEHOLDER`].
+const MATH_ENVIRONMENTS: &[&str] = &[
+    "align",
+    "align*",
+    "alignat",
+    "alignat*",
+    "displaymath",
+    "eqnarray",
+    "eqnarray*",
+    "equation",
+    "equation*",
+    "gather",
+    "gather*",
+    "math",
+    "multline",
+    "multline*",
+];
+
+/// Environments whose content is never checked.
+const VERBATIM_ENVIRONMENTS: &[&str] = &[
+    "comment",
+    "lstlisting",
+    "minted",
+    "tikzpicture",
+    "verbatim",
+    "verbatim*",
+];
+
+/// Environments that take mandatory arguments after `\begin{...}`.
+const ARGUMENT_ENVIRONMENTS: &[&str] = &[
+    "array",
+    "minipage",
+    "multicols",
+    "tabular",
+    "tabular*",
+    "tabularx",
+    "wrapfigure",
+];
+
+/// Convert a LaTeX `source` into data annotations.
+///
+/// # Examples
+///
+/// ```
+/// # use languagetool_rust::check::DataAnnotation;
+/// # use languagetool_rust::parsers::latex;
+/// let data = latex::parse(r"\section{Intro} Let $x$ be \emph{big}.");
+///
+/// assert_eq!(
+///     data.annotation,
+///     vec![
+///         DataAnnotation::new_markup(r"\section{".to_string()),
+///         DataAnnotation::new_text("Intro".to_string()),
+///         DataAnnotation::new_markup("}".to_string()),
+///         DataAnnotation::new_text(" Let ".to_string()),
+///         DataAnnotation::new_interpreted_markup("$x$".to_string(), "X".to_string()),
+///         DataAnnotation::new_text(" be ".to_string()),
+///         DataAnnotation::new_markup(r"\emph{".to_string()),
+///         DataAnnotation::new_text("big".to_string()),
+///         DataAnnotation::new_markup("}".to_string()),
+///         DataAnnotation::new_text(".".to_string()),
+///     ]
+/// );
+/// ```
+#[must_use]
+pub fn parse(source: &str) -> Data {
+    let mut parser = Parser {
+        source,
+        pos: 0,
+        builder: DataBuilder::default(),
+    };
+    parser.parse_text(false);
+    parser.builder.build()
+}
+
+/// Recursive descent parser over a LaTeX source.
+struct Parser<'source> {
+    source: &'source str,
+    /// Byte index of the next character to parse.
+    pos: usize,
+    builder: DataBuilder,
+}
+
+impl<'source> Parser<'source> {
+    /// Return the remaining, unparsed, source.
+    fn rest(&self) -> &'source str {
+        &self.source[self.pos..]
+    }
+
+    /// Advance by `len` bytes and return the consumed slice.
+    fn take(&mut self, len: usize) -> &'source str {
+        let start = self.pos;
+        self.pos = (self.pos + len).min(self.source.len());
+        &self.source[start..self.pos]
+    }
+
+    /// Parse text until the end of the source or, if `in_group`, until the
+    /// closing brace of the current group, which is left unconsumed.
+    fn parse_text(&mut self, in_group: bool) {
+        let mut depth = 0usize;
+
+        while let Some(c) = self.rest().chars().next() {
+            match c {
+                '%' => {
+                    let len = self.rest().find('\n').map_or(self.rest().len(), |i| i + 1);
+                    let comment = self.take(len);
+                    self.builder.push_markup(comment);
+                },
+                '\\' => self.parse_command(),
+                '$' => self.parse_dollar_math(),
+                '~' => {
+                    let tilde = self.take(1);
+                    self.builder.push_space_markup(tilde);
+                },
+                '{' => {
+                    depth += 1;
+                    let brace = self.take(1);
+                    self.builder.push_markup(brace);
+                },
+                '}' => {
+                    if depth == 0 && in_group {
+                        return;
+                    }
+                    depth = depth.saturating_sub(1);
+                    let brace = self.take(1);
+                    self.builder.push_markup(brace);
+                },
+                _ => {
+                    let len = self
+                        .rest()
+                        .find(['%', '\\', '$', '~', '{', '}'])
+                        .unwrap_or(self.rest().len());
+                    let text = self.take(len);
+                    self.builder.push_text(text);
+                },
+            }
+        }
+    }
+
+    /// Parse a command, starting at a backslash.
+    fn parse_command(&mut self) {
+        let rest = self.rest();
+        let name_len = rest[1..]
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len() - 1);
+
+        if name_len == 0 {
+            self.parse_control_symbol();
+            return;
+        }
+
+        let mut name = &rest[1..=name_len];
+        let start = self.pos;
+        self.pos += 1 + name_len;
+        if self.rest().starts_with('*') {
+            self.pos += 1;
+            name = &self.source[start + 1..self.pos];
+        }
+
+        match name {
+            "begin" => self.parse_environment(start),
+            _ if TEXT_COMMANDS.contains(&name.trim_end_matches('*')) => {
+                self.skip_groups('[', ']');
+                if self.rest().starts_with('{') {
+                    self.pos += 1;
+                    self.builder.push_markup(&self.source[start..self.pos]);
+                    self.parse_text(true);
+                    let brace = self.take(1);
+                    self.builder.push_markup(brace);
+                } else {
+                    self.builder.push_markup(&self.source[start..self.pos]);
+                }
+            },
+            _ if PLACEHOLDER_COMMANDS.contains(&name) => {
+                self.skip_arguments();
+                self.builder
+                    .push_interpreted_markup(&self.source[start..self.pos], MATH_PLACEHOLDER);
+            },
+            _ => {
+                self.skip_arguments();
+                self.builder.push_markup(&self.source[start..self.pos]);
+            },
+        }
+    }
+
+    /// Parse a backslash followed by a single non-letter character.
+    fn parse_control_symbol(&mut self) {
+        let Some(symbol) = self.rest()[1..].chars().next() else {
+            let backslash = self.take(1);
+            self.builder.push_markup(backslash);
+            return;
+        };
+
+        match symbol {
+            '(' => self.parse_math(r"\)"),
+            '[' => self.parse_math(r"\]"),
+            '%' | '$' | '&' | '_' | '#' | '{' | '}' => {
+                let markup = self.take(2);
+                self.builder.push_interpreted_markup(markup, &markup[1..]);
+            },
+            ' ' | ',' | '\\' => {
+                let markup = self.take(2);
+                self.builder.push_space_markup(markup);
+            },
+            _ => {
+                let markup = self.take(1 + symbol.len_utf8());
+                self.builder.push_markup(markup);
+            },
+        }
+    }
+
+    /// Parse an environment, starting after `\begin` at byte index `start`.
+    fn parse_environment(&mut self, start: usize) {
+        let name_start = self.pos + 1;
+        if !self.skip_group('{', '}') {
+            self.builder.push_markup(&self.source[start..self.pos]);
+            return;
+        }
+        // The group may be unclosed, and extend to the end of the source
+        let name = &self.source[name_start..self.pos];
+        let name = name.strip_suffix('}').unwrap_or(name).trim();
+
+        if MATH_ENVIRONMENTS.contains(&name) || VERBATIM_ENVIRONMENTS.contains(&name) {
+            let end = format!("\\end{{{name}}}");
+            let len = self
+                .rest()
+                .find(end.as_str())
+                .map_or(self.rest().len(), |i| i + end.len());
+            self.pos += len;
+
+            let markup = &self.source[start..self.pos];
+            if MATH_ENVIRONMENTS.contains(&name) {
+                self.builder
+                    .push_interpreted_markup(markup, MATH_PLACEHOLDER);
+            } else {
+                self.builder.push_markup(markup);
+            }
+            return;
+        }
+
+        self.skip_groups('[', ']');
+        if ARGUMENT_ENVIRONMENTS.contains(&name) {
+            self.skip_arguments();
+        }
+        self.builder.push_markup(&self.source[start..self.pos]);
+    }
+
+    /// Parse math delimited by a single or double dollar sign, falling back to
+    /// text if the math is never closed.
+    fn parse_dollar_math(&mut self) {
+        let delim = if self.rest().starts_with("$$") {
+            "$$"
+        } else {
+            "$"
+        };
+        self.parse_math_between(delim, delim);
+    }
+
+    /// Parse math opened by a two-character delimiter and closed by `close`.
+    fn parse_math(&mut self, close: &str) {
+        let open = &self.rest()[..2];
+        self.parse_math_between(open, close);
+    }
+
+    fn parse_math_between(&mut self, open: &str, close: &str) {
+        let inner = &self.rest()[open.len()..];
+        match find_unescaped(inner, close) {
+            Some(i) => {
+                let math = self.take(open.len() + i + close.len());
+                self.builder.push_interpreted_markup(math, MATH_PLACEHOLDER);
+            },
+            None => {
+                let text = self.take(open.len());
+                self.builder.push_text(text);
+            },
+        }
+    }
+
+    /// Skip any sequence of optional and mandatory arguments.
+    fn skip_arguments(&mut self) {
+        while self.skip_group('[', ']') || self.skip_group('{', '}') {}
+    }
+
+    /// Skip any sequence of groups delimited by `open` and `close`.
+    fn skip_groups(&mut self, open: char, close: char) {
+        while self.skip_group(open, close) {}
+    }
+
+    /// Skip a group delimited by `open` and `close`, if the source continues
+    /// with `open`. Nested groups and escaped delimiters are skipped too, and
+    /// an unclosed group extends to the end of the source.
+    fn skip_group(&mut self, open: char, close: char) -> bool {
+        if !self.rest().starts_with(open) {
+            return false;
+        }
+
+        let mut depth = 0usize;
+        let mut escaped = false;
+        for (i, c) in self.rest().char_indices() {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                c if c == open => depth += 1,
+                c if c == close => {
+                    depth -= 1;
+                    if depth == 0 {
+                        self.pos += i + c.len_utf8();
+                        return true;
+                    }
+                },
+                _ => {},
+            }
+        }
+        self.pos = self.source.len();
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        check::DataAnnotation,
+        parsers::test_utils::{checked_text, source_of},
+    };
+
+    #[test]
+    fn test_parse_roundtrip() {
+        let source = r"\documentclass[a4paper]{article}
+\usepackage{amsmath} % math
+\begin{document}
+\title{A \textbf{bold} title}
+\section*{Introduction}\label{sec:intro}
+As shown in~\cite{knuth}, we have
+\begin{equation}
+    e^{i\pi} + 1 = 0 % Euler
+\end{equation}
+which costs 5\,\$ or 10\%.\footnote{Not \emph{really}.}
+\begin{tabular}{ll} a & b \end{tabular}
+\end{document}
+";
+        let data = parse(source);
+
+        assert_eq!(source_of(&data), source);
+        assert_eq!(
+            checked_text(&data),
+            "\n \nA bold title\nIntroduction\nAs shown in X, we have\nX\nwhich costs 5 $ or \
+             10%.Not really.\n a & b \n\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_math() {
+        let data = parse(r"If $$a$$ and \(b\\\) or \[c\], but not $d.");
+
+        assert_eq!(
+            data.annotation,
+            vec![
+                DataAnnotation::new_text("If ".to_string()),
+                DataAnnotation::new_interpreted_markup("$$a$$".to_string(), "X".to_string()),
+                DataAnnotation::new_text(" and ".to_string()),
+                DataAnnotation::new_interpreted_markup(r"\(b\\\)".to_string(), "X".to_string()),
+                DataAnnotation::new_text(" or ".to_string()),
+                DataAnnotation::new_interpreted_markup(r"\[c\]".to_string(), "X".to_string()),
+                DataAnnotation::new_text(", but not $d.".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_verbatim() {
+        let source = "See:\n\\begin{verbatim}\nteh typo\n\\end{verbatim}\nDone.";
+        let data = parse(source);
+
+        assert_eq!(source_of(&data), source);
+        assert_eq!(checked_text(&data), "See:\n\nDone.");
+    }
+
+    #[test]
+    fn test_parse_unclosed() {
+        for source in [
+            r"\section{Unclosed",
+            r"\begin{equation} x",
+            r"\",
+            "{",
+            "}",
+            r"\foo[",
+            r"\begin{",
+            r"\begin{équation",
+        ] {
+            assert_eq!(source_of(&parse(source)), source);
+        }
+    }
+}
@@ -0,0 +1,638 @@
+//! Convert Markdown sources into [`Data`] annotations.
+//!
+//! The parser works line by line, following CommonMark and GitHub Flavored
+//! Markdown: front matter, code blocks, HTML blocks, thematic breaks and link
+//! reference definitions are sent as markup. Headings, block quotes, list
+//! items and table rows have their markers sent as markup and their content
+//! checked as text, unless they are skipped, see [`MarkdownOptions`].
+//!
+//! Inside text, emphasis markers, HTML tags and escaping backslashes are sent
+//! as markup, URLs are interpreted as [`PLACEHOLDER`], and so are code spans
+//! and links when they are skipped. Otherwise, only the text of links is
+//! checked.
+
+use super::{MarkdownElement, MarkdownOptions};
+use crate::check::{Data, DataBuilder};
+
+/// Token that skipped code spans and links, and URLs are interpreted as.
+pub const PLACEHOLDER: &str = "X";
+
+/// Convert a Markdown `source` into data annotations.
+///
+/// # Examples
+///
+/// ```
+/// # use languagetool_rust::check::DataAnnotation;
+/// # use languagetool_rust::parsers::{markdown, MarkdownOptions};
+/// let data = markdown::parse(
+///     "# Title\n\nRun `ltrs` *now*.\n",
+///     &MarkdownOptions::default(),
+/// );
+///
+/// assert_eq!(
+///     data.annotation,
+///     vec![
+///         DataAnnotation::new_markup("# ".to_string()),
+///         DataAnnotation::new_text("Title\n\nRun ".to_string()),
+///         DataAnnotation::new_interpreted_markup("`ltrs`".to_string(), "X".to_string()),
+///         DataAnnotation::new_text(" ".to_string()),
+///         DataAnnotation::new_markup("*".to_string()),
+///         DataAnnotation::new_text("now".to_string()),
+///         DataAnnotation::new_markup("*".to_string()),
+///         DataAnnotation::new_text(".\n".to_string()),
+///     ]
+/// );
+/// ```
+#[must_use]
+pub fn parse(source: &str, options: &MarkdownOptions) -> Data {
+    let mut builder = DataBuilder::default();
+    let mut lines = source.split_inclusive('\n').peekable();
+    let mut previous_blank = true;
+    let mut in_list = false;
+    let mut in_quote = false;
+    let mut in_table = false;
+    let mut in_html = false;
+
+    // YAML (`---`) or TOML (`+++`) front matter
+    if let Some(first) = lines.peek().copied() {
+        let delimiter = first.trim_end();
+        if delimiter == "---" || delimiter == "+++" {
+            builder.push_markup(first);
+            lines.next();
+            for line in lines.by_ref() {
+                builder.push_markup(line);
+                let trimmed = line.trim_end();
+                if trimmed == delimiter || (delimiter == "---" && trimmed == "...") {
+                    break;
+                }
+            }
+        }
+    }
+
+    while let Some(line) = lines.next() {
+        let content = line.trim();
+        let indent = line.len() - line.trim_start().len();
+
+        if content.is_empty() {
+            builder.push_text(line);
+            previous_blank = true;
+            in_quote = false;
+            in_table = false;
+            in_html = false;
+            continue;
+        }
+        let was_blank = std::mem::replace(&mut previous_blank, false);
+        if indent == 0 && was_blank {
+            in_list = false;
+        }
+
+        let indented = line.starts_with('\t') || indent >= 4;
+        if in_html || (indented && was_blank && !in_list) {
+            builder.push_markup(line);
+            continue;
+        }
+
+        if !indented {
+            if let Some(fence) = fence(content) {
+                builder.push_markup(line);
+                for line in lines.by_ref() {
+                    builder.push_markup(line);
+                    if is_closing_fence(line.trim(), fence) {
+                        break;
+                    }
+                }
+                continue;
+            }
+            if let Some(comment) = content.strip_prefix("<!--") {
+                builder.push_markup(line);
+                if !comment.contains("-->") {
+                    for line in lines.by_ref() {
+                        builder.push_markup(line);
+                        if line.contains("-->") {
+                            break;
+                        }
+                    }
+                }
+                continue;
+            }
+            if is_html_block(content) {
+                in_html = true;
+                builder.push_markup(line);
+                continue;
+            }
+            if is_thematic_break(content) {
+                builder.push_markup(line);
+                continue;
+            }
+            if let Some(label_end) = link_reference_definition(content) {
+                if content[1..].starts_with('^') {
+                    // Footnote definitions have checked text
+                    let definition = &line[indent + label_end + 2..];
+                    let marker_len = line.len() - definition.trim_start().len();
+                    builder.push_markup(&line[..marker_len]);
+                    push_line(&mut builder, &line[marker_len..], options);
+                } else {
+                    builder.push_markup(line);
+                }
+                continue;
+            }
+        }
+
+        if !in_table
+            && content.contains('|')
+            && lines.peek().is_some_and(|next| is_delimiter_row(next))
+        {
+            in_table = true;
+        }
+        if in_table && content.contains('|') {
+            if options.skips(MarkdownElement::Tables) || is_delimiter_row(line) {
+                builder.push_markup(line);
+            } else {
+                push_table_row(&mut builder, line, options);
+            }
+            continue;
+        }
+        in_table = false;
+
+        // Block quote markers, possibly nested
+        let mut prefix_len = indent;
+        let mut quoted = false;
+        while let Some(after) = line[prefix_len..].strip_prefix('>') {
+            quoted = true;
+            prefix_len = line.len() - after.trim_start_matches([' ', '\t']).len();
+        }
+        // Lazy continuation lines belong to the quote
+        in_quote = quoted || (in_quote && !was_blank);
+        if in_quote && options.skips(MarkdownElement::Quotes) {
+            builder.push_markup(line);
+            continue;
+        }
+
+        if let Some(len) = list_marker_len(&line[prefix_len..]) {
+            prefix_len += len;
+            in_list = true;
+        }
+
+        if let Some(len) = heading_marker_len(&line[prefix_len..]) {
+            if options.skips(MarkdownElement::Headings) {
+                builder.push_markup(line);
+                continue;
+            }
+            builder.push_markup(&line[..prefix_len + len]);
+            push_heading(&mut builder, &line[prefix_len + len..], options);
+            continue;
+        }
+
+        builder.push_markup(&line[..prefix_len]);
+        push_line(&mut builder, &line[prefix_len..], options);
+    }
+
+    builder.build()
+}
+
+/// Return the fence character and length if `line` opens a fenced code
+/// block, e.g., `` ```rust ``.
+fn fence(line: &str) -> Option<(char, usize)> {
+    let c = line.chars().next().filter(|c| matches!(c, '`' | '~'))?;
+    let len = line.len() - line.trim_start_matches(c).len();
+
+    // Backtick fences cannot have backticks in their info string
+    (len >= 3 && (c == '~' || !line[len..].contains('`'))).then_some((c, len))
+}
+
+/// Return whether `line` closes a code block opened by `fence`.
+fn is_closing_fence(line: &str, (c, len): (char, usize)) -> bool {
+    line.len() >= len && line.chars().all(|d| d == c)
+}
+
+/// Return whether `line` starts an HTML block, e.g., `<div>` or `</p>`.
+fn is_html_block(line: &str) -> bool {
+    let Some(tag) = line.strip_prefix('<') else {
+        return false;
+    };
+    let tag = tag.strip_prefix('/').unwrap_or(tag);
+    let name_len = tag
+        .find(|c: char| !c.is_ascii_alphanumeric())
+        .unwrap_or(tag.len());
+
+    name_len > 0
+        && tag.starts_with(|c: char| c.is_ascii_alphabetic())
+        && (tag[name_len..].is_empty() || tag[name_len..].starts_with([' ', '>', '/']))
+}
+
+/// Return whether `line` is a thematic break, e.g., `***`, or a setext
+/// heading underline, e.g., `===`.
+fn is_thematic_break(line: &str) -> bool {
+    let Some(c) = line.chars().next() else {
+        return false;
+    };
+    let count = line.chars().filter(|d| *d == c).count();
+
+    match c {
+        '-' | '*' | '_' => count >= 3 && line.chars().all(|d| d == c || d == ' ' || d == '\t'),
+        '=' => line.chars().all(|d| d == '='),
+        _ => false,
+    }
+}
+
+/// Return the index of the closing bracket of the label if `line` is a link
+/// reference definition, e.g., `[label]: https://example.com`, or a footnote
+/// definition, e.g., `[^1]: Some text.`.
+fn link_reference_definition(line: &str) -> Option<usize> {
+    let end = line.strip_prefix('[')?.find("]:")? + 1;
+
+    (end > 1).then_some(end)
+}
+
+/// Return whether `line` is the delimiter row of a table, e.g., `| --- | :-:
+/// |`.
+fn is_delimiter_row(line: &str) -> bool {
+    let line = line.trim();
+    let line = line.strip_prefix('|').unwrap_or(line);
+    let line = line.strip_suffix('|').unwrap_or(line);
+
+    line.contains('-')
+        && line.split('|').all(|cell| {
+            let cell = cell.trim();
+            let cell = cell.strip_prefix(':').unwrap_or(cell);
+            let cell = cell.strip_suffix(':').unwrap_or(cell);
+            !cell.is_empty() && cell.chars().all(|c| c == '-')
+        })
+}
+
+/// Return the length of the list marker that starts `line`, including the
+/// following space and task list checkbox, if any.
+fn list_marker_len(line: &str) -> Option<usize> {
+    let marker_len = if line.starts_with(['-', '*', '+']) {
+        1
+    } else {
+        let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        ((1..=9).contains(&digits) && line[digits..].starts_with(['.', ')']))
+            .then_some(digits + 1)?
+    };
+    let after = &line[marker_len..];
+    let spaces = after.len() - after.trim_start_matches([' ', '\t']).len();
+    if spaces == 0 && !after.trim().is_empty() {
+        return None;
+    }
+
+    let len = marker_len + spaces;
+    let checkbox = ["[ ] ", "[x] ", "[X] "]
+        .iter()
+        .any(|checkbox| line[len..].starts_with(checkbox));
+    Some(if checkbox { len + 4 } else { len })
+}
+
+/// Return the length of the ATX heading marker that starts `line`, e.g., `## `.
+fn heading_marker_len(line: &str) -> Option<usize> {
+    let hashes = line.len() - line.trim_start_matches('#').len();
+    let after = &line[hashes..];
+    let spaces = after.len() - after.trim_start_matches([' ', '\t']).len();
+
+    ((1..=6).contains(&hashes) && (spaces > 0 || after.trim().is_empty()))
+        .then_some(hashes + spaces)
+}
+
+/// Push the title of an ATX heading, sending its closing sequence, e.g.,
+/// `##`, as markup.
+fn push_heading(builder: &mut DataBuilder, line: &str, options: &MarkdownOptions) {
+    let body = line.trim_end();
+    let without_closing = body.trim_end_matches('#');
+    let title = if without_closing.is_empty() || without_closing.ends_with([' ', '\t']) {
+        without_closing.trim_end()
+    } else {
+        body
+    };
+
+    push_inline(builder, title, options);
+    builder.push_markup(&body[title.len()..]);
+    builder.push_text(&line[body.len()..]);
+}
+
+/// Push a table row, sending cell delimiters as markup.
+fn push_table_row(builder: &mut DataBuilder, line: &str, options: &MarkdownOptions) {
+    let mut cell_start = 0;
+    let mut escaped = false;
+
+    for (i, c) in line.char_indices() {
+        if c == '|' && !escaped {
+            push_inline(builder, &line[cell_start..i], options);
+            builder.push_markup("|");
+            cell_start = i + 1;
+        }
+        escaped = c == '\\' && !escaped;
+    }
+    push_line(builder, &line[cell_start..], options);
+}
+
+/// Push a line of text, whose trailing whitespace, including the line break,
+/// is kept as text.
+fn push_line(builder: &mut DataBuilder, line: &str, options: &MarkdownOptions) {
+    let body = line.trim_end();
+
+    push_inline(builder, body, options);
+    builder.push_text(&line[body.len()..]);
+}
+
+/// Kind of inline markup.
+enum Inline {
+    /// Plain markup.
+    Markup,
+    /// Markup interpreted as [`PLACEHOLDER`].
+    Placeholder,
+    /// Backslash escaping the next character.
+    Escape,
+    /// Code span delimited by the given number of backticks.
+    Code(usize),
+    /// Link or image whose text spans the given byte range.
+    Link(usize, usize),
+}
+
+/// Push text, handling inline markup.
+fn push_inline(builder: &mut DataBuilder, text: &str, options: &MarkdownOptions) {
+    let mut text_start = 0;
+    let mut i = 0;
+
+    while i < text.len() {
+        let rest = &text[i..];
+        let previous = text[..i].chars().next_back();
+
+        // Length of inline markup starting at `i`, and how it is pushed
+        let inline: Option<(usize, Inline)> = match rest.as_bytes()[0] {
+            b'\\' if rest[1..].starts_with(|c: char| c.is_ascii_punctuation()) => {
+                Some((1, Inline::Escape))
+            },
+            b'`' => code_span_len(rest).map(|(len, ticks)| (len, Inline::Code(ticks))),
+            b'!' if rest[1..].starts_with('[') => {
+                link_len(&rest[1..]).map(|(len, text_end)| (len + 1, Inline::Link(2, text_end + 1)))
+            },
+            b'[' if rest[1..].starts_with('^') => {
+                rest.find(']').map(|end| (end + 1, Inline::Markup))
+            },
+            b'[' => link_len(rest).map(|(len, text_end)| (len, Inline::Link(1, text_end))),
+            b'<' => angle_len(rest),
+            b'*' | b'_' | b'~' => emphasis_len(rest, previous).map(|len| (len, Inline::Markup)),
+            b'h' if !previous.is_some_and(char::is_alphanumeric)
+                && (rest.starts_with("https://") || rest.starts_with("http://")) =>
+            {
+                let len = rest
+                    .find(|c: char| c.is_whitespace() || c == '<')
+                    .unwrap_or(rest.len());
+                let url =
+                    rest[..len].trim_end_matches(['.', ',', ';', ':', '!', '?', ')', '\'', '"']);
+                Some((url.len(), Inline::Placeholder))
+            },
+            _ => None,
+        };
+
+        let Some((len, kind)) = inline else {
+            i += rest.chars().next().map_or(1, char::len_utf8);
+            continue;
+        };
+
+        builder.push_text(&text[text_start..i]);
+        let markup = &rest[..len];
+        match kind {
+            Inline::Markup => builder.push_markup(markup),
+            Inline::Placeholder => builder.push_interpreted_markup(markup, PLACEHOLDER),
+            Inline::Escape => {
+                builder.push_markup(markup);
+                // The escaped character is text, and never markup
+                let escaped_len = rest[1..].chars().next().map_or(0, char::len_utf8);
+                i += len;
+                text_start = i;
+                i += escaped_len;
+                continue;
+            },
+            Inline::Code(_) if options.skips(MarkdownElement::Code) => {
+                builder.push_interpreted_markup(markup, PLACEHOLDER);
+            },
+            Inline::Code(ticks) => {
+                builder.push_markup(&markup[..ticks]);
+                builder.push_text(&markup[ticks..len - ticks]);
+                builder.push_markup(&markup[len - ticks..]);
+            },
+            Inline::Link(start, end) if options.skips(MarkdownElement::Links) || start == end => {
+                builder.push_interpreted_markup(markup, PLACEHOLDER);
+            },
+            Inline::Link(start, end) => {
+                builder.push_markup(&markup[..start]);
+                push_inline(builder, &markup[start..end], options);
+                builder.push_markup(&markup[end..]);
+            },
+        }
+        i += len;
+        text_start = i;
+    }
+
+    builder.push_text(&text[text_start..]);
+}
+
+/// Return the length of the code span starting `rest`, and the number of
+/// backticks delimiting it.
+fn code_span_len(rest: &str) -> Option<(usize, usize)> {
+    let ticks = rest.len() - rest.trim_start_matches('`').len();
+    let mut i = ticks;
+
+    while let Some(start) = rest[i..].find('`') {
+        let start = i + start;
+        let run = rest[start..].len() - rest[start..].trim_start_matches('`').len();
+        if run == ticks {
+            return Some((start + run, ticks));
+        }
+        i = start + run;
+    }
+    None
+}
+
+/// Return the length of the link starting `rest`, e.g., `[text](url)` or
+/// `[text][label]`, and the index of the bracket closing its text.
+fn link_len(rest: &str) -> Option<(usize, usize)> {
+    let text_end = closing(rest, '[', ']')?;
+    let after = &rest[text_end + 1..];
+
+    let destination_len = match after.chars().next() {
+        Some('(') => closing(after, '(', ')')? + 1,
+        Some('[') => closing(after, '[', ']')? + 1,
+        _ => return None,
+    };
+    Some((text_end + 1 + destination_len, text_end))
+}
+
+/// Return the index of the `close` character matching the `open` character
+/// that starts `s`, skipping escaped characters.
+fn closing(s: &str, open: char, close: char) -> Option<usize> {
+    let mut depth = 0;
+    let mut escaped = false;
+
+    for (i, c) in s.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == open {
+            depth += 1;
+        } else if c == close {
+            depth -= 1;
+            if depth == 0 {
+                return Some(i);
+            }
+        }
+    }
+    None
+}
+
+/// Return the length and kind of the autolink, e.g., `<https://example.com>`,
+/// or the HTML tag or comment starting `rest`.
+fn angle_len(rest: &str) -> Option<(usize, Inline)> {
+    if rest.starts_with("<!--") {
+        return rest.find("-->").map(|end| (end + 3, Inline::Markup));
+    }
+    let end = rest.find('>')?;
+    let inner = &rest[1..end];
+
+    if !inner.is_empty()
+        && !inner.contains(char::is_whitespace)
+        && (inner.contains("://") || inner.contains(':') || inner.contains('@'))
+        && !inner.starts_with('/')
+    {
+        Some((end + 1, Inline::Placeholder))
+    } else if inner
+        .strip_prefix('/')
+        .unwrap_or(inner)
+        .starts_with(|c: char| c.is_ascii_alphabetic())
+    {
+        Some((end + 1, Inline::Markup))
+    } else {
+        None
+    }
+}
+
+/// Return the length of the emphasis (or strikethrough) delimiter run
+/// starting `rest`, if it can open or close emphasis.
+fn emphasis_len(rest: &str, previous: Option<char>) -> Option<usize> {
+    let c = rest.chars().next()?;
+    let len = rest.len() - rest.trim_start_matches(c).len();
+    let next = rest[len..].chars().next();
+
+    let opens = next.is_some_and(|c| !c.is_whitespace());
+    let closes = previous.is_some_and(|c| !c.is_whitespace());
+    let is_delimiter = match c {
+        // Intraword underscores are literal, e.g., in `snake_case`
+        '_' => {
+            (opens && !previous.is_some_and(char::is_alphanumeric))
+                || (closes && !next.is_some_and(char::is_alphanumeric))
+        },
+        '~' => len == 2 && (opens || closes),
+        _ => opens || closes,
+    };
+    is_delimiter.then_some(len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::test_utils::{checked_text, source_of};
+
+    const SOURCE: &str = concat!(
+        "---\n",
+        "title: Not checked\n",
+        "---\n",
+        "# Getting *started* #\n",
+        "\n",
+        "Install with `cargo install` from [crates.io](https://crates.io), see \\*docs\\*.\n",
+        "Use snake_case or __strong__ text and ~~deleted~~ words <br> here.\n",
+        "\n",
+        "```rust\n",
+        "fn main() { teh(); }\n",
+        "```\n",
+        "\n",
+        "> Quoted text with a error.\n",
+        "lazy continuation\n",
+        "\n",
+        "- [ ] First item\n",
+        "1. Second item[^1]\n",
+        "\n",
+        "| Name | Description |\n",
+        "| ---- | :---------: |\n",
+        "| ltrs | A client    |\n",
+        "\n",
+        "<div>\n",
+        "HTML block\n",
+        "</div>\n",
+        "\n",
+        "    indented code\n",
+        "\n",
+        "***\n",
+        "[crates]: https://crates.io\n",
+        "[^1]: A footnote.\n",
+    );
+
+    #[test]
+    fn test_parse() {
+        let data = parse(SOURCE, &MarkdownOptions::default());
+
+        assert_eq!(source_of(&data), SOURCE);
+        assert_eq!(
+            checked_text(&data),
+            concat!(
+                "Getting started\n",
+                "\n",
+                "Install with X from crates.io, see *docs*.\n",
+                "Use snake_case or strong text and deleted words  here.\n",
+                "\n",
+                "\n",
+                "Quoted text with a error.\n",
+                "lazy continuation\n",
+                "\n",
+                "First item\n",
+                "Second item\n",
+                "\n",
+                " Name  Description \n",
+                " ltrs  A client    \n",
+                "\n",
+                "\n",
+                "\n",
+                "A footnote.\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_skip() {
+        let options = MarkdownOptions {
+            skip: vec![
+                MarkdownElement::Headings,
+                MarkdownElement::Links,
+                MarkdownElement::Quotes,
+                MarkdownElement::Tables,
+            ],
+        };
+        let data = parse(SOURCE, &options);
+
+        assert_eq!(source_of(&data), SOURCE);
+        let checked = checked_text(&data);
+        assert!(checked.starts_with("\nInstall with cargo install from X, see *docs*.\n"));
+        assert!(!checked.contains("Quoted"));
+        assert!(!checked.contains("lazy"));
+        assert!(!checked.contains("ltrs"));
+    }
+
+    #[test]
+    fn test_parse_unclosed() {
+        for source in [
+            "```\nnever closed",
+            "<!-- never closed",
+            "`code",
+            "[text](url",
+            "![alt",
+            "<tag",
+            "\\",
+        ] {
+            assert_eq!(
+                source_of(&parse(source, &MarkdownOptions::default())),
+                source
+            );
+        }
+    }
+}
@@ -0,0 +1,708 @@
+//! Parsers that convert documents into [`Data`] annotations, so that only
+//! their prose is checked.
+//!
+//! Each parser lives behind its own feature, and [`FileType`] selects the
+//! parser to use for a given file.
+
+use crate::check::{CheckResponse, Data, DataAnnotation};
+#[cfg(feature = "cli")]
+use clap::ValueEnum;
+use std::{ops::Range, path::Path};
+
+#[cfg(feature = "asciidoc")]
+pub mod asciidoc;
+#[cfg(feature = "bibtex")]
+pub mod bibtex;
+#[cfg(feature = "html")]
+pub mod html;
+#[cfg(feature = "latex")]
+pub mod latex;
+#[cfg(feature = "markdown")]
+pub mod markdown;
+#[cfg(feature = "org")]
+pub mod org;
+#[cfg(feature = "po")]
+pub mod po;
+#[cfg(feature = "source-code")]
+pub mod source_code;
+#[cfg(feature = "toml")]
+pub mod toml;
+#[cfg(feature = "typst")]
+pub mod typst;
+#[cfg(feature = "yaml")]
+pub mod yaml;
+
+/// Keys whose values are checked by default in structured documents, such as
+/// YAML or TOML files.
+pub const DEFAULT_KEYS: &[&str] = &["description", "summary", "title"];
+
+/// Type of a checked document, which determines how it is parsed.
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FileType {
+    /// Guess the type from the file extension, falling back to raw text.
+    #[default]
+    Auto,
+    /// Raw text, checked as is.
+    Raw,
+    /// C or C++ source code, of which only comments are checked.
+    #[cfg(feature = "source-code")]
+    C,
+    /// JavaScript or TypeScript source code, of which only comments are
+    /// checked.
+    #[cfg(feature = "source-code")]
+    Javascript,
+    /// Python source code, of which only comments and docstrings are checked.
+    #[cfg(feature = "source-code")]
+    Python,
+    /// Rust source code, of which only comments are checked.
+    #[cfg(feature = "source-code")]
+    Rust,
+    /// AsciiDoc document.
+    #[cfg(feature = "asciidoc")]
+    Asciidoc,
+    /// BibTeX bibliography, of which only some fields are checked.
+    #[cfg(feature = "bibtex")]
+    Bibtex,
+    /// HTML document.
+    #[cfg(feature = "html")]
+    Html,
+    /// LaTeX document.
+    #[cfg(feature = "latex")]
+    Latex,
+    /// Markdown document, see [`MarkdownOptions`].
+    #[cfg(feature = "markdown")]
+    Markdown,
+    /// Org-mode document.
+    #[cfg(feature = "org")]
+    Org,
+    /// Gettext PO or POT file, of which only translations are checked.
+    #[cfg(feature = "po")]
+    Po,
+    /// TOML document, of which only string values of some keys are checked.
+    #[cfg(feature = "toml")]
+    Toml,
+    /// Typst document.
+    #[cfg(feature = "typst")]
+    Typst,
+    /// YAML document, of which only string values of some keys are checked.
+    #[cfg(feature = "yaml")]
+    Yaml,
+}
+
+/// Options used to parse documents.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ParseOptions {
+    /// Keys whose string values are checked in structured documents.
+    ///
+    /// A key matches a value if it is equal to the value's full key path,
+    /// e.g., `package.description`, or to its last key, e.g., `description`.
+    pub keys: Vec<String>,
+    /// Options used to parse Markdown documents.
+    pub markdown: MarkdownOptions,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            keys: DEFAULT_KEYS.iter().map(ToString::to_string).collect(),
+            markdown: MarkdownOptions::default(),
+        }
+    }
+}
+
+/// Kind of Markdown element that can be skipped, i.e., sent as markup
+/// instead of text.
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MarkdownElement {
+    /// Code spans, which are interpreted as a placeholder word.
+    ///
+    /// Code blocks are never checked.
+    Code,
+    /// Headings.
+    Headings,
+    /// Links and images, which are interpreted as a placeholder word.
+    ///
+    /// Otherwise, only their text is checked.
+    Links,
+    /// Block quotes.
+    Quotes,
+    /// Tables.
+    Tables,
+}
+
+/// Options used to parse Markdown documents.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct MarkdownOptions {
+    /// Elements whose content is not checked.
+    pub skip: Vec<MarkdownElement>,
+}
+
+impl Default for MarkdownOptions {
+    fn default() -> Self {
+        Self {
+            skip: vec![MarkdownElement::Code],
+        }
+    }
+}
+
+impl MarkdownOptions {
+    /// Return whether `element` is skipped.
+    #[must_use]
+    pub fn skips(&self, element: MarkdownElement) -> bool {
+        self.skip.contains(&element)
+    }
+}
+
+impl ParseOptions {
+    /// Return whether the value at `path` should be checked.
+    ///
+    /// Items of a sequence are checked if the sequence itself is.
+    #[must_use]
+    pub fn is_checked(&self, path: &str) -> bool {
+        let full = path.trim_end_matches(|c: char| c == ']' || c == '[' || c.is_ascii_digit());
+        let last = full.rsplit('.').next().unwrap_or(full);
+
+        self.keys
+            .iter()
+            .any(|key| key == path || key == full || key == last)
+    }
+}
+
+/// Document converted into data annotations, see [`FileType::parse`].
+#[derive(Clone, Debug, Default, PartialEq)]
+#[non_exhaustive]
+pub struct Document {
+    /// Data annotations to check.
+    pub data: Data,
+    /// Key paths of the checked values, in structured documents, with the
+    /// range of characters (not bytes) they span in the source.
+    pub key_paths: Vec<(Range<usize>, String)>,
+    /// Language the document declares to be written in, if any, e.g., from
+    /// the header of a PO file.
+    pub language: Option<String>,
+    /// Positions of the checked text in the source.
+    pub offset_map: OffsetMap,
+}
+
+/// Position in the source of a document.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SourcePosition {
+    /// Offset, in characters.
+    pub offset: usize,
+    /// Offset, in bytes.
+    pub byte: usize,
+    /// Line number, starting at 1.
+    pub line: usize,
+    /// Offset from the start of the line, in characters.
+    pub column: usize,
+}
+
+/// Map from offsets in the checked text of [`Data`], i.e., the concatenation
+/// of its text and interpreted markup, to positions in the source, i.e., the
+/// concatenation of its text and markup.
+///
+/// # Examples
+///
+/// ```
+/// # use languagetool_rust::check::{Data, DataAnnotation};
+/// # use languagetool_rust::parsers::OffsetMap;
+/// let data = Data::from_iter([
+///     DataAnnotation::markup_as_paragraph("<h1>".to_string()),
+///     DataAnnotation::new_text("Title".to_string()),
+///     DataAnnotation::new_markup("</h1>\n<p>".to_string()),
+///     DataAnnotation::new_text("Some text".to_string()),
+/// ]);
+/// let map = OffsetMap::new(&data);
+///
+/// // "Some" starts at offset 7 in the checked text, i.e., "\n\nTitleSome text"
+/// let position = map.get(7);
+/// assert_eq!(
+///     (position.offset, position.line, position.column),
+///     (18, 2, 3)
+/// );
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OffsetMap {
+    /// Source text.
+    source: String,
+    /// Offset in the checked text, offset in the source (both in characters),
+    /// and whether it is text, of each annotation.
+    segments: Vec<(usize, usize, bool)>,
+    /// Offset of the start of each line in the source, in characters and in
+    /// bytes.
+    lines: Vec<(usize, usize)>,
+}
+
+impl OffsetMap {
+    /// Build the offset map of `data`.
+    #[must_use]
+    pub fn new(data: &Data) -> Self {
+        let mut source = String::new();
+        let mut segments = Vec::with_capacity(data.annotation.len());
+        let (mut checked_offset, mut source_offset) = (0, 0);
+
+        for annotation in &data.annotation {
+            let (part, checked_len, is_text) = match annotation {
+                DataAnnotation {
+                    text: Some(text), ..
+                } => (text.as_str(), text.chars().count(), true),
+                DataAnnotation {
+                    markup: Some(markup),
+                    interpret_as,
+                    ..
+                } => {
+                    let checked_len = interpret_as.as_deref().map_or(0, |i| i.chars().count());
+                    (markup.as_str(), checked_len, false)
+                },
+                _ => continue,
+            };
+            segments.push((checked_offset, source_offset, is_text));
+            source.push_str(part);
+            checked_offset += checked_len;
+            source_offset += part.chars().count();
+        }
+        segments.push((checked_offset, source_offset, false));
+
+        let mut lines = vec![(0, 0)];
+        let mut chars = 0;
+        for (byte, c) in source.char_indices() {
+            chars += 1;
+            if c == '\n' {
+                lines.push((chars, byte + 1));
+            }
+        }
+
+        Self {
+            source,
+            segments,
+            lines,
+        }
+    }
+
+    /// Return the source offset, in characters, of the character at `offset`
+    /// in the checked text.
+    ///
+    /// Offsets in interpreted markup are mapped to the start of the markup,
+    /// and offsets past the end are mapped to the end of the source.
+    #[must_use]
+    pub fn to_source(&self, offset: usize) -> usize {
+        // Last segment starting at or before `offset`
+        let i = self
+            .segments
+            .partition_point(|(checked, ..)| *checked <= offset)
+            .saturating_sub(1);
+
+        match self.segments.get(i) {
+            Some((checked, source, true)) => source + (offset - checked),
+            Some((_, source, false)) => *source,
+            None => 0,
+        }
+    }
+
+    /// Return the position of the character at `offset` in the checked text.
+    #[must_use]
+    pub fn get(&self, offset: usize) -> SourcePosition {
+        self.source_position(self.to_source(offset))
+    }
+
+    /// Return the position of the character at `offset` (in characters) in
+    /// the source.
+    #[must_use]
+    pub fn source_position(&self, offset: usize) -> SourcePosition {
+        let line = self
+            .lines
+            .partition_point(|(start, _)| *start <= offset)
+            .max(1);
+        let (line_start, line_byte) = self.lines[line - 1];
+        let column = offset - line_start;
+        let byte = line_byte
+            + self.source[line_byte..]
+                .chars()
+                .take(column)
+                .map(char::len_utf8)
+                .sum::<usize>();
+
+        SourcePosition {
+            offset,
+            byte,
+            line,
+            column,
+        }
+    }
+
+    /// Return the source lines spanning from `start` to `end`, both
+    /// positions in the source, without the final line break.
+    #[must_use]
+    pub fn lines(&self, start: SourcePosition, end: SourcePosition) -> &str {
+        let from = self.lines[start.line - 1].1;
+        let to = self
+            .lines
+            .get(end.line)
+            .map_or(self.source.len(), |(_, byte)| *byte);
+
+        self.source[from..to].trim_end_matches(['\n', '\r'])
+    }
+}
+
+impl Document {
+    /// Return the key path of the value containing the character at `offset`,
+    /// if any.
+    #[must_use]
+    pub fn key_path_at(&self, offset: usize) -> Option<&str> {
+        self.key_paths
+            .iter()
+            .find(|(range, _)| range.contains(&offset))
+            .map(|(_, path)| path.as_str())
+    }
+}
+
+impl Document {
+    /// Build a document from key paths given as (sorted) byte ranges in
+    /// `source`, converting them into character ranges.
+    #[cfg_attr(not(any(feature = "toml", feature = "yaml")), allow(dead_code))]
+    pub(crate) fn with_key_paths(
+        data: Data,
+        source: &str,
+        key_paths: Vec<(Range<usize>, String)>,
+    ) -> Self {
+        let (mut byte, mut char) = (0, 0);
+        let mut to_char = |index: usize| {
+            char += source[byte..index].chars().count();
+            byte = index;
+            char
+        };
+        let key_paths = key_paths
+            .into_iter()
+            .map(|(range, path)| (to_char(range.start)..to_char(range.end), path))
+            .collect();
+
+        Self {
+            offset_map: OffsetMap::new(&data),
+            data,
+            key_paths,
+            language: None,
+        }
+    }
+}
+
+impl Document {
+    /// Set the key path of each match in `response`, see
+    /// [`MoreContext::key_path`](crate::check::MoreContext::key_path).
+    ///
+    /// Matches must already have more context, e.g., by converting a
+    /// [`CheckResponseWithContext`](crate::check::CheckResponseWithContext)
+    /// into a [`CheckResponse`].
+    pub fn set_key_paths(&self, response: &mut CheckResponse) {
+        for m in response.iter_matches_mut() {
+            let key_path = self.key_path_at(m.offset);
+            if let (Some(key_path), Some(more_context)) = (key_path, m.more_context.as_mut()) {
+                more_context.key_path = Some(key_path.to_string());
+            }
+        }
+    }
+}
+
+impl Document {
+    /// Replace the context of each match in `response`, which is built by the
+    /// server from the checked text, with the source lines the match spans,
+    /// so that annotated output shows the document as it was written.
+    ///
+    /// Match offsets must refer to the source, as returned by the server.
+    pub fn set_source_context(&self, response: &mut CheckResponse) {
+        for m in response.iter_matches_mut() {
+            let start = self.offset_map.source_position(m.offset);
+            let end = self.offset_map.source_position(m.offset + m.length);
+
+            m.context.text = self.offset_map.lines(start, end).to_string();
+            m.context.offset = start.column;
+            m.context.length = m.length;
+        }
+    }
+}
+
+impl From<Data> for Document {
+    fn from(data: Data) -> Self {
+        Self {
+            offset_map: OffsetMap::new(&data),
+            data,
+            key_paths: Vec::new(),
+            language: None,
+        }
+    }
+}
+
+impl FileType {
+    /// Guess the type of a file from its extension.
+    ///
+    /// Unknown extensions, and types whose feature is not enabled, are
+    /// treated as [`FileType::Raw`].
+    #[must_use]
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Self {
+        let extension = path
+            .as_ref()
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_ascii_lowercase);
+
+        match extension.as_deref() {
+            #[cfg(feature = "asciidoc")]
+            Some("adoc" | "asciidoc") => Self::Asciidoc,
+            #[cfg(feature = "source-code")]
+            Some("c" | "h" | "cc" | "cpp" | "cxx" | "hh" | "hpp") => Self::C,
+            #[cfg(feature = "source-code")]
+            Some("js" | "mjs" | "cjs" | "jsx" | "ts" | "tsx") => Self::Javascript,
+            #[cfg(feature = "source-code")]
+            Some("py" | "pyi") => Self::Python,
+            #[cfg(feature = "source-code")]
+            Some("rs") => Self::Rust,
+            #[cfg(feature = "bibtex")]
+            Some("bib") => Self::Bibtex,
+            #[cfg(feature = "html")]
+            Some("html" | "htm" | "xhtml") => Self::Html,
+            #[cfg(feature = "latex")]
+            Some("tex" | "ltx" | "sty" | "cls") => Self::Latex,
+            #[cfg(feature = "markdown")]
+            Some("md" | "markdown" | "mdown" | "mkd") => Self::Markdown,
+            #[cfg(feature = "org")]
+            Some("org") => Self::Org,
+            #[cfg(feature = "po")]
+            Some("po" | "pot") => Self::Po,
+            #[cfg(feature = "toml")]
+            Some("toml") => Self::Toml,
+            #[cfg(feature = "typst")]
+            Some("typ") => Self::Typst,
+            #[cfg(feature = "yaml")]
+            Some("yaml" | "yml") => Self::Yaml,
+            _ => Self::Raw,
+        }
+    }
+
+    /// Resolve [`FileType::Auto`] using the extension of `path`, if any.
+    #[must_use]
+    pub fn resolve(self, path: Option<&Path>) -> Self {
+        match (self, path) {
+            (Self::Auto, Some(path)) => Self::from_path(path),
+            (Self::Auto, None) => Self::Raw,
+            (file_type, _) => file_type,
+        }
+    }
+
+    /// Convert `text` into a document, or return [`None`] if it should be
+    /// checked as raw text.
+    ///
+    /// [`FileType::Auto`] is treated as [`FileType::Raw`], see
+    /// [`FileType::resolve`]. Concatenating the text and markup of the
+    /// document's annotations gives back `text`, so match offsets refer to
+    /// the original document.
+    #[must_use]
+    #[cfg_attr(
+        not(any(
+            feature = "asciidoc",
+            feature = "bibtex",
+            feature = "html",
+            feature = "latex",
+            feature = "markdown",
+            feature = "org",
+            feature = "po",
+            feature = "source-code",
+            feature = "toml",
+            feature = "typst",
+            feature = "yaml"
+        )),
+        allow(unused_variables)
+    )]
+    pub fn parse(self, text: &str, options: &ParseOptions) -> Option<Document> {
+        match self {
+            Self::Auto | Self::Raw => None,
+            #[cfg(feature = "source-code")]
+            Self::C => Some(source_code::parse(text, source_code::Language::C).into()),
+            #[cfg(feature = "source-code")]
+            Self::Javascript => {
+                Some(source_code::parse(text, source_code::Language::JavaScript).into())
+            },
+            #[cfg(feature = "source-code")]
+            Self::Python => Some(source_code::parse(text, source_code::Language::Python).into()),
+            #[cfg(feature = "source-code")]
+            Self::Rust => Some(source_code::parse(text, source_code::Language::Rust).into()),
+            #[cfg(feature = "asciidoc")]
+            Self::Asciidoc => Some(asciidoc::parse(text).into()),
+            #[cfg(feature = "bibtex")]
+            Self::Bibtex => Some(bibtex::parse(text).into()),
+            #[cfg(feature = "html")]
+            Self::Html => Some(html::parse(text).into()),
+            #[cfg(feature = "latex")]
+            Self::Latex => Some(latex::parse(text).into()),
+            #[cfg(feature = "markdown")]
+            Self::Markdown => Some(markdown::parse(text, &options.markdown).into()),
+            #[cfg(feature = "org")]
+            Self::Org => Some(org::parse(text).into()),
+            #[cfg(feature = "po")]
+            Self::Po => Some(po::parse(text)),
+            #[cfg(feature = "toml")]
+            Self::Toml => Some(toml::parse(text, options)),
+            #[cfg(feature = "typst")]
+            Self::Typst => Some(typst::parse(text).into()),
+            #[cfg(feature = "yaml")]
+            Self::Yaml => Some(yaml::parse(text, options)),
+        }
+    }
+}
+
+/// Utilities shared by the tests of parsers.
+#[cfg(test)]
+#[cfg_attr(
+    not(any(
+        feature = "asciidoc",
+        feature = "bibtex",
+        feature = "html",
+        feature = "latex",
+        feature = "markdown",
+        feature = "org",
+        feature = "po",
+        feature = "source-code",
+        feature = "toml",
+        feature = "typst",
+        feature = "yaml"
+    )),
+    allow(dead_code)
+)]
+pub(crate) mod test_utils {
+    use crate::check::Data;
+
+    /// Concatenate text and markup, which must give back the source.
+    pub(crate) fn source_of(data: &Data) -> String {
+        data.annotation
+            .iter()
+            .map(|da| da.text.as_deref().or(da.markup.as_deref()).unwrap())
+            .collect()
+    }
+
+    /// Concatenate text and interpreted markup, i.e., what gets checked.
+    pub(crate) fn checked_text(data: &Data) -> String {
+        data.annotation
+            .iter()
+            .map(|da| {
+                da.text
+                    .as_deref()
+                    .or(da.interpret_as.as_deref())
+                    .unwrap_or("")
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_type_from_path() {
+        assert_eq!(FileType::from_path("notes.txt"), FileType::Raw);
+        assert_eq!(FileType::from_path("README"), FileType::Raw);
+        #[cfg(feature = "asciidoc")]
+        assert_eq!(FileType::from_path("index.adoc"), FileType::Asciidoc);
+        #[cfg(feature = "html")]
+        assert_eq!(FileType::from_path("index.html"), FileType::Html);
+        #[cfg(feature = "latex")]
+        assert_eq!(FileType::from_path("thesis.TEX"), FileType::Latex);
+        #[cfg(feature = "bibtex")]
+        assert_eq!(FileType::from_path("refs.bib"), FileType::Bibtex);
+        #[cfg(feature = "markdown")]
+        assert_eq!(FileType::from_path("README.md"), FileType::Markdown);
+        #[cfg(feature = "org")]
+        assert_eq!(FileType::from_path("notes.org"), FileType::Org);
+        #[cfg(feature = "po")]
+        assert_eq!(FileType::from_path("locale/fr.po"), FileType::Po);
+        #[cfg(feature = "source-code")]
+        assert_eq!(FileType::from_path("src/lib.rs"), FileType::Rust);
+        #[cfg(feature = "typst")]
+        assert_eq!(FileType::from_path("paper.typ"), FileType::Typst);
+        assert_eq!(FileType::Auto.resolve(None), FileType::Raw);
+        assert_eq!(
+            FileType::Raw.resolve(Some(Path::new("thesis.tex"))),
+            FileType::Raw
+        );
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_set_key_paths() {
+        use crate::check::{test_utils::response_with_matches, CheckResponseWithContext};
+
+        let text = "name: smal\ntitle: A smal title\n";
+        let document = FileType::Yaml
+            .parse(text, &ParseOptions::default())
+            .unwrap();
+        let response = response_with_matches(text, &[(20, 4, &["small"])]);
+        let mut response: CheckResponse =
+            CheckResponseWithContext::new(text.to_string(), response).into();
+        document.set_key_paths(&mut response);
+
+        assert_eq!(
+            response.render_plain(text, Some("file.yaml")),
+            "file.yaml:2:10:  [MORFOLOGIK_RULE_EN_US] (at key title)\n  suggestions: small"
+        );
+    }
+
+    #[test]
+    fn test_offset_map() {
+        let data = Data::from_iter([
+            DataAnnotation::new_text("Thé ".to_string()),
+            DataAnnotation::new_markup("**".to_string()),
+            DataAnnotation::new_text("first\n".to_string()),
+            DataAnnotation::new_interpreted_markup("`code`".to_string(), "X".to_string()),
+            DataAnnotation::new_text(" line".to_string()),
+        ]);
+        let map = OffsetMap::new(&data);
+
+        assert_eq!(map.to_source(2), 2);
+        assert_eq!(map.to_source(4), 6);
+        assert_eq!(map.to_source(10), 12);
+        assert_eq!(map.to_source(11), 18);
+        assert_eq!(map.to_source(100), 23);
+        assert_eq!(
+            map.get(4),
+            SourcePosition {
+                offset: 6,
+                byte: 7,
+                line: 1,
+                column: 6,
+            }
+        );
+        assert_eq!(
+            map.get(12),
+            SourcePosition {
+                offset: 19,
+                byte: 20,
+                line: 2,
+                column: 7,
+            }
+        );
+
+        let (start, end) = (map.source_position(6), map.source_position(19));
+        assert_eq!(map.lines(start, end), "Thé **first\n`code` line");
+    }
+
+    #[test]
+    fn test_set_source_context() {
+        use crate::check::test_utils::response_with_matches;
+
+        let document = Document::from(Data::from_iter([
+            DataAnnotation::new_markup("# ".to_string()),
+            DataAnnotation::new_text("Title\n\nA smal text.\n".to_string()),
+        ]));
+        let mut response = response_with_matches("Title\n\nA smal text.\n", &[(11, 4, &["small"])]);
+        document.set_source_context(&mut response);
+
+        let context = &response.matches[0].context;
+        assert_eq!(context.text, "A smal text.");
+        assert_eq!((context.offset, context.length), (2, 4));
+    }
+}
@@ -0,0 +1,324 @@
+//! Convert Org-mode sources into [`Data`] annotations.
+//!
+//! The parser works line by line: headlines have their stars, TODO keyword,
+//! priority and tags sent as markup, and their title checked as text. Blocks
+//! (`#+BEGIN_SRC`, `#+BEGIN_EXAMPLE`, ...), drawers (`:PROPERTIES:`, ...),
+//! comments, keywords and planning lines are sent as markup, except for
+//! the values of [`TEXT_KEYWORDS`] and the content of [`TEXT_BLOCKS`].
+//!
+//! Inside text, links are replaced by their description, or interpreted as
+//! [`PLACEHOLDER`] when they have none, and so are code and verbatim spans.
+
+use crate::check::{Data, DataBuilder};
+
+/// Token that code, verbatim and links without a description are interpreted
+/// as.
+pub const PLACEHOLDER: &str = "X";
+
+/// Keywords, such as `#+TITLE:`, whose value is checked.
+pub const TEXT_KEYWORDS: &[&str] = &["CAPTION", "SUBTITLE", "TITLE"];
+
+/// Blocks whose content is checked.
+pub const TEXT_BLOCKS: &[&str] = &["CENTER", "QUOTE", "VERSE"];
+
+/// Keywords that can follow the stars of a headline.
+const TODO_KEYWORDS: &[&str] = &["TODO", "DONE"];
+
+/// Convert an Org-mode `source` into data annotations.
+///
+/// # Examples
+///
+/// ```
+/// # use languagetool_rust::check::DataAnnotation;
+/// # use languagetool_rust::parsers::org;
+/// let data = org::parse("* TODO Write [[https://example.com][docs]] :work:\n");
+///
+/// assert_eq!(
+///     data.annotation,
+///     vec![
+///         DataAnnotation::new_markup("* TODO ".to_string()),
+///         DataAnnotation::new_text("Write ".to_string()),
+///         DataAnnotation::new_markup("[[https://example.com][".to_string()),
+///         DataAnnotation::new_text("docs".to_string()),
+///         DataAnnotation::new_markup("]] :work:".to_string()),
+///         DataAnnotation::new_text("\n".to_string()),
+///     ]
+/// );
+/// ```
+#[must_use]
+pub fn parse(source: &str) -> Data {
+    let mut builder = DataBuilder::default();
+    let mut lines = source.split_inclusive('\n');
+
+    while let Some(line) = lines.next() {
+        let content = line.trim_end_matches(['\n', '\r']);
+        let newline = &line[content.len()..];
+        let body = content.trim_start();
+        let indent = &content[..content.len() - body.len()];
+
+        if let Some(name) = block_name(body) {
+            builder.push_markup(line);
+            if TEXT_BLOCKS
+                .iter()
+                .any(|block| block.eq_ignore_ascii_case(name))
+            {
+                continue;
+            }
+            for line in lines.by_ref() {
+                builder.push_markup(line);
+                let end = line.trim_start().get(..6);
+                if end.is_some_and(|end| end.eq_ignore_ascii_case("#+end_")) {
+                    break;
+                }
+            }
+        } else if is_drawer(body) && !body.eq_ignore_ascii_case(":END:") {
+            builder.push_markup(line);
+            for line in lines.by_ref() {
+                builder.push_markup(line);
+                if line.trim().eq_ignore_ascii_case(":END:") {
+                    break;
+                }
+            }
+        } else if let Some(value_start) = keyword_value(body) {
+            let value_start = indent.len() + value_start;
+            builder.push_markup(&content[..value_start]);
+            push_inline(&mut builder, &content[value_start..]);
+            builder.push_text(newline);
+        } else if body.starts_with("#+")
+            || body == "#"
+            || body.starts_with("# ")
+            || is_drawer(body)
+            || is_planning(body)
+        {
+            builder.push_markup(line);
+        } else if body.starts_with('|') {
+            for (i, cell) in content.split('|').enumerate() {
+                if i > 0 {
+                    builder.push_markup("|");
+                }
+                if cell.starts_with('-') {
+                    builder.push_markup(cell);
+                } else {
+                    builder.push_text(cell);
+                }
+            }
+            builder.push_text(newline);
+        } else if let Some((marker_len, tags_start)) = headline(content) {
+            builder.push_markup(&content[..marker_len]);
+            push_inline(&mut builder, &content[marker_len..tags_start]);
+            builder.push_markup(&content[tags_start..]);
+            builder.push_text(newline);
+        } else {
+            let marker_len = indent.len() + list_marker_len(body);
+            builder.push_markup(&content[..marker_len]);
+            push_inline(&mut builder, &content[marker_len..]);
+            builder.push_text(newline);
+        }
+    }
+
+    builder.build()
+}
+
+/// Return the name of the block that `line` begins, e.g., `SRC` for
+/// `#+BEGIN_SRC rust`.
+fn block_name(line: &str) -> Option<&str> {
+    let begin = line.get(..8)?;
+    if !begin.eq_ignore_ascii_case("#+begin_") {
+        return None;
+    }
+    line[8..].split_whitespace().next()
+}
+
+/// Return whether `line` is a drawer delimiter, e.g., `:PROPERTIES:`.
+fn is_drawer(line: &str) -> bool {
+    line.len() > 2
+        && line.starts_with(':')
+        && line.ends_with(':')
+        && line[1..line.len() - 1]
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Return whether `line` is a planning line, e.g., `SCHEDULED: <2024-01-01>`.
+fn is_planning(line: &str) -> bool {
+    ["CLOSED:", "DEADLINE:", "SCHEDULED:"]
+        .iter()
+        .any(|keyword| line.starts_with(keyword))
+}
+
+/// Return the byte index of the value of a text keyword, e.g., `#+TITLE:`.
+fn keyword_value(line: &str) -> Option<usize> {
+    let (keyword, value) = line.strip_prefix("#+")?.split_once(':')?;
+    TEXT_KEYWORDS
+        .iter()
+        .any(|text| text.eq_ignore_ascii_case(keyword))
+        .then(|| line.len() - value.trim_start().len())
+}
+
+/// Return the length of the markup starting a headline, i.e., its stars,
+/// TODO keyword and priority, and the byte index at which its tags start.
+fn headline(line: &str) -> Option<(usize, usize)> {
+    let stars = line.len() - line.trim_start_matches('*').len();
+    if stars == 0 || !line[stars..].starts_with(' ') {
+        return None;
+    }
+
+    let mut marker_len = stars + 1;
+    for keyword in TODO_KEYWORDS {
+        if line[marker_len..]
+            .strip_prefix(keyword)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(' '))
+        {
+            marker_len = (marker_len + keyword.len() + 1).min(line.len());
+        }
+    }
+    let rest = &line[marker_len..];
+    if rest.len() >= 4 && rest.starts_with("[#") && rest[3..].starts_with(']') {
+        marker_len = (marker_len + 5).min(line.len());
+    }
+
+    // Tags, e.g., `:work:urgent:`, at the end of the line
+    let trimmed = line.trim_end();
+    let tags_start = trimmed
+        .rfind(' ')
+        .map(|i| i + 1)
+        .filter(|&i| i > marker_len && is_tags(&trimmed[i..]))
+        .map_or(line.len(), |i| trimmed[..i].trim_end().len());
+
+    Some((marker_len, tags_start.max(marker_len)))
+}
+
+/// Return whether `s` is a list of headline tags, e.g., `:work:urgent:`.
+fn is_tags(s: &str) -> bool {
+    s.len() > 2
+        && s.starts_with(':')
+        && s.ends_with(':')
+        && s[1..s.len() - 1].split(':').all(|tag| {
+            !tag.is_empty()
+                && tag
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || "_@#%".contains(c))
+        })
+}
+
+/// Return the length of the list marker (and checkbox) starting `line`, if
+/// any.
+fn list_marker_len(line: &str) -> usize {
+    let bullet = if line.starts_with("- ") || line.starts_with("+ ") {
+        2
+    } else {
+        let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        match line[digits..].get(..2) {
+            Some(". " | ") ") if digits > 0 => digits + 2,
+            _ => return 0,
+        }
+    };
+
+    match line[bullet..].get(..4) {
+        Some("[ ] " | "[X] " | "[x] " | "[-] ") => bullet + 4,
+        _ => bullet,
+    }
+}
+
+/// Push a line of text, handling links, code and verbatim spans.
+fn push_inline(builder: &mut DataBuilder, line: &str) {
+    let mut text_start = 0;
+    let mut i = 0;
+
+    while i < line.len() {
+        let rest = &line[i..];
+        let preceded_by_space = line[..i]
+            .chars()
+            .next_back()
+            .map_or(true, |c| c.is_whitespace() || "({'\"".contains(c));
+
+        if let Some(end) = rest.starts_with("[[").then(|| rest.find("]]")).flatten() {
+            builder.push_text(&line[text_start..i]);
+            let link = &rest[..end + 2];
+            match link.find("][") {
+                Some(description) => {
+                    builder.push_markup(&link[..description + 2]);
+                    builder.push_text(&link[description + 2..end]);
+                    builder.push_markup("]]");
+                },
+                None => builder.push_interpreted_markup(link, PLACEHOLDER),
+            }
+            i += end + 2;
+            text_start = i;
+            continue;
+        }
+
+        if preceded_by_space && rest.starts_with(['~', '=']) {
+            let marker = &rest[..1];
+            let end = rest[1..]
+                .find(marker)
+                .filter(|&end| end > 0 && !rest[1..].starts_with(char::is_whitespace));
+            if let Some(end) = end {
+                builder.push_text(&line[text_start..i]);
+                builder.push_interpreted_markup(&rest[..end + 2], PLACEHOLDER);
+                i += end + 2;
+                text_start = i;
+                continue;
+            }
+        }
+
+        i += rest.chars().next().map_or(1, char::len_utf8);
+    }
+
+    builder.push_text(&line[text_start..]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::test_utils::{checked_text, source_of};
+
+    #[test]
+    fn test_parse_roundtrip() {
+        let source = concat!(
+            "#+TITLE: My notes\n",
+            "#+OPTIONS: toc:nil\n",
+            "# A comment\n",
+            "\n",
+            "* DONE [#A] First headline :work:urgent:\n",
+            "  SCHEDULED: <2024-01-01 Mon>\n",
+            "  :PROPERTIES:\n",
+            "  :ID: 1234\n",
+            "  :END:\n",
+            "Some text with ~code~ and [[https://example.com]].\n",
+            "\n",
+            "#+BEGIN_SRC rust\n",
+            "fn main() { teh(); }\n",
+            "#+END_SRC\n",
+            "\n",
+            "- [X] Done item\n",
+            "1. Numbered item\n",
+            "#+begin_quote\n",
+            "Quoted text.\n",
+            "#+end_quote\n",
+            "| a | b |\n",
+            "|---+---|\n",
+        );
+        let data = parse(source);
+
+        assert_eq!(source_of(&data), source);
+        assert_eq!(
+            checked_text(&data),
+            "My notes\n\nFirst headline\nSome text with X and X.\n\n\nDone item\nNumbered \
+             item\nQuoted text.\n a  b \n\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_unclosed() {
+        for source in [
+            "#+BEGIN_SRC\nnever",
+            ":PROPERTIES:\nnever",
+            "[[link",
+            "~code",
+            "*",
+        ] {
+            assert_eq!(source_of(&parse(source)), source);
+        }
+    }
+}
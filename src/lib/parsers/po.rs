@@ -0,0 +1,295 @@
+//! Convert Gettext PO (and POT) files into [`Data`](crate::check::Data)
+//! annotations.
+//!
+//! Only `msgstr` strings, i.e., translations, are checked, each as a separate
+//! paragraph, and everything else (comments, `msgctxt` and `msgid` strings)
+//! is sent as markup. The header entry is never checked, but its `Language`
+//! field sets [`Document::language`].
+//!
+//! Inside translations, escape sequences are interpreted as the character
+//! they stand for, and format placeholders, e.g., `%s` or `{name}`, are
+//! interpreted as [`PLACEHOLDER`].
+
+use super::Document;
+use crate::check::DataBuilder;
+
+/// Token that format placeholders are interpreted as.
+pub const PLACEHOLDER: &str = "X";
+
+/// Keyword introducing the string on a line.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Keyword {
+    /// `msgid`, whose strings tell whether the entry is the header.
+    Msgid,
+    /// `msgstr` or `msgstr[n]`, whose strings are checked.
+    Msgstr,
+    /// Any other keyword, e.g., `msgctxt` or `msgid_plural`.
+    Other,
+}
+
+/// Convert a PO `source` into a document.
+///
+/// # Examples
+///
+/// ```
+/// # use languagetool_rust::parsers::po;
+/// let source = concat!(
+///     "msgid \"\"\n",
+///     "msgstr \"Language: pt_BR\\n\"\n",
+///     "\n",
+///     "msgid \"Hello, %s!\"\n",
+///     "msgstr \"Olá, %s!\"\n",
+/// );
+/// let document = po::parse(source);
+///
+/// assert_eq!(document.language.as_deref(), Some("pt-BR"));
+/// ```
+#[must_use]
+pub fn parse(source: &str) -> Document {
+    let mut builder = DataBuilder::default();
+    let mut header = String::new();
+    let mut keyword = Keyword::Other;
+    // Whether the msgid of the current entry is empty, i.e., it is the header
+    let mut empty_msgid = false;
+    // Whether the next checked string starts a new paragraph
+    let mut new_paragraph = true;
+    // Start of the markup that was not pushed yet
+    let mut last = 0;
+    let mut pos = 0;
+
+    for line in source.split_inclusive('\n') {
+        let line_start = pos;
+        pos += line.len();
+        let line_end = line_start + line.trim_end().len();
+        let trimmed = line.trim_start();
+
+        let rest = if trimmed.starts_with('"') {
+            trimmed
+        } else {
+            let word_len = trimmed
+                .find(|c: char| c.is_whitespace() || c == '"')
+                .unwrap_or(trimmed.len());
+            keyword = match &trimmed[..word_len] {
+                "msgid" => {
+                    empty_msgid = true;
+                    Keyword::Msgid
+                },
+                word if word == "msgstr" || word.starts_with("msgstr[") => {
+                    new_paragraph = true;
+                    Keyword::Msgstr
+                },
+                "msgctxt" | "msgid_plural" => Keyword::Other,
+                _ => {
+                    // Comments, blank lines and anything unexpected
+                    keyword = Keyword::Other;
+                    continue;
+                },
+            };
+            trimmed[word_len..].trim_start()
+        };
+        if !rest.starts_with('"') {
+            continue;
+        }
+
+        let open = line_start + (line.len() - rest.len());
+        let close = string_end(source, open + 1, line_end);
+        let content = &source[open + 1..close];
+
+        match keyword {
+            Keyword::Msgid if !content.is_empty() => empty_msgid = false,
+            Keyword::Msgstr if empty_msgid => header.push_str(&unescape(content)),
+            Keyword::Msgstr if !content.is_empty() => {
+                // Continuation strings are concatenated to the previous one
+                if new_paragraph {
+                    builder.push_paragraph_markup(&source[last..=open]);
+                } else {
+                    builder.push_markup(&source[last..=open]);
+                }
+                push_string(&mut builder, content);
+                new_paragraph = false;
+                last = close;
+            },
+            _ => {},
+        }
+    }
+
+    builder.push_markup(&source[last..]);
+    Document {
+        language: language(&header),
+        ..builder.build().into()
+    }
+}
+
+/// Return the byte index of the quote closing the string that starts at
+/// `start`, or `line_end` if it is not closed on the same line.
+fn string_end(source: &str, start: usize, line_end: usize) -> usize {
+    let mut escaped = false;
+
+    for (i, c) in source[start..line_end].char_indices() {
+        if c == '"' && !escaped {
+            return start + i;
+        }
+        escaped = c == '\\' && !escaped;
+    }
+    line_end
+}
+
+/// Return the character that the escape sequence `\c` stands for.
+fn escape(c: char) -> char {
+    match c {
+        'n' => '\n',
+        't' => '\t',
+        'r' => '\r',
+        c => c,
+    }
+}
+
+/// Replace escape sequences in `content` by the characters they stand for.
+fn unescape(content: &str) -> String {
+    let mut unescaped = String::with_capacity(content.len());
+    let mut chars = content.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unescaped.extend(chars.next().map(escape)),
+            c => unescaped.push(c),
+        }
+    }
+    unescaped
+}
+
+/// Return the language set in the `Language` field of the `header`, as a
+/// language code, e.g., `"pt-BR"` for `pt_BR`.
+fn language(header: &str) -> Option<String> {
+    header
+        .lines()
+        .find_map(|line| line.strip_prefix("Language:"))
+        .map(|language| {
+            // Drop the variant, e.g., `sr@latin`
+            let language = language.split('@').next().unwrap_or_default();
+            language.trim().replace('_', "-")
+        })
+        .filter(|language| !language.is_empty())
+}
+
+/// Return the length of the format placeholder starting `rest`, if any, and
+/// what it is interpreted as.
+fn placeholder(rest: &str) -> Option<(usize, &'static str)> {
+    if let Some(spec) = rest.strip_prefix('%') {
+        if spec.starts_with('%') {
+            return Some((2, "%"));
+        }
+        // Python's named placeholders, e.g., `%(name)s`
+        let name_len = match spec.strip_prefix('(') {
+            Some(named) => named.find(')')? + 2,
+            None => 0,
+        };
+        let flags_len = spec[name_len..]
+            .find(|c: char| !(c.is_ascii_digit() || "$-+#.*hlLqjzt".contains(c)))
+            .unwrap_or(spec.len() - name_len);
+        let conversion = spec[name_len + flags_len..].chars().next()?;
+
+        (conversion.is_ascii_alphabetic() || conversion == '@')
+            .then_some((1 + name_len + flags_len + 1, PLACEHOLDER))
+    } else if let Some(braced) = rest.strip_prefix('{') {
+        let name_len = braced
+            .find(|c: char| !(c.is_alphanumeric() || "_.:".contains(c)))
+            .unwrap_or(braced.len());
+
+        braced[name_len..]
+            .starts_with('}')
+            .then_some((name_len + 2, PLACEHOLDER))
+    } else {
+        None
+    }
+}
+
+/// Push the content of a translation string.
+fn push_string(builder: &mut DataBuilder, content: &str) {
+    let mut text_start = 0;
+    let mut i = 0;
+
+    while i < content.len() {
+        let rest = &content[i..];
+        let (len, interpret_as) = if let Some(escaped) = rest.strip_prefix('\\') {
+            match escaped.chars().next() {
+                Some(c) => (1 + c.len_utf8(), escape(c).to_string()),
+                None => (1, String::new()),
+            }
+        } else if let Some((len, interpret_as)) = placeholder(rest) {
+            (len, interpret_as.to_string())
+        } else {
+            i += rest.chars().next().map_or(1, char::len_utf8);
+            continue;
+        };
+
+        builder.push_text(&content[text_start..i]);
+        builder.push_interpreted_markup(&rest[..len], &interpret_as);
+        i += len;
+        text_start = i;
+    }
+
+    builder.push_text(&content[text_start..]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::test_utils::{checked_text, source_of};
+
+    #[test]
+    fn test_parse() {
+        let source = concat!(
+            "# French translations.\n",
+            "msgid \"\"\n",
+            "msgstr \"\"\n",
+            "\"Project-Id-Version: ltrs\\n\"\n",
+            "\"Language: fr_FR@euro\\n\"\n",
+            "\n",
+            "#: src/main.rs:12\n",
+            "#, c-format\n",
+            "msgid \"Checked %d files in {time}.\"\n",
+            "msgstr \"Vérifié %d fichiers en {time}.\"\n",
+            "\n",
+            "msgctxt \"menu\"\n",
+            "msgid \"Open \\\"file\\\"\"\n",
+            "msgid_plural \"Open files\"\n",
+            "msgstr[0] \"\"\n",
+            "\"Ouvrir le \"\n",
+            "\"fichier à 100%%\"\n",
+            "msgstr[1] \"Ouvrir les fichiers\"\n",
+            "\n",
+            "#~ msgid \"Old\"\n",
+            "#~ msgstr \"Ancien\"\n",
+        );
+        let document = parse(source);
+
+        assert_eq!(source_of(&document.data), source);
+        assert_eq!(
+            checked_text(&document.data),
+            "\n\nVérifié X fichiers en X.\n\nOuvrir le fichier à 100%\n\nOuvrir les fichiers"
+        );
+        assert_eq!(document.language.as_deref(), Some("fr-FR"));
+    }
+
+    #[test]
+    fn test_parse_without_language() {
+        let document = parse("msgid \"Hello\"\nmsgstr \"Bonjour\"\n");
+
+        assert_eq!(checked_text(&document.data), "\n\nBonjour");
+        assert_eq!(document.language, None);
+    }
+
+    #[test]
+    fn test_parse_unclosed() {
+        for source in [
+            "msgid \"a\"\nmsgstr \"never",
+            "msgid \"a\"\nmsgstr \"ends with \\",
+            "msgid \"a\"\nmsgstr \"%(name",
+            "msgstr",
+            "\"",
+        ] {
+            assert_eq!(source_of(&parse(source).data), source);
+        }
+    }
+}
@@ -0,0 +1,411 @@
+//! Convert source code files into [`Data`] annotations, so that only their
+//! comments and docstrings are checked.
+//!
+//! Source code is tokenized just enough to skip string and character
+//! literals, so that comment markers inside them are ignored. Code is sent as
+//! markup, interpreted as a paragraph break when it separates two comments,
+//! and so are comment markers, e.g., `///` or the leading `*` of block comment
+//! lines. Inside comments, code spans (between backticks) are interpreted as
+//! [`PLACEHOLDER`].
+//!
+//! Python docstrings are the triple-quoted strings that start a line. Other
+//! string literals are never checked.
+
+use crate::check::{Data, DataBuilder};
+
+/// Token that code spans inside comments are interpreted as.
+pub const PLACEHOLDER: &str = "X";
+
+/// Programming language of a source file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Language {
+    /// C and C++.
+    C,
+    /// JavaScript and TypeScript.
+    JavaScript,
+    /// Python.
+    Python,
+    /// Rust.
+    Rust,
+}
+
+/// Comment and literal syntax of a language.
+struct Syntax {
+    /// Line comment markers, longest first.
+    line_comments: &'static [&'static str],
+    /// Opening and closing markers of block comments.
+    block_comment: Option<(&'static str, &'static str)>,
+    /// Delimiters of string literals, longest first.
+    quotes: &'static [&'static str],
+    /// Whether triple-quoted strings that start a line are checked.
+    docstrings: bool,
+}
+
+impl Language {
+    /// Return the comment and literal syntax of this language.
+    fn syntax(self) -> Syntax {
+        match self {
+            Self::C => {
+                Syntax {
+                    line_comments: &["//"],
+                    block_comment: Some(("/*", "*/")),
+                    quotes: &["\"", "'"],
+                    docstrings: false,
+                }
+            },
+            Self::JavaScript => {
+                Syntax {
+                    line_comments: &["//"],
+                    block_comment: Some(("/*", "*/")),
+                    quotes: &["\"", "'", "`"],
+                    docstrings: false,
+                }
+            },
+            Self::Python => {
+                Syntax {
+                    line_comments: &["#"],
+                    block_comment: None,
+                    quotes: &["\"\"\"", "'''", "\"", "'"],
+                    docstrings: true,
+                }
+            },
+            Self::Rust => {
+                Syntax {
+                    line_comments: &["///", "//!", "//"],
+                    block_comment: Some(("/*", "*/")),
+                    quotes: &["\""],
+                    docstrings: false,
+                }
+            },
+        }
+    }
+}
+
+/// Convert a `source` file written in `language` into data annotations.
+///
+/// # Examples
+///
+/// ```
+/// # use languagetool_rust::check::DataAnnotation;
+/// # use languagetool_rust::parsers::source_code::{self, Language};
+/// let data = source_code::parse("/// Return `x`.\nfn f() {}\n", Language::Rust);
+///
+/// assert_eq!(
+///     data.annotation,
+///     vec![
+///         DataAnnotation::new_markup("/// ".to_string()),
+///         DataAnnotation::new_text("Return ".to_string()),
+///         DataAnnotation::new_interpreted_markup("`x`".to_string(), "X".to_string()),
+///         DataAnnotation::new_text(".\n".to_string()),
+///         DataAnnotation::new_interpreted_markup("fn f() {}\n".to_string(), "\n\n".to_string()),
+///     ]
+/// );
+/// ```
+#[must_use]
+pub fn parse(source: &str, language: Language) -> Data {
+    let syntax = language.syntax();
+    let mut builder = DataBuilder::default();
+    let mut code_start = 0;
+    let mut i = 0;
+
+    while i < source.len() {
+        let rest = &source[i..];
+        let previous = source[..i].chars().next_back();
+
+        if let Some(marker) = syntax
+            .line_comments
+            .iter()
+            .find(|marker| rest.starts_with(**marker))
+        {
+            push_code(&mut builder, &source[code_start..i]);
+            let end = rest.find('\n').map_or(source.len(), |n| i + n);
+            let mut text_start = i + marker.len();
+            if source[text_start..end].starts_with(' ') {
+                text_start += 1;
+            }
+            builder.push_markup(&source[i..text_start]);
+            push_comment_text(&mut builder, &source[text_start..end]);
+            i = (end + 1).min(source.len());
+            builder.push_text(&source[end..i]);
+            code_start = i;
+            continue;
+        }
+
+        if let Some((open, close)) = syntax
+            .block_comment
+            .filter(|(open, _)| rest.starts_with(open))
+        {
+            push_code(&mut builder, &source[code_start..i]);
+            let content_start = i + open.len();
+            let end = source[content_start..]
+                .find(close)
+                .map_or(source.len(), |n| content_start + n);
+            // Doc comments, e.g., `/**` or `/*!`
+            let marker_end =
+                if end > content_start && source[content_start..].starts_with(['*', '!']) {
+                    content_start + 1
+                } else {
+                    content_start
+                };
+            builder.push_markup(&source[i..marker_end]);
+            push_block_content(&mut builder, &source[marker_end..end], true);
+            i = (end + close.len()).min(source.len());
+            builder.push_markup(&source[end..i]);
+            code_start = i;
+            continue;
+        }
+
+        if let Some(literal) = find_literal(rest, previous, language, &syntax) {
+            let content_start = i + literal.open_len;
+            let end = content_start + literal.len(&source[content_start..]);
+
+            let line_start = source[..i].rfind('\n').map_or(0, |n| n + 1);
+            let is_docstring = syntax.docstrings
+                && literal.close.len() == 3
+                && source[line_start..i].trim().is_empty()
+                && end - content_start >= 3
+                && source[content_start..end].ends_with(&literal.close);
+
+            if is_docstring {
+                push_code(&mut builder, &source[code_start..i]);
+                builder.push_markup(&source[i..content_start]);
+                push_block_content(&mut builder, &source[content_start..end - 3], false);
+                builder.push_markup(&source[end - 3..end]);
+                code_start = end;
+            }
+            i = end;
+            continue;
+        }
+
+        i += rest.chars().next().map_or(1, char::len_utf8);
+    }
+
+    push_code(&mut builder, &source[code_start..]);
+    builder.build()
+}
+
+/// Push code, interpreted as a paragraph break unless it is only whitespace,
+/// e.g., the indentation before a comment.
+fn push_code(builder: &mut DataBuilder, code: &str) {
+    if code.trim().is_empty() {
+        builder.push_markup(code);
+    } else {
+        builder.push_paragraph_markup(code);
+    }
+}
+
+/// Push the content of a block comment or docstring, with the indentation
+/// of each line (and its leading `*`, if `stars` is true) as markup.
+fn push_block_content(builder: &mut DataBuilder, content: &str, stars: bool) {
+    for (i, line) in content.split_inclusive('\n').enumerate() {
+        let mut text = line.trim_start_matches([' ', '\t']);
+        if i > 0 && stars {
+            if let Some(after_star) = text.strip_prefix('*') {
+                text = after_star.strip_prefix(' ').unwrap_or(after_star);
+            }
+        }
+        builder.push_markup(&line[..line.len() - text.len()]);
+        push_comment_text(builder, text);
+    }
+}
+
+/// Push the text of a comment, with code spans interpreted as
+/// [`PLACEHOLDER`].
+fn push_comment_text(builder: &mut DataBuilder, text: &str) {
+    let mut rest = text;
+
+    while let Some(start) = rest.find('`') {
+        let Some(len) = rest[start + 1..].find(['`', '\n']) else {
+            break;
+        };
+        if !rest[start + 1 + len..].starts_with('`') {
+            break;
+        }
+        builder.push_text(&rest[..start]);
+        builder.push_interpreted_markup(&rest[start..start + len + 2], PLACEHOLDER);
+        rest = &rest[start + len + 2..];
+    }
+
+    builder.push_text(rest);
+}
+
+/// String or character literal.
+struct Literal {
+    /// Length of the prefix and opening delimiter, e.g., `r#"`.
+    open_len: usize,
+    /// Closing delimiter.
+    close: String,
+    /// Whether backslashes escape the next character.
+    escapes: bool,
+    /// Whether the literal can span multiple lines.
+    multiline: bool,
+}
+
+impl Literal {
+    /// Return the length of the content of the literal, starting at `rest`,
+    /// and of its closing delimiter, or up to the end of the line (or of
+    /// `rest`) if it is not closed.
+    fn len(&self, rest: &str) -> usize {
+        let mut escaped = false;
+
+        for (i, c) in rest.char_indices() {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' && self.escapes {
+                escaped = true;
+            } else if c == '\n' && !self.multiline {
+                return i;
+            } else if rest[i..].starts_with(&self.close) {
+                return i + self.close.len();
+            }
+        }
+        rest.len()
+    }
+}
+
+/// Return the string or character literal starting `rest`, if any.
+fn find_literal(
+    rest: &str,
+    previous: Option<char>,
+    language: Language,
+    syntax: &Syntax,
+) -> Option<Literal> {
+    let prefix_len = match language {
+        Language::Python => {
+            let letters = rest.len()
+                - rest
+                    .trim_start_matches(['r', 'R', 'b', 'B', 'u', 'U', 'f', 'F'])
+                    .len();
+            letters.min(2)
+        },
+        Language::Rust if rest.starts_with("br") => 2,
+        Language::Rust if rest.starts_with(['b', 'r']) => 1,
+        _ => 0,
+    };
+    // Prefixes cannot be the end of an identifier
+    if prefix_len > 0 && previous.is_some_and(|c| c.is_alphanumeric() || c == '_') {
+        return None;
+    }
+    let after_prefix = &rest[prefix_len..];
+
+    if language == Language::Rust {
+        if rest[..prefix_len].ends_with('r') {
+            let hashes = after_prefix.len() - after_prefix.trim_start_matches('#').len();
+            return after_prefix[hashes..].starts_with('"').then(|| {
+                Literal {
+                    open_len: prefix_len + hashes + 1,
+                    close: format!("\"{}", "#".repeat(hashes)),
+                    escapes: false,
+                    multiline: true,
+                }
+            });
+        }
+        if let Some(after_quote) = after_prefix.strip_prefix('\'') {
+            // Character literals, as opposed to lifetimes
+            let mut chars = after_quote.chars();
+            let is_char = match chars.next() {
+                Some('\\') => true,
+                Some(_) => chars.next() == Some('\''),
+                None => false,
+            };
+            return is_char.then(|| {
+                Literal {
+                    open_len: prefix_len + 1,
+                    close: "'".to_string(),
+                    escapes: true,
+                    multiline: false,
+                }
+            });
+        }
+    }
+
+    syntax
+        .quotes
+        .iter()
+        .find(|quote| after_prefix.starts_with(**quote))
+        .map(|quote| {
+            Literal {
+                open_len: prefix_len + quote.len(),
+                close: quote.to_string(),
+                escapes: true,
+                multiline: quote.len() == 3 || *quote == "`" || language == Language::Rust,
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::test_utils::{checked_text, source_of};
+
+    #[test]
+    fn test_parse_rust() {
+        let source = concat!(
+            "//! Crate docs.\n",
+            "\n",
+            "/// Return the `answer`.\n",
+            "/// Always the same.\n",
+            "fn answer<'a>(s: &'a str) -> usize {\n",
+            "    let _ = (\"// not a comment\", r#\"/* \"nor\" this */\"#, '\\'', '\"');\n",
+            "    42 // The answer.\n",
+            "}\n",
+            "/**\n",
+            " * Block comment.\n",
+            " */\n",
+        );
+        let data = parse(source, Language::Rust);
+
+        assert_eq!(source_of(&data), source);
+        assert_eq!(
+            checked_text(&data),
+            "Crate docs.\nReturn the X.\nAlways the same.\n\n\nThe answer.\n\n\n\nBlock comment.\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_python() {
+        let source = concat!(
+            "# A comment.\n",
+            "def f():\n",
+            "    \"\"\"Docstring.\n",
+            "\n",
+            "    More details.\n",
+            "    \"\"\"\n",
+            "    return f\"# {'''not''' } a comment\"\n",
+        );
+        let data = parse(source, Language::Python);
+
+        assert_eq!(source_of(&data), source);
+        assert_eq!(
+            checked_text(&data),
+            "A comment.\n\n\nDocstring.\n\nMore details.\n\n\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_c_and_javascript() {
+        let source = "int x = '\"'; /* Some\n   text. */\nchar *s = \"/* no */\";\n";
+        let data = parse(source, Language::C);
+        assert_eq!(source_of(&data), source);
+        assert_eq!(checked_text(&data), "\n\nSome\ntext. \n\n");
+
+        let source = "const s = `// ${x}\n`; // Done.\n";
+        let data = parse(source, Language::JavaScript);
+        assert_eq!(source_of(&data), source);
+        assert_eq!(checked_text(&data), "\n\nDone.\n");
+    }
+
+    #[test]
+    fn test_parse_unclosed() {
+        for (source, language) in [
+            ("/* never", Language::C),
+            ("\"never", Language::JavaScript),
+            ("r#\"never\"", Language::Rust),
+            ("'''never", Language::Python),
+            ("// `code", Language::Rust),
+        ] {
+            assert_eq!(source_of(&parse(source, language)), source);
+        }
+    }
+}
@@ -0,0 +1,248 @@
+//! Convert TOML documents into [`Data`](crate::check::Data) annotations.
+//!
+//! Only string values whose key is listed in [`ParseOptions::keys`] are
+//! checked, each as a separate paragraph, and everything else is sent as
+//! markup. Both single-line and multi-line strings are supported, but strings
+//! inside arrays or inline tables are never checked.
+
+use super::{Document, ParseOptions};
+use crate::check::DataBuilder;
+use std::collections::HashMap;
+
+/// Convert a TOML `source` into a document.
+///
+/// # Examples
+///
+/// ```
+/// # use languagetool_rust::parsers::{toml, ParseOptions};
+/// let source = "[package]\nname = \"ltrs\"\ndescription = \"A LanguageTool client.\"\n";
+/// let document = toml::parse(source, &ParseOptions::default());
+///
+/// assert_eq!(
+///     document.key_paths,
+///     vec![(39..61, "package.description".to_string())]
+/// );
+/// ```
+#[must_use]
+pub fn parse(source: &str, options: &ParseOptions) -> Document {
+    let mut builder = DataBuilder::default();
+    let mut key_paths = Vec::new();
+    let mut table = String::new();
+    let mut array_tables: HashMap<String, usize> = HashMap::new();
+    let mut pos = 0;
+
+    while pos < source.len() {
+        let line_end = source[pos..]
+            .find('\n')
+            .map_or(source.len(), |i| pos + i + 1);
+        let line = &source[pos..line_end];
+        let trimmed = line.trim_start();
+
+        if let Some(header) = trimmed.strip_prefix("[[") {
+            let name = header.split("]]").next().unwrap_or_default();
+            let name = parse_key(name).join(".");
+            let index = array_tables.entry(name.clone()).or_insert(0);
+            table = format!("{name}[{index}]");
+            *index += 1;
+        } else if let Some(header) = trimmed.strip_prefix('[') {
+            let name = header.split(']').next().unwrap_or_default();
+            table = parse_key(name).join(".");
+        } else if let Some((key, value_start)) = split_key_value(line) {
+            let path = match table.as_str() {
+                "" => key.join("."),
+                table => format!("{table}.{}", key.join(".")),
+            };
+            let value_start = pos + value_start;
+
+            if let Some((content, content_end)) = find_string(source, value_start) {
+                // The value ends at the end of the line where the string ends
+                let value_end = source[content_end..]
+                    .find('\n')
+                    .map_or(source.len(), |i| content_end + i + 1);
+
+                if options.is_checked(&path) {
+                    // Each value is checked as a separate paragraph
+                    builder.push_paragraph_markup(&source[pos..content.start]);
+                    if source[value_start..].starts_with('"') {
+                        builder.push_escaped_text(&source[content.clone()]);
+                    } else {
+                        builder.push_text(&source[content.clone()]);
+                    }
+                    builder.push_markup(&source[content.end..value_end]);
+                    key_paths.push((content, path));
+                } else {
+                    builder.push_markup(&source[pos..value_end]);
+                }
+                pos = value_end;
+                continue;
+            }
+        }
+
+        builder.push_markup(line);
+        pos = line_end;
+    }
+
+    Document::with_key_paths(builder.build(), source, key_paths)
+}
+
+/// Split a dotted key into its parts, removing quotes.
+fn parse_key(key: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut rest = key.trim();
+
+    while !rest.is_empty() {
+        let (part, next) = match rest.chars().next() {
+            Some(quote @ ('"' | '\'')) => {
+                match rest[1..].find(quote) {
+                    Some(i) => (rest[1..=i].to_string(), &rest[i + 2..]),
+                    // Unterminated quoted key, up to the end
+                    None => (rest[1..].to_string(), ""),
+                }
+            },
+            _ => {
+                let end = rest.find('.').unwrap_or(rest.len());
+                (rest[..end].trim().to_string(), &rest[end..])
+            },
+        };
+        parts.push(part);
+        rest = next.trim_start().trim_start_matches('.').trim_start();
+    }
+    parts
+}
+
+/// Split a `key = value` line, returning the parsed key and the byte index at
+/// which the value starts.
+fn split_key_value(line: &str) -> Option<(Vec<String>, usize)> {
+    let trimmed = line.trim_start();
+    if trimmed.is_empty() || trimmed.starts_with('#') {
+        return None;
+    }
+
+    let mut quote = None;
+    for (i, c) in line.char_indices() {
+        match (c, quote) {
+            ('"' | '\'', None) => quote = Some(c),
+            (c, Some(q)) if c == q => quote = None,
+            ('=', None) => {
+                let value = &line[i + 1..];
+                let value_start = i + 1 + value.len() - value.trim_start().len();
+                return Some((parse_key(&line[..i]), value_start));
+            },
+            _ => {},
+        }
+    }
+    None
+}
+
+/// Find the string starting at byte index `start` in `source`, if any, and
+/// return the byte range of its content and the byte index after its closing
+/// delimiter.
+fn find_string(source: &str, start: usize) -> Option<(std::ops::Range<usize>, usize)> {
+    let rest = &source[start..];
+    let delimiter = ["\"\"\"", "'''", "\"", "'"]
+        .into_iter()
+        .find(|delimiter| rest.starts_with(delimiter))?;
+    let content_start = start + delimiter.len();
+    let escapes = delimiter.starts_with('"');
+
+    let mut search = content_start;
+    loop {
+        let i = search + source[search..].find(delimiter)?;
+        if delimiter.len() == 1 && source[content_start..i].contains('\n') {
+            return None;
+        }
+        let backslashes = source[content_start..i]
+            .chars()
+            .rev()
+            .take_while(|c| *c == '\\')
+            .count();
+        if escapes && backslashes % 2 == 1 {
+            search = i + 1;
+            continue;
+        }
+        return Some((content_start..i, i + delimiter.len()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::test_utils::{checked_text, source_of};
+
+    #[test]
+    fn test_parse() {
+        let source = r#"title = "Top level"
+# description = "A comment"
+
+[package]
+name = "ltrs"
+description = "Say \"hi\"." # trailing comment
+
+[[bin]]
+name = "a"
+summary = '''
+Multi-line
+summary'''
+
+[[bin]]
+"summary" = 'Another bin'
+keywords = ["description"]
+"#;
+        let document = parse(source, &ParseOptions::default());
+
+        assert_eq!(source_of(&document.data), source);
+        assert_eq!(
+            checked_text(&document.data),
+            "\n\nTop level\n\nSay \"hi\".\n\n\nMulti-line\nsummary\n\nAnother bin"
+        );
+        let paths: Vec<&str> = document
+            .key_paths
+            .iter()
+            .map(|(_, path)| path.as_str())
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                "title",
+                "package.description",
+                "bin[0].summary",
+                "bin[1].summary"
+            ]
+        );
+        assert_eq!(document.key_path_at(12), Some("title"));
+        assert_eq!(document.key_path_at(25), None);
+    }
+
+    #[test]
+    fn test_parse_custom_keys() {
+        let options = ParseOptions {
+            keys: vec!["package.name".to_string()],
+            ..Default::default()
+        };
+        let document = parse(
+            "[package]\nname = \"ltrs\"\n[bin]\nname = \"b\"\n",
+            &options,
+        );
+
+        assert_eq!(checked_text(&document.data), "\n\nltrs");
+        assert_eq!(
+            document.key_paths,
+            vec![(18..22, "package.name".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_parse_unclosed() {
+        for source in [
+            "description = \"unclosed",
+            "description = '''\nnever",
+            "[table",
+            "\n\n[\"\"\"🦀",
+        ] {
+            let document = parse(source, &ParseOptions::default());
+            assert_eq!(source_of(&document.data), source);
+        }
+        assert_eq!(parse_key("\"clé"), vec!["clé"]);
+        assert_eq!(parse_key("a.\"b.c\".d"), vec!["a", "b.c", "d"]);
+    }
+}
@@ -0,0 +1,490 @@
+//! Convert Typst sources into [`Data`] annotations.
+//!
+//! Headings, list and term markers, emphasis delimiters, escaping backslashes,
+//! comments and labels, e.g., `<intro>`, are sent as markup. Raw blocks and
+//! code, such as `#set` and `#show` rules, are sent as markup, except for the
+//! content blocks of function calls, e.g., `[docs]` in `#emph[docs]`, which
+//! are checked as text.
+//!
+//! Inline math, raw text, references and citations, e.g., `@smith2024`, bare
+//! URLs and interpolated values, e.g., `#version`, are interpreted as
+//! [`PLACEHOLDER`], so that the surrounding sentence remains correct.
+
+use crate::check::{Data, DataBuilder};
+
+/// Token that inline math, raw text, references, URLs and interpolated values
+/// are interpreted as.
+pub const PLACEHOLDER: &str = "X";
+
+/// Keywords whose statement, up to the end of the line, is sent as markup.
+const STATEMENT_KEYWORDS: &[&str] = &[
+    "for", "if", "import", "include", "let", "return", "set", "show", "while",
+];
+
+/// Convert a Typst `source` into data annotations.
+///
+/// # Examples
+///
+/// ```
+/// # use languagetool_rust::check::DataAnnotation;
+/// # use languagetool_rust::parsers::typst;
+/// let data = typst::parse("#set text(lang: \"en\")\n= Intro <intro>\nLet $x$ be *big* @knuth.\n");
+///
+/// assert_eq!(
+///     data.annotation,
+///     vec![
+///         DataAnnotation::new_markup("#set text(lang: \"en\")".to_string()),
+///         DataAnnotation::new_text("\n".to_string()),
+///         DataAnnotation::new_markup("= ".to_string()),
+///         DataAnnotation::new_text("Intro ".to_string()),
+///         DataAnnotation::new_markup("<intro>".to_string()),
+///         DataAnnotation::new_text("\nLet ".to_string()),
+///         DataAnnotation::new_interpreted_markup("$x$".to_string(), "X".to_string()),
+///         DataAnnotation::new_text(" be ".to_string()),
+///         DataAnnotation::new_markup("*".to_string()),
+///         DataAnnotation::new_text("big".to_string()),
+///         DataAnnotation::new_markup("*".to_string()),
+///         DataAnnotation::new_text(" ".to_string()),
+///         DataAnnotation::new_interpreted_markup("@knuth".to_string(), "X".to_string()),
+///         DataAnnotation::new_text(".\n".to_string()),
+///     ]
+/// );
+/// ```
+#[must_use]
+pub fn parse(source: &str) -> Data {
+    let mut builder = DataBuilder::default();
+    push_content(&mut builder, source);
+    builder.build()
+}
+
+/// Kind of markup.
+enum Markup {
+    /// Plain markup.
+    Plain,
+    /// Markup interpreted as [`PLACEHOLDER`].
+    Placeholder,
+    /// Markup interpreted as the given text.
+    Interpreted(&'static str),
+    /// Backslash escaping the next character.
+    Escape,
+    /// Function call, of which content blocks start and end at the given
+    /// byte ranges.
+    Call(Vec<(usize, usize)>),
+}
+
+/// Push content, i.e., Typst markup mode.
+fn push_content(builder: &mut DataBuilder, content: &str) {
+    let mut text_start = 0;
+    let mut i = 0;
+    let mut line_start = true;
+
+    while i < content.len() {
+        let rest = &content[i..];
+        let c = rest.chars().next().unwrap_or_default();
+        let previous = content[..i].chars().next_back();
+
+        if line_start && !c.is_whitespace() {
+            line_start = false;
+            if let Some(len) = block_marker_len(rest) {
+                builder.push_text(&content[text_start..i]);
+                builder.push_markup(&rest[..len]);
+                i += len;
+                text_start = i;
+                continue;
+            }
+        }
+
+        let markup: Option<(usize, Markup)> = match c {
+            '\n' => {
+                line_start = true;
+                None
+            },
+            '\\' => {
+                let escaped = rest[1..].chars().next();
+                if escaped.is_some_and(|c| !c.is_whitespace()) {
+                    Some((1, Markup::Escape))
+                } else {
+                    // Line break
+                    Some((1, Markup::Interpreted("\n")))
+                }
+            },
+            '/' if rest.starts_with("//") => {
+                Some((rest.find('\n').unwrap_or(rest.len()), Markup::Plain))
+            },
+            '/' if rest.starts_with("/*") => {
+                Some((
+                    rest.find("*/").map_or(rest.len(), |end| end + 2),
+                    Markup::Plain,
+                ))
+            },
+            '`' => Some(raw_len(rest)),
+            '$' => {
+                Some((
+                    closing(rest, '$').map_or(rest.len(), |end| end + 1),
+                    Markup::Placeholder,
+                ))
+            },
+            '<' => label_len(rest).map(|len| (len, Markup::Plain)),
+            '@' if !previous.is_some_and(char::is_alphanumeric) => {
+                reference_len(rest).map(|len| (len, Markup::Placeholder))
+            },
+            '#' => Some(code_len(rest)),
+            '*' | '_' if is_delimiter(rest, previous) => Some((1, Markup::Plain)),
+            '~' => Some((1, Markup::Interpreted("\u{a0}"))),
+            '-' if rest.starts_with("---") => Some((3, Markup::Interpreted("—"))),
+            '-' if rest.starts_with("--") => Some((2, Markup::Interpreted("–"))),
+            'h' if !previous.is_some_and(char::is_alphanumeric)
+                && (rest.starts_with("https://") || rest.starts_with("http://")) =>
+            {
+                let len = rest
+                    .find(|c: char| c.is_whitespace() || matches!(c, ']' | ')' | '<'))
+                    .unwrap_or(rest.len());
+                let url = rest[..len].trim_end_matches(['.', ',', ';', ':', '!', '?']);
+                Some((url.len(), Markup::Placeholder))
+            },
+            _ => None,
+        };
+
+        let Some((len, kind)) = markup else {
+            i += c.len_utf8();
+            continue;
+        };
+
+        builder.push_text(&content[text_start..i]);
+        let markup = &rest[..len];
+        match kind {
+            Markup::Plain => builder.push_markup(markup),
+            Markup::Placeholder => builder.push_interpreted_markup(markup, PLACEHOLDER),
+            Markup::Interpreted(interpret_as) => {
+                builder.push_interpreted_markup(markup, interpret_as);
+            },
+            Markup::Escape => {
+                builder.push_markup(markup);
+                // The escaped character is text, and never markup
+                i += len;
+                text_start = i;
+                i += rest[1..].chars().next().map_or(0, char::len_utf8);
+                continue;
+            },
+            Markup::Call(blocks) => {
+                let mut end = 0;
+                for (start, block_end) in blocks {
+                    builder.push_markup(&markup[end..=start]);
+                    push_content(builder, &markup[start + 1..block_end]);
+                    end = block_end;
+                }
+                builder.push_markup(&markup[end..]);
+            },
+        }
+        i += len;
+        text_start = i;
+    }
+
+    builder.push_text(&content[text_start..]);
+}
+
+/// Return the length of the heading, list or term marker that starts `line`,
+/// e.g., `== `, `- `, `+ ` or `/ `.
+fn block_marker_len(line: &str) -> Option<usize> {
+    let marker_len = if line.starts_with('=') {
+        line.len() - line.trim_start_matches('=').len()
+    } else if line.starts_with(['-', '+', '/']) && !line.starts_with("//") {
+        1
+    } else {
+        let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        if digits == 0 || !line[digits..].starts_with('.') {
+            return None;
+        }
+        digits + 1
+    };
+    let after = &line[marker_len..];
+    let spaces = after.len() - after.trim_start_matches([' ', '\t']).len();
+
+    (spaces > 0).then_some(marker_len + spaces)
+}
+
+/// Return the length and kind of the raw text that starts `rest`, e.g.,
+/// `` `code` `` or a raw block fenced by three or more backticks.
+fn raw_len(rest: &str) -> (usize, Markup) {
+    let ticks = rest.len() - rest.trim_start_matches('`').len();
+
+    match ticks {
+        1 => {
+            (
+                rest[1..].find('`').map_or(rest.len(), |end| end + 2),
+                Markup::Placeholder,
+            )
+        },
+        2 => (2, Markup::Plain),
+        _ => {
+            let fence = &rest[..ticks];
+            let len = rest[ticks..]
+                .find(fence)
+                .map_or(rest.len(), |end| ticks + end + ticks);
+            let is_block = rest[..len].contains('\n');
+            (
+                len,
+                if is_block {
+                    Markup::Plain
+                } else {
+                    Markup::Placeholder
+                },
+            )
+        },
+    }
+}
+
+/// Return the length of the label that starts `rest`, e.g., `<intro>`.
+fn label_len(rest: &str) -> Option<usize> {
+    let end = rest.find('>')?;
+    let name = &rest[1..end];
+
+    (!name.is_empty() && name.chars().all(is_label_char)).then_some(end + 1)
+}
+
+/// Return the length of the reference or citation that starts `rest`, e.g.,
+/// `@intro` or `@smith2024[p. 7]`.
+fn reference_len(rest: &str) -> Option<usize> {
+    let name_len = rest[1..]
+        .find(|c: char| !is_label_char(c))
+        .unwrap_or(rest.len() - 1);
+    // A trailing dot or colon ends the sentence
+    let name = rest[1..1 + name_len].trim_end_matches(['.', ':']);
+    if name.is_empty() {
+        return None;
+    }
+
+    let len = 1 + name.len();
+    let supplement = rest[len..]
+        .starts_with('[')
+        .then(|| balanced_end(&rest[len..]))
+        .flatten();
+    Some(supplement.map_or(len, |end| len + end + 1))
+}
+
+/// Return whether `c` can be part of a label name.
+fn is_label_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '-' | ':' | '.')
+}
+
+/// Return whether the `*` or `_` that starts `rest` delimits strong or
+/// emphasized text.
+fn is_delimiter(rest: &str, previous: Option<char>) -> bool {
+    // Intraword underscores are literal, e.g., in `snake_case`
+    rest.starts_with('*')
+        || !(previous.is_some_and(char::is_alphanumeric)
+            && rest[1..].starts_with(char::is_alphanumeric))
+}
+
+/// Return the length and kind of the code expression that starts `rest`,
+/// e.g., `#set text(lang: "en")`, `#emph[docs]` or `#version`.
+fn code_len(rest: &str) -> (usize, Markup) {
+    let after = &rest[1..];
+    let name_len = after
+        .find(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | '-' | '.')))
+        .unwrap_or(after.len());
+    let name = after[..name_len].trim_end_matches(['.', '-']);
+
+    if name.is_empty() {
+        // Code block, e.g., `#{ ... }`, or content block, e.g., `#[ ... ]`
+        return match after.chars().next() {
+            Some('{' | '(') => {
+                (
+                    balanced_end(after).map_or(rest.len(), |end| end + 2),
+                    Markup::Plain,
+                )
+            },
+            Some('[') => {
+                match balanced_end(after) {
+                    Some(end) => (end + 2, Markup::Call(vec![(1, end + 1)])),
+                    // Unclosed content block, up to the end of the text
+                    None => (rest.len(), Markup::Call(vec![(1, rest.len())])),
+                }
+            },
+            _ => (1, Markup::Plain),
+        };
+    }
+    if STATEMENT_KEYWORDS.contains(&name) {
+        return (1 + statement_len(after), Markup::Plain);
+    }
+
+    // Arguments and trailing content blocks of a function call
+    let mut len = 1 + name.len();
+    let mut blocks = Vec::new();
+    while let Some(open) = rest[len..]
+        .chars()
+        .next()
+        .filter(|c| matches!(c, '(' | '['))
+    {
+        let Some(end) = balanced_end(&rest[len..]) else {
+            if open == '[' {
+                // Unclosed trailing content block, up to the end of the text
+                blocks.push((len, rest.len()));
+                return (rest.len(), Markup::Call(blocks));
+            }
+            return (rest.len(), Markup::Plain);
+        };
+        if open == '[' {
+            blocks.push((len, len + end));
+        }
+        len += end + 1;
+    }
+
+    if len == 1 + name.len() {
+        (len, Markup::Placeholder)
+    } else {
+        (len, Markup::Call(blocks))
+    }
+}
+
+/// Return the length of the statement that starts `code`, i.e., up to the end
+/// of the line, or further if brackets are still open.
+fn statement_len(code: &str) -> usize {
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (i, c) in code.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if in_string {
+            match c {
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {},
+            }
+        } else {
+            match c {
+                '"' => in_string = true,
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' | '}' => depth = depth.saturating_sub(1),
+                '\n' if depth == 0 => return i,
+                _ => {},
+            }
+        }
+    }
+    code.len()
+}
+
+/// Return the index of the bracket closing the one that starts `s`, skipping
+/// brackets in strings.
+fn balanced_end(s: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (i, c) in s.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if in_string {
+            in_string = c != '"';
+        } else {
+            match c {
+                '"' => in_string = true,
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' | '}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(i);
+                    }
+                },
+                _ => {},
+            }
+        }
+    }
+    None
+}
+
+/// Return the index of the first unescaped `delimiter` after the one that
+/// starts `s`.
+fn closing(s: &str, delimiter: char) -> Option<usize> {
+    let mut escaped = false;
+
+    for (i, c) in s.char_indices().skip(1) {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == delimiter {
+            return Some(i);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::test_utils::{checked_text, source_of};
+
+    #[test]
+    fn test_parse_roundtrip() {
+        let source = concat!(
+            "#import \"template.typ\": *\n",
+            "#show heading: it => [\n",
+            "  #set align(center)\n",
+            "  #it\n",
+            "]\n",
+            "\n",
+            "= Introduction <intro>\n",
+            "// A comment with a error\n",
+            "As shown in @intro and @smith2024[p. 7], the sum $sum_(i=1)^n i$ is _large_.\n",
+            "Use `teh` or #link(\"https://typst.app\")[the web app] at https://typst.app.\n",
+            "Version #version uses snake_case -- and~more.\\\n",
+            "- First item\n",
+            "+ Second item\n",
+            "/ Term: Description\n",
+            "#figure(image(\"a.png\"), caption: [A figure])\n",
+            "```rust\n",
+            "fn main() { teh(); }\n",
+            "```\n",
+            "/* Block\n",
+            "comment */ Escaped \\#hash and \\$dollar.\n",
+        );
+        let data = parse(source);
+
+        assert_eq!(source_of(&data), source);
+        assert_eq!(
+            checked_text(&data),
+            concat!(
+                "\n",
+                "\n",
+                "\n",
+                "Introduction \n",
+                "\n",
+                "As shown in X and X, the sum X is large.\n",
+                "Use X or the web app at X.\n",
+                "Version X uses snake_case – and\u{a0}more.\n\n",
+                "First item\n",
+                "Second item\n",
+                "Term: Description\n",
+                "\n",
+                "\n",
+                " Escaped #hash and $dollar.\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_unclosed() {
+        for source in [
+            "$x",
+            "`code",
+            "```\nnever closed",
+            "/* never closed",
+            "#emph[never closed",
+            "#set text(",
+            "@ref[never closed",
+            "\\",
+            "#[",
+            "\"#[",
+            "#[content never closed",
+            "#emph(\"a\")[é",
+        ] {
+            assert_eq!(source_of(&parse(source)), source);
+        }
+        assert_eq!(checked_text(&parse("#[Some text")), "Some text");
+    }
+}
@@ -0,0 +1,383 @@
+//! Convert YAML documents into [`Data`](crate::check::Data) annotations.
+//!
+//! Only string values whose key is listed in [`ParseOptions::keys`] are
+//! checked, each as a separate paragraph, and everything else is sent as
+//! markup. Block-style mappings and sequences are supported, with plain,
+//! quoted, literal (`|`) and folded (`>`) scalars, but flow collections and
+//! scalars spanning multiple lines without a block indicator are never
+//! checked.
+
+use super::{Document, ParseOptions};
+use crate::check::DataBuilder;
+
+/// Convert a YAML `source` into a document.
+///
+/// # Examples
+///
+/// ```
+/// # use languagetool_rust::parsers::{yaml, ParseOptions};
+/// let source = "metadata:\n  name: ltrs\n  description: A LanguageTool client.\n";
+/// let document = yaml::parse(source, &ParseOptions::default());
+///
+/// assert_eq!(
+///     document.key_paths,
+///     vec![(38..60, "metadata.description".to_string())]
+/// );
+/// ```
+#[must_use]
+pub fn parse(source: &str, options: &ParseOptions) -> Document {
+    let mut builder = DataBuilder::default();
+    let mut key_paths = Vec::new();
+    let mut stack: Vec<Node> = Vec::new();
+
+    let mut lines = Vec::new();
+    let mut start = 0;
+    for line in source.split_inclusive('\n') {
+        lines.push((start, line));
+        start += line.len();
+    }
+
+    let mut i = 0;
+    while i < lines.len() {
+        let (start, line) = lines[i];
+        i += 1;
+        let content = line.trim_end_matches(['\n', '\r']);
+        let body = content.trim_start_matches(' ');
+
+        if ["---", "..."].iter().any(|marker| body.starts_with(marker)) {
+            stack.clear();
+        }
+        if body.is_empty() || body.starts_with(['#', '%']) || body.starts_with("---") {
+            builder.push_markup(line);
+            continue;
+        }
+
+        let Some((node_indent, value_start)) = parse_line(&mut stack, content) else {
+            builder.push_markup(line);
+            continue;
+        };
+        let path = key_path(&stack);
+        let checked = options.is_checked(&path);
+        let value = &content[value_start..];
+
+        if value.starts_with(['|', '>']) {
+            // Block scalar: more indented (or blank) lines that follow
+            let block_start = i;
+            while i < lines.len() {
+                let next = lines[i].1.trim_end_matches(['\n', '\r']);
+                let indent = next.len() - next.trim_start_matches(' ').len();
+                if !next.trim().is_empty() && indent <= node_indent {
+                    break;
+                }
+                i += 1;
+            }
+
+            if !checked || block_start == i {
+                builder.push_markup(line);
+                for (_, line) in &lines[block_start..i] {
+                    builder.push_markup(line);
+                }
+                continue;
+            }
+
+            builder.push_paragraph_markup(line);
+            for (_, line) in &lines[block_start..i] {
+                let text = line.trim_start_matches(' ');
+                builder.push_markup(&line[..line.len() - text.len()]);
+                builder.push_text(text);
+            }
+            let (last_start, last_line) = lines[i - 1];
+            key_paths.push((lines[block_start].0..last_start + last_line.len(), path));
+            continue;
+        }
+
+        let Some((content_range, style)) = find_scalar(value) else {
+            builder.push_markup(line);
+            continue;
+        };
+        if !checked {
+            builder.push_markup(line);
+            continue;
+        }
+
+        let content_start = value_start + content_range.start;
+        let content_end = value_start + content_range.end;
+        builder.push_paragraph_markup(&line[..content_start]);
+        let scalar = &line[content_start..content_end];
+        match style {
+            Style::Plain => builder.push_text(scalar),
+            Style::DoubleQuoted => builder.push_escaped_text(scalar),
+            Style::SingleQuoted => {
+                for (i, part) in scalar.split("''").enumerate() {
+                    if i > 0 {
+                        builder.push_interpreted_markup("''", "'");
+                    }
+                    builder.push_text(part);
+                }
+            },
+        }
+        builder.push_markup(&line[content_end..]);
+        key_paths.push((start + content_start..start + content_end, path));
+    }
+
+    Document::with_key_paths(builder.build(), source, key_paths)
+}
+
+/// Node of the path leading to the current line.
+#[derive(Debug)]
+struct Node {
+    /// Indentation of the node's key or dash.
+    indent: usize,
+    segment: Segment,
+    /// Whether the node's value is on the following lines.
+    open: bool,
+}
+
+/// Part of a key path.
+#[derive(Debug)]
+enum Segment {
+    /// Mapping key.
+    Key(String),
+    /// Index in a sequence.
+    Index(usize),
+}
+
+/// Join the segments of `stack` into a key path, e.g., `items[0].title`.
+fn key_path(stack: &[Node]) -> String {
+    let mut path = String::new();
+    for node in stack {
+        match &node.segment {
+            Segment::Key(key) if path.is_empty() => path.push_str(key),
+            Segment::Key(key) => {
+                path.push('.');
+                path.push_str(key);
+            },
+            Segment::Index(index) => path.push_str(&format!("[{index}]")),
+        }
+    }
+    path
+}
+
+/// Return whether `body` starts a sequence item.
+fn is_item(body: &str) -> bool {
+    body == "-" || body.starts_with("- ")
+}
+
+/// Update `stack` with the keys and sequence items found on a line, and
+/// return the indentation of the innermost node and the byte index at which
+/// its scalar value starts, if the line has one.
+fn parse_line(stack: &mut Vec<Node>, content: &str) -> Option<(usize, usize)> {
+    let mut indent = content.len() - content.trim_start_matches(' ').len();
+    let mut item_indent = None;
+
+    loop {
+        let body = &content[indent..];
+
+        // Remove nodes that are not ancestors of the current line, keeping
+        // sequences whose items are not indented more than their key
+        while let Some(top) = stack.last() {
+            if top.indent < indent || (top.indent == indent && top.open && is_item(body)) {
+                break;
+            }
+            stack.pop();
+        }
+
+        if is_item(body) {
+            match stack.last_mut() {
+                Some(Node {
+                    indent: top_indent,
+                    segment: Segment::Index(index),
+                    ..
+                }) if *top_indent == indent => *index += 1,
+                _ => {
+                    stack.push(Node {
+                        indent,
+                        segment: Segment::Index(0),
+                        open: true,
+                    })
+                },
+            }
+            let rest = &body[1..];
+            if rest.trim().is_empty() {
+                return None;
+            }
+            item_indent = Some(indent);
+            indent += 1 + rest.len() - rest.trim_start_matches(' ').len();
+            continue;
+        }
+
+        if let Some((key, value_start)) = split_key(body) {
+            let value_start = indent + value_start;
+            let value = content[value_start..].trim();
+            let open = value.is_empty() || value.starts_with('#');
+            stack.push(Node {
+                indent,
+                segment: Segment::Key(key),
+                open,
+            });
+            return (!open).then_some((indent, value_start));
+        }
+
+        return item_indent.map(|item_indent| (item_indent, indent));
+    }
+}
+
+/// Split a `key: value` line, returning the key and the byte index at which
+/// the value starts.
+fn split_key(body: &str) -> Option<(String, usize)> {
+    let (key, colon) = match body.chars().next()? {
+        quote @ ('"' | '\'') => {
+            let end = body[1..].find(quote)? + 1;
+            (body[1..end].to_string(), end + 1)
+        },
+        '[' | '{' | '&' | '*' | '!' | '|' | '>' | '#' => return None,
+        _ => {
+            let colon = body
+                .match_indices(':')
+                .map(|(i, _)| i)
+                .find(|&i| body[i + 1..].is_empty() || body[i + 1..].starts_with(' '))?;
+            (body[..colon].trim_end().to_string(), colon)
+        },
+    };
+
+    let after = body[colon..].strip_prefix(':')?;
+    if !(after.is_empty() || after.starts_with(' ')) || key.contains(" #") {
+        return None;
+    }
+    let value_start = colon + 1 + after.len() - after.trim_start().len();
+    Some((key, value_start))
+}
+
+/// Style of a scalar on a single line.
+enum Style {
+    Plain,
+    /// Double-quoted, with escape sequences.
+    DoubleQuoted,
+    /// Single-quoted, where `''` stands for a single quote.
+    SingleQuoted,
+}
+
+/// Find the content of the string scalar in `value`, if any, and return its
+/// byte range and style.
+fn find_scalar(value: &str) -> Option<(std::ops::Range<usize>, Style)> {
+    match value.chars().next()? {
+        '"' => {
+            let mut escaped = false;
+            for (i, c) in value.char_indices().skip(1) {
+                match c {
+                    _ if escaped => escaped = false,
+                    '\\' => escaped = true,
+                    '"' => return Some((1..i, Style::DoubleQuoted)),
+                    _ => {},
+                }
+            }
+            None
+        },
+        '\'' => {
+            let mut chars = value.char_indices().skip(1).peekable();
+            while let Some((i, c)) = chars.next() {
+                if c == '\'' {
+                    if chars.peek().is_some_and(|(_, next)| *next == '\'') {
+                        chars.next();
+                    } else {
+                        return Some((1..i, Style::SingleQuoted));
+                    }
+                }
+            }
+            None
+        },
+        '[' | '{' | '&' | '*' | '!' | '@' | '`' => None,
+        _ => {
+            let end = value.find(" #").unwrap_or(value.len());
+            Some((0..value[..end].trim_end().len(), Style::Plain))
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::test_utils::{checked_text, source_of};
+
+    #[test]
+    fn test_parse() {
+        let source = "\
+# description: a comment
+title: Top level # trailing comment
+metadata:
+  name: ltrs
+  annotations:
+    description: \"Say \\\"hi\\\".\"
+items:
+  - title: 'It''s first'
+    tags: [a, b]
+  - name: second
+    summary: |
+      Multi-line
+      summary.
+
+    other: value
+---
+description: >-
+  Folded
+";
+        let document = parse(source, &ParseOptions::default());
+
+        assert_eq!(source_of(&document.data), source);
+        assert_eq!(
+            checked_text(&document.data),
+            "\n\nTop level\n\nSay \"hi\".\n\nIt's first\n\nMulti-line\nsummary.\n\n\n\nFolded\n"
+        );
+        let paths: Vec<&str> = document
+            .key_paths
+            .iter()
+            .map(|(_, path)| path.as_str())
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                "title",
+                "metadata.annotations.description",
+                "items[0].title",
+                "items[1].summary",
+                "description"
+            ]
+        );
+        assert_eq!(document.key_path_at(32), Some("title"));
+    }
+
+    #[test]
+    fn test_parse_sequence_of_strings() {
+        let options = ParseOptions {
+            keys: vec!["keywords".to_string()],
+            ..Default::default()
+        };
+        let document = parse("keywords:\n  - first\n  - \"second\"\nname: x\n", &options);
+
+        assert_eq!(checked_text(&document.data), "\n\nfirst\n\nsecond");
+        let not_indented = parse("keywords:\n- first\n- \"second\"\nname: x\n", &options);
+        assert_eq!(
+            not_indented
+                .key_paths
+                .iter()
+                .map(|(_, path)| path.as_str())
+                .collect::<Vec<_>>(),
+            vec!["keywords[0]", "keywords[1]"]
+        );
+        assert_eq!(
+            document.key_paths,
+            vec![
+                (14..19, "keywords[0]".to_string()),
+                (25..31, "keywords[1]".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_unclosed() {
+        for source in ["title: \"unclosed", "title: 'unclosed", "summary: |", "- "] {
+            let document = parse(source, &ParseOptions::default());
+            assert_eq!(source_of(&document.data), source);
+        }
+    }
+}
@@ -0,0 +1,326 @@
+//! Metadata of the rules and categories known to LanguageTool, so that their
+//! IDs can be passed to `--enabled-rules`, `--disabled-categories`, etc.
+//!
+//! Rules are fetched from the server's `configinfo` endpoint, which lists the
+//! rules of a given language. Servers that do not provide it, like the public
+//! API, can be replaced by [`RulesResponse::builtin`], a small table of
+//! commonly used rules.
+
+use crate::languages::LanguageCode;
+#[cfg(feature = "cli")]
+use clap::{Args, Parser, Subcommand};
+use serde::{Deserialize, Serialize};
+
+/// Rule categories, by ID.
+const CATEGORIES: &[(&str, &str)] = &[
+    ("CASING", "Capitalization"),
+    ("COLLOCATIONS", "Collocations"),
+    ("COMPOUNDING", "Compounding"),
+    ("CONFUSED_WORDS", "Commonly Confused Words"),
+    ("GRAMMAR", "Grammar"),
+    ("MISC", "Miscellaneous"),
+    ("NONSTANDARD_PHRASES", "Nonstandard Phrases"),
+    ("PUNCTUATION", "Punctuation"),
+    ("REDUNDANCY", "Redundant Phrases"),
+    ("SEMANTICS", "Semantics"),
+    ("STYLE", "Style"),
+    ("TYPOGRAPHY", "Typography"),
+    ("TYPOS", "Possible Typo"),
+];
+
+/// Commonly used rules, as `(id, category id, description, language)`, where
+/// a language of `None` means that the rule exists for all languages.
+const RULES: &[(&str, &str, &str, Option<&str>)] = &[
+    (
+        "COMMA_PARENTHESIS_WHITESPACE",
+        "TYPOGRAPHY",
+        "Use of whitespace before comma and before/after parentheses",
+        None,
+    ),
+    (
+        "DOUBLE_PUNCTUATION",
+        "PUNCTUATION",
+        "Use of two consecutive dots or commas",
+        None,
+    ),
+    (
+        "UNPAIRED_BRACKETS",
+        "PUNCTUATION",
+        "Unpaired braces, brackets, quotation marks and similar symbols",
+        None,
+    ),
+    (
+        "UPPERCASE_SENTENCE_START",
+        "CASING",
+        "Checks that a sentence starts with an uppercase letter",
+        None,
+    ),
+    (
+        "WHITESPACE_RULE",
+        "TYPOGRAPHY",
+        "Whitespace repetition (bad formatting)",
+        None,
+    ),
+    ("EN_A_VS_AN", "MISC", "Use of 'a' vs. 'an'", Some("en")),
+    (
+        "EN_COMPOUNDS",
+        "COMPOUNDING",
+        "Hyphenated words, e.g., 'case-sensitive' instead of 'case sensitive'",
+        Some("en"),
+    ),
+    (
+        "EN_CONTRACTION_SPELLING",
+        "TYPOS",
+        "Spelling of English contractions",
+        Some("en"),
+    ),
+    ("EN_QUOTES", "TYPOGRAPHY", "Smart quotes (“”)", Some("en")),
+    (
+        "EN_UNPAIRED_QUOTES",
+        "PUNCTUATION",
+        "Unpaired quotes",
+        Some("en"),
+    ),
+    (
+        "ENGLISH_WORD_REPEAT_BEGINNING_RULE",
+        "STYLE",
+        "Successive sentences beginning with the same word",
+        Some("en"),
+    ),
+    (
+        "ENGLISH_WORD_REPEAT_RULE",
+        "MISC",
+        "Word repetition (e.g. 'will will')",
+        Some("en"),
+    ),
+    (
+        "MORFOLOGIK_RULE_EN_GB",
+        "TYPOS",
+        "Possible spelling mistake (British English)",
+        Some("en"),
+    ),
+    (
+        "MORFOLOGIK_RULE_EN_US",
+        "TYPOS",
+        "Possible spelling mistake (American English)",
+        Some("en"),
+    ),
+    ("PASSIVE_VOICE", "STYLE", "Passive voice", Some("en")),
+    (
+        "TOO_LONG_SENTENCE",
+        "STYLE",
+        "Readability: sentence over 40 words",
+        Some("en"),
+    ),
+    (
+        "GERMAN_SPELLER_RULE",
+        "TYPOS",
+        "Possible spelling mistake",
+        Some("de"),
+    ),
+    (
+        "DE_AGREEMENT",
+        "GRAMMAR",
+        "Agreement of article, adjective and noun",
+        Some("de"),
+    ),
+    (
+        "DE_CASE",
+        "CASING",
+        "Capitalization of nouns and adjectives",
+        Some("de"),
+    ),
+    (
+        "FR_SPELLING_RULE",
+        "TYPOS",
+        "Possible spelling mistake",
+        Some("fr"),
+    ),
+    (
+        "FRENCH_WHITESPACE",
+        "TYPOGRAPHY",
+        "Non-breaking space before punctuation",
+        Some("fr"),
+    ),
+];
+
+/// Metadata of a rule.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct RuleInfo {
+    /// Rule ID, e.g., `"EN_A_VS_AN"`.
+    #[serde(rename = "ruleId")]
+    pub id: String,
+    /// Rule description.
+    #[serde(default)]
+    pub description: String,
+    /// Category ID, e.g., `"MISC"`.
+    #[serde(default)]
+    pub category_id: String,
+    /// Category name, e.g., `"Miscellaneous"`.
+    #[serde(default)]
+    pub category_name: String,
+    /// `true` if the rule must be enabled explicitly.
+    #[serde(default, rename = "isDefaultOff")]
+    pub default_off: bool,
+}
+
+/// List of rules, either returned by the server's `configinfo` endpoint, see
+/// [`ServerClient::rules`](crate::server::ServerClient::rules), or
+/// [`RulesResponse::builtin`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct RulesResponse {
+    /// Rules, in the order returned by the server.
+    #[serde(default)]
+    pub rules: Vec<RuleInfo>,
+}
+
+impl RulesResponse {
+    /// Return the bundled rules that exist for `language`, or all of them if
+    /// it is [`LanguageCode::AUTO`].
+    ///
+    /// This is only a small subset of LanguageTool's rules, meant to be used
+    /// when the server cannot list them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use languagetool_rust::{languages::LanguageCode, rule_info::RulesResponse};
+    /// let rules = RulesResponse::builtin(&LanguageCode::EN_US);
+    ///
+    /// assert!(rules.rules.iter().any(|rule| rule.id == "EN_A_VS_AN"));
+    /// assert!(rules.rules.iter().all(|rule| rule.id != "DE_CASE"));
+    /// ```
+    #[must_use]
+    pub fn builtin(language: &LanguageCode) -> Self {
+        let rules = RULES
+            .iter()
+            .filter(|(_, _, _, primary)| {
+                language.is_auto() || primary.map_or(true, |primary| primary == language.primary())
+            })
+            .map(|&(id, category_id, description, _)| {
+                RuleInfo {
+                    id: id.to_string(),
+                    description: description.to_string(),
+                    category_id: category_id.to_string(),
+                    category_name: CATEGORIES
+                        .iter()
+                        .find(|(id, _)| *id == category_id)
+                        .map(|(_, name)| name.to_string())
+                        .unwrap_or_default(),
+                    default_off: false,
+                }
+            })
+            .collect();
+        Self { rules }
+    }
+
+    /// Keep only the rules of a category, ignoring case.
+    #[must_use]
+    pub fn with_category(mut self, category_id: &str) -> Self {
+        self.rules
+            .retain(|rule| rule.category_id.eq_ignore_ascii_case(category_id));
+        self
+    }
+}
+
+/// Arguments to list rules.
+#[cfg(feature = "cli")]
+#[derive(Args, Clone, Debug)]
+pub struct RulesListArgs {
+    /// Language whose rules are listed.
+    #[clap(
+        short = 'l',
+        long,
+        default_value = "en-US",
+        value_parser = crate::check::parse_language_code
+    )]
+    pub language: LanguageCode,
+    /// If present, only list the rules of this category, e.g., `STYLE`.
+    #[clap(long, value_name = "ID")]
+    pub category: Option<String>,
+    /// If present, list the bundled rules without asking the server. These
+    /// are also listed, with a warning, if the server cannot list its rules.
+    #[clap(long)]
+    pub offline: bool,
+    /// If present, print raw JSON instead of one rule per line.
+    #[clap(short = 'r', long)]
+    pub raw: bool,
+}
+
+#[cfg(feature = "cli")]
+impl RulesListArgs {
+    /// Format `rules` as requested, i.e., one rule per line, as its ID, its
+    /// category ID and its description separated by tabs, or as pretty JSON.
+    ///
+    /// # Errors
+    ///
+    /// If the rules cannot be serialized.
+    pub fn format(&self, rules: &RulesResponse) -> serde_json::Result<String> {
+        if self.raw {
+            return serde_json::to_string_pretty(rules);
+        }
+        Ok(rules
+            .rules
+            .iter()
+            .map(|rule| format!("{}\t{}\t{}", rule.id, rule.category_id, rule.description))
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+}
+
+/// Rules' subcommand.
+#[cfg(feature = "cli")]
+#[derive(Clone, Debug, Subcommand)]
+pub enum RulesSubcommand {
+    /// List the rules of a language.
+    List(RulesListArgs),
+}
+
+/// Discover the IDs of rules and categories.
+#[cfg(feature = "cli")]
+#[derive(Debug, Parser)]
+pub struct RulesCommand {
+    /// Subcommand.
+    #[command(subcommand)]
+    pub subcommand: RulesSubcommand,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_rules() {
+        let all = RulesResponse::builtin(&LanguageCode::AUTO);
+        assert_eq!(all.rules.len(), RULES.len());
+        assert!(all.rules.iter().all(|rule| !rule.category_name.is_empty()));
+
+        let german = RulesResponse::builtin(&LanguageCode::DE_DE).with_category("casing");
+        let ids: Vec<&str> = german.rules.iter().map(|rule| rule.id.as_str()).collect();
+        assert_eq!(ids, ["UPPERCASE_SENTENCE_START", "DE_CASE"]);
+    }
+
+    #[test]
+    fn test_deserialize_config_info() {
+        let rules: RulesResponse = serde_json::from_str(
+            r#"{
+                "software": {"name": "LanguageTool"},
+                "rules": [{
+                    "ruleId": "PASSIVE_VOICE",
+                    "description": "Passive voice",
+                    "isDefaultOff": true,
+                    "categoryId": "STYLE",
+                    "categoryName": "Style"
+                }]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(rules.rules[0].id, "PASSIVE_VOICE");
+        assert_eq!(rules.rules[0].category_id, "STYLE");
+        assert!(rules.rules[0].default_off);
+    }
+}
@@ -0,0 +1,1036 @@
+//! Local rules, checked on the client, imported from LanguageTool's
+//! `grammar.xml` rule files.
+//!
+//! Only a subset of LanguageTool's pattern rules is supported: sequences of
+//! tokens that match either a literal word or a regular expression, with an
+//! optional `<marker>`, a message, and suggestions that may refer to the
+//! matched tokens (`\1`, `<match no="1"/>`). Rules that use anything else,
+//! e.g., part-of-speech tags, inflection, exceptions or antipatterns, are
+//! skipped and listed in [`RuleSet::skipped`], so that they can be reported.
+//!
+//! Text is split into tokens made of consecutive alphanumeric characters, and
+//! every other character that is not whitespace is a token on its own.
+
+use crate::{
+    check::{Category, Context, Data, Match, Replacement, Rule as MatchRule, Url},
+    error::{Error, Result},
+};
+use regex::{Regex, RegexBuilder};
+use serde_json::Map;
+use std::{collections::HashMap, ops::Range};
+
+/// Number of characters kept on each side of a match in its context.
+const CONTEXT_CHARS: usize = 40;
+
+/// Category of rules that are not inside a `<category>` element.
+const DEFAULT_CATEGORY: (&str, &str) = ("LOCAL", "Local rules");
+
+/// What a pattern token matches.
+#[derive(Clone, Debug)]
+enum Matcher {
+    /// Any token, i.e., `<token/>`.
+    Any,
+    /// Token equal to a word.
+    Literal {
+        /// Word to match.
+        text: String,
+        /// Whether case matters.
+        case_sensitive: bool,
+    },
+    /// Token fully matching a regular expression.
+    Regex(Regex),
+}
+
+/// Token of a rule's pattern.
+#[derive(Clone, Debug)]
+struct TokenPattern {
+    /// What the token matches.
+    matcher: Matcher,
+    /// Whether the token matches everything the matcher does not.
+    negate: bool,
+}
+
+impl TokenPattern {
+    /// Return whether `token` matches this pattern.
+    fn is_match(&self, token: &str) -> bool {
+        let is_match = match &self.matcher {
+            Matcher::Any => true,
+            Matcher::Literal {
+                text,
+                case_sensitive: true,
+            } => text == token,
+            Matcher::Literal {
+                text,
+                case_sensitive: false,
+            } => text.to_lowercase() == token.to_lowercase(),
+            Matcher::Regex(regex) => regex.is_match(token),
+        };
+        is_match != self.negate
+    }
+}
+
+/// Part of a message or a suggestion.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Part {
+    /// Literal text.
+    Text(String),
+    /// Text of the matched token at this (zero-based) index in the pattern.
+    Token(usize),
+}
+
+/// Segment of a message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Segment {
+    /// Parts of the segment.
+    parts: Vec<Part>,
+    /// Whether the segment is a suggestion, i.e., a replacement.
+    suggestion: bool,
+}
+
+/// Render `parts` using the text of the matched `tokens`.
+fn render(parts: &[Part], tokens: &[&str]) -> String {
+    parts
+        .iter()
+        .map(|part| {
+            match part {
+                Part::Text(text) => text.as_str(),
+                Part::Token(index) => tokens[*index],
+            }
+        })
+        .collect()
+}
+
+/// Pattern rule, see the [module documentation](self).
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct Rule {
+    /// Rule id, e.g., `"MY_RULE"`, which is the id of the rule group for
+    /// rules inside a `<rulegroup>`.
+    pub id: String,
+    /// Index of the rule in its rule group, if any, starting from 1.
+    pub sub_id: Option<String>,
+    /// Rule description, i.e., its name.
+    pub description: String,
+    /// Category of the rule.
+    pub category: Category,
+    /// Issue type, e.g., `"grammar"`.
+    pub issue_type: String,
+    /// Short message.
+    pub short_message: String,
+    /// URL explaining the rule, if any.
+    pub url: Option<String>,
+    /// Tokens of the pattern.
+    tokens: Vec<TokenPattern>,
+    /// Tokens of the pattern that are part of the match.
+    marker: Range<usize>,
+    /// Message, including suggestions.
+    message: Vec<Segment>,
+}
+
+/// Token of the checked text, with its byte offset.
+type Token<'a> = (usize, &'a str);
+
+/// Split `text` into tokens.
+fn tokenize(text: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut word_start = None;
+
+    for (i, c) in text.char_indices() {
+        if c.is_alphanumeric() {
+            word_start.get_or_insert(i);
+            continue;
+        }
+        if let Some(start) = word_start.take() {
+            tokens.push((start, &text[start..i]));
+        }
+        if !c.is_whitespace() {
+            tokens.push((i, &text[i..i + c.len_utf8()]));
+        }
+    }
+    if let Some(start) = word_start {
+        tokens.push((start, &text[start..]));
+    }
+    tokens
+}
+
+impl Rule {
+    /// Return the matches of this rule in `text`, split into `tokens`.
+    fn find(&self, text: &str, tokens: &[Token<'_>]) -> Vec<Match> {
+        if tokens.len() < self.tokens.len() {
+            return Vec::new();
+        }
+
+        (0..=tokens.len() - self.tokens.len())
+            .filter_map(|start| {
+                let window = &tokens[start..start + self.tokens.len()];
+                self.tokens
+                    .iter()
+                    .zip(window)
+                    .all(|(pattern, (_, token))| pattern.is_match(token))
+                    .then(|| self.to_match(text, window))
+            })
+            .collect()
+    }
+
+    /// Build the match of this rule for the matched `window` of tokens.
+    fn to_match(&self, text: &str, window: &[Token<'_>]) -> Match {
+        let (start, _) = window[self.marker.start];
+        let (last, last_text) = window[self.marker.end - 1];
+        let end = last + last_text.len();
+        let matched: Vec<&str> = window.iter().map(|(_, token)| *token).collect();
+
+        let message = self
+            .message
+            .iter()
+            .map(|segment| {
+                let text = render(&segment.parts, &matched);
+                if segment.suggestion {
+                    format!("“{text}”")
+                } else {
+                    text
+                }
+            })
+            .collect::<String>();
+        let message = message.split_whitespace().collect::<Vec<_>>().join(" ");
+        let replacements = self
+            .message
+            .iter()
+            .filter(|segment| segment.suggestion)
+            .map(|segment| Replacement::from(render(&segment.parts, &matched)))
+            .collect();
+
+        let context_start = text[..start]
+            .char_indices()
+            .rev()
+            .nth(CONTEXT_CHARS - 1)
+            .map_or(0, |(i, _)| i);
+        let context_end = text[end..]
+            .char_indices()
+            .nth(CONTEXT_CHARS)
+            .map_or(text.len(), |(i, _)| end + i);
+        let context = Context {
+            length: text[start..end].chars().count(),
+            offset: text[context_start..start].chars().count(),
+            text: text[context_start..context_end].to_string(),
+        };
+
+        Match {
+            length: context.length,
+            offset: text[..start].chars().count(),
+            sentence: context.text.clone(),
+            context,
+            context_for_sure_match: 0,
+            ignore_for_incomplete_sentence: false,
+            message,
+            more_context: None,
+            replacements,
+            rule: MatchRule {
+                category: self.category.clone(),
+                description: self.description.clone(),
+                id: self.id.clone(),
+                is_premium: Some(false),
+                issue_type: self.issue_type.clone(),
+                source_file: None,
+                sub_id: self.sub_id.clone(),
+                tags: None,
+                urls: self.url.clone().map(|value| vec![Url { value }]),
+                extra: Map::new(),
+            },
+            short_message: self.short_message.clone(),
+            type_: crate::check::Type {
+                type_name: "Other".to_string(),
+            },
+            extra: Map::new(),
+        }
+    }
+}
+
+/// Rule that could not be imported.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SkippedRule {
+    /// Rule id, with its index in its rule group, if any, e.g.,
+    /// `"MY_RULE[2]"`.
+    pub id: String,
+    /// Why the rule was skipped.
+    pub reason: String,
+}
+
+impl std::fmt::Display for SkippedRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "rule {} was skipped: {}", self.id, self.reason)
+    }
+}
+
+/// Set of local rules.
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct RuleSet {
+    /// Imported rules.
+    pub rules: Vec<Rule>,
+    /// Rules that could not be imported, because they use unsupported
+    /// constructs.
+    pub skipped: Vec<SkippedRule>,
+}
+
+impl RuleSet {
+    /// Import the supported rules of a LanguageTool `grammar.xml` file.
+    ///
+    /// # Errors
+    ///
+    /// If `xml` is not well-formed. Unsupported rules are not errors, but are
+    /// listed in [`RuleSet::skipped`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use languagetool_rust::rules::RuleSet;
+    /// let rules = RuleSet::from_grammar_xml(
+    ///     r#"<rules lang="en">
+    ///         <rule id="TEH" name="Typo of 'the'">
+    ///             <pattern><token>teh</token></pattern>
+    ///             <message>Did you mean <suggestion>the</suggestion>?</message>
+    ///         </rule>
+    ///     </rules>"#,
+    /// )
+    /// .unwrap();
+    /// let matches = rules.check("I saw teh cat.");
+    ///
+    /// assert_eq!(matches[0].offset, 6);
+    /// assert_eq!(matches[0].message, "Did you mean “the”?");
+    /// ```
+    pub fn from_grammar_xml(xml: &str) -> Result<Self> {
+        let root = parse_xml(xml)?;
+        let mut rules = Self::default();
+        let category = Category {
+            id: DEFAULT_CATEGORY.0.to_string(),
+            name: DEFAULT_CATEGORY.1.to_string(),
+        };
+
+        rules.import(&root, &category);
+        Ok(rules)
+    }
+
+    /// Import the rules found in `element`, recursively.
+    fn import(&mut self, element: &Element, category: &Category) {
+        for child in element.elements() {
+            match child.name.as_str() {
+                "category" => {
+                    let category = Category {
+                        id: child.attribute("id").unwrap_or(&category.id).to_string(),
+                        name: child
+                            .attribute("name")
+                            .unwrap_or(&category.name)
+                            .to_string(),
+                    };
+                    self.import(child, &category);
+                },
+                "rulegroup" => {
+                    let id = child.attribute("id").unwrap_or_default();
+                    // Elements shared by all the rules of the group
+                    let shared = child
+                        .elements()
+                        .find(|e| !matches!(e.name.as_str(), "rule" | "short" | "url"));
+
+                    for (i, rule) in child.elements().filter(|e| e.name == "rule").enumerate() {
+                        let sub_id = (i + 1).to_string();
+                        let imported = match shared {
+                            Some(shared) => {
+                                Err(format!("<{}> in <rulegroup> is not supported", shared.name))
+                            },
+                            None => import_rule(rule, Some(child), id, Some(&sub_id), category),
+                        };
+                        self.push(imported, format!("{id}[{sub_id}]"));
+                    }
+                },
+                "rule" => {
+                    let id = child.attribute("id").unwrap_or_default();
+                    let imported = import_rule(child, None, id, None, category);
+                    self.push(imported, id.to_string());
+                },
+                _ => self.import(child, category),
+            }
+        }
+    }
+
+    /// Add an imported rule, or record why rule `id` was skipped.
+    fn push(&mut self, imported: std::result::Result<Rule, String>, id: String) {
+        match imported {
+            Ok(rule) => self.rules.push(rule),
+            Err(reason) => self.skipped.push(SkippedRule { id, reason }),
+        }
+    }
+
+    /// Return the matches of all the rules in `text`, sorted by offset.
+    #[must_use]
+    pub fn check(&self, text: &str) -> Vec<Match> {
+        let tokens = tokenize(text);
+        let mut matches: Vec<Match> = self
+            .rules
+            .iter()
+            .flat_map(|rule| rule.find(text, &tokens))
+            .collect();
+
+        matches.sort_by_key(|m| m.offset);
+        matches
+    }
+
+    /// Return the matches of all the rules in the text of `data`, with
+    /// offsets referring to the whole data, including markup.
+    ///
+    /// Each text annotation is checked separately, so patterns never span
+    /// markup.
+    #[must_use]
+    pub fn check_data(&self, data: &Data) -> Vec<Match> {
+        let mut matches = Vec::new();
+        let mut offset = 0;
+
+        for annotation in &data.annotation {
+            if let Some(text) = &annotation.text {
+                matches.extend(self.check(text).into_iter().map(|mut m| {
+                    m.offset += offset;
+                    m
+                }));
+                offset += text.chars().count();
+            } else if let Some(markup) = &annotation.markup {
+                offset += markup.chars().count();
+            }
+        }
+        matches
+    }
+}
+
+/// Import a `<rule>`, optionally inside a `<rulegroup>`, or return why it is
+/// not supported.
+fn import_rule(
+    rule: &Element,
+    group: Option<&Element>,
+    id: &str,
+    sub_id: Option<&str>,
+    category: &Category,
+) -> std::result::Result<Rule, String> {
+    if id.is_empty() {
+        return Err("missing id".to_string());
+    }
+    let attribute = |name| {
+        rule.attribute(name)
+            .or_else(|| group.and_then(|group| group.attribute(name)))
+    };
+    if matches!(attribute("default"), Some("off" | "temp_off")) {
+        return Err("disabled by default".to_string());
+    }
+    let child_text = |name| {
+        rule.elements()
+            .chain(group.into_iter().flat_map(Element::elements))
+            .find(|e| e.name == name)
+            .map(Element::text)
+    };
+
+    let mut pattern = None;
+    let mut message = None;
+    for child in rule.elements() {
+        match child.name.as_str() {
+            "pattern" => pattern = Some(child),
+            "message" => message = Some(child),
+            "example" | "short" | "url" => {},
+            name => return Err(format!("<{name}> is not supported")),
+        }
+    }
+    let pattern = pattern.ok_or("only <pattern> rules are supported")?;
+    let message = message.ok_or("missing <message>")?;
+
+    let case_sensitive = pattern.attribute("case_sensitive") == Some("yes");
+    let mut tokens = Vec::new();
+    let mut marker = None;
+    for child in pattern.elements() {
+        match child.name.as_str() {
+            "token" => tokens.push(import_token(child, case_sensitive)?),
+            "marker" if marker.is_none() => {
+                let start = tokens.len();
+                for token in child.elements() {
+                    if token.name != "token" {
+                        return Err(format!("<{}> in <marker> is not supported", token.name));
+                    }
+                    tokens.push(import_token(token, case_sensitive)?);
+                }
+                marker = Some(start..tokens.len());
+            },
+            name => return Err(format!("<{name}> in <pattern> is not supported")),
+        }
+    }
+    let marker = marker.unwrap_or(0..tokens.len());
+    if marker.is_empty() {
+        return Err("empty <pattern> or <marker>".to_string());
+    }
+
+    Ok(Rule {
+        id: id.to_string(),
+        sub_id: sub_id.map(ToString::to_string),
+        description: attribute("name").unwrap_or(id).to_string(),
+        category: category.clone(),
+        issue_type: attribute("type").unwrap_or("grammar").to_string(),
+        short_message: child_text("short").unwrap_or_default(),
+        url: child_text("url"),
+        message: import_message(message, tokens.len())?,
+        tokens,
+        marker,
+    })
+}
+
+/// Import a `<token>`, or return why it is not supported.
+fn import_token(
+    token: &Element,
+    case_sensitive: bool,
+) -> std::result::Result<TokenPattern, String> {
+    let mut regexp = false;
+    let mut negate = false;
+    let mut case_sensitive = case_sensitive;
+
+    for (name, value) in &token.attributes {
+        match name.as_str() {
+            "regexp" => regexp = value == "yes",
+            "negate" => negate = value == "yes",
+            "case_sensitive" => case_sensitive = value == "yes",
+            name => return Err(format!("token attribute `{name}` is not supported")),
+        }
+    }
+    if let Some(child) = token.elements().next() {
+        return Err(format!("<{}> in <token> is not supported", child.name));
+    }
+
+    let text = token.text().trim().to_string();
+    let matcher = if text.is_empty() {
+        Matcher::Any
+    } else if regexp {
+        let regex = RegexBuilder::new(&format!("^(?:{text})$"))
+            .case_insensitive(!case_sensitive)
+            .build()
+            .map_err(|e| format!("unsupported regular expression {text:?}: {e}"))?;
+        Matcher::Regex(regex)
+    } else {
+        Matcher::Literal {
+            text,
+            case_sensitive,
+        }
+    };
+    Ok(TokenPattern { matcher, negate })
+}
+
+/// Import a `<message>`, whose references must point to one of the
+/// `token_count` tokens, or return why it is not supported.
+fn import_message(
+    message: &Element,
+    token_count: usize,
+) -> std::result::Result<Vec<Segment>, String> {
+    message
+        .children
+        .iter()
+        .map(|node| {
+            match node {
+                Node::Element(e) if e.name == "suggestion" => {
+                    Ok(Segment {
+                        parts: import_parts(&e.children, &e.name, token_count)?,
+                        suggestion: true,
+                    })
+                },
+                node => {
+                    Ok(Segment {
+                        parts: import_parts(
+                            std::slice::from_ref(node),
+                            &message.name,
+                            token_count,
+                        )?,
+                        suggestion: false,
+                    })
+                },
+            }
+        })
+        .collect()
+}
+
+/// Import the `nodes` of a `parent` element, which may only be text and
+/// `<match no="N"/>` elements, or return why they are not supported.
+fn import_parts(
+    nodes: &[Node],
+    parent: &str,
+    token_count: usize,
+) -> std::result::Result<Vec<Part>, String> {
+    let mut parts = Vec::new();
+
+    for node in nodes {
+        match node {
+            Node::Text(text) => parts.extend(text_parts(text, token_count)?),
+            Node::Element(e) if e.name == "match" && e.attributes.len() == 1 => {
+                let no = e.attribute("no").unwrap_or_default();
+                parts.push(token_reference(no, token_count)?);
+            },
+            Node::Element(e) => return Err(format!("<{}> in <{parent}> is not supported", e.name)),
+        }
+    }
+    Ok(parts)
+}
+
+/// Return the part referring to token `no`, counted from 1.
+fn token_reference(no: &str, token_count: usize) -> std::result::Result<Part, String> {
+    match no.parse::<usize>() {
+        Ok(no) if (1..=token_count).contains(&no) => Ok(Part::Token(no - 1)),
+        _ => Err(format!("invalid token reference {no:?}")),
+    }
+}
+
+/// Split `text` into literal parts and token references, e.g., `\1`.
+fn text_parts(text: &str, token_count: usize) -> std::result::Result<Vec<Part>, String> {
+    let mut parts = Vec::new();
+    let mut rest = text;
+
+    while let Some(i) = rest.find('\\') {
+        let digits = rest[i + 1..]
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len() - i - 1);
+        if digits == 0 {
+            parts.push(Part::Text(rest[..=i].to_string()));
+            rest = &rest[i + 1..];
+            continue;
+        }
+        parts.push(Part::Text(rest[..i].to_string()));
+        parts.push(token_reference(&rest[i + 1..i + 1 + digits], token_count)?);
+        rest = &rest[i + 1 + digits..];
+    }
+    parts.push(Part::Text(rest.to_string()));
+    parts.retain(|part| *part != Part::Text(String::new()));
+    Ok(parts)
+}
+
+/// Node of an XML document.
+#[derive(Clone, Debug)]
+enum Node {
+    /// Element.
+    Element(Element),
+    /// Text, with entities replaced.
+    Text(String),
+}
+
+/// Element of an XML document.
+#[derive(Clone, Debug, Default)]
+struct Element {
+    /// Tag name.
+    name: String,
+    /// Attributes, with entities replaced in values.
+    attributes: Vec<(String, String)>,
+    /// Child nodes.
+    children: Vec<Node>,
+}
+
+impl Element {
+    /// Return the value of an attribute, if present.
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Return an iterator over child elements.
+    fn elements(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter_map(|node| {
+            match node {
+                Node::Element(element) => Some(element),
+                Node::Text(_) => None,
+            }
+        })
+    }
+
+    /// Return the concatenated text of the child text nodes.
+    fn text(&self) -> String {
+        self.children
+            .iter()
+            .filter_map(|node| {
+                match node {
+                    Node::Text(text) => Some(text.as_str()),
+                    Node::Element(_) => None,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Return an error about invalid rules.
+fn invalid(message: impl std::fmt::Display) -> Error {
+    Error::InvalidRules(message.to_string())
+}
+
+/// Parse an XML document into a root element, whose children are the
+/// document's top-level elements.
+///
+/// Internal entities declared in the document type definition are replaced,
+/// which LanguageTool uses to share regular expressions between rules.
+///
+/// Rule files only use a small part of XML, so this parser is written by hand
+/// rather than adding an XML crate to the `rules` feature: there are no
+/// namespaces, no external entities, and invalid documents are errors.
+fn parse_xml(xml: &str) -> Result<Element> {
+    let mut entities = Entities::default();
+    let mut stack = vec![Element::default()];
+    let mut rest = xml;
+
+    while !rest.is_empty() {
+        let parent = stack.last_mut().expect("the root is never popped");
+
+        if let Some(after) = rest.strip_prefix("<!--") {
+            let end = after
+                .find("-->")
+                .ok_or_else(|| invalid("unclosed comment"))?;
+            rest = &after[end + 3..];
+        } else if let Some(after) = rest.strip_prefix("<![CDATA[") {
+            let end = after.find("]]>").ok_or_else(|| invalid("unclosed CDATA"))?;
+            parent.children.push(Node::Text(after[..end].to_string()));
+            rest = &after[end + 3..];
+        } else if let Some(after) = rest.strip_prefix("<!DOCTYPE") {
+            let end = doctype_end(after).ok_or_else(|| invalid("unclosed DOCTYPE"))?;
+            parse_entities(&after[..end], &mut entities)?;
+            rest = &after[end + 1..];
+        } else if let Some(after) = rest.strip_prefix("<?") {
+            let end = after
+                .find("?>")
+                .ok_or_else(|| invalid("unclosed processing instruction"))?;
+            rest = &after[end + 2..];
+        } else if let Some(after) = rest.strip_prefix("</") {
+            let end = after.find('>').ok_or_else(|| invalid("unclosed tag"))?;
+            let name = after[..end].trim();
+            if stack.len() == 1 || stack.last().map(|e| e.name.as_str()) != Some(name) {
+                return Err(invalid(format!("unexpected closing tag </{name}>")));
+            }
+            let element = stack.pop().expect("checked above");
+            stack
+                .last_mut()
+                .expect("the root is never popped")
+                .children
+                .push(Node::Element(element));
+            rest = &after[end + 1..];
+        } else if let Some(after) = rest.strip_prefix('<') {
+            let end = tag_end(after).ok_or_else(|| invalid("unclosed tag"))?;
+            let (tag, empty) = match after[..end].strip_suffix('/') {
+                Some(tag) => (tag, true),
+                None => (&after[..end], false),
+            };
+            let element = parse_tag(tag, &mut entities)?;
+            if empty {
+                parent.children.push(Node::Element(element));
+            } else {
+                stack.push(element);
+            }
+            rest = &after[end + 1..];
+        } else {
+            let end = rest.find('<').unwrap_or(rest.len());
+            parent
+                .children
+                .push(Node::Text(unescape(&rest[..end], &mut entities)?));
+            rest = &rest[end..];
+        }
+    }
+
+    match stack.pop() {
+        Some(root) if stack.is_empty() => Ok(root),
+        Some(element) => Err(invalid(format!("unclosed element <{}>", element.name))),
+        None => unreachable!("the root is never popped"),
+    }
+}
+
+/// Return the index of `>` ending a tag, ignoring quoted attribute values.
+fn tag_end(s: &str) -> Option<usize> {
+    let mut quote = None;
+
+    for (i, c) in s.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), c) if q == c => quote = None,
+            (None, '>') => return Some(i),
+            _ => {},
+        }
+    }
+    None
+}
+
+/// Return the index of `>` ending a document type definition, ignoring its
+/// internal subset, i.e., `[...]`, and quoted values.
+fn doctype_end(s: &str) -> Option<usize> {
+    let mut quote = None;
+    let mut in_subset = false;
+
+    for (i, c) in s.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), c) if q == c => quote = None,
+            (None, '[') => in_subset = true,
+            (None, ']') => in_subset = false,
+            (None, '>') if !in_subset => return Some(i),
+            _ => {},
+        }
+    }
+    None
+}
+
+/// Maximum number of bytes that entity references may expand to in a
+/// document, so that nested entities cannot exhaust memory.
+const MAX_ENTITY_EXPANSION: usize = 1 << 20;
+
+/// Internal entities of an XML document.
+#[derive(Debug, Default)]
+struct Entities {
+    /// Replacement text of each entity, by name.
+    values: HashMap<String, String>,
+    /// Number of bytes that entity references expanded to so far.
+    expanded: usize,
+}
+
+/// Parse the `<!ENTITY name "value">` declarations of a document type
+/// definition.
+fn parse_entities(doctype: &str, entities: &mut Entities) -> Result<()> {
+    for declaration in doctype.split("<!ENTITY").skip(1) {
+        let declaration = declaration.trim_start();
+        let name_end = declaration
+            .find(char::is_whitespace)
+            .ok_or_else(|| invalid("invalid entity declaration"))?;
+        let value = declaration[name_end..].trim_start();
+        let quote = value
+            .chars()
+            .next()
+            .filter(|c| matches!(c, '"' | '\''))
+            .ok_or_else(|| invalid("only internal entities are supported"))?;
+        let value_end = value[1..]
+            .find(quote)
+            .ok_or_else(|| invalid("unclosed entity value"))?;
+        let value = unescape(&value[1..=value_end], entities)?;
+
+        entities
+            .values
+            .insert(declaration[..name_end].to_string(), value);
+    }
+    Ok(())
+}
+
+/// Parse the content of a start tag, i.e., its name and attributes.
+fn parse_tag(tag: &str, entities: &mut Entities) -> Result<Element> {
+    let tag = tag.trim();
+    let name_end = tag.find(char::is_whitespace).unwrap_or(tag.len());
+    let mut element = Element {
+        name: tag[..name_end].to_string(),
+        ..Default::default()
+    };
+    let mut rest = tag[name_end..].trim_start();
+
+    while !rest.is_empty() {
+        let (name, value) = rest
+            .split_once('=')
+            .ok_or_else(|| invalid(format!("invalid attribute in <{}>", element.name)))?;
+        let value = value.trim_start();
+        let quote = value
+            .chars()
+            .next()
+            .filter(|c| matches!(c, '"' | '\''))
+            .ok_or_else(|| invalid(format!("unquoted attribute in <{}>", element.name)))?;
+        let value_end = value[1..]
+            .find(quote)
+            .ok_or_else(|| invalid(format!("unclosed attribute in <{}>", element.name)))?;
+
+        element.attributes.push((
+            name.trim().to_string(),
+            unescape(&value[1..=value_end], entities)?,
+        ));
+        rest = value[value_end + 2..].trim_start();
+    }
+    Ok(element)
+}
+
+/// Replace entity and character references in `text`.
+///
+/// Fails if entities expanded to more than [`MAX_ENTITY_EXPANSION`] bytes in
+/// the document.
+fn unescape(text: &str, entities: &mut Entities) -> Result<String> {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        unescaped.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find(';')
+            .ok_or_else(|| invalid("unterminated entity reference"))?;
+        let name = &rest[start + 1..start + end];
+        let character = |code: Option<u32>| {
+            code.and_then(char::from_u32)
+                .ok_or_else(|| invalid(format!("invalid character reference &{name};")))
+        };
+
+        match name {
+            "lt" => unescaped.push('<'),
+            "gt" => unescaped.push('>'),
+            "amp" => unescaped.push('&'),
+            "quot" => unescaped.push('"'),
+            "apos" => unescaped.push('\''),
+            _ => {
+                if let Some(hex) = name.strip_prefix("#x") {
+                    unescaped.push(character(u32::from_str_radix(hex, 16).ok())?);
+                } else if let Some(decimal) = name.strip_prefix('#') {
+                    unescaped.push(character(decimal.parse().ok())?);
+                } else {
+                    let value = entities
+                        .values
+                        .get(name)
+                        .ok_or_else(|| invalid(format!("unknown entity &{name};")))?;
+                    entities.expanded += value.len();
+                    if entities.expanded > MAX_ENTITY_EXPANSION {
+                        return Err(invalid("entities expand to too much text"));
+                    }
+                    unescaped.push_str(value);
+                }
+            },
+        }
+        rest = &rest[start + end + 1..];
+    }
+    unescaped.push_str(rest);
+    Ok(unescaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GRAMMAR: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE rules [
+    <!ENTITY weekdays "monday|tuesday|wednesday">
+]>
+<!-- Custom rules -->
+<rules lang="en">
+    <category id="CUSTOM" name="Custom rules">
+        <rule id="WEEKDAY_CASE" name="Weekdays are capitalized">
+            <pattern case_sensitive="yes">
+                <token regexp="yes">&weekdays;</token>
+            </pattern>
+            <message>Weekdays are capitalized in English.</message>
+            <url>https://example.com/weekdays</url>
+        </rule>
+        <rulegroup id="DOUBLE_WORD" name="Repeated words">
+            <short>Repeated word</short>
+            <rule>
+                <pattern>
+                    <token>the</token>
+                    <marker><token>the</token></marker>
+                </pattern>
+                <message>Remove the repeated <suggestion>\1</suggestion> &amp; check again.</message>
+            </rule>
+            <rule>
+                <pattern>
+                    <token inflected="yes">be</token>
+                </pattern>
+                <message>Unsupported.</message>
+            </rule>
+        </rulegroup>
+    </category>
+    <rule id="COULD_OF" name="Could of" type="misspelling">
+        <pattern>
+            <marker>
+                <token regexp="yes">could|would</token>
+                <token>of</token>
+            </marker>
+            <token negate="yes">course</token>
+        </pattern>
+        <message>Did you mean <suggestion><match no="1"/> have</suggestion>?</message>
+    </rule>
+    <rule id="WITH_ANTIPATTERN" name="Antipattern">
+        <antipattern><token>a</token></antipattern>
+        <pattern><token>a</token></pattern>
+        <message>Unsupported.</message>
+    </rule>
+</rules>
+"#;
+
+    #[test]
+    fn test_from_grammar_xml() {
+        let rules = RuleSet::from_grammar_xml(GRAMMAR).unwrap();
+
+        assert_eq!(rules.rules.len(), 3);
+        assert_eq!(
+            rules
+                .skipped
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            [
+                "rule DOUBLE_WORD[2] was skipped: token attribute `inflected` is not supported",
+                "rule WITH_ANTIPATTERN was skipped: <antipattern> is not supported",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check() {
+        let rules = RuleSet::from_grammar_xml(GRAMMAR).unwrap();
+        let text = "On monday, the the cat could of slept. Could of course!";
+        let matches = rules.check(text);
+
+        assert_eq!(matches.len(), 3);
+
+        assert_eq!(matches[0].rule.id, "WEEKDAY_CASE");
+        assert_eq!(matches[0].rule.category.id, "CUSTOM");
+        assert_eq!(matches[0].matched_text(), "monday");
+        assert_eq!(
+            matches[0].rule.urls.as_ref().unwrap()[0].value,
+            "https://example.com/weekdays"
+        );
+
+        assert_eq!(matches[1].rule.sub_id.as_deref(), Some("1"));
+        assert_eq!(matches[1].offset, 15);
+        assert_eq!(matches[1].matched_text(), "the");
+        assert_eq!(matches[1].short_message, "Repeated word");
+        assert_eq!(
+            matches[1].message,
+            "Remove the repeated “the” & check again."
+        );
+        assert_eq!(matches[1].replacements[0].value, "the");
+
+        assert_eq!(matches[2].rule.issue_type, "misspelling");
+        assert_eq!(matches[2].matched_text(), "could of");
+        assert_eq!(matches[2].replacements[0].value, "could have");
+    }
+
+    #[test]
+    fn test_check_data() {
+        let rules = RuleSet::from_grammar_xml(GRAMMAR).unwrap();
+        let data = Data::from_iter([
+            crate::check::DataAnnotation::new_markup("<b>".to_string()),
+            crate::check::DataAnnotation::new_text("the the".to_string()),
+        ]);
+
+        assert_eq!(rules.check_data(&data)[0].offset, 7);
+    }
+
+    #[test]
+    fn test_invalid_xml() {
+        for xml in [
+            "<rules><rule></rules>",
+            "<rules>",
+            "<rules>&unknown;</rules>",
+            "<rules id=unquoted></rules>",
+        ] {
+            assert!(matches!(
+                RuleSet::from_grammar_xml(xml),
+                Err(Error::InvalidRules(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_entity_expansion_limit() {
+        let mut doctype = String::from("<!DOCTYPE rules [<!ENTITY lol0 \"lol\">");
+        for i in 1..10 {
+            let previous = format!("&lol{};", i - 1);
+            doctype.push_str(&format!("<!ENTITY lol{i} \"{}\">", previous.repeat(10)));
+        }
+        let xml = format!("{doctype}]><rules>&lol9;</rules>");
+
+        assert!(matches!(
+            RuleSet::from_grammar_xml(&xml),
+            Err(Error::InvalidRules(message)) if message.contains("entities")
+        ));
+    }
+}
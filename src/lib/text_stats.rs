@@ -0,0 +1,185 @@
+//! Word count and readability statistics of a text, e.g., reported with
+//! `ltrs check --stats`.
+//!
+//! Sentences are guessed locally, see [`sentence_ranges`], and syllables are
+//! counted as groups of vowels, which is a good approximation for English.
+
+use crate::check::{sentence_ranges, Data};
+use serde::Serialize;
+use std::fmt;
+
+/// Minimum number of letters of a long word, as counted by [`TextStats::lix`].
+const LONG_WORD_LEN: usize = 7;
+
+/// Return `true` if `c` is a vowel, including accented ones.
+fn is_vowel(c: char) -> bool {
+    "aeiouyàáâãäåæèéêëìíîïòóôõöøœùúûüý".contains(c.to_lowercase().next().unwrap_or(c))
+}
+
+/// Return the approximate number of syllables of `word`, i.e., its number of
+/// groups of vowels, not counting a final silent `e`, and at least one.
+fn syllables(word: &str) -> usize {
+    let mut count = 0;
+    let mut previous_vowel = false;
+    for c in word.chars() {
+        let vowel = is_vowel(c);
+        if vowel && !previous_vowel {
+            count += 1;
+        }
+        previous_vowel = vowel;
+    }
+    let lowercase = word.to_lowercase();
+    if count > 1 && lowercase.ends_with('e') && !lowercase.ends_with("le") {
+        count -= 1;
+    }
+    count.max(1)
+}
+
+/// Word count and readability statistics of a text.
+///
+/// # Examples
+///
+/// ```
+/// # use languagetool_rust::text_stats::TextStats;
+/// let stats = TextStats::new("The cat sat on the mat. It was happy.");
+///
+/// assert_eq!(stats.words, 9);
+/// assert_eq!(stats.sentences, 2);
+/// assert_eq!(stats.average_sentence_length(), 4.5);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+#[non_exhaustive]
+pub struct TextStats {
+    /// Number of words, i.e., of whitespace-separated tokens with at least
+    /// one letter or digit.
+    pub words: usize,
+    /// Number of sentences with at least one word.
+    pub sentences: usize,
+    /// Approximate number of syllables.
+    pub syllables: usize,
+    /// Number of words with more than six letters.
+    pub long_words: usize,
+}
+
+impl TextStats {
+    /// Compute the statistics of `text`.
+    #[must_use]
+    pub fn new(text: &str) -> Self {
+        let mut stats = Self::default();
+        for word in text.split_whitespace() {
+            let letters: String = word.chars().filter(|c| c.is_alphanumeric()).collect();
+            if letters.is_empty() {
+                continue;
+            }
+            stats.words += 1;
+            stats.syllables += syllables(&letters);
+            if letters.chars().count() >= LONG_WORD_LEN {
+                stats.long_words += 1;
+            }
+        }
+
+        let chars: Vec<char> = text.chars().collect();
+        stats.sentences = sentence_ranges(text)
+            .into_iter()
+            .filter(|[start, end]| chars[*start..*end].iter().any(|c| c.is_alphanumeric()))
+            .count();
+        stats
+    }
+
+    /// Compute the statistics of the text of `data`, without its markup,
+    /// unless it is interpreted as some text.
+    #[must_use]
+    pub fn from_data(data: &Data) -> Self {
+        let text: String = data
+            .annotation
+            .iter()
+            .filter_map(|annotation| {
+                annotation
+                    .text
+                    .as_deref()
+                    .or(annotation.interpret_as.as_deref())
+            })
+            .collect();
+        Self::new(&text)
+    }
+
+    /// Return the average number of words per sentence.
+    #[must_use]
+    pub fn average_sentence_length(&self) -> f64 {
+        self.words as f64 / self.sentences.max(1) as f64
+    }
+
+    /// Return the Flesch reading ease score, from about 100 for very easy
+    /// texts down to 0 (or less) for very difficult ones.
+    ///
+    /// Its coefficients are calibrated for English.
+    #[must_use]
+    pub fn flesch_reading_ease(&self) -> f64 {
+        let syllables_per_word = self.syllables as f64 / self.words.max(1) as f64;
+        206.835 - 1.015 * self.average_sentence_length() - 84.6 * syllables_per_word
+    }
+
+    /// Return the LIX readability index, from about 20 for very easy texts to
+    /// 60 for very difficult ones, in any language.
+    #[must_use]
+    pub fn lix(&self) -> f64 {
+        self.average_sentence_length() + 100.0 * self.long_words as f64 / self.words.max(1) as f64
+    }
+}
+
+impl fmt::Display for TextStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let plural = |n: usize| if n == 1 { "" } else { "s" };
+        write!(
+            f,
+            "{} word{}, {} sentence{} ({:.1} words per sentence), Flesch reading ease {:.1}, \
+             LIX {:.1}",
+            self.words,
+            plural(self.words),
+            self.sentences,
+            plural(self.sentences),
+            self.average_sentence_length(),
+            self.flesch_reading_ease(),
+            self.lix()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::check::DataAnnotation;
+
+    #[test]
+    fn test_syllables() {
+        assert_eq!(syllables("cat"), 1);
+        assert_eq!(syllables("make"), 1);
+        assert_eq!(syllables("table"), 2);
+        assert_eq!(syllables("readability"), 5);
+        assert_eq!(syllables("rhythm"), 1);
+    }
+
+    #[test]
+    fn test_text_stats() {
+        let stats = TextStats::new("The cat sat on the mat. It was happy.");
+        assert_eq!(stats.syllables, 10);
+        assert_eq!(stats.long_words, 0);
+        assert!(stats.flesch_reading_ease() > 90.0);
+        assert_eq!(stats.lix(), 4.5);
+
+        assert_eq!(TextStats::new(" \n- "), TextStats::default());
+        assert_eq!(TextStats::default().lix(), 0.0);
+
+        let data = Data::from_iter([
+            DataAnnotation::new_markup("<b>".to_string()),
+            DataAnnotation::new_text("Readability matters.".to_string()),
+            DataAnnotation::new_interpreted_markup("<br/>".to_string(), "\n\n".to_string()),
+            DataAnnotation::new_text("Really".to_string()),
+        ]);
+        let stats = TextStats::from_data(&data);
+        assert_eq!(stats.words, 3);
+        assert_eq!(stats.sentences, 2);
+        assert_eq!(stats.long_words, 2);
+        assert!(stats.to_string().starts_with("3 words, 2 sentences (1.5 words per sentence)"));
+    }
+}
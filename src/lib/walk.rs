@@ -0,0 +1,156 @@
+//! Expansion of directories into the files they contain, so that a whole
+//! documentation tree can be checked with, e.g., `ltrs check docs/`.
+//!
+//! Directories are walked recursively, in a deterministic order. Hidden files
+//! and directories, like `.git`, and binary files are skipped, as well as the
+//! files that do not match the globs given with `--glob`, if any, and,
+//! optionally, the files ignored by `.gitignore` and `.ignore` files.
+
+use crate::error::Result;
+use ::ignore::{overrides::OverrideBuilder, WalkBuilder};
+use std::{
+    fs::File,
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+/// Number of bytes read from the start of a file to tell if it is binary.
+const BINARY_DETECTION_LEN: u64 = 8192;
+
+/// Return `true` if the start of a file contains a NUL byte, which never
+/// appears in text files.
+fn is_binary(path: &Path) -> Result<bool> {
+    let mut start = Vec::new();
+    File::open(path)?
+        .take(BINARY_DETECTION_LEN)
+        .read_to_end(&mut start)?;
+    Ok(start.contains(&0))
+}
+
+/// Return the files of `path`, which are `path` itself if it is a file, or
+/// the text files found by walking it recursively if it is a directory.
+///
+/// `globs` are matched against paths relative to `path`, like in
+/// `.gitignore` files: only matching files are kept, unless the glob starts
+/// with `!`, in which case matching files are excluded. Globs do not apply to
+/// a `path` that is a file.
+///
+/// If `respect_gitignore` is `true`, the files ignored by `.gitignore`,
+/// `.ignore` and git's global and `info/exclude` files are skipped.
+///
+/// # Errors
+///
+/// If a glob is invalid, or if a directory or a file cannot be read.
+pub fn walk_path(path: &Path, globs: &[String], respect_gitignore: bool) -> Result<Vec<PathBuf>> {
+    if !path.is_dir() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+
+    let mut overrides = OverrideBuilder::new(path);
+    for glob in globs {
+        overrides.add(glob)?;
+    }
+    let overrides = overrides.build()?;
+    // Unlike `WalkBuilder::overrides`, filtering entries does not take
+    // precedence over ignore files
+    let walker = WalkBuilder::new(path)
+        .standard_filters(false)
+        .hidden(true)
+        .git_ignore(respect_gitignore)
+        .git_global(respect_gitignore)
+        .git_exclude(respect_gitignore)
+        .ignore(respect_gitignore)
+        .parents(respect_gitignore)
+        .require_git(false)
+        .filter_entry(move |entry| {
+            let is_dir = entry.file_type().is_some_and(|file_type| file_type.is_dir());
+            !overrides.matched(entry.path(), is_dir).is_ignore()
+        })
+        .sort_by_file_name(|a, b| a.cmp(b))
+        .build();
+
+    let mut filenames = Vec::new();
+    for entry in walker {
+        let entry = entry?;
+        if entry.file_type().is_some_and(|file_type| file_type.is_file())
+            && !is_binary(entry.path())?
+        {
+            filenames.push(entry.into_path());
+        }
+    }
+    Ok(filenames)
+}
+
+/// Expand each of `paths` with [`walk_path`], keeping their order.
+///
+/// # Errors
+///
+/// If any path cannot be walked.
+pub fn walk_paths(
+    paths: &[PathBuf],
+    globs: &[String],
+    respect_gitignore: bool,
+) -> Result<Vec<PathBuf>> {
+    let mut filenames = Vec::new();
+    for path in paths {
+        filenames.extend(walk_path(path, globs, respect_gitignore)?);
+    }
+    Ok(filenames)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_walk_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("docs/guide")).unwrap();
+        std::fs::create_dir(root.join(".hidden")).unwrap();
+        for file in [
+            "README.md",
+            "docs/index.md",
+            "docs/notes.txt",
+            "docs/guide/b.md",
+            "docs/guide/a.md",
+            "docs/build.md",
+            ".hidden/secret.md",
+        ] {
+            std::fs::write(root.join(file), "Some text.").unwrap();
+        }
+        std::fs::write(root.join("docs/image.png"), b"\x89PNG\0\0").unwrap();
+        std::fs::write(root.join(".gitignore"), "build.md\n").unwrap();
+
+        let relative = |filenames: Vec<PathBuf>| {
+            filenames
+                .iter()
+                .map(|path| path.strip_prefix(root).unwrap().to_string_lossy().into_owned())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            relative(walk_path(root, &[], false).unwrap()),
+            [
+                "README.md",
+                "docs/build.md",
+                "docs/guide/a.md",
+                "docs/guide/b.md",
+                "docs/index.md",
+                "docs/notes.txt"
+            ]
+        );
+        assert_eq!(
+            relative(walk_path(root, &["**/*.md".to_string()], true).unwrap()),
+            ["README.md", "docs/guide/a.md", "docs/guide/b.md", "docs/index.md"]
+        );
+        assert_eq!(
+            relative(walk_path(root, &["!guide/".to_string()], true).unwrap()),
+            ["README.md", "docs/index.md", "docs/notes.txt"]
+        );
+
+        let file = root.join("docs/image.png");
+        assert_eq!(walk_path(&file, &["*.md".to_string()], true).unwrap(), [file]);
+        assert!(walk_path(root, &["a{".to_string()], false).is_err());
+    }
+}
@@ -1,11 +1,11 @@
 //! Structures for `words` requests and responses.
 
 use crate::{
-    check::serialize_option_vec_string,
+    check::{deserialize_option_vec_string, serialize_option_vec_string, CheckResponse},
     error::{Error, Result},
 };
 #[cfg(feature = "cli")]
-use clap::{Args, Parser, Subcommand};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use serde::{Deserialize, Serialize};
 
 /// Parse `v` if valid word.
@@ -37,15 +37,35 @@ pub fn parse_word(v: &str) -> Result<String> {
 pub struct LoginArgs {
     /// Your username as used to log in at languagetool.org.
     #[cfg_attr(
-        feature = "cli",
+        all(feature = "cli", not(feature = "keyring")),
         clap(short = 'u', long, required = true, env = "LANGUAGETOOL_USERNAME")
     )]
+    #[cfg_attr(
+        feature = "keyring",
+        clap(
+            short = 'u',
+            long,
+            default_value = "",
+            hide_default_value = true,
+            env = "LANGUAGETOOL_USERNAME"
+        )
+    )]
     pub username: String,
     /// [Your API key](https://languagetool.org/editor/settings/api).
     #[cfg_attr(
-        feature = "cli",
+        all(feature = "cli", not(feature = "keyring")),
         clap(short = 'k', long, required = true, env = "LANGUAGETOOL_API_KEY")
     )]
+    #[cfg_attr(
+        feature = "keyring",
+        clap(
+            short = 'k',
+            long,
+            default_value = "",
+            hide_default_value = true,
+            env = "LANGUAGETOOL_API_KEY"
+        )
+    )]
     pub api_key: String,
 }
 
@@ -58,7 +78,7 @@ pub struct LoginArgs {
 pub struct WordsRequest {
     /// Offset of where to start in the list of words.
     #[cfg_attr(feature = "cli", clap(long, default_value = "0"))]
-    offset: isize,
+    pub(crate) offset: isize,
     /// Maximum number of words to return.
     #[cfg_attr(feature = "cli", clap(long, default_value = "10"))]
     pub limit: isize,
@@ -66,10 +86,17 @@ pub struct WordsRequest {
     #[cfg_attr(feature = "cli", clap(flatten))]
     #[serde(flatten)]
     pub login: LoginArgs,
-    /// Comma-separated list of dictionaries to include words from; uses special
-    /// default dictionary if this is unset.
-    #[cfg_attr(feature = "cli", clap(long))]
-    #[serde(serialize_with = "serialize_option_vec_string")]
+    /// Comma-separated (or repeated) list of dictionaries to include words
+    /// from; uses special default dictionary if this is unset.
+    #[cfg_attr(
+        feature = "cli",
+        clap(long, value_delimiter = ',', action = clap::ArgAction::Append)
+    )]
+    #[serde(
+        default,
+        serialize_with = "serialize_option_vec_string",
+        deserialize_with = "deserialize_option_vec_string"
+    )]
     pub dicts: Option<Vec<String>>,
 }
 
@@ -91,10 +118,17 @@ pub struct WordsRequestArgs {
     #[cfg_attr(feature = "cli", clap(flatten))]
     #[serde(flatten)]
     pub login: Option<LoginArgs>,
-    /// Comma-separated list of dictionaries to include words from; uses special
-    /// default dictionary if this is unset.
-    #[cfg_attr(feature = "cli", clap(long))]
-    #[serde(serialize_with = "serialize_option_vec_string")]
+    /// Comma-separated (or repeated) list of dictionaries to include words
+    /// from; uses special default dictionary if this is unset.
+    #[cfg_attr(
+        feature = "cli",
+        clap(long, value_delimiter = ',', action = clap::ArgAction::Append)
+    )]
+    #[serde(
+        default,
+        serialize_with = "serialize_option_vec_string",
+        deserialize_with = "deserialize_option_vec_string"
+    )]
     pub dicts: Option<Vec<String>>,
 }
 
@@ -105,7 +139,7 @@ impl From<WordsRequestArgs> for WordsRequest {
         Self {
             offset: args.offset,
             limit: args.limit,
-            login: args.login.unwrap(),
+            login: args.login.unwrap_or_default(),
             dicts: args.dicts,
         }
     }
@@ -159,6 +193,108 @@ pub struct WordsDeleteRequest {
     pub dict: Option<String>,
 }
 
+/// Arguments to add words, read from a file, to one of the user's personal
+/// dictionaries.
+#[cfg(feature = "cli")]
+#[derive(Args, Clone, Debug, PartialEq, Eq)]
+pub struct WordsImportArgs {
+    /// File to read words from, one word per line. Empty lines are ignored.
+    #[clap(required = true)]
+    pub file: std::path::PathBuf,
+    /// Login arguments.
+    #[clap(flatten)]
+    pub login: LoginArgs,
+    /// Name of the dictionary to add the words to; non-existent dictionaries
+    /// are created after calling this; if unset, adds to special default
+    /// dictionary.
+    #[clap(long)]
+    pub dict: Option<String>,
+    /// Minimum delay, in milliseconds, between two consecutive requests.
+    #[clap(long, default_value_t = 100)]
+    pub interval: u64,
+}
+
+/// Output format of exported words.
+#[cfg(feature = "cli")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum WordsExportFormat {
+    /// One word per line.
+    #[default]
+    Plain,
+    /// Same JSON as the words command.
+    Json,
+}
+
+/// Arguments to export all the words from the user's personal dictionaries.
+#[cfg(feature = "cli")]
+#[derive(Args, Clone, Debug, PartialEq, Eq)]
+pub struct WordsExportArgs {
+    /// Login arguments.
+    #[clap(flatten)]
+    pub login: LoginArgs,
+    /// Comma-separated (or repeated) list of dictionaries to include words
+    /// from; uses special default dictionary if this is unset.
+    #[clap(long, value_delimiter = ',', action = clap::ArgAction::Append)]
+    pub dicts: Option<Vec<String>>,
+    /// Number of words to retrieve per request.
+    #[clap(long, default_value_t = 500)]
+    pub page_size: isize,
+    /// Output format.
+    #[clap(short = 'f', long, value_enum, default_value_t)]
+    pub format: WordsExportFormat,
+    /// File to write words to, instead of standard output.
+    #[clap(short = 'o', long)]
+    pub output: Option<std::path::PathBuf>,
+}
+
+#[cfg(feature = "cli")]
+impl From<&WordsExportArgs> for WordsRequest {
+    #[inline]
+    fn from(args: &WordsExportArgs) -> Self {
+        Self {
+            offset: 0,
+            limit: args.page_size,
+            login: args.login.clone(),
+            dicts: args.dicts.clone(),
+        }
+    }
+}
+
+/// Arguments to list the words of some of the user's personal dictionaries,
+/// grouped by dictionary.
+#[cfg(feature = "cli")]
+#[derive(Args, Clone, Debug, PartialEq, Eq)]
+pub struct WordsDictsArgs {
+    /// Login arguments.
+    #[clap(flatten)]
+    pub login: LoginArgs,
+    /// Comma-separated (or repeated) list of dictionaries to list words from.
+    /// The API cannot list the names of dictionaries, so they must be given.
+    #[clap(
+        long,
+        required = true,
+        value_delimiter = ',',
+        action = clap::ArgAction::Append
+    )]
+    pub dicts: Vec<String>,
+    /// Number of words to retrieve per request.
+    #[clap(long, default_value_t = 500)]
+    pub page_size: isize,
+}
+
+#[cfg(feature = "cli")]
+impl From<&WordsDictsArgs> for WordsRequest {
+    #[inline]
+    fn from(args: &WordsDictsArgs) -> Self {
+        Self {
+            offset: 0,
+            limit: args.page_size,
+            login: args.login.clone(),
+            dicts: Some(args.dicts.clone()),
+        }
+    }
+}
+
 /// Words' optional subcommand.
 #[cfg(feature = "cli")]
 #[derive(Clone, Debug, Subcommand)]
@@ -167,6 +303,13 @@ pub enum WordsSubcommand {
     Add(WordsAddRequest),
     /// Remove a word from some user's list.
     Delete(WordsDeleteRequest),
+    /// Add words, read from a file, to some user's list.
+    Import(WordsImportArgs),
+    /// Export all the words from some user's list.
+    Export(WordsExportArgs),
+    /// List all the words from some user's dictionaries, grouped by
+    /// dictionary.
+    Dicts(WordsDictsArgs),
 }
 
 /// Retrieve some user's words list.
@@ -191,6 +334,41 @@ pub struct WordsResponse {
     pub words: Vec<String>,
 }
 
+impl WordsResponse {
+    /// Remove spelling matches whose flagged word is one of the listed words,
+    /// and return the number of removed matches.
+    ///
+    /// This is useful with servers that ignore the `dicts` parameter of check
+    /// requests, such as some self-hosted servers.
+    pub fn filter(&self, response: &mut CheckResponse) -> usize {
+        let len = response.matches.len();
+
+        response.matches.retain(|m| {
+            m.rule.issue_type != "misspelling" || !self.words.contains(&m.matched_text())
+        });
+        len - response.matches.len()
+    }
+}
+
+/// Words of one of the user's personal dictionaries.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct DictWords {
+    /// Name of the dictionary.
+    pub dict: String,
+    /// List of words.
+    pub words: Vec<String>,
+}
+
+/// Words of several personal dictionaries, grouped by dictionary, see
+/// [`ServerClient::words_by_dict`](crate::server::ServerClient::words_by_dict).
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct WordsByDictResponse {
+    /// Words of each dictionary, in the requested order.
+    pub dicts: Vec<DictWords>,
+}
+
 /// LanguageTool POST word add response.
 #[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
 #[non_exhaustive]
@@ -199,6 +377,20 @@ pub struct WordsAddResponse {
     pub added: bool,
 }
 
+/// Summary of adding multiple words, see
+/// [`ServerClient::words_add_many`](crate::server::ServerClient::words_add_many).
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct WordsAddManyResponse {
+    /// Words that were added.
+    pub added: Vec<String>,
+    /// Words that were not sent because they are not valid (see
+    /// [`parse_word`]).
+    pub skipped: Vec<String>,
+    /// Words that could not be added, with the reason why.
+    pub failed: Vec<(String, String)>,
+}
+
 /// LanguageTool POST word delete response.
 #[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
 #[non_exhaustive]
@@ -206,3 +398,62 @@ pub struct WordsDeleteResponse {
     /// `true` if word was correctly removed.
     pub deleted: bool,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::check::test_utils::response_with_matches;
+
+    #[test]
+    fn test_words_response_filter() {
+        let text = "Run ltrs or ltrz.";
+        let mut response = response_with_matches(text, &[(4, 4, &[]), (12, 4, &["ltrs"])]);
+        let words = WordsResponse {
+            words: vec!["ltrs".to_string()],
+        };
+
+        assert_eq!(words.filter(&mut response), 1);
+        assert_eq!(response.matches.len(), 1);
+        assert_eq!(response.matches[0].offset, 12);
+    }
+
+    #[test]
+    fn test_words_requests_round_trip() {
+        let login = LoginArgs {
+            username: "me@example.com".to_string(),
+            api_key: "secret".to_string(),
+        };
+        let request = WordsRequest {
+            offset: 10,
+            limit: 20,
+            login: login.clone(),
+            dicts: Some(vec!["a".to_string(), "b".to_string()]),
+        };
+        let json = serde_json::to_string(&request).unwrap();
+        assert!(json.contains(r#""apiKey":"secret""#));
+        assert!(json.contains(r#""dicts":"a,b""#));
+        assert_eq!(serde_json::from_str::<WordsRequest>(&json).unwrap(), request);
+
+        let request = WordsAddRequest {
+            word: "ltrs".to_string(),
+            login: login.clone(),
+            dict: Some("a".to_string()),
+        };
+        let json = serde_json::to_string(&request).unwrap();
+        assert_eq!(
+            serde_json::from_str::<WordsAddRequest>(&json).unwrap(),
+            request
+        );
+
+        let request = WordsDeleteRequest {
+            word: "ltrs".to_string(),
+            login,
+            dict: None,
+        };
+        let json = serde_json::to_string(&request).unwrap();
+        assert_eq!(
+            serde_json::from_str::<WordsDeleteRequest>(&json).unwrap(),
+            request
+        );
+    }
+}
@@ -1,5 +1,8 @@
 use assert_cmd::Command;
-use predicates::{boolean::OrPredicate, str::contains};
+use predicates::{
+    boolean::{OrPredicate, PredicateBooleanExt},
+    str::contains,
+};
 
 #[test]
 fn test_basic_check_text() {
@@ -106,6 +109,181 @@ fn test_basic_check_unexisting_file() {
     assert.failure().stderr(contains("invalid filename"));
 }
 
+#[test]
+fn test_check_write_file() {
+    use std::io::Write;
+
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    writeln!(file, "Some phrase with a smal mistake.").unwrap();
+
+    let mut cmd = Command::cargo_bin("ltrs").unwrap();
+    let assert = cmd
+        .arg("check")
+        .arg("--write")
+        .arg("--backup")
+        .arg(file.path().to_str().unwrap())
+        .assert();
+    assert.success();
+
+    let mut backup = file.path().as_os_str().to_owned();
+    backup.push(".bak");
+    let backup = std::path::PathBuf::from(backup);
+
+    assert_eq!(
+        std::fs::read_to_string(file.path()).unwrap(),
+        "Some phrase with a small mistake.\n"
+    );
+    assert_eq!(
+        std::fs::read_to_string(&backup).unwrap(),
+        "Some phrase with a smal mistake.\n"
+    );
+    std::fs::remove_file(backup).unwrap();
+}
+
+#[test]
+fn test_check_write_requires_files() {
+    let mut cmd = Command::cargo_bin("ltrs").unwrap();
+    let assert = cmd
+        .arg("check")
+        .arg("--write")
+        .arg("-t")
+        .arg("\"some text that is given as text\"")
+        .assert();
+    assert.failure();
+}
+
+#[test]
+fn test_check_format_sarif() {
+    use std::io::Write;
+
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    writeln!(file, "Some text with a error inside.").unwrap();
+
+    let mut cmd = Command::cargo_bin("ltrs").unwrap();
+    let assert = cmd
+        .arg("check")
+        .arg("--format")
+        .arg("sarif")
+        .arg(file.path().to_str().unwrap())
+        .assert();
+    assert
+        .success()
+        .stdout(contains("\"version\": \"2.1.0\""))
+        .stdout(contains("\"startLine\": 1"));
+}
+
+#[test]
+fn test_invalid_header() {
+    let mut cmd = Command::cargo_bin("ltrs").unwrap();
+    let assert = cmd.arg("--header").arg("Authorization").arg("ping").assert();
+    assert
+        .failure()
+        .stderr(contains("should be formatted as KEY:VALUE"));
+}
+
+#[test]
+fn test_missing_cacert() {
+    let mut cmd = Command::cargo_bin("ltrs").unwrap();
+    let assert = cmd.arg("--cacert").arg("missing.pem").arg("ping").assert();
+    assert.failure().stderr(contains("No such file or directory"));
+}
+
+#[test]
+fn test_check_unknown_format() {
+    let mut cmd = Command::cargo_bin("ltrs").unwrap();
+    let assert = cmd
+        .arg("check")
+        .arg("--format")
+        .arg("junit")
+        .arg("-t")
+        .arg("Some text.")
+        .assert();
+    assert.failure().stderr(contains(
+        "unknown output format \\\"junit\\\", expected one of: text, json, fingerprints, diff, \
+         sarif, checkstyle",
+    ));
+}
+
+#[test]
+fn test_check_format_conflicts_with_raw() {
+    let mut cmd = Command::cargo_bin("ltrs").unwrap();
+    let assert = cmd
+        .arg("check")
+        .arg("--raw")
+        .arg("--format")
+        .arg("checkstyle")
+        .arg("-t")
+        .arg("\"some text that is given as text\"")
+        .assert();
+    assert.failure().stderr(contains("cannot be used with"));
+}
+
+#[test]
+fn test_check_list_matches_only() {
+    let mut cmd = Command::cargo_bin("ltrs").unwrap();
+    let assert = cmd
+        .arg("check")
+        .arg("--list-matches-only")
+        .arg("-t")
+        .arg("Some text with a error inside.")
+        .assert();
+    assert
+        .success()
+        .stdout(predicates::str::is_match(r"^[0-9a-f]{16} EN_A_VS_AN\n$").unwrap());
+}
+
+#[test]
+fn test_check_sample_files() {
+    let file = tempfile::NamedTempFile::new().unwrap();
+
+    let mut cmd = Command::cargo_bin("ltrs").unwrap();
+    let assert = cmd
+        .arg("check")
+        .arg("--sample-files")
+        .arg("0")
+        .arg("--seed")
+        .arg("42")
+        .arg(file.path().to_str().unwrap())
+        .assert();
+    assert
+        .success()
+        .stderr(contains("sample of 0 out of 1 files (--seed 42)"));
+}
+
+#[test]
+fn test_check_per_file_timeout() {
+    use std::io::Write;
+
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    writeln!(file, "Some text.").unwrap();
+
+    let mut cmd = Command::cargo_bin("ltrs").unwrap();
+    let assert = cmd
+        .arg("check")
+        .arg("--per-file-timeout")
+        .arg("0")
+        .arg(file.path().to_str().unwrap())
+        .assert();
+    assert
+        .failure()
+        .stderr(contains("skipped, checking took more than 0 second(s)"))
+        .stderr(contains("1 file(s) were skipped"));
+}
+
+#[test]
+fn test_check_with_wrong_sample() {
+    let file = tempfile::NamedTempFile::new().unwrap();
+
+    let mut cmd = Command::cargo_bin("ltrs").unwrap();
+    let assert = cmd
+        .arg("check")
+        .arg("--sample")
+        .arg("0%")
+        .arg(file.path().to_str().unwrap())
+        .assert();
+    assert.failure().stderr(contains("invalid value"));
+}
+
 #[test]
 fn test_check_with_language() {
     let mut cmd = Command::cargo_bin("ltrs").unwrap();
@@ -196,6 +374,22 @@ fn test_check_with_key_only() {
     ));
 }
 
+#[test]
+fn test_check_remote_dictionary_filter_without_login() {
+    let mut cmd = Command::cargo_bin("ltrs").unwrap();
+    let assert = cmd
+        .env_remove("LANGUAGETOOL_USERNAME")
+        .env_remove("LANGUAGETOOL_API_KEY")
+        .arg("check")
+        .arg("-t")
+        .arg("\"some text that is given as text\"")
+        .arg("--use-remote-dictionary-filter")
+        .assert();
+    assert.failure().stderr(contains(
+        "the following required arguments were not provided",
+    ));
+}
+
 #[test]
 fn test_check_with_dict() {
     let mut cmd = Command::cargo_bin("ltrs").unwrap();
@@ -443,6 +637,140 @@ fn test_languages() {
     assert.success();
 }
 
+#[test]
+fn test_rules_list_offline() {
+    let mut cmd = Command::cargo_bin("ltrs").unwrap();
+    let assert = cmd
+        .args(["rules", "list", "--offline", "--language", "en-GB"])
+        .args(["--category", "typos"])
+        .assert();
+    assert
+        .success()
+        .stdout(contains("MORFOLOGIK_RULE_EN_GB\tTYPOS\t"))
+        .stdout(contains("GERMAN_SPELLER_RULE").not());
+}
+
+#[test]
+fn test_server_config_generate_and_validate() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("server.properties");
+
+    let mut cmd = Command::cargo_bin("ltrs").unwrap();
+    let assert = cmd
+        .args(["server-config", "generate", "--max-text-length", "50000"])
+        .args(["--disable-rule-ids", "WHITESPACE_RULE,EN_QUOTES", "-o"])
+        .arg(&path)
+        .assert();
+    assert.success();
+    let content = std::fs::read_to_string(&path).unwrap();
+    assert!(content.contains("maxTextLength=50000"));
+    assert!(content.contains("disableRuleIds=WHITESPACE_RULE,EN_QUOTES"));
+
+    let mut cmd = Command::cargo_bin("ltrs").unwrap();
+    let assert = cmd.args(["server-config", "validate"]).arg(&path).assert();
+    assert
+        .success()
+        .stdout(contains("is a valid server configuration"));
+
+    std::fs::write(&path, "maxTextLenght=50000\n").unwrap();
+    let mut cmd = Command::cargo_bin("ltrs").unwrap();
+    let assert = cmd.args(["server-config", "validate"]).arg(&path).assert();
+    assert.failure().stderr(contains("unknown key"));
+}
+
+#[test]
+fn test_build_info() {
+    let mut cmd = Command::cargo_bin("ltrs").unwrap();
+    let assert = cmd.arg("--build-info").assert();
+    assert
+        .success()
+        .stdout(contains(env!("CARGO_PKG_VERSION")))
+        .stdout(contains("features: "));
+}
+
+#[test]
+fn test_config_init_and_show() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("config.toml");
+
+    let mut cmd = Command::cargo_bin("ltrs").unwrap();
+    let assert = cmd
+        .arg("--config")
+        .arg(&path)
+        .arg("config")
+        .arg("init")
+        .assert();
+    assert.success().stdout(contains("Created"));
+
+    let mut cmd = Command::cargo_bin("ltrs").unwrap();
+    let assert = cmd
+        .arg("--config")
+        .arg(&path)
+        .arg("config")
+        .arg("init")
+        .assert();
+    assert.failure().stderr(contains("already exists"));
+
+    std::fs::write(&path, "[check]\nlanguage = \"fr\"\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("ltrs").unwrap();
+    let assert = cmd
+        .arg("--config")
+        .arg(&path)
+        .arg("config")
+        .arg("show")
+        .assert();
+    assert.success().stdout(contains("language = \"fr\""));
+
+    let mut cmd = Command::cargo_bin("ltrs").unwrap();
+    let assert = cmd
+        .arg("--config")
+        .arg(&path)
+        .arg("check")
+        .arg("--help")
+        .assert();
+    assert.success().stdout(contains("[default: fr]"));
+}
+
+#[test]
+fn test_cache_stats_and_clear() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let mut cmd = Command::cargo_bin("ltrs").unwrap();
+    let assert = cmd
+        .env("XDG_CACHE_HOME", dir.path())
+        .arg("cache")
+        .arg("stats")
+        .assert();
+    assert
+        .success()
+        .stdout(contains("entries: 0"))
+        .stdout(contains("hit rate: -"));
+
+    let mut cmd = Command::cargo_bin("ltrs").unwrap();
+    let assert = cmd
+        .env("XDG_CACHE_HOME", dir.path())
+        .arg("cache")
+        .arg("clear")
+        .assert();
+    assert
+        .success()
+        .stdout(contains("Removed 0 cached response(s)"));
+}
+
+#[test]
+fn test_config_missing_file() {
+    let mut cmd = Command::cargo_bin("ltrs").unwrap();
+    let assert = cmd
+        .arg("--config")
+        .arg("does-not-exist.toml")
+        .arg("ping")
+        .assert();
+    assert
+        .failure()
+        .stderr(contains("invalid configuration: does-not-exist.toml"));
+}
+
 #[test]
 fn test_ping() {
     let mut cmd = Command::cargo_bin("ltrs").unwrap();
@@ -486,6 +814,62 @@ fn test_words_add() {
     ));
 }
 
+#[test]
+fn test_words_import() {
+    use std::io::Write;
+
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    writeln!(file, "my-word\nsome words\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("ltrs").unwrap();
+    let assert = cmd
+        .arg("words")
+        .arg("import")
+        .arg("--username")
+        .arg("user")
+        .arg("--api-key")
+        .arg("key")
+        .arg(file.path().to_str().unwrap())
+        .assert();
+    assert
+        .success()
+        .stderr(contains("0 added, 1 skipped, 1 failed"));
+}
+
+#[test]
+fn test_words_export() {
+    // TODO: remove the "invalid request" predicate as of LT 6.0
+    let mut cmd = Command::cargo_bin("ltrs").unwrap();
+    let assert = cmd
+        .arg("words")
+        .arg("export")
+        .arg("--username")
+        .arg("user")
+        .arg("--api-key")
+        .arg("key")
+        .assert();
+    assert.failure().stderr(OrPredicate::new(
+        contains("AuthException"),
+        contains("invalid request"),
+    ));
+}
+
+#[test]
+fn test_words_export_invalid_page_size() {
+    let mut cmd = Command::cargo_bin("ltrs").unwrap();
+    let assert = cmd
+        .arg("words")
+        .arg("export")
+        .arg("--username")
+        .arg("user")
+        .arg("--api-key")
+        .arg("key")
+        .arg("--page-size")
+        .arg("0")
+        .assert();
+    assert.failure().stderr(contains("positive"));
+}
+
 #[test]
 fn test_words_delete() {
     let mut cmd = Command::cargo_bin("ltrs").unwrap();
@@ -503,3 +887,408 @@ fn test_words_delete() {
         contains("invalid request"),
     ));
 }
+
+/// Start a server that answers `count` check requests with a spelling match
+/// on the first four characters of the text, and return its URL and the
+/// thread to join once all the requests are sent.
+fn spawn_check_server(count: usize) -> (String, std::thread::JoinHandle<()>) {
+    use std::{
+        io::{Read, Write},
+        net::TcpListener,
+    };
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let body = serde_json::json!({
+        "language": {
+            "code": "en-US",
+            "detectedLanguage": {"code": "en-US", "name": "English (US)"},
+            "name": "English (US)",
+        },
+        "matches": [{
+            "context": {"length": 4, "offset": 0, "text": "smal text"},
+            "contextForSureMatch": 0,
+            "ignoreForIncompleteSentence": false,
+            "length": 4,
+            "message": "Possible spelling mistake",
+            "offset": 0,
+            "replacements": [{"value": "small"}],
+            "rule": {
+                "category": {"id": "TYPOS", "name": "Possible Typo"},
+                "description": "",
+                "id": "MORFOLOGIK_RULE_EN_US",
+                "issueType": "misspelling",
+            },
+            "sentence": "smal text",
+            "shortMessage": "",
+            "type": {"typeName": "Other"},
+        }],
+        "software": {
+            "apiVersion": 1,
+            "buildDate": "",
+            "name": "LanguageTool",
+            "premium": false,
+            "status": "",
+            "version": "",
+        },
+    })
+    .to_string();
+    let server = std::thread::spawn(move || {
+        for stream in listener.incoming().take(count) {
+            let mut stream = stream.unwrap();
+            // Read the whole request, so that closing the stream does not reset it
+            let mut request = String::new();
+            let mut buffer = [0; 4096];
+            while !request.contains("text=") && !request.contains("data=") {
+                let len = stream.read(&mut buffer).unwrap();
+                request.push_str(&String::from_utf8_lossy(&buffer[..len]));
+            }
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                body.len()
+            )
+            .unwrap();
+        }
+    });
+
+    (format!("http://127.0.0.1:{port}"), server)
+}
+
+#[test]
+fn test_check_files_concurrently_in_order() {
+    let (url, server) = spawn_check_server(3);
+    let dir = tempfile::tempdir().unwrap();
+    let filenames: Vec<_> = ["a.txt", "b.txt", "c.txt"]
+        .iter()
+        .map(|name| {
+            let path = dir.path().join(name);
+            std::fs::write(&path, "smal text").unwrap();
+            path
+        })
+        .collect();
+
+    let mut cmd = Command::cargo_bin("ltrs").unwrap();
+    let output = cmd
+        .args(["--hostname", &url])
+        .args(["check", "--jobs", "3", "--summary-only"])
+        .args(&filenames)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    server.join().unwrap();
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 3);
+    for (line, filename) in lines.iter().zip(&filenames) {
+        assert_eq!(
+            *line,
+            format!("{}: 1 issue: 1 spelling, 0 grammar, 0 style", filename.display())
+        );
+    }
+}
+
+#[test]
+fn test_check_summary_counts_suppressed_matches() {
+    let (url, server) = spawn_check_server(1);
+    let dir = tempfile::tempdir().unwrap();
+    let filename = dir.path().join("a.txt");
+    std::fs::write(&filename, "smal text").unwrap();
+
+    let mut cmd = Command::cargo_bin("ltrs").unwrap();
+    let output = cmd
+        .args(["--hostname", &url])
+        .args(["check", "--summary-only", "--ignore-pattern", "^smal$"])
+        .arg(&filename)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    server.join().unwrap();
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(
+        stdout.trim_end(),
+        format!(
+            "{}: no issues (1 suppressed: 1 by ignore patterns)",
+            filename.display()
+        )
+    );
+
+    // JSON output lists them by mechanism too
+    let (url, server) = spawn_check_server(1);
+    let mut cmd = Command::cargo_bin("ltrs").unwrap();
+    let output = cmd
+        .args(["--hostname", &url])
+        .args(["check", "--format", "json", "--ignore-pattern", "^smal$"])
+        .arg(&filename)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    server.join().unwrap();
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["matches"], serde_json::json!([]));
+    assert_eq!(report["suppressed"]["ignorePatterns"], 1);
+    assert_eq!(report["suppressed"]["ignoreFile"], 0);
+}
+
+#[test]
+fn test_check_max_errors() {
+    let (url, server) = spawn_check_server(3);
+    let dir = tempfile::tempdir().unwrap();
+    let filenames: Vec<_> = ["a.txt", "b.txt", "c.txt", "d.txt"]
+        .iter()
+        .map(|name| {
+            let path = dir.path().join(name);
+            std::fs::write(&path, "smal text").unwrap();
+            path
+        })
+        .collect();
+
+    let mut cmd = Command::cargo_bin("ltrs").unwrap();
+    let output = cmd
+        .args(["--hostname", &url])
+        .args(["check", "--jobs", "1", "--max-errors", "2", "--summary-only"])
+        .args(&filenames)
+        .output()
+        .unwrap();
+    server.join().unwrap();
+
+    assert!(!output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout.lines().count(), 3);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("only reporting the first 0 out of 1 match(es)"), "{stderr}");
+    assert!(stderr.contains("1 file(s) were not checked"), "{stderr}");
+    assert!(stderr.contains("stopped after reporting 2 match(es)"), "{stderr}");
+}
+
+#[test]
+fn test_check_max_errors_exactly_reached() {
+    let (url, server) = spawn_check_server(3);
+    let dir = tempfile::tempdir().unwrap();
+    let filenames: Vec<_> = ["a.txt", "b.txt"]
+        .iter()
+        .map(|name| {
+            let path = dir.path().join(name);
+            std::fs::write(&path, "smal text").unwrap();
+            path
+        })
+        .collect();
+
+    let mut cmd = Command::cargo_bin("ltrs").unwrap();
+    let output = cmd
+        .args(["--hostname", &url])
+        .args(["check", "--jobs", "1", "--max-errors", "2", "--summary-only"])
+        .args(&filenames)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(String::from_utf8(output.stdout).unwrap().lines().count(), 2);
+
+    // Same with the text read from standard input
+    let mut cmd = Command::cargo_bin("ltrs").unwrap();
+    let output = cmd
+        .args(["--hostname", &url])
+        .args(["check", "--max-errors", "1", "--summary-only", "--text", "smal text"])
+        .output()
+        .unwrap();
+    server.join().unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn test_check_directory_with_glob() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir(dir.path().join("guide")).unwrap();
+    for file in ["index.md", "notes.txt", "guide/intro.md"] {
+        std::fs::write(dir.path().join(file), "smal text").unwrap();
+    }
+    let (url, server) = spawn_check_server(2);
+
+    let mut cmd = Command::cargo_bin("ltrs").unwrap();
+    let output = cmd
+        .args(["--hostname", &url])
+        .args(["check", "--glob", "**/*.md", "--summary-only"])
+        .arg(dir.path())
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    server.join().unwrap();
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with(&dir.path().join("guide/intro.md").display().to_string()));
+    assert!(lines[1].starts_with(&dir.path().join("index.md").display().to_string()));
+}
+
+#[test]
+fn test_check_stdin_filepath() {
+    let (url, server) = spawn_check_server(1);
+
+    let mut cmd = Command::cargo_bin("ltrs").unwrap();
+    let output = cmd
+        .args(["--hostname", &url])
+        .args(["check", "--stdin-filepath", "docs/intro.md"])
+        .write_stdin("smal text")
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    server.join().unwrap();
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("docs/intro.md"), "{stdout}");
+}
+
+#[test]
+fn test_check_diff() {
+    let dir = tempfile::tempdir().unwrap();
+    let git = |args: &[&str]| {
+        let status = std::process::Command::new("git")
+            .args(["-c", "user.name=ltrs", "-c", "user.email=ltrs@example.com"])
+            .args(args)
+            .current_dir(dir.path())
+            .status()
+            .unwrap();
+        assert!(status.success());
+    };
+    git(&["init", "--quiet"]);
+    std::fs::write(dir.path().join("a.txt"), "smal text\n").unwrap();
+    std::fs::write(dir.path().join("b.txt"), "smal text\n").unwrap();
+    git(&["add", "."]);
+    git(&["commit", "--quiet", "-m", "Initial commit"]);
+    // The match on the first line is only reported for `b.txt`
+    std::fs::write(dir.path().join("a.txt"), "smal text\nother text\n").unwrap();
+    std::fs::write(dir.path().join("b.txt"), "smal texts\n").unwrap();
+    git(&["add", "b.txt"]);
+    let (url, server) = spawn_check_server(1);
+
+    let mut cmd = Command::cargo_bin("ltrs").unwrap();
+    let output = cmd
+        .current_dir(dir.path())
+        .args(["--hostname", &url])
+        .args(["check", "--diff", "--staged", "--summary-only"])
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    server.join().unwrap();
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("b.txt: 1 issue"));
+    assert!(!stdout.contains("a.txt"));
+
+    let (url, server) = spawn_check_server(2);
+    let mut cmd = Command::cargo_bin("ltrs").unwrap();
+    let output = cmd
+        .current_dir(dir.path())
+        .args(["--hostname", &url])
+        .args(["check", "--diff", "--since", "HEAD", "--summary-only"])
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    server.join().unwrap();
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("a.txt: no issues"));
+    assert!(stdout.contains("b.txt: 1 issue"));
+}
+
+#[test]
+fn test_benchmark() {
+    let (url, server) = spawn_check_server(3);
+
+    let mut cmd = Command::cargo_bin("ltrs").unwrap();
+    let output = cmd
+        .args(["--hostname", &url])
+        .args(["benchmark", "--requests", "3", "--concurrency", "1", "--json"])
+        .output()
+        .unwrap();
+    server.join().unwrap();
+
+    assert!(output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["requests"], 3);
+    assert_eq!(report["failed"], 0);
+    assert!(report["latency_ms"]["p99"].as_f64().unwrap() > 0.0);
+}
+
+#[test]
+fn test_check_stats() {
+    let (url, server) = spawn_check_server(1);
+
+    let mut cmd = Command::cargo_bin("ltrs").unwrap();
+    let output = cmd
+        .args(["--hostname", &url])
+        .args(["check", "--stats", "--summary-only", "--text", "smal text"])
+        .output()
+        .unwrap();
+    server.join().unwrap();
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.contains("2 words, 1 sentence (2.0 words per sentence)"),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn test_check_spell_only() {
+    let (url, server) = spawn_check_server(1);
+
+    let mut cmd = Command::cargo_bin("ltrs").unwrap();
+    let output = cmd
+        .args(["--hostname", &url])
+        .args(["check", "--spell-only", "--include-request", "--raw"])
+        .args(["--text", "smal text"])
+        .output()
+        .unwrap();
+    server.join().unwrap();
+
+    let response: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(response["request"]["enabledCategories"], "TYPOS");
+    assert_eq!(response["request"]["enabledOnly"], true);
+
+    let mut cmd = Command::cargo_bin("ltrs").unwrap();
+    let assert = cmd
+        .args(["check", "--spell-only", "--disabled-rules", "A", "--text", "text"])
+        .assert();
+    assert.failure().stderr(contains("cannot be used with"));
+}